@@ -0,0 +1,65 @@
+//! Deserializing string-heavy documents, where most strings have nothing to unescape.
+
+use serde::Deserialize as _;
+
+#[global_allocator]
+static ALLOC: divan::AllocProfiler = divan::AllocProfiler::system();
+
+fn main() {
+    divan::main();
+}
+
+#[derive(serde::Deserialize)]
+#[expect(dead_code)] // Only deserialized
+struct Record<'a> {
+    #[serde(borrow)]
+    name: &'a str,
+
+    #[serde(borrow)]
+    tags: Vec<&'a str>,
+
+    kind: Kind,
+}
+
+#[derive(serde::Deserialize)]
+enum Kind {
+    Building,
+    Street,
+    Park,
+}
+
+#[derive(serde::Deserialize)]
+#[expect(dead_code)] // Only deserialized
+struct Owned {
+    name: String,
+}
+
+fn generate_records(count: usize) -> String {
+    let mut eon_source = String::from("[\n");
+    for i in 0..count {
+        eon_source += &format!(
+            "    {{ \"name\": \"Record number {i}\", \"tags\": [\"red\", 'green', \"blue\"], \"kind\": \"Street\" }},\n"
+        );
+    }
+    eon_source += "]\n";
+    eon_source
+}
+
+#[divan::bench]
+fn bench_borrowed_strings(bencher: divan::Bencher<'_, '_>) {
+    let eon_source = generate_records(10_000);
+    bencher.bench_local(move || {
+        let mut deserializer = eon::StreamDeserializer::new(&eon_source);
+        let records =
+            Vec::<Record<'_>>::deserialize(&mut deserializer).expect("Failed to deserialize");
+        divan::black_box(records);
+    });
+}
+
+#[divan::bench]
+fn bench_owned_strings(bencher: divan::Bencher<'_, '_>) {
+    let eon_source = generate_records(10_000);
+    bencher.bench_local(move || {
+        divan::black_box(eon::from_str::<Vec<Owned>>(&eon_source).expect("Failed to deserialize"));
+    });
+}
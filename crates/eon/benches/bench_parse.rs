@@ -76,3 +76,12 @@ fn bench_full_parse(bencher: divan::Bencher<'_, '_>) {
         eon::Value::from_str(&eon_source).expect("Failed to parse Eon source");
     });
 }
+
+#[divan::bench]
+fn bench_validate(bencher: divan::Bencher<'_, '_>) {
+    let eon_source = generate_big_file(1_000_000);
+    bencher.bench_local(move || {
+        eon_syntax::validate(&eon_source, &Default::default())
+            .expect("Failed to validate Eon source");
+    });
+}
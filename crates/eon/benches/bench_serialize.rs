@@ -0,0 +1,48 @@
+//! Serializing many small structs, e.g. in an export pipeline.
+
+#[global_allocator]
+static ALLOC: divan::AllocProfiler = divan::AllocProfiler::system();
+
+fn main() {
+    divan::main();
+}
+
+#[derive(serde::Serialize)]
+struct Point {
+    name: &'static str,
+    x: f32,
+    y: f32,
+    tags: [u8; 3],
+}
+
+fn points() -> Vec<Point> {
+    (0..1000)
+        .map(|i| Point {
+            name: "point",
+            x: i as f32,
+            y: -(i as f32),
+            tags: [1, 2, 3],
+        })
+        .collect()
+}
+
+#[divan::bench]
+fn bench_to_value(bencher: divan::Bencher<'_, '_>) {
+    let points = points();
+    bencher.bench_local(move || {
+        for point in &points {
+            divan::black_box(eon::to_value(point).expect("Failed to serialize"));
+        }
+    });
+}
+
+#[divan::bench]
+fn bench_reused_serializer(bencher: divan::Bencher<'_, '_>) {
+    let points = points();
+    let serializer = eon::Serializer::new();
+    bencher.bench_local(move || {
+        for point in &points {
+            divan::black_box(serializer.to_value(point).expect("Failed to serialize"));
+        }
+    });
+}
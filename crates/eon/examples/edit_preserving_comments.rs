@@ -0,0 +1,90 @@
+//! Save changed settings back into a config file that the user has edited by hand,
+//! without losing their comments, formatting, or keys unknown to the application.
+//!
+//! ```text
+//! cargo run -p eon --example edit_preserving_comments
+//! ```
+
+use eon::{CommentMap, EonDocumented, FormatOptions, KeyPath};
+
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+struct Settings {
+    theme: String,
+    font_size: u32,
+    recent_files: Vec<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            theme: "light".to_owned(),
+            font_size: 14,
+            recent_files: vec![],
+        }
+    }
+}
+
+impl EonDocumented for Settings {
+    fn document(prefix: &KeyPath, comments: &mut CommentMap) {
+        comments.insert(prefix.clone().key("theme"), "Either \"light\" or \"dark\"");
+        comments.insert(prefix.clone().key("font_size"), "In points");
+        comments.insert(
+            prefix.clone().key("recent_files"),
+            "Managed by the application",
+        );
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // First launch: write a documented starter file.
+    let template = eon::generate_template::<Settings>()?;
+    println!("Generated:\n{template}");
+
+    // The user opens it in their editor, changes the theme, and adds some notes of their own:
+    let mut edited = template
+        .replace("theme: \"light\"", "theme: \"dark\" // easier on the eyes")
+        .replace(
+            "// In points",
+            "// In points\n// TODO: try 16 on the big monitor",
+        );
+    edited.push_str("\n// Not known to the application, but kept anyway\nexperimental_gpu: true\n");
+    println!("Edited by the user:\n{edited}");
+
+    // Next launch: load the settings, ignoring the unknown key.
+    let mut settings: Settings = eon::from_str(&edited)?;
+    assert_eq!(settings.theme, "dark", "The user's edit should be loaded");
+
+    // The application changes some settings and saves them back:
+    settings.font_size = 16;
+    settings.recent_files.push("notes.eon".to_owned());
+    let saved = eon::update_document(&edited, &settings)?;
+    println!("Saved:\n{saved}");
+
+    for kept in [
+        "// Either \"light\" or \"dark\"",
+        "// easier on the eyes",
+        "// TODO: try 16 on the big monitor",
+        "experimental_gpu: true",
+    ] {
+        assert!(saved.contains(kept), "Lost {kept:?} when saving");
+    }
+    assert!(saved.contains("font_size: 16"), "font_size was not updated");
+    assert!(
+        saved.contains("\"notes.eon\""),
+        "recent_files was not updated"
+    );
+
+    // Formatting the file keeps the comments too:
+    let formatted = eon::reformat(&saved, &FormatOptions::default())?;
+    assert_eq!(
+        eon::reformat(&formatted, &FormatOptions::default())?,
+        formatted,
+        "Formatting should be stable"
+    );
+    assert!(
+        formatted.contains("// TODO: try 16 on the big monitor"),
+        "reformat should keep comments"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,116 @@
+//! Reload a config file whenever it changes, keeping the last good config if an edit is invalid.
+//!
+//! ```text
+//! cargo run -p eon --example hot_reload
+//! ```
+//!
+//! A real application would poll from a timer or use a file watcher;
+//! here we edit the file ourselves between polls so that the example terminates.
+
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct Config {
+    greeting: String,
+    volume: f32,
+
+    #[serde(default)]
+    muted: bool,
+}
+
+/// Keeps the latest valid [`Config`] loaded from a file.
+struct HotReloader {
+    path: PathBuf,
+
+    /// The contents of the file the last time we polled it.
+    ///
+    /// Comparing the contents rather than the modification time
+    /// means we don't miss edits on file systems with coarse timestamps.
+    last_source: String,
+
+    config: Config,
+}
+
+impl HotReloader {
+    fn load(path: impl Into<PathBuf>) -> Result<Self, Box<dyn std::error::Error>> {
+        let path = path.into();
+        let source = std::fs::read_to_string(&path)?;
+        let config = parse(&path, &source)?;
+        Ok(Self {
+            path,
+            last_source: source,
+            config,
+        })
+    }
+
+    /// Reload the config if the file changed.
+    ///
+    /// Returns `true` if a new config was loaded.
+    /// If the new contents are invalid, the error is reported and the previous config is kept.
+    fn poll(&mut self) -> bool {
+        let source = match std::fs::read_to_string(&self.path) {
+            Ok(source) => source,
+            Err(err) => {
+                eprintln!("Failed to read {}: {err}", self.path.display());
+                return false;
+            }
+        };
+        if source == self.last_source {
+            return false;
+        }
+
+        let result = parse(&self.path, &source);
+        self.last_source = source;
+        match result {
+            Ok(config) => {
+                self.config = config;
+                true
+            }
+            Err(err) => {
+                eprintln!("Keeping the previous config:\n{err}");
+                false
+            }
+        }
+    }
+}
+
+fn parse(path: &Path, source: &str) -> Result<Config, eon::Error> {
+    eon::from_str(source).map_err(|err| err.with_source_name(&path.display().to_string()))
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("config.eon");
+
+    std::fs::write(&path, "greeting: \"Hello\"\nvolume: 0.5\n")?;
+    let mut reloader = HotReloader::load(&path)?;
+    println!("Loaded: {:?}", reloader.config);
+
+    // Nothing changed:
+    assert!(!reloader.poll(), "The file was not edited");
+
+    // A valid edit is picked up:
+    std::fs::write(&path, "greeting: \"Hej\"\nvolume: 0.8\nmuted: true\n")?;
+    assert!(reloader.poll(), "The edit should be picked up");
+    println!("Reloaded: {:?}", reloader.config);
+
+    // A broken edit is reported, and the previous config is kept:
+    std::fs::write(&path, "greeting: \"Hej\"\nvolume: loud\n")?;
+    assert!(!reloader.poll(), "An invalid edit should not be applied");
+    assert_eq!(
+        reloader.config,
+        Config {
+            greeting: "Hej".to_owned(),
+            volume: 0.8,
+            muted: true,
+        },
+        "The previous config should be kept"
+    );
+
+    // …until the user fixes it:
+    std::fs::write(&path, "greeting: \"Hej\"\nvolume: 1.0\n")?;
+    assert!(reloader.poll(), "The fixed file should be picked up");
+    println!("Reloaded: {:?}", reloader.config);
+
+    Ok(())
+}
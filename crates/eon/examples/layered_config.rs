@@ -0,0 +1,139 @@
+//! Build the effective config of an application from several layers:
+//! built-in defaults, an environment-specific file, an optional local file, and command line overrides.
+//!
+//! ```text
+//! cargo run -p eon --example layered_config
+//! ```
+
+use std::path::Path;
+
+use eon::{FormatOptions, MergeStrategy, Value};
+
+#[derive(Debug, serde::Deserialize)]
+struct Config {
+    server: Server,
+    log: Log,
+    plugins: Vec<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct Server {
+    host: String,
+    port: u16,
+    workers: u32,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct Log {
+    level: String,
+    color: bool,
+}
+
+const DEFAULTS: &str = r#"
+// Shared between all environments
+@include "log.eon"
+
+server: {
+    host: "localhost"
+    port: 8080
+    workers: 4
+}
+plugins: ["metrics"]
+"#;
+
+const LOG: &str = r#"
+log: {
+    level: "info"
+    color: true
+}
+"#;
+
+const PRODUCTION: &str = r#"
+server: {
+    host: "0.0.0.0"
+    workers: 16
+}
+log: { color: false }
+plugins: ["tracing"]
+"#;
+
+/// Write the config files a deployment would have on disk.
+fn write_files(dir: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    std::fs::write(dir.join("defaults.eon"), DEFAULTS)?;
+    std::fs::write(dir.join("log.eon"), LOG)?;
+    std::fs::write(dir.join("production.eon"), PRODUCTION)?;
+    // No `local.eon`: it is optional.
+    Ok(())
+}
+
+/// Load and merge the layers, with later layers taking precedence.
+fn load_layers(dir: &Path, overrides: &[&str]) -> Result<Value, Box<dyn std::error::Error>> {
+    let mut config = Value::from_file(dir.join("defaults.eon"))?;
+
+    for name in ["production.eon", "local.eon"] {
+        let path = dir.join(name);
+        if path.exists() {
+            // Plugins accumulate, everything else is replaced:
+            config.deep_merge(Value::from_file(&path)?, MergeStrategy::Append);
+        }
+    }
+
+    // Overrides like `server.port=9000`, e.g. from `--set` flags:
+    for item in overrides {
+        let (path, value) = item
+            .split_once('=')
+            .ok_or_else(|| format!("Expected PATH=VALUE, got {item:?}"))?;
+        let target = config
+            .get_path_mut(path)
+            .ok_or_else(|| format!("Unknown config key {path:?}"))?;
+        *target = eon::parse_scalar(value)?;
+    }
+
+    Ok(config)
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    write_files(dir.path())?;
+
+    let merged = load_layers(dir.path(), &["server.port=9000", "log.level=\"debug\""])?;
+    println!(
+        "Effective config:\n{}",
+        merged.format(&FormatOptions::default())
+    );
+
+    // Look up single values without a schema…
+    assert_eq!(
+        merged.get_path("server.workers"),
+        Some(&Value::from(16)),
+        "production overrides the default workers"
+    );
+    assert_eq!(
+        merged.get_path("plugins[1]").and_then(Value::as_str),
+        Some("tracing"),
+        "production appends its plugins"
+    );
+
+    // …or deserialize the whole thing into your own types:
+    let config: Config = eon::from_str(&merged.format(&FormatOptions::default()))?;
+    println!("{config:#?}");
+    assert_eq!(config.server.host, "0.0.0.0", "production sets the host");
+    assert_eq!(config.server.port, 9000, "the override sets the port");
+    assert_eq!(config.server.workers, 16, "production sets the workers");
+    assert_eq!(config.log.level, "debug", "the override sets the log level");
+    assert!(!config.log.color, "production disables colors");
+    assert_eq!(
+        config.plugins,
+        ["metrics", "tracing"],
+        "plugins accumulate over the layers"
+    );
+
+    // Mistakes in the overrides are reported:
+    match load_layers(dir.path(), &["server.prot=9000"]) {
+        Ok(_) => return Err("Misspelled keys should be an error".into()),
+        Err(err) => println!("Error: {err}"),
+    }
+
+    Ok(())
+}
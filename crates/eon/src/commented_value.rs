@@ -0,0 +1,480 @@
+//! A [`Value`] with comments: a middle ground between [`Value`] and [`TokenTree`].
+
+use eon_syntax::{
+    Comment, FormatOptions, KeyPath, PathSegment, Result, TokenKeyValue, TokenList, TokenMap,
+    TokenTree, TokenValue, TokenVariant, escape_and_quote, is_valid_identifier,
+    unescape_and_unquote,
+};
+
+use crate::{Error, Map, Value, comments::comment_lines, token_tree_from_value::token_tree};
+
+/// A [`Value`] together with the comments in and around it.
+///
+/// Like a [`TokenTree`], this keeps the comments of a document,
+/// but the values are already parsed, and there are no spans or quoted strings to deal with.
+///
+/// Comments are stored as text without the leading `//`, with multiple lines joined by `\n`.
+/// This is lossy: `///` doc comments and `#` comments are written back as `//` comments,
+/// and blank lines between entries are not kept.
+///
+/// ## Example
+/// ```
+/// use eon::{CommentedValue, FormatOptions, Value};
+///
+/// let mut doc: CommentedValue = "// The port to listen on\nport: 8080 // Not 80\n".parse().unwrap();
+///
+/// let port = doc.get_path_mut(&"port".parse().unwrap()).unwrap();
+/// assert_eq!(port.comment.as_deref(), Some("The port to listen on"));
+/// assert_eq!(port.suffix_comment.as_deref(), Some("Not 80"));
+/// port.data = Value::from(9000).into();
+///
+/// assert_eq!(
+///     doc.format(&FormatOptions::default()),
+///     "// The port to listen on\nport: 9000 // Not 80\n"
+/// );
+/// assert_eq!(doc.to_value(), "port: 9000".parse().unwrap());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CommentedValue {
+    /// The comments on the lines above the value.
+    ///
+    /// For a map entry, this includes the comments above its key.
+    pub comment: Option<String>,
+
+    /// The value itself.
+    pub data: CommentedData,
+
+    /// The comment after the value on the same line.
+    pub suffix_comment: Option<String>,
+
+    /// The comments after the last entry of a map, list, or variant, before the closing bracket.
+    ///
+    /// Ignored for other values.
+    pub closing_comment: Option<String>,
+}
+
+/// The contents of a [`CommentedValue`].
+///
+/// Maps, lists, and variants contain [`CommentedValue`]s, so that each entry can have its own comments.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CommentedData {
+    /// Any value that is not a map, list, or variant.
+    ///
+    /// A map, list, or variant stored here works, but its entries have no comments.
+    Value(Value),
+
+    /// The entries of a list.
+    List(Vec<CommentedValue>),
+
+    /// The key-value pairs of a map, in order.
+    ///
+    /// Only the values have comments. The comments above a key belong to its value.
+    Map(Vec<(Value, CommentedValue)>),
+
+    /// A variant, like `"Rgb"(255, 0, 0)`.
+    Variant {
+        /// The name of the variant, like `Rgb`.
+        name: String,
+
+        /// The values of the variant, like `255, 0, 0`.
+        values: Vec<CommentedValue>,
+    },
+}
+
+impl Default for CommentedData {
+    fn default() -> Self {
+        Self::Value(Value::Null)
+    }
+}
+
+impl CommentedValue {
+    /// Set the comment on the lines above the value.
+    ///
+    /// See [`Self::comment`].
+    pub fn with_comment(mut self, text: impl Into<String>) -> Self {
+        self.comment = Some(text.into());
+        self
+    }
+
+    /// Set the comment after the value on the same line.
+    ///
+    /// See [`Self::suffix_comment`].
+    pub fn with_suffix_comment(mut self, text: impl Into<String>) -> Self {
+        self.suffix_comment = Some(text.into());
+        self
+    }
+
+    /// Convert a [`TokenTree`], keeping its comments.
+    ///
+    /// You must provide the full Eon source string so that we can produce good error messages.
+    /// Fails for the same documents as [`Value::try_from_token_tree`], e.g. ones with duplicate keys.
+    pub fn try_from_token_tree(eon_source: &str, tt: &TokenTree<'_>) -> Result<Self> {
+        // Report errors exactly like for a `Value`, so that the conversion below can't fail halfway:
+        Value::try_from_token_tree(eon_source, tt)?;
+        Self::from_tree(eon_source, tt)
+    }
+
+    fn from_tree(eon_source: &str, tt: &TokenTree<'_>) -> Result<Self> {
+        let children = |trees: &[TokenTree<'_>]| -> Result<Vec<Self>> {
+            trees
+                .iter()
+                .map(|tt| Self::from_tree(eon_source, tt))
+                .collect()
+        };
+
+        let (data, closing_comments): (_, &[Comment<'_>]) = match &tt.value {
+            TokenValue::Map(map) => {
+                let mut entries = Vec::with_capacity(map.key_values.len());
+                for TokenKeyValue { key, value } in &map.key_values {
+                    let key_value = match &key.value {
+                        TokenValue::Identifier(key) => Value::String(key.to_string()),
+                        _ => Value::try_from_token_tree(eon_source, key)?,
+                    };
+                    let mut entry = Self::from_tree(eon_source, value)?;
+                    entry.comment = join_comments(
+                        key.prefix_comments
+                            .iter()
+                            .chain(&key.doc_comments)
+                            .chain(&value.prefix_comments),
+                    );
+                    if entry.suffix_comment.is_none() {
+                        entry.suffix_comment = key.suffix_comment.as_deref().map(comment_text);
+                    }
+                    entries.push((key_value, entry));
+                }
+                (CommentedData::Map(entries), map.closing_comments.as_slice())
+            }
+            TokenValue::List(list) => (
+                CommentedData::List(children(&list.values)?),
+                list.closing_comments.as_slice(),
+            ),
+            TokenValue::Variant(variant) => {
+                let name = unescape_and_unquote(&variant.quoted_name)
+                    .map_err(|err| Error::new(eon_source, variant.name_span, err))?;
+                (
+                    CommentedData::Variant {
+                        name,
+                        values: children(&variant.values)?,
+                    },
+                    variant.closing_comments.as_slice(),
+                )
+            }
+            value => (
+                CommentedData::Value(Value::try_from_tree_value(eon_source, tt.span, value)?),
+                &[],
+            ),
+        };
+
+        Ok(Self {
+            comment: join_comments(tt.prefix_comments.iter().chain(&tt.doc_comments)),
+            data,
+            suffix_comment: tt.suffix_comment.as_deref().map(comment_text),
+            closing_comment: join_comments(closing_comments),
+        })
+    }
+
+    /// The [`Value`], without the comments.
+    pub fn to_value(&self) -> Value {
+        match &self.data {
+            CommentedData::Value(value) => value.clone(),
+            CommentedData::List(values) => values.iter().map(Self::to_value).collect(),
+            CommentedData::Map(entries) => Value::Map(
+                entries
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.to_value()))
+                    .collect::<Map>(),
+            ),
+            CommentedData::Variant { name, values } => {
+                Value::new_variant(name.clone(), values.iter().map(Self::to_value).collect())
+            }
+        }
+    }
+
+    /// Pretty-print to an Eon string, with the comments.
+    pub fn format(&self, options: &FormatOptions) -> String {
+        self.token_tree(options).format(options)
+    }
+
+    /// Find the value at the given path, like `server.ports[2]`.
+    ///
+    /// An index into a map looks up the integer key.
+    pub fn get_path(&self, path: &KeyPath) -> Option<&Self> {
+        path.segments
+            .iter()
+            .try_fold(self, |value, segment| match (&value.data, segment) {
+                (CommentedData::Map(entries), segment) => entries
+                    .iter()
+                    .find(|(key, _)| key_matches(key, segment))
+                    .map(|(_, value)| value),
+                (
+                    CommentedData::List(values) | CommentedData::Variant { values, .. },
+                    PathSegment::Index(index),
+                ) => values.get(*index),
+                _ => None,
+            })
+    }
+
+    /// Find the value at the given path, like `server.ports[2]`, e.g. to change its comments.
+    ///
+    /// An index into a map looks up the integer key.
+    pub fn get_path_mut(&mut self, path: &KeyPath) -> Option<&mut Self> {
+        path.segments
+            .iter()
+            .try_fold(self, |value, segment| match (&mut value.data, segment) {
+                (CommentedData::Map(entries), segment) => entries
+                    .iter_mut()
+                    .find(|(key, _)| key_matches(key, segment))
+                    .map(|(_, value)| value),
+                (
+                    CommentedData::List(values) | CommentedData::Variant { values, .. },
+                    PathSegment::Index(index),
+                ) => values.get_mut(*index),
+                _ => None,
+            })
+    }
+
+    fn token_tree(&self, options: &FormatOptions) -> TokenTree<'static> {
+        let children = |values: &[Self]| -> Vec<TokenTree<'static>> {
+            values
+                .iter()
+                .map(|value| value.token_tree(options))
+                .collect()
+        };
+        let closing_comments = comments(self.closing_comment.as_deref());
+
+        let value = match &self.data {
+            CommentedData::Value(value) => token_tree(value.clone(), options).value,
+            CommentedData::List(values) => TokenValue::List(TokenList {
+                values: children(values),
+                closing_comments,
+                trailing_comma: false,
+            }),
+            CommentedData::Map(entries) => {
+                let all_keys_are_identifiers = entries
+                    .iter()
+                    .all(|(key, _)| matches!(key, Value::String(key) if is_valid_identifier(key)));
+                TokenValue::Map(TokenMap {
+                    includes: vec![],
+                    key_values: entries
+                        .iter()
+                        .map(|(key, value)| {
+                            let mut key = match key {
+                                Value::String(key) if all_keys_are_identifiers => {
+                                    TokenTree::from(TokenValue::Identifier(key.clone().into()))
+                                }
+                                key => token_tree(key.clone(), options),
+                            };
+                            let mut value = value.token_tree(options);
+                            key.prefix_comments = std::mem::take(&mut value.prefix_comments);
+                            TokenKeyValue { key, value }
+                        })
+                        .collect(),
+                    closing_comments,
+                    trailing_comma: false,
+                })
+            }
+            CommentedData::Variant { name, values } => TokenValue::Variant(TokenVariant {
+                name_span: None,
+                quoted_name: escape_and_quote(name).into(),
+                values: children(values),
+                closing_comments,
+                trailing_comma: false,
+            }),
+        };
+
+        let mut tree = TokenTree::from(value);
+        tree.prefix_comments = comments(self.comment.as_deref());
+        tree.suffix_comment = self
+            .suffix_comment
+            .as_deref()
+            .map(|text| Comment::Owned(format!("// {text}")));
+        tree
+    }
+}
+
+impl From<Value> for CommentedData {
+    fn from(value: Value) -> Self {
+        let children = |values: Vec<Value>| values.into_iter().map(CommentedValue::from).collect();
+        match value {
+            Value::List(values) => Self::List(children(values)),
+            Value::Map(map) => Self::Map(
+                map.into_iter()
+                    .map(|(key, value)| (key, CommentedValue::from(value)))
+                    .collect(),
+            ),
+            Value::Variant(variant) => Self::Variant {
+                name: variant.name,
+                values: children(variant.values.into_vec()),
+            },
+            value => Self::Value(value),
+        }
+    }
+}
+
+/// A [`CommentedValue`] without any comments, ready to have some added.
+impl From<Value> for CommentedValue {
+    fn from(value: Value) -> Self {
+        Self {
+            data: value.into(),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<CommentedValue> for Value {
+    fn from(value: CommentedValue) -> Self {
+        value.to_value()
+    }
+}
+
+impl From<CommentedValue> for TokenTree<'static> {
+    fn from(value: CommentedValue) -> Self {
+        value.token_tree(&FormatOptions::default())
+    }
+}
+
+impl std::str::FromStr for CommentedValue {
+    type Err = crate::Error;
+
+    fn from_str(eon_source: &str) -> Result<Self> {
+        let tt = TokenTree::parse_str(eon_source)?;
+        Self::try_from_token_tree(eon_source, &tt)
+    }
+}
+
+impl std::fmt::Display for CommentedValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.format(&FormatOptions::default()).fmt(f)
+    }
+}
+
+/// Does the map key match the path segment?
+fn key_matches(key: &Value, segment: &PathSegment) -> bool {
+    match segment {
+        PathSegment::Key(name) => key.as_string() == Some(name.as_str()),
+        PathSegment::Index(index) => {
+            key.as_number().and_then(|number| number.as_u64()) == Some(*index as u64)
+        }
+    }
+}
+
+/// The text of a comment, without the leading `//`, `///`, or `#`, and one space after it.
+fn comment_text(comment: &str) -> String {
+    let text = comment
+        .strip_prefix('#')
+        .unwrap_or_else(|| comment.trim_start_matches('/'));
+    text.strip_prefix(' ').unwrap_or(text).to_owned()
+}
+
+/// The text of the comments, one per line.
+fn join_comments(comments: impl IntoIterator<Item = impl AsRef<str>>) -> Option<String> {
+    let lines: Vec<String> = comments
+        .into_iter()
+        .map(|comment| comment_text(comment.as_ref()))
+        .collect();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+/// The comment lines for the text, if any.
+fn comments(text: Option<&str>) -> Vec<Comment<'static>> {
+    text.map_or_else(Vec::new, |text| {
+        comment_lines(text).map(Comment::Owned).collect()
+    })
+}
+
+#[test]
+fn test_commented_value() {
+    let source = r#"
+// The server
+server: {
+    /// Where to listen
+    host: "localhost" // or 0.0.0.0
+    // The ports
+    /// that we use
+    ports: [
+        80 // http
+        443
+        // More to come
+    ]
+}
+color: "Rgb"(
+    // red
+    255,
+    0,
+    0
+)
+// The end
+"#;
+    let value: CommentedValue = source.parse().unwrap();
+    assert_eq!(value.to_value(), source.parse::<Value>().unwrap());
+
+    let comment = |path: &str| {
+        value
+            .get_path(&path.parse().unwrap())
+            .unwrap()
+            .comment
+            .clone()
+    };
+    assert_eq!(comment("server").as_deref(), Some("The server"));
+    assert_eq!(comment("server.host").as_deref(), Some("Where to listen"));
+    assert_eq!(
+        comment("server.ports").as_deref(),
+        Some("The ports\nthat we use")
+    );
+    assert_eq!(comment("color[0]").as_deref(), Some("red"));
+    assert_eq!(comment("color[1]"), None);
+
+    let ports = value.get_path(&"server.ports".parse().unwrap()).unwrap();
+    assert_eq!(ports.closing_comment.as_deref(), Some("More to come"));
+    let http = value.get_path(&"server.ports[0]".parse().unwrap()).unwrap();
+    assert_eq!(http.suffix_comment.as_deref(), Some("http"));
+    assert_eq!(value.closing_comment.as_deref(), Some("The end"));
+
+    // Formatting keeps the comments, and is stable:
+    let options = FormatOptions::default();
+    let formatted = value.format(&options);
+    for comment in [
+        "// The server",
+        "// Where to listen",
+        "// or 0.0.0.0",
+        "// that we use",
+        "// http",
+        "// More to come",
+        "// red",
+        "// The end",
+    ] {
+        assert!(
+            formatted.contains(comment),
+            "Lost {comment:?}:\n{formatted}"
+        );
+    }
+    let reparsed: CommentedValue = formatted.parse().unwrap();
+    assert_eq!(reparsed, value);
+    assert_eq!(reparsed.format(&options), formatted);
+
+    // Errors are the same as for a `Value`:
+    assert!("a: 1, a: 2".parse::<CommentedValue>().is_err());
+    assert!("a: nul".parse::<CommentedValue>().is_err());
+}
+
+#[test]
+fn test_commented_value_from_value() {
+    let value: Value = r#"name: "Eon", 42: [1, 2]"#.parse().unwrap();
+    let mut commented = CommentedValue::from(value.clone());
+    assert_eq!(commented.to_value(), value);
+
+    let name = commented.get_path_mut(&"name".parse().unwrap()).unwrap();
+    *name = name.clone().with_comment("The name\n\nof the thing");
+    let second = commented
+        .get_path_mut(&KeyPath::root().index(42).index(1))
+        .unwrap();
+    *second = second.clone().with_suffix_comment("Second");
+
+    let formatted = commented.format(&FormatOptions::default());
+    let reparsed: CommentedValue = formatted.parse().unwrap();
+    assert_eq!(reparsed, commented, "{formatted}");
+    assert!(
+        formatted.contains("// The name\n//\n// of the thing\n"),
+        "{formatted}"
+    );
+}
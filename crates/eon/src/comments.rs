@@ -0,0 +1,131 @@
+//! Adding comments to a formatted [`crate::Value`], e.g. for self-documenting default config files.
+
+use std::collections::BTreeMap;
+
+use eon_syntax::{KeyPath, TokenTree};
+
+/// Comments to add to specific paths of a document when formatting it.
+///
+/// Useful for writing default config files that explain themselves.
+/// See [`crate::Value::format_with_comments`] and `eon::to_string_with_comments`.
+///
+/// ## Example
+/// ```
+/// use eon::{CommentMap, FormatOptions, Value};
+///
+/// let value: Value = "port: 8080".parse().unwrap();
+/// let comments = CommentMap::new().with_comment("port".parse().unwrap(), "The port to listen on");
+/// assert_eq!(
+///     value.format_with_comments(&FormatOptions::default(), &comments),
+///     "// The port to listen on\nport: 8080\n"
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CommentMap {
+    /// The comment lines for each path, including the leading `//`.
+    comments: BTreeMap<KeyPath, Vec<String>>,
+}
+
+impl CommentMap {
+    /// An empty [`CommentMap`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a comment above the value at the given path.
+    ///
+    /// The text should not include the leading `//`.
+    /// Multi-line text is turned into multiple comment lines.
+    pub fn with_comment(mut self, path: KeyPath, text: &str) -> Self {
+        self.insert(path, text);
+        self
+    }
+
+    /// Add a comment above the value at the given path.
+    ///
+    /// The text should not include the leading `//`.
+    /// Multi-line text is turned into multiple comment lines.
+    /// Adding more comments to the same path adds more lines.
+    pub fn insert(&mut self, path: KeyPath, text: &str) {
+        self.comments
+            .entry(path)
+            .or_default()
+            .extend(comment_lines(text));
+    }
+
+    /// Are there no comments?
+    pub fn is_empty(&self) -> bool {
+        self.comments.is_empty()
+    }
+
+    /// Add the comments to the given tree.
+    ///
+    /// Comments for paths that are not in the tree are ignored.
+    pub(crate) fn apply<'a>(&'a self, tree: &mut TokenTree<'a>) {
+        for (path, lines) in &self.comments {
+            for line in lines {
+                tree.add_prefix_comment(path, line);
+            }
+        }
+    }
+}
+
+/// Each line of the text as a `// line` comment.
+pub(crate) fn comment_lines(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.lines().map(|line| {
+        if line.is_empty() {
+            "//".to_owned()
+        } else {
+            format!("// {line}")
+        }
+    })
+}
+
+/// A type that can document its own fields, for generating self-documenting config files.
+///
+/// Implement this for your config types, then use `eon::generate_template` to write a starter config
+/// with the default values and a comment above each field.
+///
+/// ## Example
+/// ```
+/// use eon::{CommentMap, EonDocumented, KeyPath};
+///
+/// struct Server {
+///     port: u16,
+/// }
+///
+/// impl EonDocumented for Server {
+///     fn document(prefix: &KeyPath, comments: &mut CommentMap) {
+///         comments.insert(prefix.clone().key("port"), "The port to listen on");
+///     }
+/// }
+///
+/// struct Config {
+///     server: Server,
+/// }
+///
+/// impl EonDocumented for Config {
+///     fn document(prefix: &KeyPath, comments: &mut CommentMap) {
+///         let server = prefix.clone().key("server");
+///         comments.insert(server.clone(), "Where to serve from");
+///         Server::document(&server, comments);
+///     }
+/// }
+///
+/// let comments = Config::comments();
+/// assert!(!comments.is_empty());
+/// ```
+pub trait EonDocumented {
+    /// Add a comment for each field of `Self` to `comments`, at paths starting with `prefix`.
+    ///
+    /// Nested documented types can be documented by calling their [`Self::document`]
+    /// with a longer prefix.
+    fn document(prefix: &KeyPath, comments: &mut CommentMap);
+
+    /// All the comments for `Self`, at paths relative to `Self`.
+    fn comments() -> CommentMap {
+        let mut comments = CommentMap::new();
+        Self::document(&KeyPath::root(), &mut comments);
+        comments
+    }
+}
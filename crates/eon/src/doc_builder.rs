@@ -0,0 +1,168 @@
+//! A builder for Eon documents with comments.
+
+use std::borrow::Cow;
+
+use eon_syntax::{TokenKeyValue, TokenList, TokenMap, TokenTree, TokenValue};
+
+use crate::Value;
+
+/// Builds a [`TokenTree`] (a map or a list) with comments, ready to be formatted.
+///
+/// This is useful for generating config files with explanatory comments,
+/// without having to go through string templates.
+///
+/// Comments include the slashes, like `"// The port to listen on"`.
+/// Comments added before a value (or before a key) is added are put above that entry.
+///
+/// ## Example
+/// ```
+/// use eon::{DocBuilder, FormatOptions};
+///
+/// let doc = DocBuilder::map()
+///     .key("port")
+///     .comment("// TCP port")
+///     .value(8080)
+///     .key("hosts")
+///     .tree(DocBuilder::list().value("localhost").suffix_comment("// for testing"))
+///     .build();
+///
+/// assert_eq!(
+///     doc.format(&FormatOptions::default()),
+///     "// TCP port\nport: 8080\nhosts: [\n\t\"localhost\" // for testing\n]\n"
+/// );
+/// ```
+#[derive(Debug)]
+pub struct DocBuilder {
+    tree: TokenTree<'static>,
+
+    /// Waiting for a value.
+    pending_key: Option<TokenTree<'static>>,
+
+    /// To be put above the next entry.
+    pending_comments: Vec<Cow<'static, str>>,
+}
+
+impl DocBuilder {
+    /// Start building a map, like `{ key: value }`.
+    pub fn map() -> Self {
+        Self::new(TokenValue::Map(TokenMap {
+            includes: vec![],
+            key_values: vec![],
+            closing_comments: vec![],
+            trailing_comma: false,
+        }))
+    }
+
+    /// Start building a list, like `[ a, b, c ]`.
+    pub fn list() -> Self {
+        Self::new(TokenValue::List(TokenList {
+            values: vec![],
+            closing_comments: vec![],
+            trailing_comma: false,
+        }))
+    }
+
+    fn new(value: TokenValue<'static>) -> Self {
+        Self {
+            tree: value.into(),
+            pending_key: None,
+            pending_comments: vec![],
+        }
+    }
+
+    /// Add a comment line, like `"// Explanation"`, above the next entry.
+    ///
+    /// Comments added after the last entry end up before the closing bracket.
+    pub fn comment(mut self, comment: impl Into<Cow<'static, str>>) -> Self {
+        self.pending_comments.push(comment.into());
+        self
+    }
+
+    /// Add a comment, like `"// Explanation"`, after the last added value, on the same line.
+    ///
+    /// Does nothing if no value has been added yet.
+    pub fn suffix_comment(mut self, comment: impl Into<Cow<'static, str>>) -> Self {
+        let last = match &mut self.tree.value {
+            TokenValue::Map(map) => map.key_values.last_mut().map(|kv| &mut kv.value),
+            TokenValue::List(list) => list.values.last_mut(),
+            _ => None,
+        };
+        if let Some(last) = last {
+            last.suffix_comment = Some(comment.into());
+        }
+        self
+    }
+
+    /// Set the key of the next map entry. Must be followed by [`Self::value`] or [`Self::tree`].
+    ///
+    /// # Panics
+    /// If this is building a list, or if the previous key has no value.
+    pub fn key(mut self, key: &str) -> Self {
+        assert!(
+            matches!(self.tree.value, TokenValue::Map(_)),
+            "DocBuilder::key called when building a list"
+        );
+        assert!(
+            self.pending_key.is_none(),
+            "DocBuilder::key called twice without a value in between"
+        );
+        self.pending_key = Some(TokenTree::key(key));
+        self
+    }
+
+    /// Add a value to the list, or as the value of the last [`Self::key`] of the map.
+    ///
+    /// # Panics
+    /// If this is building a map, and there is no key waiting for a value.
+    pub fn value(self, value: impl Into<Value>) -> Self {
+        self.tree(TokenTree::from(value.into()))
+    }
+
+    /// Like [`Self::value`], but with an already built [`TokenTree`], e.g. another [`DocBuilder`].
+    ///
+    /// # Panics
+    /// If this is building a map, and there is no key waiting for a value.
+    pub fn tree(mut self, value: impl Into<TokenTree<'static>>) -> Self {
+        let mut value = value.into();
+        let comments = std::mem::take(&mut self.pending_comments);
+        match &mut self.tree.value {
+            TokenValue::Map(map) => {
+                let mut key = self
+                    .pending_key
+                    .take()
+                    .expect("DocBuilder: a map value must be preceded by a key");
+                key.prefix_comments.extend(comments);
+                map.key_values.push(TokenKeyValue { key, value });
+            }
+            TokenValue::List(list) => {
+                value.prefix_comments.extend(comments);
+                list.values.push(value);
+            }
+            _ => unreachable!("DocBuilder only builds maps and lists"),
+        }
+        self
+    }
+
+    /// Finish building the document.
+    ///
+    /// # Panics
+    /// If a key is still waiting for a value.
+    pub fn build(mut self) -> TokenTree<'static> {
+        assert!(
+            self.pending_key.is_none(),
+            "DocBuilder::build called with a key without a value"
+        );
+        match &mut self.tree.value {
+            TokenValue::Map(map) => map.closing_comments = self.pending_comments,
+            TokenValue::List(list) => list.closing_comments = self.pending_comments,
+            _ => unreachable!("DocBuilder only builds maps and lists"),
+        }
+        self.tree
+    }
+}
+
+impl From<DocBuilder> for TokenTree<'static> {
+    fn from(builder: DocBuilder) -> Self {
+        builder.build()
+    }
+}
@@ -0,0 +1,146 @@
+//! Resolving `@include "other.eon"` directives when loading Eon files.
+
+use std::path::{Path, PathBuf};
+
+use eon_syntax::{Label, ParseOptions, TokenInclude, TokenTree, TokenValue};
+
+use crate::{
+    Error, Map, Result, Value,
+    value_from_token_tree::{Converter, KeyOrigin},
+};
+
+impl Value {
+    /// Load an Eon file, resolving any `@include "other.eon"` directives in it.
+    ///
+    /// Included paths are relative to the directory of the including file.
+    /// An included file must contain a map, which is merged into the map containing the
+    /// `@include`, as if its key-value pairs were written before those of the including map.
+    ///
+    /// Keys that are defined both in an included file and elsewhere are handled
+    /// according to the default [`ParseOptions::duplicate_keys`] (an error).
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_file_with_options(path, &ParseOptions::default())
+    }
+
+    /// Like [`Self::from_file`], but using the given [`ParseOptions`].
+    pub fn from_file_with_options(path: impl AsRef<Path>, options: &ParseOptions) -> Result<Self> {
+        let path = path.as_ref();
+        let canonical = path
+            .canonicalize()
+            .map_err(|err| Error::custom(format!("Failed to read {}: {err}", path.display())))?;
+
+        let mut loader = Loader {
+            options: options.clone(),
+            files: vec![],
+            sources: vec![],
+            stack: vec![],
+        };
+        loader.load(path, canonical).map(|(value, _)| value)
+    }
+}
+
+/// Loads Eon files and whatever they include.
+pub(crate) struct Loader {
+    options: ParseOptions,
+
+    /// The path of every loaded file, parallel to `sources`.
+    files: Vec<PathBuf>,
+
+    /// `(name, contents)` of every loaded file.
+    sources: Vec<(String, String)>,
+
+    /// Canonical paths and names of the files currently being loaded, for cycle detection.
+    stack: Vec<(PathBuf, String)>,
+}
+
+impl Loader {
+    /// The `(name, contents)` of a loaded source.
+    pub fn source(&self, index: usize) -> &(String, String) {
+        &self.sources[index]
+    }
+
+    /// Load the file at `path`, which canonicalizes to `canonical`.
+    ///
+    /// If the file contains a map, the origin of each of its keys is also returned.
+    fn load(&mut self, path: &Path, canonical: PathBuf) -> Result<(Value, Vec<KeyOrigin>)> {
+        let name = path.display().to_string();
+        let contents = std::fs::read_to_string(path)
+            .map_err(|err| Error::custom(format!("Failed to read {name}: {err}")))?;
+
+        let source_index = self.sources.len();
+        self.files.push(path.to_owned());
+        self.sources.push((name.clone(), contents.clone()));
+        self.stack.push((canonical, name.clone()));
+
+        let options = self.options.clone().with_keep_comments(false);
+        let result = TokenTree::parse_str_with(&contents, &options).and_then(|tt| {
+            let mut converter = Converter::new(&contents)
+                .with_duplicate_keys(options.duplicate_keys)
+                .with_loader(self, source_index);
+            if let TokenValue::Map(map) = &tt.value {
+                converter
+                    .map(map)
+                    .map(|(map, origins)| (Value::Map(map), origins))
+            } else {
+                converter.value(&tt).map(|value| (value, vec![]))
+            }
+        });
+
+        self.stack.pop();
+        result.map_err(|err| err.with_source_name(&name))
+    }
+
+    /// Load the map included by an `@include` directive in the source with index `from`.
+    pub fn include(
+        &mut self,
+        from: usize,
+        include: &TokenInclude<'_>,
+    ) -> Result<(Map, Vec<KeyOrigin>)> {
+        let relative = include
+            .path()
+            .map_err(|err| self.error_at(from, include, format!("Invalid path: {err}")))?;
+        let path = match self.files[from].parent() {
+            Some(dir) => dir.join(&relative),
+            None => PathBuf::from(&relative),
+        };
+
+        let canonical = path.canonicalize().map_err(|err| {
+            self.error_at(
+                from,
+                include,
+                format!("Failed to include {relative}: {err}"),
+            )
+        })?;
+
+        if let Some(cycle_start) = self.stack.iter().position(|(p, _)| p == &canonical) {
+            let cycle = self.stack[cycle_start..]
+                .iter()
+                .map(|(_, name)| name.as_str())
+                .chain(std::iter::once(path.display().to_string().as_str()))
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            return Err(self.error_at(from, include, format!("Include cycle: {cycle}")));
+        }
+
+        match self.load(&path, canonical)? {
+            (Value::Map(map), origins) => Ok((map, origins)),
+            _ => Err(self.error_at(
+                from,
+                include,
+                format!("{relative} must contain a map to be included"),
+            )),
+        }
+    }
+
+    fn error_at(&self, from: usize, include: &TokenInclude<'_>, message: String) -> Error {
+        let (name, contents) = self.source(from).clone();
+        match include.span {
+            Some(span) => Error::new_labeled(
+                [(name.clone(), contents)],
+                message,
+                vec![Label::new(name, span, "Included here")],
+            ),
+            None => Error::custom(format!("{name}: {message}")),
+        }
+    }
+}
@@ -0,0 +1,297 @@
+//! Lossless conversion between Eon and JSON.
+//!
+//! Most Eon values map directly to JSON.
+//! Eon-specific constructs are encoded as JSON objects with special `$`-prefixed keys:
+//!
+//! | Eon                                 | JSON                                          |
+//! | ----------------------------------- | --------------------------------------------- |
+//! | `null`, `true`, `false`             | `null`, `true`, `false`                       |
+//! | `"string"`                          | `"string"`                                    |
+//! | `42`, `-1`, `3.14`                  | `42`, `-1`, `3.14`                            |
+//! | `+inf`, `-inf`, `+nan`              | `{ "$number": "+inf" }`, …                    |
+//! | Integers that don't fit in 64 bits  | `{ "$number": "340282366920938463463374607431768211455" }` |
+//! | `2024-05-01T12:30:00Z`              | `{ "$timestamp": "2024-05-01T12:30:00Z" }`    |
+//! | `b"89504e47"`                       | `{ "$bytes": "89504e47" }`                    |
+//! | `[1, 2]`                            | `[1, 2]`                                      |
+//! | `{ key: value }`                    | `{ "key": value }`                            |
+//! | `{ 1: "one" }` (non-string keys)    | `{ "$map": [[1, "one"]] }`                    |
+//! | `"Rgb"(255, 0, 0)`                  | `{ "$variant": "Rgb", "$values": [255, 0, 0] }` |
+//!
+//! The order of map keys is preserved.
+//!
+//! When converting from JSON, only objects with exactly these keys (and valid contents)
+//! are decoded as the special Eon values; all other objects become Eon maps.
+//!
+//! So that a map key like `$number` can't be mistaken for a special key,
+//! keys made of one or more `$` followed by the name of a special key get an extra `$`:
+//! `{ "$number": 1 }` becomes `{ "$$number": 1 }`, and `$$number` becomes `$number` again when converting back.
+//! Other keys, like `$schema`, are left as they are.
+//! Converting Eon → JSON → Eon therefore always gives back the same value.
+
+use super::{bytes_from_hex, escape_key, hex_from_bytes, unescape_key};
+use crate::{Error, Map, Number, Result, Timestamp, Value};
+
+const NUMBER_KEY: &str = "$number";
+const TIMESTAMP_KEY: &str = "$timestamp";
+const BYTES_KEY: &str = "$bytes";
+const MAP_KEY: &str = "$map";
+const VARIANT_KEY: &str = "$variant";
+const VALUES_KEY: &str = "$values";
+
+/// Parse a JSON document into an Eon [`Value`].
+///
+/// See the [module-level docs](self) for how JSON maps to Eon.
+pub fn from_json_str(json: &str) -> Result<Value> {
+    serde_json::from_str::<serde_json::Value>(json)
+        .map(Value::from)
+        .map_err(|err| Error::custom(format!("Invalid JSON: {err}")))
+}
+
+/// Convert an Eon [`Value`] into a pretty-printed JSON document.
+///
+/// See the [module-level docs](self) for how Eon maps to JSON.
+pub fn to_json_string(value: &Value) -> String {
+    let json = serde_json::Value::from(value);
+    serde_json::to_string_pretty(&json)
+        .unwrap_or_else(|err| unreachable!("A serde_json::Value can always be serialized: {err}"))
+}
+
+impl From<serde_json::Value> for Value {
+    fn from(json: serde_json::Value) -> Self {
+        match json {
+            serde_json::Value::Null => Self::Null,
+            serde_json::Value::Bool(b) => Self::Bool(b),
+            serde_json::Value::Number(number) => Self::Number(number_from_json(&number)),
+            serde_json::Value::String(string) => Self::String(string),
+            serde_json::Value::Array(array) => {
+                Self::List(array.into_iter().map(Self::from).collect())
+            }
+            serde_json::Value::Object(object) => decode_special(&object).unwrap_or_else(|| {
+                Self::Map(
+                    object
+                        .into_iter()
+                        .map(|(key, value)| (unescape_key(&key).to_owned(), Self::from(value)))
+                        .collect(),
+                )
+            }),
+        }
+    }
+}
+
+impl From<&Value> for serde_json::Value {
+    fn from(value: &Value) -> Self {
+        match value {
+            Value::Null => Self::Null,
+            Value::Bool(b) => Self::Bool(*b),
+            Value::Number(number) => number_to_json(number),
+            Value::Timestamp(timestamp) => {
+                single_key_object(TIMESTAMP_KEY, Self::String(timestamp.to_string()))
+            }
+            Value::String(string) => Self::String(string.clone()),
+            Value::Bytes(bytes) => {
+                single_key_object(BYTES_KEY, Self::String(hex_from_bytes(bytes)))
+            }
+            Value::List(list) => Self::Array(list.iter().map(Self::from).collect()),
+            Value::Map(map) => {
+                if map.keys().all(|key| matches!(key, Value::String(_))) {
+                    Self::Object(
+                        map.iter()
+                            .map(|(key, value)| {
+                                let key = escape_key(key.as_string().unwrap_or_default());
+                                (key.into_owned(), Self::from(value))
+                            })
+                            .collect(),
+                    )
+                } else {
+                    let pairs = map
+                        .iter()
+                        .map(|(key, value)| Self::Array(vec![Self::from(key), Self::from(value)]))
+                        .collect();
+                    single_key_object(MAP_KEY, Self::Array(pairs))
+                }
+            }
+            Value::Variant(variant) => {
+                let mut object = serde_json::Map::new();
+                object.insert(VARIANT_KEY.to_owned(), Self::String(variant.name.clone()));
+                object.insert(
+                    VALUES_KEY.to_owned(),
+                    Self::Array(variant.values.iter().map(Self::from).collect()),
+                );
+                Self::Object(object)
+            }
+        }
+    }
+}
+
+impl From<Value> for serde_json::Value {
+    fn from(value: Value) -> Self {
+        Self::from(&value)
+    }
+}
+
+fn single_key_object(key: &str, value: serde_json::Value) -> serde_json::Value {
+    let mut object = serde_json::Map::new();
+    object.insert(key.to_owned(), value);
+    serde_json::Value::Object(object)
+}
+
+fn number_to_json(number: &Number) -> serde_json::Value {
+    let json_number = if number.is_float() {
+        number.as_f64().and_then(serde_json::Number::from_f64)
+    } else if let Some(n) = number.as_i64() {
+        Some(n.into())
+    } else {
+        number.as_u64().map(Into::into)
+    };
+
+    json_number.map_or_else(
+        || single_key_object(NUMBER_KEY, serde_json::Value::String(number.to_string())),
+        serde_json::Value::Number,
+    )
+}
+
+fn number_from_json(number: &serde_json::Number) -> Number {
+    if let Some(n) = number.as_u64() {
+        n.into()
+    } else if let Some(n) = number.as_i64() {
+        n.into()
+    } else {
+        number.as_f64().unwrap_or(f64::NAN).into()
+    }
+}
+
+/// Decode the special `$`-objects, or return `None` if this is a normal map.
+fn decode_special(object: &serde_json::Map<String, serde_json::Value>) -> Option<Value> {
+    if object.len() == 1 {
+        if let Some(serde_json::Value::String(number)) = object.get(NUMBER_KEY) {
+            return number.parse::<Number>().ok().map(Value::Number);
+        }
+
+        if let Some(serde_json::Value::String(timestamp)) = object.get(TIMESTAMP_KEY) {
+            return timestamp.parse::<Timestamp>().ok().map(Value::Timestamp);
+        }
+
+        if let Some(serde_json::Value::String(hex)) = object.get(BYTES_KEY) {
+            return bytes_from_hex(hex).map(Value::Bytes);
+        }
+
+        if let Some(serde_json::Value::Array(pairs)) = object.get(MAP_KEY) {
+            let mut map = Map::with_capacity(pairs.len());
+            for pair in pairs {
+                let serde_json::Value::Array(pair) = pair else {
+                    return None;
+                };
+                let [key, value] = pair.as_slice() else {
+                    return None;
+                };
+                map.insert(Value::from(key.clone()), Value::from(value.clone()));
+            }
+            // Otherwise it would have been encoded as a normal object:
+            let has_non_string_key = map.keys().any(|key| !matches!(key, Value::String(_)));
+            return has_non_string_key.then_some(Value::Map(map));
+        }
+    }
+
+    if object.len() == 2 {
+        if let (Some(serde_json::Value::String(name)), Some(serde_json::Value::Array(values))) =
+            (object.get(VARIANT_KEY), object.get(VALUES_KEY))
+        {
+            if !values.is_empty() {
+                return Some(Value::new_variant(
+                    name.clone(),
+                    values.iter().cloned().map(Value::from).collect(),
+                ));
+            }
+        }
+    }
+
+    None
+}
+
+#[test]
+fn test_json_roundtrip() {
+    let eon: Value = r#"
+        null: null
+        bool: true
+        int: -42
+        big: 340282366920938463463374607431768211455
+        float: 3.5
+        whole_float: 1.0
+        special: [+inf, -inf, +nan]
+        timestamp: 2024-05-01T12:30:00Z
+        bytes: b"89504e47"
+        list: [1, "two", { three: 3 }]
+        int_keys: { 1: "one", 2: "two" }
+        color: "Rgb"(255, 0, 0)
+        simple_enum: "Green"
+    "#
+    .parse()
+    .unwrap();
+
+    let json = to_json_string(&eon);
+    assert_eq!(from_json_str(&json).unwrap(), eon);
+
+    let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(json["float"], serde_json::json!(3.5));
+    assert_eq!(json["special"][0], serde_json::json!({ "$number": "+inf" }));
+    assert_eq!(
+        json["timestamp"],
+        serde_json::json!({ "$timestamp": "2024-05-01T12:30:00Z" })
+    );
+    assert_eq!(
+        json["int_keys"],
+        serde_json::json!({ "$map": [[1, "one"], [2, "two"]] })
+    );
+    assert_eq!(
+        json["color"],
+        serde_json::json!({ "$variant": "Rgb", "$values": [255, 0, 0] })
+    );
+}
+
+#[test]
+fn test_json_objects_that_look_special() {
+    // These are not valid special objects, so they should stay maps:
+    for json in [
+        r#"{ "$number": "not a number" }"#,
+        r#"{ "$number": 42 }"#,
+        r#"{ "$timestamp": "2024-05-01" }"#,
+        r#"{ "$bytes": "not hex" }"#,
+        r#"{ "$map": [[1, 2, 3]] }"#,
+        r#"{ "$map": [["all", "strings"]] }"#,
+        r#"{ "$map": [] }"#,
+        r#"{ "$variant": "Rgb", "$values": [] }"#,
+        r#"{ "$variant": "Rgb", "$values": [1], "extra": 2 }"#,
+    ] {
+        let eon = from_json_str(json).unwrap();
+        assert!(matches!(eon, Value::Map(_)), "{json} should be a map");
+        assert_eq!(from_json_str(&to_json_string(&eon)).unwrap(), eon, "{json}");
+    }
+}
+
+#[test]
+fn test_json_special_keys_roundtrip() {
+    let eon: Value = r#"
+        "$number": "+inf"
+        "$timestamp": "2024-05-01T12:30:00Z"
+        "$bytes": "ff"
+        "$map": []
+        "$$variant": "Rgb"
+        "$values": [255, 0, 0]
+        "$schema": "https://example.com/schema.json"
+        nested: [{ "$number": "+inf" }, { "$variant": "Rgb", "$values": [255, 0, 0] }]
+    "#
+    .parse()
+    .unwrap();
+
+    let json = to_json_string(&eon);
+    assert_eq!(from_json_str(&json).unwrap(), eon, "{json}");
+
+    let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(json["$$number"], serde_json::json!("+inf"));
+    assert_eq!(json["$$$variant"], serde_json::json!("Rgb"));
+    assert_eq!(
+        json["$schema"],
+        serde_json::json!("https://example.com/schema.json")
+    );
+    assert_eq!(json["nested"][0], serde_json::json!({ "$$number": "+inf" }));
+}
@@ -0,0 +1,71 @@
+//! Converting between Eon and other formats.
+//!
+//! Each format is behind a feature flag of the same name.
+
+#[cfg(feature = "json")]
+pub mod json;
+
+#[cfg(feature = "toml")]
+pub mod toml;
+
+#[cfg(feature = "yaml")]
+pub mod yaml;
+
+/// `[0x89, 0x50]` -> `"8950"`
+#[cfg(any(feature = "json", feature = "toml"))]
+fn hex_from_bytes(bytes: &[u8]) -> String {
+    let literal = eon_syntax::quote_bytes(bytes);
+    literal["b\"".len()..literal.len() - 1].to_owned()
+}
+
+/// `"8950"` -> `[0x89, 0x50]`
+#[cfg(any(feature = "json", feature = "toml"))]
+fn bytes_from_hex(hex: &str) -> Option<Vec<u8>> {
+    eon_syntax::unquote_bytes(&format!("b\"{hex}\"")).ok()
+}
+
+/// The names of the special `$`-keys, like `$number`, without the `$`.
+#[cfg(any(feature = "json", feature = "toml"))]
+const SPECIAL_KEY_NAMES: [&str; 6] = ["number", "timestamp", "bytes", "map", "variant", "values"];
+
+/// Is this a special key with one or more `$`, like `$number` or `$$number`?
+#[cfg(any(feature = "json", feature = "toml"))]
+fn looks_special(key: &str) -> bool {
+    let name = key.trim_start_matches('$');
+    name.len() < key.len() && SPECIAL_KEY_NAMES.contains(&name)
+}
+
+/// Escape a map key so it can't be mistaken for a special key: `$number` -> `$$number`.
+#[cfg(any(feature = "json", feature = "toml"))]
+fn escape_key(key: &str) -> std::borrow::Cow<'_, str> {
+    if looks_special(key) {
+        format!("${key}").into()
+    } else {
+        key.into()
+    }
+}
+
+/// Undo [`escape_key`]: `$$number` -> `$number`.
+#[cfg(any(feature = "json", feature = "toml"))]
+fn unescape_key(key: &str) -> &str {
+    if looks_special(key) && key.starts_with("$$") {
+        &key[1..]
+    } else {
+        key
+    }
+}
+
+#[cfg(any(feature = "json", feature = "toml"))]
+#[test]
+fn test_escape_key() {
+    for (key, escaped) in [
+        ("number", "number"),
+        ("$schema", "$schema"),
+        ("$number", "$$number"),
+        ("$$values", "$$$values"),
+        ("$numbers", "$numbers"),
+    ] {
+        assert_eq!(escape_key(key), escaped);
+        assert_eq!(unescape_key(escaped), key);
+    }
+}
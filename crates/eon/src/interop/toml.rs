@@ -0,0 +1,365 @@
+//! Conversion between Eon and TOML.
+//!
+//! TOML tables and arrays map directly to Eon maps and lists.
+//! Constructs that only one of the formats has are mapped like this:
+//!
+//! | Eon                                 | TOML                                          |
+//! | ----------------------------------- | --------------------------------------------- |
+//! | `1979-05-27T07:32:00Z`              | `1979-05-27T07:32:00Z` (an offset datetime)   |
+//! | `"1979-05-27T07:32:00"`             | `1979-05-27T07:32:00` (a local datetime) ¹    |
+//! | `"1979-05-27"`, `"07:32:00"`        | `1979-05-27`, `07:32:00` (a local date/time) ¹ |
+//! | `+inf`, `-inf`, `+nan`              | `inf`, `-inf`, `nan`                          |
+//! | Integers that don't fit in an `i64` | `{ "$number" = "18446744073709551615" }`      |
+//! | `{ 1: "one" }` (non-string keys)    | `{ "$map" = [[1, "one"]] }`                   |
+//! | `"Rgb"(255, 0, 0)`                  | `{ "$variant" = "Rgb", "$values" = [255, 0, 0] }` |
+//! | `b"89504e47"`                       | `{ "$bytes" = "89504e47" }`                   |
+//! | `null`                              | (error)                                       |
+//!
+//! TOML offset datetimes become Eon [`Timestamp`]s.
+//! ¹ The other TOML datetimes (local datetimes, dates, and times) have no Eon equivalent,
+//! so they become Eon strings in the standard TOML/RFC 3339 notation.
+//! Only Eon timestamps become TOML datetimes, so these stay strings when converting back to TOML.
+//!
+//! The `$`-tables use the same encoding as [`interop::json`](super), and are only decoded
+//! when they have exactly these keys with valid contents.
+//! All other tables become Eon maps.
+//! Like in [`interop::json`](super), a map key like `$number` is written as `$$number`,
+//! so that it can't be mistaken for a `$`-table, and is turned back into `$number` when converting back.
+//!
+//! So TOML → Eon → TOML gives back the same document,
+//! except that a key like `$number` in a table that isn't a valid `$`-table comes back as `$$number`.
+//!
+//! TOML has no `null`, so converting an Eon value containing `null` to TOML is an error.
+//! The root of a TOML document must be a table, so [`to_toml_string`] requires an Eon map.
+//! The order of map keys is preserved.
+
+use super::{bytes_from_hex, escape_key, hex_from_bytes, unescape_key};
+use crate::{Error, KeyPath, Map, Number, PathSegment, Result, Timestamp, Value};
+
+const NUMBER_KEY: &str = "$number";
+const BYTES_KEY: &str = "$bytes";
+const MAP_KEY: &str = "$map";
+const VARIANT_KEY: &str = "$variant";
+const VALUES_KEY: &str = "$values";
+
+/// Parse a TOML document into an Eon [`Value`].
+///
+/// The result is always a [`Value::Map`].
+/// See the [module-level docs](self) for how TOML maps to Eon.
+pub fn from_toml_str(toml: &str) -> Result<Value> {
+    toml.parse::<toml::Table>()
+        .map(|table| Value::from(toml::Value::Table(table)))
+        .map_err(|err| Error::custom(format!("Invalid TOML: {err}")))
+}
+
+/// Convert an Eon [`Value`] into a TOML document.
+///
+/// Fails if the value is not a map, or if it contains a `null`.
+/// See the [module-level docs](self) for how Eon maps to TOML.
+pub fn to_toml_string(value: &Value) -> Result<String> {
+    if !matches!(value, Value::Map(_)) {
+        return Err(Error::custom(
+            "Only an Eon map can be converted to a TOML document",
+        ));
+    }
+    let toml::Value::Table(table) = to_toml(value, &mut KeyPath::root())? else {
+        unreachable!("A map always converts to a TOML table");
+    };
+    toml::to_string_pretty(&table)
+        .map_err(|err| Error::custom(format!("Failed to write TOML: {err}")))
+}
+
+/// Convert an Eon [`Value`] into a [`toml::Value`].
+///
+/// Fails if the value contains a `null`.
+/// See the [module-level docs](self) for how Eon maps to TOML.
+pub fn to_toml_value(value: &Value) -> Result<toml::Value> {
+    to_toml(value, &mut KeyPath::root())
+}
+
+impl From<toml::Value> for Value {
+    fn from(toml: toml::Value) -> Self {
+        match toml {
+            toml::Value::String(string) => Self::String(string),
+            toml::Value::Integer(n) => Self::Number(n.into()),
+            toml::Value::Float(n) => Self::Number(n.into()),
+            toml::Value::Boolean(b) => Self::Bool(b),
+            toml::Value::Datetime(datetime) => {
+                let datetime = datetime.to_string();
+                datetime
+                    .parse::<Timestamp>()
+                    .map_or(Self::String(datetime), Self::Timestamp)
+            }
+            toml::Value::Array(array) => Self::List(array.into_iter().map(Self::from).collect()),
+            toml::Value::Table(table) => decode_special(&table).unwrap_or_else(|| {
+                Self::Map(
+                    table
+                        .into_iter()
+                        .map(|(key, value)| (unescape_key(&key).to_owned(), Self::from(value)))
+                        .collect(),
+                )
+            }),
+        }
+    }
+}
+
+/// The `path` is only used for error messages.
+fn to_toml(value: &Value, path: &mut KeyPath) -> Result<toml::Value> {
+    Ok(match value {
+        Value::Null => {
+            return Err(Error::custom(if path.is_root() {
+                "TOML has no null value".to_owned()
+            } else {
+                format!("TOML has no null value, so `{path}` cannot be converted")
+            }));
+        }
+        Value::Bool(b) => toml::Value::Boolean(*b),
+        Value::Number(number) => number_to_toml(number),
+        Value::Timestamp(timestamp) => timestamp
+            .as_str()
+            .parse::<toml::value::Datetime>()
+            .map_or_else(
+                |_| toml::Value::String(timestamp.to_string()),
+                toml::Value::Datetime,
+            ),
+        Value::String(string) => toml::Value::String(string.clone()),
+        Value::Bytes(bytes) => {
+            single_key_table(BYTES_KEY, toml::Value::String(hex_from_bytes(bytes)))
+        }
+        Value::List(list) => toml::Value::Array(list_to_toml(list, path)?),
+        Value::Map(map) => {
+            if map.keys().all(|key| matches!(key, Value::String(_))) {
+                let mut table = toml::Table::new();
+                for (key, value) in map {
+                    let key = key.as_string().unwrap_or_default();
+                    path.segments.push(key.into());
+                    table.insert(escape_key(key).into_owned(), to_toml(value, path)?);
+                    path.segments.pop();
+                }
+                toml::Value::Table(table)
+            } else {
+                let mut pairs = Vec::with_capacity(map.len());
+                for (key, value) in map {
+                    path.segments.push(PathSegment::Key(key.to_string()));
+                    pairs.push(toml::Value::Array(vec![
+                        to_toml(key, path)?,
+                        to_toml(value, path)?,
+                    ]));
+                    path.segments.pop();
+                }
+                single_key_table(MAP_KEY, toml::Value::Array(pairs))
+            }
+        }
+        Value::Variant(variant) => {
+            let mut table = toml::Table::new();
+            table.insert(
+                VARIANT_KEY.to_owned(),
+                toml::Value::String(variant.name.clone()),
+            );
+            table.insert(
+                VALUES_KEY.to_owned(),
+                toml::Value::Array(list_to_toml(&variant.values, path)?),
+            );
+            toml::Value::Table(table)
+        }
+    })
+}
+
+fn list_to_toml<'a>(
+    values: impl IntoIterator<Item = &'a Value>,
+    path: &mut KeyPath,
+) -> Result<Vec<toml::Value>> {
+    values
+        .into_iter()
+        .enumerate()
+        .map(|(i, value)| {
+            path.segments.push(i.into());
+            let result = to_toml(value, path);
+            path.segments.pop();
+            result
+        })
+        .collect()
+}
+
+fn single_key_table(key: &str, value: toml::Value) -> toml::Value {
+    let mut table = toml::Table::new();
+    table.insert(key.to_owned(), value);
+    toml::Value::Table(table)
+}
+
+fn number_to_toml(number: &Number) -> toml::Value {
+    if number.is_float() {
+        toml::Value::Float(number.as_f64().unwrap_or(f64::NAN))
+    } else if let Some(n) = number.as_i64() {
+        toml::Value::Integer(n)
+    } else {
+        single_key_table(NUMBER_KEY, toml::Value::String(number.to_string()))
+    }
+}
+
+/// Decode the special `$`-tables, or return `None` if this is a normal table.
+fn decode_special(table: &toml::Table) -> Option<Value> {
+    if table.len() == 1 {
+        if let Some(toml::Value::String(number)) = table.get(NUMBER_KEY) {
+            return number.parse::<Number>().ok().map(Value::Number);
+        }
+
+        if let Some(toml::Value::String(hex)) = table.get(BYTES_KEY) {
+            return bytes_from_hex(hex).map(Value::Bytes);
+        }
+
+        if let Some(toml::Value::Array(pairs)) = table.get(MAP_KEY) {
+            let mut map = Map::with_capacity(pairs.len());
+            for pair in pairs {
+                let toml::Value::Array(pair) = pair else {
+                    return None;
+                };
+                let [key, value] = pair.as_slice() else {
+                    return None;
+                };
+                map.insert(Value::from(key.clone()), Value::from(value.clone()));
+            }
+            // Otherwise it would have been encoded as a normal table:
+            let has_non_string_key = map.keys().any(|key| !matches!(key, Value::String(_)));
+            return has_non_string_key.then_some(Value::Map(map));
+        }
+    }
+
+    if table.len() == 2 {
+        if let (Some(toml::Value::String(name)), Some(toml::Value::Array(values))) =
+            (table.get(VARIANT_KEY), table.get(VALUES_KEY))
+        {
+            if !values.is_empty() {
+                return Some(Value::new_variant(
+                    name.clone(),
+                    values.iter().cloned().map(Value::from).collect(),
+                ));
+            }
+        }
+    }
+
+    None
+}
+
+#[test]
+fn test_toml_to_eon() {
+    let toml = r#"
+title = "Example"
+released = 1979-05-27T07:32:00Z
+birthday = 1979-05-27
+ratio = 0.5
+ints = [1, 2, 3]
+
+[server]
+host = "localhost"
+port = 8080
+
+[[products]]
+name = "Hammer"
+
+[[products]]
+name = "Nail"
+"#;
+
+    let eon = from_toml_str(toml).unwrap();
+    let expected: Value = r#"
+        title: "Example"
+        released: 1979-05-27T07:32:00Z
+        birthday: "1979-05-27"
+        ratio: 0.5
+        ints: [1, 2, 3]
+        server: { host: "localhost", port: 8080 }
+        products: [{ name: "Hammer" }, { name: "Nail" }]
+    "#
+    .parse()
+    .unwrap();
+    assert_eq!(eon, expected);
+
+    // Timestamps stay datetimes when going back to TOML, but local dates are now strings:
+    let roundtripped = to_toml_string(&eon)
+        .unwrap()
+        .parse::<toml::Table>()
+        .unwrap();
+    let mut original = toml.parse::<toml::Table>().unwrap();
+    original.insert("birthday".to_owned(), "1979-05-27".into());
+    assert_eq!(roundtripped, original);
+    assert!(roundtripped["released"].is_datetime());
+}
+
+#[test]
+fn test_eon_toml_roundtrip() {
+    let eon: Value = r#"
+        bool: true
+        int: -42
+        big: 18446744073709551615
+        float: 3.5
+        special: [+inf, -inf]
+        list: [1, "two", { three: 3 }]
+        int_keys: { 1: "one", 2: "two" }
+        color: "Rgb"(255, 0, 0)
+        simple_enum: "Green"
+        bytes: b"89504e47"
+    "#
+    .parse()
+    .unwrap();
+
+    let toml = to_toml_string(&eon).unwrap();
+    assert_eq!(from_toml_str(&toml).unwrap(), eon);
+
+    let table: toml::Table = toml.parse().unwrap();
+    assert_eq!(
+        table["big"]["$number"].as_str(),
+        Some("18446744073709551615")
+    );
+    assert_eq!(table["color"]["$variant"].as_str(), Some("Rgb"));
+}
+
+#[test]
+fn test_toml_timestamp_like_string_roundtrip() {
+    let eon: Value = r#"
+        string: "1979-05-27T07:32:00Z"
+        timestamp: 1979-05-27T07:32:00Z
+    "#
+    .parse()
+    .unwrap();
+
+    let toml = to_toml_string(&eon).unwrap();
+    assert_eq!(from_toml_str(&toml).unwrap(), eon, "{toml}");
+
+    let table: toml::Table = toml.parse().unwrap();
+    assert_eq!(table["string"].as_str(), Some("1979-05-27T07:32:00Z"));
+    assert!(table["timestamp"].is_datetime(), "{toml}");
+}
+
+#[test]
+fn test_toml_special_keys_roundtrip() {
+    let eon: Value = r#"
+        "$number": "+inf"
+        "$$bytes": "ff"
+        "$schema": "https://example.com/schema.toml"
+        color: { "$variant": "Rgb", "$values": [255, 0, 0] }
+    "#
+    .parse()
+    .unwrap();
+
+    let toml = to_toml_string(&eon).unwrap();
+    assert_eq!(from_toml_str(&toml).unwrap(), eon, "{toml}");
+
+    let table: toml::Table = toml.parse().unwrap();
+    assert_eq!(table["$$number"].as_str(), Some("+inf"));
+    assert_eq!(table["$$$bytes"].as_str(), Some("ff"));
+    assert!(table.contains_key("$schema"), "{toml}");
+    assert_eq!(table["color"]["$$variant"].as_str(), Some("Rgb"));
+}
+
+#[test]
+fn test_eon_to_toml_errors() {
+    let eon: Value = "server: { ports: [80, null] }".parse().unwrap();
+    assert_eq!(
+        to_toml_string(&eon).unwrap_err().to_string(),
+        "TOML has no null value, so `server.ports[1]` cannot be converted"
+    );
+
+    let eon: Value = "[1, 2, 3]".parse().unwrap();
+    assert!(to_toml_string(&eon).is_err());
+}
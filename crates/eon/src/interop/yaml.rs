@@ -0,0 +1,144 @@
+//! Importing YAML into Eon.
+//!
+//! This is meant for migrating existing YAML configs to Eon, and follows the YAML 1.2 core schema:
+//!
+//! | YAML                                  | Eon                             |
+//! | ------------------------------------- | ------------------------------- |
+//! | `null`, `Null`, `~`, or nothing       | `null`                          |
+//! | `true`, `false`                       | `true`, `false`                 |
+//! | `yes`, `no`, `on`, `off`              | `"yes"`, `"no"`, `"on"`, `"off"` |
+//! | `42`, `0x2A`, `0o52`                  | `42`                            |
+//! | `0755`, `08` (leading zeros)          | `"0755"`, `"08"`                |
+//! | `.inf`, `-.inf`, `.nan`               | `+inf`, `-inf`, `+nan`          |
+//! | `!Rgb [255, 0, 0]`                    | `"Rgb"(255, 0, 0)`              |
+//! | `!Gray 128`                           | `"Gray"(128)`                   |
+//! | `!Point {x: 1, y: 2}`                 | `"Point"({x: 1, y: 2})`         |
+//! | `!Black`                              | `"Black"`                       |
+//!
+//! Anchors and aliases (`&name` and `*name`) are resolved by copying the anchored value,
+//! and merge keys (`<<: *name`) are applied, with explicit keys taking precedence.
+//!
+//! The standard tags (`!!str`, `!!int`, …) are applied when parsing,
+//! and all other tags become Eon variants, as shown above.
+//!
+//! The order of map keys is preserved.
+
+use crate::{Error, Map, Number, Result, Value};
+
+/// Parse a single YAML document into an Eon [`Value`].
+///
+/// See the [module-level docs](self) for how YAML maps to Eon.
+pub fn from_yaml_str(yaml: &str) -> Result<Value> {
+    let mut yaml: serde_yaml::Value =
+        serde_yaml::from_str(yaml).map_err(|err| Error::custom(format!("Invalid YAML: {err}")))?;
+    yaml.apply_merge()
+        .map_err(|err| Error::custom(format!("Invalid YAML merge key: {err}")))?;
+    Ok(from_yaml(yaml))
+}
+
+fn from_yaml(yaml: serde_yaml::Value) -> Value {
+    match yaml {
+        serde_yaml::Value::Null => Value::Null,
+        serde_yaml::Value::Bool(b) => Value::Bool(b),
+        serde_yaml::Value::Number(number) => Value::Number(number_from_yaml(&number)),
+        serde_yaml::Value::String(string) => Value::String(string),
+        serde_yaml::Value::Sequence(sequence) => {
+            Value::List(sequence.into_iter().map(from_yaml).collect())
+        }
+        serde_yaml::Value::Mapping(mapping) => Value::Map(map_from_yaml(mapping)),
+        serde_yaml::Value::Tagged(tagged) => {
+            let serde_yaml::value::TaggedValue { tag, value } = *tagged;
+            let tag = tag.to_string();
+            let name = tag.strip_prefix('!').unwrap_or(&tag).to_owned();
+            let values = match value {
+                serde_yaml::Value::Null => vec![],
+                serde_yaml::Value::Sequence(sequence) => {
+                    sequence.into_iter().map(from_yaml).collect()
+                }
+                value => vec![from_yaml(value)],
+            };
+            Value::new_variant(name, values)
+        }
+    }
+}
+
+fn map_from_yaml(mapping: serde_yaml::Mapping) -> Map {
+    mapping
+        .into_iter()
+        .map(|(key, value)| (from_yaml(key), from_yaml(value)))
+        .collect()
+}
+
+fn number_from_yaml(number: &serde_yaml::Number) -> Number {
+    if let Some(n) = number.as_u64() {
+        n.into()
+    } else if let Some(n) = number.as_i64() {
+        n.into()
+    } else {
+        number.as_f64().unwrap_or(f64::NAN).into()
+    }
+}
+
+#[test]
+fn test_yaml_to_eon() {
+    let yaml = r#"
+name: Example
+nulls: [null, Null, ~, ]
+empty:
+bools: [true, false, yes, no]
+ints: [42, 0x2A, 0o52, -1]
+octal_ish: 0755
+zip: 01234
+floats: [3.5, 1e3, .inf, -.inf]
+quoted: "123"
+defaults: &defaults
+  host: localhost
+  port: 80
+server:
+  <<: *defaults
+  port: 8080
+copy: *defaults
+colors:
+  - !Black
+  - !Gray 128
+  - !Rgb [255, 0, 0]
+  - !Point {x: 1, y: 2}
+forced: !!str 123
+1: one
+"#;
+
+    let eon = from_yaml_str(yaml).unwrap();
+    let expected: Value = r#"
+        name: "Example"
+        nulls: [null, null, null]
+        empty: null
+        bools: [true, false, "yes", "no"]
+        ints: [42, 42, 42, -1]
+        octal_ish: "0755"
+        zip: "01234"
+        floats: [3.5, 1000.0, +inf, -inf]
+        quoted: "123"
+        defaults: { host: "localhost", port: 80 }
+        server: { port: 8080, host: "localhost" }
+        copy: { host: "localhost", port: 80 }
+        colors: ["Black", "Gray"(128), "Rgb"(255, 0, 0), "Point"({ x: 1, y: 2 })]
+        forced: "123"
+        1: "one"
+    "#
+    .parse()
+    .unwrap();
+    assert_eq!(eon, expected);
+}
+
+#[test]
+fn test_yaml_errors() {
+    assert!(from_yaml_str("a: [1, 2").is_err());
+    assert!(
+        from_yaml_str("a: 1\n---\nb: 2\n").is_err(),
+        "Multiple documents"
+    );
+    assert!(
+        from_yaml_str("a:\n  <<: 42\n").is_err(),
+        "Invalid merge key"
+    );
+}
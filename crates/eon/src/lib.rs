@@ -31,6 +31,23 @@
 //!
 //! Serialize any value that implements `serde::Serialize` into Eon using [`to_string`]
 //!
+//! For very large documents, use a [`StreamDeserializer`], which does not build a syntax tree first.
+//!
+//! Use [`update_document`] to save a value into an existing (possibly user-edited) Eon file,
+//! keeping its comments and any keys unknown to your type.
+//!
+//! Use [`to_string_with_comments`] to add explanatory comments to the output,
+//! e.g. when writing a default config file.
+//!
+//! Wrap a field in [`Spanned`] to also get where in the source it was defined,
+//! e.g. for pointing at it in your own error messages.
+//!
+//! ## Usage without `serde`
+//! Implement [`ToEon`] and [`FromEon`] to convert directly to and from a [`Value`].
+//! With the `derive` feature you can `#[derive(ToEon, FromEon)]`,
+//! which supports `#[eon(rename = "…")]`, `#[eon(default)]`, and `#[eon(skip)]`,
+//! and turns doc-comments on fields into comments in the output of [`ToEon::to_eon_string`].
+//!
 //! ## Usage with [`Value`]
 //! You can also treat an Eon document as a dynamically types [`Value`].
 //!
@@ -38,20 +55,41 @@
 //!
 //! Serialize a [`Value`] into an Eon string using [`Value::format`].
 //!
+//! Look up nested values with [`Value::get_path`], e.g. `value.get_path("server.ports[2].name")`.
+//!
 //! You can also convert anything that implements `serde::Serialize` into a [`Value`] using [`to_value`],
+//! or using a reusable [`Serializer`].
 //!
 //! ## Reading/writing comments
 //! An Eon document can contain comments, which are NOT part of the [`Value`] type.
-//! To load and serialize comments, use the low-level [`eon_syntax`] crate instead.
+//! To load, edit, and write the comments together with the values, use a [`CommentedValue`].
+//! For full control over the layout of the document, use the low-level [`eon_syntax`] crate instead.
+//!
+//! To generate a document with comments, e.g. a default config file, use a [`DocBuilder`].
 //!
 //! ## Formatting Eon files
 //! Use [`reformat`] to format an Eon file.
 //! You can also use the [`eonfmt`](http://crates.io/crates/eonfmt) CLI tool.
 //!
+//! ## Converting to and from other formats
+//! See the [`interop`] module, e.g. `interop::json` (behind the `json` feature),
+//! `interop::toml` (behind the `toml` feature), and `interop::yaml` (behind the `yaml` feature).
+//!
+//! ## Thread safety
+//! [`Value`], [`Map`], [`Error`], and the other types of this crate are all `Send + Sync`,
+//! so a parsed config can be shared between threads, e.g. in an `Arc`.
+//!
 //! ## Feature flags
 #![cfg_attr(feature = "document-features", doc = document_features::document_features!())]
 #![warn(missing_docs)] // let's keep eon well-documented
 
+mod commented_value;
+mod comments;
+mod doc_builder;
+mod include;
+pub mod interop;
+mod suggestions;
+mod to_from_eon;
 mod token_tree_from_value;
 mod value;
 mod value_from_token_tree;
@@ -60,10 +98,52 @@ mod value_from_token_tree;
 mod serde;
 
 pub use {
-    crate::value::{Map, Number, Value, Variant},
-    eon_syntax::{Error, FormatOptions, Result, reformat},
+    crate::commented_value::{CommentedData, CommentedValue},
+    crate::comments::{CommentMap, EonDocumented},
+    crate::doc_builder::DocBuilder,
+    crate::to_from_eon::{FromEon, ToEon},
+    crate::value::{
+        Entry, Map, MergeStrategy, Number, OccupiedEntry, Timestamp, VacantEntry, Value, Variant,
+        parse_scalar,
+    },
+    eon_syntax::{
+        ControlCharacterPolicy, Diagnostic, DuplicateKeyPolicy, EmptyDocumentPolicy, Error,
+        ErrorKind, FormatOptions, HashComments, KeyPath, Layout, MultilineSeparator,
+        NumberLiterals, ParseOptions, PathSegment, RenderOptions, Result, Severity, SortKeys, Span,
+        TopLevel, TrailingSeparator, reformat,
+    },
 };
 
+// Parsed configs and errors can be shared freely between threads:
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Error>();
+    assert_send_sync::<Map>();
+    assert_send_sync::<Number>();
+    assert_send_sync::<Timestamp>();
+    assert_send_sync::<Value>();
+    assert_send_sync::<Variant>();
+    #[cfg(feature = "serde")]
+    assert_send_sync::<SerializationError>();
+    #[cfg(feature = "serde")]
+    assert_send_sync::<DeserializationError>();
+};
+
+#[cfg(feature = "telemetry")]
+pub use eon_syntax::telemetry;
+
+#[cfg(feature = "derive")]
+pub use eon_derive::{FromEon, ToEon};
+
+#[doc(hidden)]
+pub use crate::to_from_eon::__private;
+
+#[cfg(all(feature = "serde", feature = "decimal"))]
+pub use self::serde::decimal;
+
+#[cfg(feature = "serde")]
+pub use self::serde::timestamp;
+
 /// External crates used by `eon`.
 pub mod external {
     pub use eon_syntax;
@@ -71,4 +151,9 @@ pub mod external {
 }
 
 #[cfg(feature = "serde")]
-pub use self::serde::{SerializationError, from_str, to_string, to_value};
+pub use self::serde::{
+    DeserializationError, FieldCase, Loss, LossReport, SerializationError, Serializer, Spanned,
+    StreamDeserializer, VariantPayloadStyle, from_str, from_str_strict, from_str_with_options,
+    from_str_with_warnings, generate_template, roundtrip_check, to_string, to_string_with_comments,
+    to_value, update_document,
+};
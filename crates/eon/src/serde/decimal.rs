@@ -0,0 +1,84 @@
+//! Serialize and deserialize a [`rust_decimal::Decimal`] exactly, as an Eon number.
+//!
+//! Use it with `#[serde(with = "eon::decimal")]`:
+//!
+//! ```rust
+//! use rust_decimal::Decimal;
+//!
+//! #[derive(serde::Deserialize, serde::Serialize)]
+//! struct Invoice {
+//!     #[serde(with = "eon::decimal")]
+//!     amount: Decimal,
+//! }
+//!
+//! let invoice: Invoice = eon::from_str("amount: 0.123456789012345678901").unwrap();
+//! assert_eq!(invoice.amount.to_string(), "0.123456789012345678901");
+//!
+//! let eon = eon::to_string(&invoice, &Default::default()).unwrap();
+//! assert_eq!(eon, "amount: 0.123456789012345678901\n");
+//! ```
+//!
+//! Without this, a `Decimal` would go through an `f64` and lose precision.
+//! With serializers other than Eon's, the decimal is written as a string.
+
+use serde::{Deserializer, Serializer, de};
+
+use rust_decimal::Decimal;
+
+// A `Decimal` is serialized as a newtype struct with this magic name around its string representation,
+// which the Eon serializer recognizes and turns into a number.
+pub(crate) const NAME: &str = "$__eon_private_Decimal";
+
+/// Serialize a [`Decimal`] as an Eon number.
+///
+/// ## Errors
+/// If the serializer fails.
+pub fn serialize<S: Serializer>(decimal: &Decimal, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_newtype_struct(NAME, &decimal.to_string())
+}
+
+/// Deserialize a [`Decimal`] from an Eon number, without going through an `f64`.
+///
+/// ## Errors
+/// If the value is not a number that fits in a [`Decimal`].
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Decimal, D::Error> {
+    // The Eon deserializer recognizes the name, and gives us the exact text of the number:
+    deserializer.deserialize_newtype_struct(NAME, DecimalVisitor)
+}
+
+struct DecimalVisitor;
+
+impl<'de> de::Visitor<'de> for DecimalVisitor {
+    type Value = Decimal;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        formatter.write_str("a decimal number")
+    }
+
+    // Other deserializers don't know the name, and give us the value as is:
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Decimal, D::Error> {
+        deserializer.deserialize_str(self)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Decimal, E> {
+        let v = v.replace('_', "");
+        Decimal::from_str_exact(&v)
+            .or_else(|_err| Decimal::from_scientific(&v))
+            .map_err(|err| E::custom(format!("Invalid decimal {v:?}: {err}")))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Decimal, E> {
+        Ok(Decimal::from(v))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Decimal, E> {
+        Ok(Decimal::from(v))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Decimal, E> {
+        self.visit_str(ryu::Buffer::new().format(v))
+    }
+}
@@ -2,8 +2,15 @@ use std::fmt;
 
 use serde::de::{Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
 
+use super::serialize_value::decode_special_map;
 use crate::{Map, Number, Value};
 
+/// [`Value`] asks for a newtype struct with this name,
+/// so that our own deserializer knows to present timestamps and variants the way [`Value`] serializes them.
+///
+/// Other deserializers just call [`Visitor::visit_newtype_struct`].
+pub(crate) const NAME: &str = "$__eon_private_Value";
+
 impl<'de> Deserialize<'de> for Value {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -34,6 +41,16 @@ impl<'de> Deserialize<'de> for Value {
                 Ok(Value::Number(Number::from(v)))
             }
 
+            #[inline]
+            fn visit_i128<E>(self, v: i128) -> Result<Self::Value, E> {
+                Ok(Value::Number(Number::from(v)))
+            }
+
+            #[inline]
+            fn visit_u128<E>(self, v: u128) -> Result<Self::Value, E> {
+                Ok(Value::Number(Number::from(v)))
+            }
+
             #[inline]
             fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E> {
                 Ok(Value::Number(Number::from(v)))
@@ -49,6 +66,16 @@ impl<'de> Deserialize<'de> for Value {
                 Ok(Value::String(v))
             }
 
+            #[inline]
+            fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E> {
+                Ok(Value::Bytes(v.to_vec()))
+            }
+
+            #[inline]
+            fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+                Ok(Value::Bytes(v))
+            }
+
             #[inline]
             fn visit_none<E>(self) -> Result<Value, E> {
                 Ok(Value::Null)
@@ -67,6 +94,14 @@ impl<'de> Deserialize<'de> for Value {
                 Ok(Value::Null)
             }
 
+            #[inline]
+            fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Value, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                deserializer.deserialize_any(self)
+            }
+
             #[inline]
             fn visit_seq<V>(self, mut access: V) -> Result<Value, V::Error>
             where
@@ -81,6 +116,7 @@ impl<'de> Deserialize<'de> for Value {
                 Ok(Value::List(list))
             }
 
+            // Timestamps and variants are written as special maps, see `serialize_value`.
             fn visit_map<V>(self, mut access: V) -> Result<Value, V::Error>
             where
                 V: MapAccess<'de>,
@@ -91,10 +127,10 @@ impl<'de> Deserialize<'de> for Value {
                     map.insert(key, value);
                 }
 
-                Ok(Value::Map(map))
+                Ok(decode_special_map(map))
             }
         }
 
-        deserializer.deserialize_any(ValueVisitor)
+        deserializer.deserialize_newtype_struct(NAME, ValueVisitor)
     }
 }
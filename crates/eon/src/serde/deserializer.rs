@@ -1,20 +1,29 @@
 // See https://serde.rs/impl-deserializer.html
 
-use std::str::FromStr as _;
+use std::{borrow::Cow, cell::RefCell, str::FromStr as _};
 
 use serde::{
     Deserializer as _,
     de::{self, Error as _, Visitor},
 };
 
-use crate::Number;
+use crate::{Number, Timestamp};
 
-use eon_syntax::{Span, TokenKeyValue, TokenTree, TokenValue, unescape_and_unquote};
+use super::{
+    FieldCase, deserialize_value, key_deserializer::IdentifierKeyDeserializer, serialize_value,
+    spanned,
+};
+
+use eon_syntax::{
+    ErrorKind, Span, TokenKeyValue, TokenTree, TokenValue, unescape_and_unquote,
+    unescape_and_unquote_cow, unquote_bytes,
+};
 
 #[derive(Debug, Clone)]
 pub struct DeserError {
     pub msg: String,
     pub span: Option<Span>,
+    pub kind: ErrorKind,
 }
 
 impl DeserError {
@@ -22,16 +31,18 @@ impl DeserError {
         Self {
             msg: msg.into(),
             span,
+            kind: ErrorKind::Other,
         }
     }
 
     pub fn into_error(self, eon_source: &str) -> crate::Error {
-        let Self { msg, span } = self;
-        if let Some(span) = span {
+        let Self { msg, span, kind } = self;
+        let error = if let Some(span) = span {
             crate::Error::new_at(eon_source, span, msg)
         } else {
             crate::Error::custom(msg)
-        }
+        };
+        error.with_kind(kind)
     }
 }
 
@@ -49,29 +60,114 @@ impl std::fmt::Display for DeserError {
 
 impl de::Error for DeserError {
     fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Self::new(None, msg.to_string())
+    }
+
+    fn unknown_variant(variant: &str, expected: &'static [&'static str]) -> Self {
         Self {
-            msg: msg.to_string(),
-            span: None,
+            kind: crate::suggestions::unknown_variant_kind(variant, expected),
+            ..Self::custom(crate::suggestions::unknown_name_message(
+                "variant", variant, expected,
+            ))
         }
     }
+
+    fn unknown_field(field: &str, expected: &'static [&'static str]) -> Self {
+        Self::custom(crate::suggestions::unknown_name_message(
+            "field", field, expected,
+        ))
+    }
 }
 
 type Result<T = (), E = DeserError> = std::result::Result<T, E>;
 
 // ----------------------------------------------------
 
+/// Map keys that did not match any field of the struct they are in, with their spans and messages.
+pub type UnknownFields = RefCell<Vec<(Span, String)>>;
+
 /// Consumes a [`TokenTree`] and "deserializes" it into a value that implements
 /// [`serde::de::Deserialize`] (e.g. has `#[derive(serde::Deserialize)]` on it).
 pub struct TokenTreeDeserializer<'de> {
     value: &'de TokenTree<'de>,
+
+    /// If we are deserializing a struct, these are its fields.
+    struct_fields: &'static [&'static str],
+
+    /// If set, record unknown struct fields here instead of silently ignoring them.
+    unknown_fields: Option<&'de UnknownFields>,
+
+    /// Are we deserializing a map key? Then numbers can be read as strings.
+    is_key: bool,
 }
 
 impl<'de> TokenTreeDeserializer<'de> {
     pub fn new(value: &'de TokenTree<'de>) -> Self {
-        Self { value }
+        Self::nested(value, None)
+    }
+
+    /// Deserialize a value inside of another one, sharing its [`UnknownFields`].
+    fn nested(value: &'de TokenTree<'de>, unknown_fields: Option<&'de UnknownFields>) -> Self {
+        Self {
+            value,
+            struct_fields: &[],
+            unknown_fields,
+            is_key: false,
+        }
+    }
+
+    /// Deserialize the key of a map, e.g. the `1` in `{ 1: "one" }`.
+    fn key(key: &'de TokenTree<'de>, unknown_fields: Option<&'de UnknownFields>) -> Self {
+        Self {
+            is_key: true,
+            ..Self::nested(key, unknown_fields)
+        }
+    }
+
+    /// Record all map keys that are not fields of the struct they are in.
+    pub fn with_unknown_fields(mut self, unknown_fields: &'de UnknownFields) -> Self {
+        self.unknown_fields = Some(unknown_fields);
+        self
+    }
+
+    /// Deserialize a struct with these fields.
+    ///
+    /// This lets us accept e.g. `maxWidth` or `max-width` for the field `max_width`.
+    fn with_struct_fields(mut self, struct_fields: &'static [&'static str]) -> Self {
+        self.struct_fields = struct_fields;
+        self
     }
 }
 
+/// Implements `deserialize_i8`, `deserialize_u8`, etc.
+///
+/// Floats without a fractional part, like `3.0`, are accepted too.
+macro_rules! deserialize_integer {
+    ($($method:ident => $visit:ident: $ty:ty),* $(,)?) => {$(
+        fn $method<V>(self, visitor: V) -> Result<V::Value>
+        where
+            V: Visitor<'de>,
+        {
+            let number = self.number()?;
+            let integer = number
+                .as_i128()
+                .and_then(|n| <$ty>::try_from(n).ok())
+                .or_else(|| number.as_u128().and_then(|n| <$ty>::try_from(n).ok()));
+            let Some(integer) = integer else {
+                return Err(if number.as_i128().is_none() && number.as_u128().is_none() {
+                    self.unexpected(concat!("an integer (", stringify!($ty), ")"))
+                } else {
+                    DeserError::new(
+                        self.value.span,
+                        format!(concat!("{} is out of range for ", stringify!($ty)), number),
+                    )
+                });
+            };
+            self.spanned(visitor.$visit(integer))
+        }
+    )*};
+}
+
 impl<'de> de::Deserializer<'de> for TokenTreeDeserializer<'de> {
     type Error = DeserError;
 
@@ -92,41 +188,61 @@ impl<'de> de::Deserializer<'de> for TokenTreeDeserializer<'de> {
                 }
             },
 
-            TokenValue::Number(num_str) => match Number::from_str(num_str) {
-                Ok(number) => {
-                    if let Some(n) = number.as_u64() {
-                        visitor.visit_u64(n)
-                    } else if let Some(n) = number.as_i64() {
-                        visitor.visit_i64(n)
-                    } else if let Some(n) = number.as_f64() {
-                        visitor.visit_f64(n)
-                    } else if let Some(n) = number.as_i128() {
-                        visitor.visit_i128(n)
-                    } else if let Some(n) = number.as_u128() {
-                        visitor.visit_u128(n)
-                    } else {
-                        Err(DeserError::new(span, format!("Invalid numbner: {number}")))
-                    }
-                }
+            TokenValue::Number(num_str) => visit_number(visitor, num_str),
+
+            // Timestamps are given to the visitor as strings,
+            // which is what e.g. `chrono` and `time` expect:
+            TokenValue::Timestamp(timestamp) => match Timestamp::from_str(timestamp) {
+                Ok(_) => visitor.visit_borrowed_str(timestamp),
                 Err(err) => Err(DeserError::new(span, err)),
             },
 
-            TokenValue::QuotedString(quoted) => unescape_and_unquote(quoted)
+            TokenValue::Bytes(literal) => match unquote_bytes(literal) {
+                Ok(bytes) => visitor.visit_byte_buf(bytes),
+                Err(err) => Err(DeserError::new(span, err)),
+            },
+
+            // Most strings have nothing to unescape, and can be borrowed from the source:
+            TokenValue::QuotedString(quoted) => unescape_and_unquote_cow(quoted)
                 .map_err(|err| {
                     DeserError::new(
                         span,
                         format!("Failed to unescape quoted string: {quoted:?}: {err}"),
                     )
                 })
-                .and_then(|unescaped| visitor.visit_string(unescaped)),
+                .and_then(|unescaped| visit_cow_str(visitor, unescaped)),
 
-            TokenValue::List(list) => visitor.visit_seq(ListAccessor(&list.values)),
+            TokenValue::List(list) => {
+                visitor.visit_seq(ListAccessor(&list.values, self.unknown_fields))
+            }
 
-            TokenValue::Map(map) => visitor.visit_map(MapAccessor {
-                kvs: &map.key_values,
-            }),
+            TokenValue::Map(map) => {
+                if let Some(include) = map.includes.first() {
+                    Err(DeserError::new(
+                        include.span,
+                        "@include is only supported when loading a file, e.g. with Value::from_file",
+                    ))
+                } else {
+                    visitor.visit_map(MapAccessor {
+                        kvs: &map.key_values,
+                        struct_fields: self.struct_fields,
+                        unknown_fields: self.unknown_fields,
+                    })
+                }
+            }
 
-            TokenValue::Variant(_) => Err(DeserError::new(span, "Did not expect a variant here")),
+            // This happens inside `#[serde(tag = …)]` and `#[serde(untagged)]` enums,
+            // which buffer their contents before knowing the types.
+            // Present the variant the way serde writes externally tagged enums, i.e. `{"Rgb": [255, 0, 0]}`,
+            // so that any enum can then be deserialized from it:
+            TokenValue::Variant(variant) => match unescape_and_unquote(&variant.quoted_name) {
+                Ok(name) => visitor.visit_map(VariantAsMapAccessor {
+                    name: Some(name),
+                    values: &variant.values,
+                    unknown_fields: self.unknown_fields,
+                }),
+                Err(err) => Err(DeserError::new(variant.name_span.or(span), err)),
+            },
         };
 
         if let Err(err) = &mut result {
@@ -149,6 +265,44 @@ impl<'de> de::Deserializer<'de> for TokenTreeDeserializer<'de> {
         visitor.visit_some(self)
     }
 
+    // `Vec<u8>` asks for a sequence, so we let it read the bytes one by one.
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        if let TokenValue::Bytes(literal) = &self.value.value {
+            let bytes =
+                unquote_bytes(literal).map_err(|err| DeserError::new(self.value.span, err))?;
+            visitor.visit_seq(de::value::SeqDeserializer::new(bytes.into_iter()))
+        } else {
+            self.deserialize_any(visitor)
+        }
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        if name == spanned::NAME && fields == spanned::FIELDS {
+            let Some(span) = self.value.span else {
+                return Err(DeserError::custom("Missing span for Spanned value"));
+            };
+            visitor.visit_map(SpannedAccessor {
+                span,
+                value: Some(self.value),
+                next_field: 0,
+                unknown_fields: self.unknown_fields,
+            })
+        } else {
+            self.with_struct_fields(fields).deserialize_any(visitor)
+        }
+    }
+
     fn deserialize_enum<V>(
         self,
         _enum_name: &'static str,
@@ -191,27 +345,322 @@ impl<'de> de::Deserializer<'de> for TokenTreeDeserializer<'de> {
         let name = variant_names.iter().find(|&&name| name == unquoted_name);
 
         let Some(name) = name else {
-            return Err(DeserError::new(
-                self.value.span,
-                format!("Expected one of: {variant_names:?}, got: {quoted_name}"),
-            ));
+            return Err(DeserError {
+                span: self.value.span,
+                ..de::Error::unknown_variant(&unquoted_name, variant_names)
+            });
         };
 
         visitor.visit_enum(EnumAccessor {
             name_span: self.value.span,
             name,
             values,
+            unknown_fields: self.unknown_fields,
         })
     }
 
-    serde::forward_to_deserialize_any! {
-        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
-        bytes byte_buf unit unit_struct newtype_struct seq tuple
-        tuple_struct map struct identifier ignored_any
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match &self.value.value {
+            TokenValue::Identifier(identifier) if identifier == "true" => {
+                self.spanned(visitor.visit_bool(true))
+            }
+            TokenValue::Identifier(identifier) if identifier == "false" => {
+                self.spanned(visitor.visit_bool(false))
+            }
+            _ => Err(self.unexpected("true or false")),
+        }
+    }
+
+    deserialize_integer! {
+        deserialize_i8 => visit_i8: i8,
+        deserialize_i16 => visit_i16: i16,
+        deserialize_i32 => visit_i32: i32,
+        deserialize_i64 => visit_i64: i64,
+        deserialize_i128 => visit_i128: i128,
+        deserialize_u8 => visit_u8: u8,
+        deserialize_u16 => visit_u16: u16,
+        deserialize_u32 => visit_u32: u32,
+        deserialize_u64 => visit_u64: u64,
+        deserialize_u128 => visit_u128: u128,
+    }
+
+    fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let number = self.float()?;
+        self.spanned(visitor.visit_f32(number as f32))
+    }
+
+    fn deserialize_f64<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let number = self.float()?;
+        self.spanned(visitor.visit_f64(number))
+    }
+
+    fn deserialize_char<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let string = self.string()?;
+        let mut chars = string.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => self.spanned(visitor.visit_char(c)),
+            _ => Err(self.unexpected("a single character")),
+        }
+    }
+
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let string = self.string()?;
+        self.spanned(visit_cow_str(visitor, string))
+    }
+
+    fn deserialize_string<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match &self.value.value {
+            TokenValue::Bytes(_) | TokenValue::QuotedString(_) | TokenValue::List(_) => {
+                self.deserialize_any(visitor)
+            }
+            _ => Err(self.unexpected("bytes")),
+        }
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match &self.value.value {
+            TokenValue::Identifier(identifier) if identifier == "null" => {
+                self.spanned(visitor.visit_unit())
+            }
+            _ => Err(self.unexpected("null")),
+        }
+    }
+
+    fn deserialize_unit_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        // A `Decimal` wants the exact text of the number:
+        #[cfg(feature = "decimal")]
+        if name == super::decimal::NAME {
+            if let TokenValue::Number(number) = &self.value.value {
+                return self.spanned(visitor.visit_borrowed_str(number));
+            }
+        }
+
+        if name != deserialize_value::NAME {
+            return visitor.visit_newtype_struct(self);
+        }
+
+        // Deserializing an `eon::Value`, which wants timestamps and variants as the special maps it serializes them as:
+        match &self.value.value {
+            TokenValue::Timestamp(timestamp) => visitor.visit_map(de::value::MapDeserializer::new(
+                std::iter::once((serialize_value::TIMESTAMP_KEY, &**timestamp)),
+            )),
+            TokenValue::Variant(variant) => match unescape_and_unquote(&variant.quoted_name) {
+                Ok(name) => visitor.visit_map(TaggedVariantAccessor {
+                    name: Some(name),
+                    values: Some(&variant.values),
+                    unknown_fields: self.unknown_fields,
+                }),
+                Err(err) => Err(DeserError::new(variant.name_span.or(self.value.span), err)),
+            },
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match &self.value.value {
+            TokenValue::Map(_) => self.deserialize_any(visitor),
+            _ => Err(self.unexpected("a map")),
+        }
+    }
+
+    fn deserialize_identifier<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        // Struct fields can be both names and indices (`0: …`), so let the visitor decide:
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+}
+
+impl<'de> TokenTreeDeserializer<'de> {
+    /// Give the error the span of this value, unless it already has one.
+    fn spanned<T>(&self, result: Result<T>) -> Result<T> {
+        result.map_err(|mut err| {
+            err.span = err.span.or(self.value.span);
+            err
+        })
+    }
+
+    /// An error saying we expected something else here.
+    fn unexpected(&self, expected: &str) -> DeserError {
+        let got = match &self.value.value {
+            TokenValue::Identifier(identifier) => identifier.to_string(),
+            TokenValue::Number(number) => number.to_string(),
+            TokenValue::Timestamp(timestamp) => format!("the timestamp {timestamp}"),
+            TokenValue::Bytes(_) => "bytes".to_owned(),
+            TokenValue::QuotedString(quoted) => format!("the string {quoted}"),
+            TokenValue::List(_) => "a list".to_owned(),
+            TokenValue::Map(_) => "a map".to_owned(),
+            TokenValue::Variant(variant) => format!("the variant {}(…)", variant.quoted_name),
+        };
+        let hint = match &self.value.value {
+            TokenValue::Identifier(identifier) => crate::suggestions::suggest_keyword(identifier)
+                .map(|suggestion| format!(". Did you mean: {suggestion}?"))
+                .unwrap_or_default(),
+            _ => String::new(),
+        };
+        DeserError::new(
+            self.value.span,
+            format!("Expected {expected}, got {got}{hint}"),
+        )
+    }
+
+    fn number(&self) -> Result<Number> {
+        match &self.value.value {
+            TokenValue::Number(num_str) => {
+                Number::from_str(num_str).map_err(|err| DeserError::new(self.value.span, err))
+            }
+            _ => Err(self.unexpected("a number")),
+        }
+    }
+
+    /// Any number as a float, rounding large integers to the closest float.
+    fn float(&self) -> Result<f64> {
+        let number = self.number()?;
+        Ok(number
+            .as_f64()
+            .or_else(|| number.as_i128().map(|n| n as f64))
+            .or_else(|| number.as_u128().map(|n| n as f64))
+            .unwrap_or(f64::NAN))
+    }
+
+    /// A quoted string, or anything else that can be read as a string:
+    /// identifiers (i.e. map keys), timestamps, and numeric map keys like `1: …`.
+    ///
+    /// The keywords `true`, `false`, and `null` are not strings.
+    fn string(&self) -> Result<Cow<'de, str>> {
+        let value: &'de TokenValue<'de> = &self.value.value;
+        match value {
+            TokenValue::Identifier(identifier)
+                if !matches!(identifier.as_ref(), "true" | "false" | "null") =>
+            {
+                Ok(Cow::Borrowed(identifier.as_ref()))
+            }
+            TokenValue::Number(number) if self.is_key => Ok(Cow::Borrowed(number.as_ref())),
+            TokenValue::Timestamp(timestamp) => match Timestamp::from_str(timestamp) {
+                Ok(_) => Ok(Cow::Borrowed(timestamp.as_ref())),
+                Err(err) => Err(DeserError::new(self.value.span, err)),
+            },
+            TokenValue::QuotedString(quoted) => unescape_and_unquote_cow(quoted).map_err(|err| {
+                DeserError::new(
+                    self.value.span,
+                    format!("Failed to unescape quoted string: {quoted:?}: {err}"),
+                )
+            }),
+            _ => Err(self.unexpected("a string")),
+        }
+    }
+}
+
+/// Give the string to the visitor without copying it, if it is borrowed from the source.
+pub(super) fn visit_cow_str<'de, V, E>(visitor: V, string: Cow<'de, str>) -> Result<V::Value, E>
+where
+    V: Visitor<'de>,
+    E: de::Error,
+{
+    match string {
+        Cow::Borrowed(string) => visitor.visit_borrowed_str(string),
+        Cow::Owned(string) => visitor.visit_string(string),
+    }
+}
+
+/// Parse a number, and give it to the visitor as the smallest fitting type.
+pub(super) fn visit_number<'de, V, E>(visitor: V, num_str: &str) -> Result<V::Value, E>
+where
+    V: Visitor<'de>,
+    E: de::Error,
+{
+    let number = Number::from_str(num_str).map_err(E::custom)?;
+    if let Some(n) = number.as_u64() {
+        visitor.visit_u64(n)
+    } else if let Some(n) = number.as_i64() {
+        visitor.visit_i64(n)
+    } else if let Some(n) = number.as_f64() {
+        visitor.visit_f64(n)
+    } else if let Some(n) = number.as_i128() {
+        visitor.visit_i128(n)
+    } else if let Some(n) = number.as_u128() {
+        visitor.visit_u128(n)
+    } else {
+        Err(E::custom(format!("Invalid numbner: {number}")))
     }
 }
 
-struct ListAccessor<'de>(&'de [TokenTree<'de>]);
+struct ListAccessor<'de>(&'de [TokenTree<'de>], Option<&'de UnknownFields>);
 
 impl<'de> de::SeqAccess<'de> for ListAccessor<'de> {
     type Error = DeserError;
@@ -226,7 +675,7 @@ impl<'de> de::SeqAccess<'de> for ListAccessor<'de> {
     {
         if let [first, rest @ ..] = self.0 {
             self.0 = rest;
-            seed.deserialize(TokenTreeDeserializer::new(first))
+            seed.deserialize(TokenTreeDeserializer::nested(first, self.1))
                 .map(Some)
         } else {
             Ok(None)
@@ -234,8 +683,141 @@ impl<'de> de::SeqAccess<'de> for ListAccessor<'de> {
     }
 }
 
+/// The values of a map with the keys `0, 1, 2, …` (in any order), sorted by key.
+fn values_by_index<'de>(kvs: &'de [TokenKeyValue<'de>]) -> Option<Vec<&'de TokenTree<'de>>> {
+    let mut values = vec![None; kvs.len()];
+    for kv in kvs {
+        let TokenValue::Number(index) = &kv.key.value else {
+            return None;
+        };
+        let index = index.parse::<usize>().ok()?;
+        let slot = values.get_mut(index)?;
+        if slot.is_some() {
+            return None;
+        }
+        *slot = Some(&kv.value);
+    }
+    values.into_iter().collect()
+}
+
+struct ListRefAccessor<'de>(
+    std::vec::IntoIter<&'de TokenTree<'de>>,
+    Option<&'de UnknownFields>,
+);
+
+impl<'de> de::SeqAccess<'de> for ListRefAccessor<'de> {
+    type Error = DeserError;
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.0.len())
+    }
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        self.0
+            .next()
+            .map(|value| seed.deserialize(TokenTreeDeserializer::nested(value, self.1)))
+            .transpose()
+    }
+}
+
+/// Presents a variant like `"Rgb"(255, 0, 0)` as the map `{"Rgb": [255, 0, 0]}`.
+///
+/// A single value is not wrapped in a list, and no values becomes `null`,
+/// just like how serde represents externally tagged newtype and unit variants.
+struct VariantAsMapAccessor<'de> {
+    /// Set until the name has been visited.
+    name: Option<String>,
+    values: &'de [TokenTree<'de>],
+    unknown_fields: Option<&'de UnknownFields>,
+}
+
+impl<'de> de::MapAccess<'de> for VariantAsMapAccessor<'de> {
+    type Error = DeserError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        match &self.name {
+            Some(name) => seed
+                .deserialize(de::value::StrDeserializer::new(name))
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        self.name = None;
+        match self.values {
+            [] => seed.deserialize(de::value::UnitDeserializer::new()),
+            [value] => seed.deserialize(TokenTreeDeserializer::nested(value, self.unknown_fields)),
+            values => seed.deserialize(de::value::SeqAccessDeserializer::new(ListAccessor(
+                values,
+                self.unknown_fields,
+            ))),
+        }
+    }
+}
+
+/// Presents a variant like `"Rgb"(255, 0, 0)` as `{"$variant": "Rgb", "$values": [255, 0, 0]}`,
+/// which is how an `eon::Value` serializes variants.
+struct TaggedVariantAccessor<'de> {
+    /// Set until the name has been visited.
+    name: Option<String>,
+
+    /// Set until the values have been visited.
+    values: Option<&'de [TokenTree<'de>]>,
+
+    unknown_fields: Option<&'de UnknownFields>,
+}
+
+impl<'de> de::MapAccess<'de> for TaggedVariantAccessor<'de> {
+    type Error = DeserError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        let key = if self.name.is_some() {
+            serialize_value::VARIANT_KEY
+        } else if self.values.is_some() {
+            serialize_value::VALUES_KEY
+        } else {
+            return Ok(None);
+        };
+        seed.deserialize(de::value::StrDeserializer::new(key))
+            .map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        if let Some(name) = self.name.take() {
+            seed.deserialize(de::value::StringDeserializer::new(name))
+        } else {
+            let values = self.values.take().unwrap_or_default();
+            seed.deserialize(de::value::SeqAccessDeserializer::new(ListAccessor(
+                values,
+                self.unknown_fields,
+            )))
+        }
+    }
+}
+
 struct MapAccessor<'de> {
     kvs: &'de [TokenKeyValue<'de>],
+
+    /// If this map is a struct, these are its fields.
+    struct_fields: &'static [&'static str],
+
+    unknown_fields: Option<&'de UnknownFields>,
 }
 
 impl<'de> de::MapAccess<'de> for MapAccessor<'de> {
@@ -249,12 +831,34 @@ impl<'de> de::MapAccess<'de> for MapAccessor<'de> {
     where
         K: de::DeserializeSeed<'de>,
     {
-        if let Some(kv) = self.kvs.first() {
-            seed.deserialize(TokenTreeDeserializer::new(&kv.key))
-                .map(Some)
-        } else {
-            Ok(None)
+        let Some(kv) = self.kvs.first() else {
+            return Ok(None);
+        };
+        if let Some(field) = struct_field_in_other_case(self.struct_fields, &kv.key.value) {
+            return seed
+                .deserialize(de::value::BorrowedStrDeserializer::new(field))
+                .map(Some);
+        }
+        if let (Some(unknown_fields), Some(span)) = (self.unknown_fields, kv.key.span) {
+            if let Some(message) = unknown_field_message(self.struct_fields, &kv.key.value) {
+                unknown_fields.borrow_mut().push((span, message));
+            }
+        }
+        // Keys like `null` are strings, unless the key type says otherwise.
+        // Other identifier keys stay below, so they can be `Spanned`.
+        if let TokenValue::Identifier(identifier) = &kv.key.value {
+            if matches!(identifier.as_ref(), "null" | "true" | "false") {
+                return seed
+                    .deserialize(IdentifierKeyDeserializer::new(identifier))
+                    .map_err(|mut err: DeserError| {
+                        err.span = err.span.or(kv.key.span);
+                        err
+                    })
+                    .map(Some);
+            }
         }
+        seed.deserialize(TokenTreeDeserializer::key(&kv.key, self.unknown_fields))
+            .map(Some)
     }
 
     fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
@@ -263,17 +867,100 @@ impl<'de> de::MapAccess<'de> for MapAccessor<'de> {
     {
         if let [first, rest @ ..] = self.kvs {
             self.kvs = rest;
-            seed.deserialize(TokenTreeDeserializer::new(&first.value))
+            seed.deserialize(TokenTreeDeserializer::nested(
+                &first.value,
+                self.unknown_fields,
+            ))
         } else {
             Err(DeserError::custom("No more values in map"))
         }
     }
 }
 
+/// If the key is not one of the struct fields, but matches one written in another [`FieldCase`],
+/// return that field.
+fn struct_field_in_other_case(
+    struct_fields: &'static [&'static str],
+    key: &TokenValue<'_>,
+) -> Option<&'static str> {
+    if struct_fields.is_empty() {
+        return None;
+    }
+    let key = key.as_key_str()?;
+    if struct_fields.contains(&key.as_ref()) {
+        return None;
+    }
+    struct_fields.iter().copied().find(|field| {
+        [FieldCase::CamelCase, FieldCase::KebabCase]
+            .iter()
+            .any(|case| case.apply(field) == key)
+    })
+}
+
+/// If the key is not one of the struct fields (in any [`FieldCase`]), explain why.
+fn unknown_field_message(
+    struct_fields: &'static [&'static str],
+    key: &TokenValue<'_>,
+) -> Option<String> {
+    if struct_fields.is_empty() {
+        return None; // Not a struct
+    }
+    let key = key.as_key_str()?;
+    if struct_fields.contains(&key.as_ref()) {
+        return None;
+    }
+    Some(crate::suggestions::unknown_name_message(
+        "field",
+        &key,
+        struct_fields,
+    ))
+}
+
+/// Feeds the fields of a [`crate::Spanned`] to its visitor.
+struct SpannedAccessor<'de> {
+    span: Span,
+    value: Option<&'de TokenTree<'de>>,
+    next_field: usize,
+    unknown_fields: Option<&'de UnknownFields>,
+}
+
+impl<'de> de::MapAccess<'de> for SpannedAccessor<'de> {
+    type Error = DeserError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        spanned::FIELDS
+            .get(self.next_field)
+            .map(|&field| seed.deserialize(de::value::BorrowedStrDeserializer::new(field)))
+            .transpose()
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let field = self.next_field;
+        self.next_field += 1;
+        match field {
+            0 => seed.deserialize(de::value::UsizeDeserializer::new(self.span.start)),
+            1 => seed.deserialize(de::value::UsizeDeserializer::new(self.span.end)),
+            _ => match self.value.take() {
+                Some(value) => {
+                    seed.deserialize(TokenTreeDeserializer::nested(value, self.unknown_fields))
+                }
+                None => Err(DeserError::custom("No more values in Spanned")),
+            },
+        }
+    }
+}
+
 struct EnumAccessor<'de> {
     name_span: Option<Span>,
     name: &'de str,
     values: &'de [TokenTree<'de>],
+    unknown_fields: Option<&'de UnknownFields>,
 }
 
 impl<'de> de::EnumAccess<'de> for EnumAccessor<'de> {
@@ -312,7 +999,10 @@ impl<'de> de::VariantAccess<'de> for EnumAccessor<'de> {
             ));
         }
 
-        seed.deserialize(TokenTreeDeserializer::new(&self.values[0]))
+        seed.deserialize(TokenTreeDeserializer::nested(
+            &self.values[0],
+            self.unknown_fields,
+        ))
     }
 
     // `enum Enum { TupleVariant(a, b, c) }`
@@ -322,11 +1012,21 @@ impl<'de> de::VariantAccess<'de> for EnumAccessor<'de> {
     {
         if len != self.values.len() {
             if self.values.len() == 1 {
-                if let TokenValue::List(list) = &self.values[0].value {
-                    if list.values.len() == len {
+                match &self.values[0].value {
+                    TokenValue::List(list) if list.values.len() == len => {
                         // Allow `"TupleVariant"([1, 2, 3])` to be interpreted as `"TupleVariant"(1, 2, 3)`
-                        return visitor.visit_seq(ListAccessor(&list.values));
+                        return visitor.visit_seq(ListAccessor(&list.values, self.unknown_fields));
+                    }
+                    TokenValue::Map(map) if map.key_values.len() == len => {
+                        // Allow `"TupleVariant"({0: 1, 1: 2, 2: 3})` to be interpreted as `"TupleVariant"(1, 2, 3)`
+                        if let Some(values) = values_by_index(&map.key_values) {
+                            return visitor.visit_seq(ListRefAccessor(
+                                values.into_iter(),
+                                self.unknown_fields,
+                            ));
+                        }
                     }
+                    _ => {}
                 }
             }
 
@@ -341,11 +1041,11 @@ impl<'de> de::VariantAccess<'de> for EnumAccessor<'de> {
             ));
         }
 
-        visitor.visit_seq(ListAccessor(self.values))
+        visitor.visit_seq(ListAccessor(self.values, self.unknown_fields))
     }
 
     // `enum Enum { StructVariant{ a: … } }`
-    fn struct_variant<V>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value>
+    fn struct_variant<V>(self, fields: &'static [&'static str], visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
@@ -359,7 +1059,9 @@ impl<'de> de::VariantAccess<'de> for EnumAccessor<'de> {
             ));
         }
 
-        TokenTreeDeserializer::new(&self.values[0]).deserialize_any(visitor)
+        TokenTreeDeserializer::nested(&self.values[0], self.unknown_fields)
+            .with_struct_fields(fields)
+            .deserialize_any(visitor)
     }
 }
 
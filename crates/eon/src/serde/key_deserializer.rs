@@ -0,0 +1,83 @@
+use std::marker::PhantomData;
+
+use serde::de::{self, Visitor};
+
+/// Deserializes an unquoted map key, like the `null` in `{ null: 1 }`.
+///
+/// Such keys are strings, even if they look like keywords, just like in a [`crate::Value`].
+/// Only types that can't be strings, like `bool` and `Option`, get to see the keyword.
+pub(crate) struct IdentifierKeyDeserializer<'de, E> {
+    identifier: &'de str,
+    marker: PhantomData<E>,
+}
+
+impl<'de, E> IdentifierKeyDeserializer<'de, E> {
+    pub fn new(identifier: &'de str) -> Self {
+        Self {
+            identifier,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, E: de::Error> de::Deserializer<'de> for IdentifierKeyDeserializer<'de, E> {
+    type Error = E;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_borrowed_str(self.identifier)
+    }
+
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        match self.identifier {
+            "true" => visitor.visit_bool(true),
+            "false" => visitor.visit_bool(false),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        if self.identifier == "null" {
+            visitor.visit_unit()
+        } else {
+            self.deserialize_any(visitor)
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        if self.identifier == "null" {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_enum(de::value::BorrowedStrDeserializer::new(self.identifier))
+    }
+
+    serde::forward_to_deserialize_any! {
+        i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit_struct newtype_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
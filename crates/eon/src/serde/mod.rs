@@ -1,14 +1,26 @@
+#[cfg(feature = "decimal")]
+pub mod decimal;
 mod deserialize_value;
 mod deserializer;
+mod key_deserializer;
+mod roundtrip;
+mod serialize_value;
 mod serializer;
+mod spanned;
+mod stream_deserializer;
+pub mod timestamp;
+mod update;
 
 use serde::Serialize;
 
-use crate::{FormatOptions, Value};
+use crate::{CommentMap, DuplicateKeyPolicy, EonDocumented, FormatOptions, ParseOptions, Value};
 
-use self::serializer::Serializer;
-
-pub use self::serializer::SerializationError;
+pub use self::{
+    roundtrip::{Loss, LossReport, roundtrip_check},
+    serializer::{FieldCase, SerializationError, Serializer, VariantPayloadStyle},
+    spanned::Spanned,
+    stream_deserializer::{DeserializationError, StreamDeserializer},
+};
 
 /// Serialize a value (using serde) into a [`Value`].
 ///
@@ -38,8 +50,7 @@ pub fn to_value<T>(value: &T) -> Result<Value, SerializationError>
 where
     T: ?Sized + Serialize,
 {
-    let serializer = Serializer::default();
-    value.serialize(&serializer)
+    Serializer::default().to_value(value)
 }
 
 /// Serialize a value (using serde) into an Eon string.
@@ -71,6 +82,127 @@ where
     to_value(value).map(|value| value.format(options))
 }
 
+/// Like [`to_string`], but adds comments above the values at the given paths.
+///
+/// Comments for paths that are not in the value are ignored.
+///
+/// ## Example
+/// ```rust
+/// #[derive(serde::Serialize)]
+/// struct Config {
+///     port: u16,
+/// }
+///
+/// let comments = eon::CommentMap::new()
+///     .with_comment("port".parse().unwrap(), "The port to listen on");
+/// let eon_string = eon::to_string_with_comments(
+///     &Config { port: 8080 },
+///     &eon::FormatOptions::default(),
+///     &comments,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(eon_string, "// The port to listen on\nport: 8080\n");
+/// ```
+pub fn to_string_with_comments<T>(
+    value: &T,
+    options: &FormatOptions,
+    comments: &CommentMap,
+) -> Result<String, SerializationError>
+where
+    T: Serialize,
+{
+    to_value(value).map(|value| value.format_with_comments(options, comments))
+}
+
+/// Generate a self-documenting starter config from the default value of `T`,
+/// with the comments from [`EonDocumented`] above each field.
+///
+/// This is what you want for e.g. a `myapp config init` command.
+///
+/// ## Example
+/// ```rust
+/// use eon::{CommentMap, EonDocumented, KeyPath};
+///
+/// #[derive(Default, serde::Serialize)]
+/// struct Config {
+///     port: u16,
+///     verbose: bool,
+/// }
+///
+/// impl EonDocumented for Config {
+///     fn document(prefix: &KeyPath, comments: &mut CommentMap) {
+///         comments.insert(prefix.clone().key("port"), "The port to listen on.\n0 means any free port.");
+///         comments.insert(prefix.clone().key("verbose"), "Log every request");
+///     }
+/// }
+///
+/// assert_eq!(
+///     eon::generate_template::<Config>().unwrap(),
+///     "// The port to listen on.\n// 0 means any free port.\nport: 0\n\n// Log every request\nverbose: false\n"
+/// );
+/// ```
+///
+/// ## Errors
+/// If the default value fails to serialize.
+pub fn generate_template<T>() -> Result<String, SerializationError>
+where
+    T: Default + EonDocumented + Serialize,
+{
+    to_string_with_comments(&T::default(), &FormatOptions::default(), &T::comments())
+}
+
+/// Update an existing Eon document with a value, preserving comments and keys unknown to `T`.
+///
+/// Only the values that differ are replaced, and new keys are added to the existing maps,
+/// so a user-edited config file keeps its comments and any keys that `T` does not know about.
+/// The document is then reformatted using the default [`FormatOptions`].
+///
+/// This is useful for e.g. GUI apps that save their settings into a user-editable config file.
+///
+/// ## Example
+/// ```rust
+/// #[derive(serde::Serialize)]
+/// struct Settings {
+///     volume: f32,
+///     theme: String,
+/// }
+///
+/// let existing = r#"
+/// // How loud things are
+/// volume: 0.5
+///
+/// // Added by the user, unknown to `Settings`
+/// debug: true
+/// "#;
+///
+/// let settings = Settings {
+///     volume: 0.8,
+///     theme: "dark".to_owned(),
+/// };
+///
+/// let updated = eon::update_document(existing, &settings).unwrap();
+/// assert_eq!(updated, r#"// How loud things are
+/// volume: 0.8
+/// theme: "dark"
+///
+/// // Added by the user, unknown to `Settings`
+/// debug: true
+/// "#);
+/// ```
+///
+/// ## Errors
+/// If the existing document is not valid Eon, or if `value` fails to serialize.
+pub fn update_document<T>(existing_source: &str, value: &T) -> Result<String, crate::Error>
+where
+    T: ?Sized + Serialize,
+{
+    let value = to_value(value).map_err(|err| crate::Error::custom(err.to_string()))?;
+    let mut tree = eon_syntax::TokenTree::parse_str(existing_source)?;
+    self::update::update_tree(existing_source, &mut tree, value);
+    Ok(tree.format(&FormatOptions::default()))
+}
+
 /// Parse an Eon value from a string into a type `T` that implements [`serde::de::DeserializeOwned`].
 ///
 /// ## Example
@@ -91,12 +223,132 @@ where
 /// assert_eq!(config.string, "Hello Eon!");
 /// assert_eq!(config.age, 42);
 /// ```
+///
+/// If a key appears more than once in a map, the first value is used
+/// ([`DuplicateKeyPolicy::FirstWins`]).
+/// Use [`from_str_with_options`] to make that an error instead.
 pub fn from_str<T>(eon_source: &str) -> Result<T, crate::Error>
 where
     T: serde::de::DeserializeOwned,
 {
-    eon_syntax::TokenTree::parse_str(eon_source).and_then(|token_tree| {
+    let options = ParseOptions::default().with_duplicate_keys(DuplicateKeyPolicy::FirstWins);
+    from_str_with_options(eon_source, &options)
+}
+
+/// Like [`from_str`], but using the given [`ParseOptions`].
+///
+/// ## Example
+/// ```rust
+/// use eon::{DuplicateKeyPolicy, ParseOptions};
+///
+/// let options = ParseOptions::new().with_duplicate_keys(DuplicateKeyPolicy::LastWins);
+/// let port: std::collections::BTreeMap<String, u16> =
+///     eon::from_str_with_options("port: 80\nport: 8080", &options).unwrap();
+/// assert_eq!(port["port"], 8080);
+/// ```
+pub fn from_str_with_options<T>(eon_source: &str, options: &ParseOptions) -> Result<T, crate::Error>
+where
+    T: serde::de::DeserializeOwned,
+{
+    // Comments can't end up in `T`, so don't bother collecting them:
+    let options = options.clone().with_keep_comments(false);
+    eon_syntax::TokenTree::parse_str_with(eon_source, &options).and_then(|token_tree| {
         let deser = self::deserializer::TokenTreeDeserializer::new(&token_tree);
         T::deserialize(deser).map_err(|err| err.into_error(eon_source))
     })
 }
+
+/// Like [`from_str_with_options`], but also returns warnings for any map keys
+/// that are not fields of the struct they are in, pointing at the keys.
+///
+/// Without this, unknown fields are silently ignored (unless the struct has `#[serde(deny_unknown_fields)]`).
+/// This catches typos in hand-edited config files.
+///
+/// Only structs are checked, so e.g. maps deserialized into a `BTreeMap`, or structs using
+/// `#[serde(flatten)]` or tagged enums, never produce warnings.
+///
+/// The parse warnings of [`eon_syntax::TokenTree::parse_str_with_warnings`] are included too.
+///
+/// ## Example
+/// ```rust
+/// #[derive(serde::Deserialize)]
+/// struct Config {
+///     port: u16,
+/// }
+///
+/// let (config, warnings) =
+///     eon::from_str_with_warnings::<Config>("port: 80\nhots: [\"a\"]", &Default::default()).unwrap();
+/// assert_eq!(config.port, 80);
+/// assert_eq!(warnings.len(), 1);
+/// assert_eq!(warnings[0].message(), "Unknown field `hots`, expected one of: `port`");
+/// ```
+pub fn from_str_with_warnings<T>(
+    eon_source: &str,
+    options: &ParseOptions,
+) -> Result<(T, Vec<crate::Error>), crate::Error>
+where
+    T: serde::de::DeserializeOwned,
+{
+    let (value, mut warnings, unknown_fields) = from_str_with_unknown_fields(eon_source, options)?;
+    warnings.extend(
+        unknown_fields
+            .into_inner()
+            .into_iter()
+            .map(|(span, message)| crate::Error::new_at(eon_source, span, message)),
+    );
+    Ok((value, warnings))
+}
+
+/// Returns the value, the parse warnings, and the unknown fields.
+fn from_str_with_unknown_fields<T>(
+    eon_source: &str,
+    options: &ParseOptions,
+) -> Result<(T, Vec<crate::Error>, self::deserializer::UnknownFields), crate::Error>
+where
+    T: serde::de::DeserializeOwned,
+{
+    let options = options.clone().with_keep_comments(false);
+    let (token_tree, warnings) =
+        eon_syntax::TokenTree::parse_str_with_warnings(eon_source, &options)?;
+    let unknown_fields = self::deserializer::UnknownFields::default();
+    let deser = self::deserializer::TokenTreeDeserializer::new(&token_tree)
+        .with_unknown_fields(&unknown_fields);
+    let value = T::deserialize(deser).map_err(|err| err.into_error(eon_source))?;
+    Ok((value, warnings, unknown_fields))
+}
+
+/// Like [`from_str`], but fails if there are any map keys that are not fields of the struct they are in.
+///
+/// Unlike `#[serde(deny_unknown_fields)]`, this reports all unknown fields at once, pointing at each of them.
+/// See [`from_str_with_warnings`] for details.
+///
+/// Duplicate keys are handled like in [`from_str`], and other parse warnings are ignored.
+///
+/// ## Example
+/// ```rust
+/// #[derive(Debug, serde::Deserialize)]
+/// struct Config {
+///     port: u16,
+/// }
+///
+/// let err = eon::from_str_strict::<Config>("port: 80\nhots: [\"a\"]").unwrap_err();
+/// assert_eq!(err.message(), "Unknown field `hots`, expected one of: `port`");
+/// ```
+pub fn from_str_strict<T>(eon_source: &str) -> Result<T, crate::Error>
+where
+    T: serde::de::DeserializeOwned,
+{
+    let options = ParseOptions::default().with_duplicate_keys(DuplicateKeyPolicy::FirstWins);
+    let (value, _parse_warnings, unknown_fields) =
+        from_str_with_unknown_fields(eon_source, &options)?;
+    let labels = unknown_fields.into_inner();
+    match labels.as_slice() {
+        [] => Ok(value),
+        [(span, message)] => Err(crate::Error::new_at(eon_source, *span, message.clone())),
+        _ => Err(crate::Error::new_at_labels(
+            eon_source,
+            format!("{} unknown fields", labels.len()),
+            labels,
+        )),
+    }
+}
@@ -0,0 +1,224 @@
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::{FormatOptions, KeyPath, Value};
+
+use super::Serializer;
+
+/// A single value that changed when round-tripping through Eon.
+///
+/// See [`roundtrip_check`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Loss {
+    /// Where in the value the change happened.
+    pub path: KeyPath,
+
+    /// The value before the round-trip, or `None` if it was added.
+    pub before: Option<Value>,
+
+    /// The value after the round-trip, or `None` if it was lost.
+    pub after: Option<Value>,
+}
+
+impl std::fmt::Display for Loss {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let path = if self.path.is_root() {
+            "<root>".to_owned()
+        } else {
+            self.path.to_string()
+        };
+        match (&self.before, &self.after) {
+            (Some(before), Some(after)) => {
+                let (before, after) = (inline(before), inline(after));
+                if before == after {
+                    // E.g. the sign of a NaN, which Eon does not preserve
+                    write!(f, "{path}: {before} changed its bit pattern")
+                } else {
+                    write!(f, "{path}: {before} -> {after}")
+                }
+            }
+            (Some(before), None) => write!(f, "{path}: {} was lost", inline(before)),
+            (None, Some(after)) => write!(f, "{path}: {} was added", inline(after)),
+            (None, None) => write!(f, "{path}: changed"),
+        }
+    }
+}
+
+/// Everything that did not survive a round-trip through Eon.
+///
+/// Returned by [`roundtrip_check`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LossReport {
+    /// The Eon that the value was serialized into.
+    pub eon: String,
+
+    /// Set if the value could not be serialized, or the Eon could not be deserialized.
+    pub error: Option<String>,
+
+    /// The values that changed, in depth-first order.
+    pub losses: Vec<Loss>,
+}
+
+impl LossReport {
+    /// Did the value survive the round-trip unchanged?
+    pub fn is_lossless(&self) -> bool {
+        self.error.is_none() && self.losses.is_empty()
+    }
+}
+
+impl std::error::Error for LossReport {}
+
+impl std::fmt::Display for LossReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(error) = &self.error {
+            return write!(f, "Round-trip failed: {error}");
+        }
+        write!(f, "Round-trip changed {} value(s):", self.losses.len())?;
+        for loss in &self.losses {
+            write!(f, "\n  {loss}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Check that a value survives being serialized to Eon and deserialized back.
+///
+/// The value is converted with [`crate::to_string`], parsed back with [`crate::from_str`],
+/// and both versions are compared as [`Value`]s.
+/// This catches e.g. `Some(None)` turning into `None`,
+/// or a NaN losing its sign.
+///
+/// ## Example
+/// ```rust
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// struct Config {
+///     retries: Option<Option<u32>>,
+/// }
+///
+/// assert!(eon::roundtrip_check(&Config { retries: Some(Some(3)) }).is_ok());
+///
+/// let report = eon::roundtrip_check(&Config { retries: Some(None) }).unwrap_err();
+/// assert_eq!(report.losses.len(), 1);
+/// assert_eq!(report.losses[0].path.to_string(), "retries");
+/// ```
+///
+/// ## Errors
+/// A [`LossReport`] if serialization or deserialization fails, or if anything changed.
+pub fn roundtrip_check<T>(value: &T) -> Result<(), LossReport>
+where
+    T: Serialize + DeserializeOwned,
+{
+    let mut report = LossReport::default();
+
+    // Compare with `Some` marked explicitly, so that we notice when an `Option` is flattened:
+    let marked = Serializer {
+        mark_some: true,
+        ..Default::default()
+    };
+
+    let (eon, before) = match super::to_string(value, &FormatOptions::default())
+        .and_then(|eon| Ok((eon, marked.to_value(value)?)))
+    {
+        Ok(ok) => ok,
+        Err(err) => {
+            report.error = Some(format!("Failed to serialize: {err}"));
+            return Err(report);
+        }
+    };
+    report.eon = eon;
+
+    let after = super::from_str::<T>(&report.eon)
+        .map_err(|err| format!("Failed to deserialize: {}", err.message()))
+        .and_then(|t| {
+            marked
+                .to_value(&t)
+                .map_err(|err| format!("Failed to serialize: {err}"))
+        });
+    match after {
+        Ok(after) => diff(KeyPath::root(), &before, &after, &mut report.losses),
+        Err(err) => report.error = Some(err),
+    }
+
+    if report.is_lossless() {
+        Ok(())
+    } else {
+        Err(report)
+    }
+}
+
+fn diff(path: KeyPath, before: &Value, after: &Value, losses: &mut Vec<Loss>) {
+    match (before, after) {
+        (Value::Map(a), Value::Map(b)) => {
+            for (key, a_value) in a {
+                let path = path.clone().key(key_string(key));
+                if let Some(b_value) = b.get(key) {
+                    diff(path, a_value, b_value, losses);
+                } else {
+                    losses.push(Loss {
+                        path,
+                        before: Some(a_value.clone()),
+                        after: None,
+                    });
+                }
+            }
+            for (key, b_value) in b {
+                if !a.contains_key(key) {
+                    losses.push(Loss {
+                        path: path.clone().key(key_string(key)),
+                        before: None,
+                        after: Some(b_value.clone()),
+                    });
+                }
+            }
+        }
+        (Value::List(a), Value::List(b)) if a.len() == b.len() => {
+            for (i, (a, b)) in a.iter().zip(b).enumerate() {
+                diff(path.clone().index(i), a, b, losses);
+            }
+        }
+        (Value::Variant(a), Value::Variant(b))
+            if a.name == b.name && a.values.len() == b.values.len() =>
+        {
+            for (i, (a, b)) in a.values.iter().zip(b.values.iter()).enumerate() {
+                diff(path.clone().index(i), a, b, losses);
+            }
+        }
+        (Value::Number(a), Value::Number(b)) => {
+            // `Number` considers all NaNs equal, but we want to report e.g. a lost sign:
+            let same = match (a.is_float() || b.is_float(), a.as_f64(), b.as_f64()) {
+                (true, Some(a), Some(b)) => a.to_bits() == b.to_bits(),
+                _ => a == b,
+            };
+            if !same {
+                losses.push(Loss {
+                    path,
+                    before: Some(before.clone()),
+                    after: Some(after.clone()),
+                });
+            }
+        }
+        _ => {
+            if before != after {
+                losses.push(Loss {
+                    path,
+                    before: Some(before.clone()),
+                    after: Some(after.clone()),
+                });
+            }
+        }
+    }
+}
+
+fn key_string(key: &Value) -> String {
+    if let Value::String(key) = key {
+        key.clone()
+    } else {
+        inline(key)
+    }
+}
+
+fn inline(value: &Value) -> String {
+    value
+        .format(&FormatOptions::default())
+        .trim()
+        .replace('\n', " ")
+}
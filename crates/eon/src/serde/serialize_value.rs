@@ -0,0 +1,134 @@
+//! Serializing a [`Value`] with serde, e.g. to cache it in a binary format like `CBOR` or `MessagePack`.
+//!
+//! Most values map directly to the serde data model.
+//! Serde has no notion of timestamps, and only supports variants with names known at compile time,
+//! so these are written as maps with special `$`-prefixed keys, like in `eon::interop::json`:
+//!
+//! | Eon                    | serde                                           |
+//! | ---------------------- | ----------------------------------------------- |
+//! | `2024-05-01T12:30:00Z` | `{ "$timestamp": "2024-05-01T12:30:00Z" }`      |
+//! | `"Rgb"(255, 0, 0)`     | `{ "$variant": "Rgb", "$values": [255, 0, 0] }` |
+//!
+//! When deserializing a [`Value`], only maps with exactly these keys (and valid contents)
+//! are decoded as timestamps and variants, so a [`Value`] always round-trips.
+
+use serde::ser::{Serialize, SerializeMap as _, SerializeSeq as _, Serializer};
+
+use crate::{Map, Number, Timestamp, Value};
+
+pub(super) const TIMESTAMP_KEY: &str = "$timestamp";
+pub(super) const VARIANT_KEY: &str = "$variant";
+pub(super) const VALUES_KEY: &str = "$values";
+
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Null => serializer.serialize_unit(),
+            Self::Bool(b) => serializer.serialize_bool(*b),
+            Self::Number(number) => serialize_number(number, serializer),
+            Self::Timestamp(timestamp) => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry(TIMESTAMP_KEY, timestamp.as_str())?;
+                map.end()
+            }
+            Self::String(string) => serializer.serialize_str(string),
+            Self::Bytes(bytes) => serializer.serialize_bytes(bytes),
+            Self::List(list) => {
+                let mut seq = serializer.serialize_seq(Some(list.len()))?;
+                for value in list {
+                    seq.serialize_element(value)?;
+                }
+                seq.end()
+            }
+            Self::Map(map) => {
+                let mut out = serializer.serialize_map(Some(map.len()))?;
+                for (key, value) in map {
+                    out.serialize_entry(key, value)?;
+                }
+                out.end()
+            }
+            Self::Variant(variant) => {
+                let mut map = serializer.serialize_map(Some(2))?;
+                map.serialize_entry(VARIANT_KEY, &variant.name)?;
+                map.serialize_entry(VALUES_KEY, variant.values.as_slice())?;
+                map.end()
+            }
+        }
+    }
+}
+
+/// Integers are written as integers, and everything else as an `f64`.
+fn serialize_number<S: Serializer>(number: &Number, serializer: S) -> Result<S::Ok, S::Error> {
+    if let Some(n) = number.as_i64() {
+        serializer.serialize_i64(n)
+    } else if let Some(n) = number.as_u64() {
+        serializer.serialize_u64(n)
+    } else if let Some(n) = number.as_i128() {
+        serializer.serialize_i128(n)
+    } else if let Some(n) = number.as_u128() {
+        serializer.serialize_u128(n)
+    } else {
+        serializer.serialize_f64(number.as_f64().unwrap_or(f64::NAN))
+    }
+}
+
+/// Decode the special `$`-maps written by [`Value::serialize`] back into timestamps and variants.
+///
+/// All other maps are returned as they are.
+pub(super) fn decode_special_map(map: Map) -> Value {
+    match map.len() {
+        1 => {
+            if let Some(timestamp) = map.get_str(TIMESTAMP_KEY) {
+                let timestamp = match timestamp {
+                    Value::Timestamp(timestamp) => Some(timestamp.clone()),
+                    Value::String(string) => string.parse::<Timestamp>().ok(),
+                    _ => None,
+                };
+                if let Some(timestamp) = timestamp {
+                    return Value::Timestamp(timestamp);
+                }
+            }
+        }
+        2 => {
+            if let (Some(Value::String(name)), Some(Value::List(values))) =
+                (map.get_str(VARIANT_KEY), map.get_str(VALUES_KEY))
+            {
+                if !values.is_empty() {
+                    return Value::new_variant(name.clone(), values.clone());
+                }
+            }
+        }
+        _ => {}
+    }
+    Value::Map(map)
+}
+
+#[test]
+fn test_decode_special_map() {
+    let decode = |source: &str| {
+        let Value::Map(map) = source.parse::<Value>().unwrap() else {
+            panic!("Expected a map: {source}");
+        };
+        decode_special_map(map)
+    };
+
+    assert_eq!(
+        decode(r#""$timestamp": "2024-05-01T12:30:00Z""#),
+        "2024-05-01T12:30:00Z".parse().unwrap()
+    );
+    assert_eq!(
+        decode(r#""$variant": "Rgb", "$values": [255, 0, 0]"#),
+        r#""Rgb"(255, 0, 0)"#.parse().unwrap()
+    );
+
+    // Anything else is a normal map:
+    for source in [
+        r#""$timestamp": "not a timestamp""#,
+        r#""$timestamp": "2024-05-01T12:30:00Z", extra: 1"#,
+        r#""$variant": "Rgb", "$values": []"#,
+        r#""$variant": "Rgb""#,
+        r#""$variant": 42, "$values": [1]"#,
+    ] {
+        assert!(matches!(decode(source), Value::Map(_)), "{source}");
+    }
+}
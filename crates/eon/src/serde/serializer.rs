@@ -5,7 +5,8 @@ use serde::{
     ser::{self, Error as _},
 };
 
-use crate::{Map, Value, serde::to_value, value::Variant};
+use super::serialize_value::decode_special_map;
+use crate::{FormatOptions, Map, Timestamp, Value, value::Variant};
 
 use vec1::vec1;
 
@@ -33,22 +34,230 @@ impl ser::Error for SerializationError {
 
 type Result<T = (), E = SerializationError> = std::result::Result<T, E>;
 
-#[derive(Default)]
-pub struct Serializer {}
+/// Serializes values (using serde) into [`Value`]s.
+///
+/// [`crate::to_value`] and [`crate::to_string`] use a default [`Serializer`].
+/// In hot loops you can instead create one [`Serializer`] and reuse it.
+///
+/// ## Example
+/// ```rust
+/// let serializer = eon::Serializer::new();
+/// for i in 0..3 {
+///     let value = serializer.to_value(&[i, i + 1]).unwrap();
+///     assert_eq!(value, eon::Value::List(vec![i.into(), (i + 1).into()]));
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Serializer {
+    /// How to write the payload of tuple variants.
+    pub variant_payload_style: VariantPayloadStyle,
+
+    /// How to write the names of struct fields.
+    pub field_case: FieldCase,
+
+    /// What [`serde::Serializer::is_human_readable`] returns.
+    ///
+    /// Types like `std::net::IpAddr` and `uuid::Uuid` are written as strings (like `"127.0.0.1"`)
+    /// if this is `true` (the default, like in serde),
+    /// and in a more compact form (like bytes or a list of numbers) if it is `false`.
+    pub human_readable: bool,
+
+    /// Write `Some(x)` as `"Some"(x)` instead of just `x`,
+    /// so that [`crate::roundtrip_check`] can tell `Some(None)` from `None`.
+    pub(crate) mark_some: bool,
+}
+
+/// How a [`Serializer`] writes the payload of tuple variants,
+/// e.g. `enum Color { Rgb(u8, u8, u8), … }`.
+///
+/// When deserializing, both styles are accepted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum VariantPayloadStyle {
+    /// `"Rgb"(255, 0, 0)`
+    #[default]
+    Positional,
+
+    /// `"Rgb"({0: 255, 1: 0, 2: 0})`, using the field indices as keys.
+    Named,
+}
+
+/// How a [`Serializer`] writes the names of struct fields,
+/// on top of any `#[serde(rename…)]` attributes.
+///
+/// When deserializing a struct with [`crate::from_str`], fields written in any of these cases are accepted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FieldCase {
+    /// Write field names as they are, e.g. `max_width`.
+    #[default]
+    Preserve,
+
+    /// `maxWidth`
+    CamelCase,
+
+    /// `"max-width"`
+    ///
+    /// Note that these keys are quoted, since `-` is not allowed in identifiers.
+    KebabCase,
+}
+
+impl FieldCase {
+    /// Convert a `snake_case` field name to this case.
+    ///
+    /// Leading underscores are kept as they are.
+    pub fn apply(self, field: &str) -> std::borrow::Cow<'_, str> {
+        let separator = match self {
+            Self::Preserve => return field.into(),
+            Self::CamelCase => None,
+            Self::KebabCase => Some('-'),
+        };
+
+        let prefix_len = field.len() - field.trim_start_matches('_').len();
+        let (prefix, rest) = field.split_at(prefix_len);
+        if !rest.contains('_') {
+            return field.into();
+        }
+
+        let mut out = prefix.to_owned();
+        let mut at_word_start = false;
+        for c in rest.chars() {
+            if c == '_' {
+                at_word_start = true;
+            } else if at_word_start {
+                if let Some(separator) = separator {
+                    out.push(separator);
+                    out.push(c);
+                } else {
+                    out.extend(c.to_uppercase());
+                }
+                at_word_start = false;
+            } else {
+                out.push(c);
+            }
+        }
+        if at_word_start {
+            out.push('_'); // Keep trailing underscores
+        }
+        out.into()
+    }
+}
+
+impl Default for Serializer {
+    fn default() -> Self {
+        Self {
+            variant_payload_style: VariantPayloadStyle::default(),
+            field_case: FieldCase::default(),
+            human_readable: true,
+            mark_some: false,
+        }
+    }
+}
+
+impl Serializer {
+    /// Create a new [`Serializer`] with the default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// See [`VariantPayloadStyle`].
+    ///
+    /// ## Example
+    /// ```rust
+    /// #[derive(serde::Serialize)]
+    /// enum Color {
+    ///     Rgb(u8, u8, u8),
+    /// }
+    ///
+    /// let serializer = eon::Serializer::new().with_variant_payload_style(eon::VariantPayloadStyle::Named);
+    /// let value = serializer.to_value(&Color::Rgb(255, 0, 0)).unwrap();
+    /// let expected: eon::Value = r#""Rgb"({0: 255, 1: 0, 2: 0})"#.parse().unwrap();
+    /// assert_eq!(value, expected);
+    /// ```
+    #[inline]
+    pub fn with_variant_payload_style(
+        mut self,
+        variant_payload_style: VariantPayloadStyle,
+    ) -> Self {
+        self.variant_payload_style = variant_payload_style;
+        self
+    }
+
+    /// See [`FieldCase`].
+    ///
+    /// ## Example
+    /// ```rust
+    /// #[derive(serde::Serialize, serde::Deserialize)]
+    /// struct Window {
+    ///     max_width: u32,
+    /// }
+    ///
+    /// let serializer = eon::Serializer::new().with_field_case(eon::FieldCase::CamelCase);
+    /// let string = serializer.to_string(&Window { max_width: 800 }, &Default::default()).unwrap();
+    /// assert_eq!(string, "maxWidth: 800\n");
+    ///
+    /// // Any case is accepted when deserializing:
+    /// let window: Window = eon::from_str(&string).unwrap();
+    /// assert_eq!(window.max_width, 800);
+    /// ```
+    #[inline]
+    pub fn with_field_case(mut self, field_case: FieldCase) -> Self {
+        self.field_case = field_case;
+        self
+    }
+
+    /// See [`Self::human_readable`].
+    ///
+    /// ## Example
+    /// ```rust
+    /// let address: std::net::IpAddr = "127.0.0.1".parse().unwrap();
+    ///
+    /// let value = eon::Serializer::new().to_value(&address).unwrap();
+    /// assert_eq!(value, eon::Value::from("127.0.0.1"));
+    ///
+    /// let value = eon::Serializer::new().with_human_readable(false).to_value(&address).unwrap();
+    /// let expected: eon::Value = r#""V4"([127, 0, 0, 1])"#.parse().unwrap();
+    /// assert_eq!(value, expected);
+    /// ```
+    #[inline]
+    pub fn with_human_readable(mut self, human_readable: bool) -> Self {
+        self.human_readable = human_readable;
+        self
+    }
+
+    /// Serialize a value into a [`Value`].
+    pub fn to_value<T>(&self, value: &T) -> Result<Value>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
 
-impl ser::Serializer for &'_ Serializer {
+    /// Serialize a value into an Eon string.
+    pub fn to_string<T>(&self, value: &T, options: &FormatOptions) -> Result<String>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.to_value(value).map(|value| value.format(options))
+    }
+}
+
+impl<'a> ser::Serializer for &'a Serializer {
     // What we produce as output.
     type Ok = Value;
 
     type Error = SerializationError;
 
-    type SerializeSeq = ListSerializer;
-    type SerializeTuple = ListSerializer;
-    type SerializeTupleStruct = ListSerializer;
-    type SerializeTupleVariant = TupleVariantSerializer;
-    type SerializeMap = MapSerializer;
-    type SerializeStruct = MapSerializer;
-    type SerializeStructVariant = StructVariantSerializer;
+    type SerializeSeq = ListSerializer<'a>;
+    type SerializeTuple = ListSerializer<'a>;
+    type SerializeTupleStruct = ListSerializer<'a>;
+    type SerializeTupleVariant = TupleVariantSerializer<'a>;
+    type SerializeMap = MapSerializer<'a>;
+    type SerializeStruct = MapSerializer<'a>;
+    type SerializeStructVariant = StructVariantSerializer<'a>;
+
+    #[inline]
+    fn is_human_readable(&self) -> bool {
+        self.human_readable
+    }
 
     #[inline]
     fn serialize_bool(self, v: bool) -> Result<Value> {
@@ -144,7 +353,14 @@ impl ser::Serializer for &'_ Serializer {
     where
         T: ?Sized + Serialize,
     {
-        value.serialize(self)
+        if self.mark_some {
+            Ok(Value::new_variant(
+                "Some".to_owned(),
+                vec![value.serialize(self)?],
+            ))
+        } else {
+            value.serialize(self)
+        }
     }
 
     // ()
@@ -171,11 +387,35 @@ impl ser::Serializer for &'_ Serializer {
 
     // Treat newtype structs as insignificant wrappers around the data they contain.
     #[inline]
-    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<Value>
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<Value>
     where
         T: ?Sized + Serialize,
     {
-        value.serialize(self)
+        let value = value.serialize(self)?;
+
+        if name == super::timestamp::NAME {
+            return match value {
+                Value::String(timestamp) => timestamp
+                    .parse::<Timestamp>()
+                    .map(Value::Timestamp)
+                    .map_err(SerializationError::custom),
+                Value::Timestamp(_) => Ok(value),
+                _ => Err(SerializationError::custom(
+                    "Expected an RFC 3339 string for a timestamp",
+                )),
+            };
+        }
+
+        #[cfg(feature = "decimal")]
+        if name == super::decimal::NAME {
+            if let Value::String(decimal) = &value {
+                return decimal
+                    .parse()
+                    .map(Value::Number)
+                    .map_err(SerializationError::custom);
+            }
+        }
+        Ok(value)
     }
 
     // `enum Enum { VariantName(Value), … }`
@@ -199,13 +439,13 @@ impl ser::Serializer for &'_ Serializer {
     /// Serialize a list
     #[inline]
     fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq> {
-        Ok(ListSerializer::with_capacity(len.unwrap_or(0)))
+        Ok(ListSerializer::with_capacity(self, len.unwrap_or(0)))
     }
 
     /// Serialize tuples as lists, so (a b c) is the same as [a b c].
     #[inline]
     fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple> {
-        Ok(ListSerializer::with_capacity(len))
+        Ok(ListSerializer::with_capacity(self, len))
     }
 
     /// Named tuples, e.g. `struct Rgb(u8, u8, u8)`.
@@ -215,7 +455,7 @@ impl ser::Serializer for &'_ Serializer {
         _name: &'static str,
         len: usize,
     ) -> Result<Self::SerializeTupleStruct> {
-        Ok(ListSerializer::with_capacity(len))
+        Ok(ListSerializer::with_capacity(self, len))
     }
 
     /// Enum variant that are tuples, e.g. `enum Color { Rgb(u8, u8, u8), … }`.
@@ -227,12 +467,16 @@ impl ser::Serializer for &'_ Serializer {
         variant_name: &'static str,
         len: usize,
     ) -> Result<Self::SerializeTupleVariant> {
-        Ok(TupleVariantSerializer::with_capacity(variant_name, len))
+        Ok(TupleVariantSerializer::with_capacity(
+            self,
+            variant_name,
+            len,
+        ))
     }
 
     #[inline]
     fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap> {
-        Ok(MapSerializer::with_capacity(len.unwrap_or(0)))
+        Ok(MapSerializer::with_capacity(self, len.unwrap_or(0)))
     }
 
     #[inline]
@@ -241,7 +485,7 @@ impl ser::Serializer for &'_ Serializer {
         _struct_name: &'static str,
         len: usize,
     ) -> Result<Self::SerializeStruct> {
-        Ok(MapSerializer::with_capacity(len))
+        Ok(MapSerializer::with_capacity(self, len))
     }
 
     /// ```ignore
@@ -261,26 +505,32 @@ impl ser::Serializer for &'_ Serializer {
         variant_name: &'static str,
         len: usize,
     ) -> Result<Self::SerializeStructVariant> {
-        Ok(StructVariantSerializer::with_capacity(variant_name, len))
+        Ok(StructVariantSerializer::with_capacity(
+            self,
+            variant_name,
+            len,
+        ))
     }
 }
 
 // -----------------------------------------------------------------------------------------------
 
 /// [a, b, c]
-pub struct ListSerializer {
+pub struct ListSerializer<'a> {
+    serializer: &'a Serializer,
     list: Vec<Value>,
 }
 
-impl ListSerializer {
-    fn with_capacity(capacity: usize) -> Self {
+impl<'a> ListSerializer<'a> {
+    fn with_capacity(serializer: &'a Serializer, capacity: usize) -> Self {
         Self {
+            serializer,
             list: Vec::with_capacity(capacity),
         }
     }
 }
 
-impl ser::SerializeSeq for ListSerializer {
+impl ser::SerializeSeq for ListSerializer<'_> {
     type Ok = Value;
     type Error = SerializationError;
 
@@ -290,7 +540,7 @@ impl ser::SerializeSeq for ListSerializer {
     where
         T: ?Sized + Serialize,
     {
-        self.list.push(to_value(value)?);
+        self.list.push(value.serialize(self.serializer)?);
         Ok(())
     }
 
@@ -302,7 +552,7 @@ impl ser::SerializeSeq for ListSerializer {
 }
 
 /// (a, b, c)
-impl ser::SerializeTuple for ListSerializer {
+impl ser::SerializeTuple for ListSerializer<'_> {
     type Ok = Value;
     type Error = SerializationError;
 
@@ -311,7 +561,7 @@ impl ser::SerializeTuple for ListSerializer {
     where
         T: ?Sized + Serialize,
     {
-        self.list.push(to_value(value)?);
+        self.list.push(value.serialize(self.serializer)?);
         Ok(())
     }
 
@@ -322,7 +572,7 @@ impl ser::SerializeTuple for ListSerializer {
     }
 }
 // Named tuples, e.g. `struct Rgb(u8, u8, u8)`.
-impl ser::SerializeTupleStruct for ListSerializer {
+impl ser::SerializeTupleStruct for ListSerializer<'_> {
     type Ok = Value;
     type Error = SerializationError;
 
@@ -331,7 +581,7 @@ impl ser::SerializeTupleStruct for ListSerializer {
     where
         T: ?Sized + Serialize,
     {
-        self.list.push(to_value(value)?);
+        self.list.push(value.serialize(self.serializer)?);
         Ok(())
     }
 
@@ -344,21 +594,27 @@ impl ser::SerializeTupleStruct for ListSerializer {
 // -----------------------------------------------------------------------------------------------
 
 /// Enum variant that are tuples, e.g. `enum Color { Rgb(u8, u8, u8), … }`.
-pub struct TupleVariantSerializer {
+pub struct TupleVariantSerializer<'a> {
+    serializer: &'a Serializer,
     variant_name: &'static str,
     values: Vec<Value>,
 }
 
-impl TupleVariantSerializer {
-    fn with_capacity(variant_name: &'static str, capacity: usize) -> Self {
+impl<'a> TupleVariantSerializer<'a> {
+    fn with_capacity(
+        serializer: &'a Serializer,
+        variant_name: &'static str,
+        capacity: usize,
+    ) -> Self {
         Self {
+            serializer,
             variant_name,
             values: Vec::with_capacity(capacity),
         }
     }
 }
 
-impl ser::SerializeTupleVariant for TupleVariantSerializer {
+impl ser::SerializeTupleVariant for TupleVariantSerializer<'_> {
     type Ok = Value;
     type Error = SerializationError;
 
@@ -367,16 +623,27 @@ impl ser::SerializeTupleVariant for TupleVariantSerializer {
     where
         T: ?Sized + Serialize,
     {
-        self.values.push(to_value(value)?);
+        self.values.push(value.serialize(self.serializer)?);
         Ok(())
     }
 
     #[inline]
     fn end(self) -> Result<Value> {
         let Self {
+            serializer,
             variant_name,
             values,
         } = self;
+        let values = match serializer.variant_payload_style {
+            VariantPayloadStyle::Positional => values,
+            VariantPayloadStyle::Named => {
+                let map = (0_u64..)
+                    .zip(values)
+                    .map(|(index, value)| (Value::from(index), value))
+                    .collect();
+                vec![Value::Map(map)]
+            }
+        };
         Ok(Value::new_variant(variant_name.to_owned(), values))
     }
 }
@@ -384,21 +651,23 @@ impl ser::SerializeTupleVariant for TupleVariantSerializer {
 // -----------------------------------------------------------------------------------------------
 
 /// Used for maps, structs, and enum variants that are structs.
-pub struct MapSerializer {
+pub struct MapSerializer<'a> {
+    serializer: &'a Serializer,
     map: Map,
     last_key: Option<Value>,
 }
 
-impl MapSerializer {
-    fn with_capacity(capacity: usize) -> Self {
+impl<'a> MapSerializer<'a> {
+    fn with_capacity(serializer: &'a Serializer, capacity: usize) -> Self {
         Self {
+            serializer,
             map: Map::with_capacity(capacity),
             last_key: None,
         }
     }
 }
 
-impl ser::SerializeMap for MapSerializer {
+impl ser::SerializeMap for MapSerializer<'_> {
     type Ok = Value;
     type Error = SerializationError;
 
@@ -413,7 +682,7 @@ impl ser::SerializeMap for MapSerializer {
             ));
         }
 
-        self.last_key = Some(to_value(key)?);
+        self.last_key = Some(key.serialize(self.serializer)?);
 
         Ok(())
     }
@@ -427,7 +696,7 @@ impl ser::SerializeMap for MapSerializer {
         T: ?Sized + Serialize,
     {
         if let Some(key) = self.last_key.take() {
-            self.map.insert(key, to_value(value)?);
+            self.map.insert(key, value.serialize(self.serializer)?);
             Ok(())
         } else {
             Err(SerializationError::custom(
@@ -443,11 +712,12 @@ impl ser::SerializeMap for MapSerializer {
                 "serialize_value not called after serialize_key",
             ));
         }
-        Ok(Value::Map(self.map))
+        // E.g. a timestamp or variant from `impl Serialize for Value`:
+        Ok(decode_special_map(self.map))
     }
 }
 
-impl ser::SerializeStruct for MapSerializer {
+impl ser::SerializeStruct for MapSerializer<'_> {
     type Ok = Value;
     type Error = SerializationError;
 
@@ -456,7 +726,10 @@ impl ser::SerializeStruct for MapSerializer {
     where
         T: ?Sized + Serialize,
     {
-        self.map.insert(to_value(key)?, to_value(value)?);
+        self.map.insert(
+            Value::String(self.serializer.field_case.apply(key).into_owned()),
+            value.serialize(self.serializer)?,
+        );
         Ok(())
     }
 
@@ -469,14 +742,16 @@ impl ser::SerializeStruct for MapSerializer {
 // -----------------------------------------------------------------------------------------------
 
 /// For enum variants containing a struct, e.g. `enum EnumName { VariantName { key: Value, … }, … }`.
-pub struct StructVariantSerializer {
+pub struct StructVariantSerializer<'a> {
+    serializer: &'a Serializer,
     name: &'static str,
     map: Map,
 }
 
-impl StructVariantSerializer {
-    fn with_capacity(name: &'static str, capacity: usize) -> Self {
+impl<'a> StructVariantSerializer<'a> {
+    fn with_capacity(serializer: &'a Serializer, name: &'static str, capacity: usize) -> Self {
         Self {
+            serializer,
             name,
             map: Map::with_capacity(capacity),
         }
@@ -492,7 +767,7 @@ impl StructVariantSerializer {
 ///     …
 /// }
 /// ```
-impl ser::SerializeStructVariant for StructVariantSerializer {
+impl ser::SerializeStructVariant for StructVariantSerializer<'_> {
     type Ok = Value;
     type Error = SerializationError;
 
@@ -501,7 +776,10 @@ impl ser::SerializeStructVariant for StructVariantSerializer {
     where
         T: ?Sized + Serialize,
     {
-        self.map.insert(to_value(key)?, to_value(value)?);
+        self.map.insert(
+            Value::String(self.serializer.field_case.apply(key).into_owned()),
+            value.serialize(self.serializer)?,
+        );
         Ok(())
     }
 
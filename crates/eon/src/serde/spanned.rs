@@ -0,0 +1,134 @@
+use serde::de;
+
+use eon_syntax::Span;
+
+// `Spanned` is deserialized as a struct with these magic names,
+// which the Eon deserializer recognizes and answers with the span of the value.
+pub(crate) const NAME: &str = "$__eon_private_Spanned";
+pub(crate) const START: &str = "$__eon_private_start";
+pub(crate) const END: &str = "$__eon_private_end";
+pub(crate) const VALUE: &str = "$__eon_private_value";
+pub(crate) const FIELDS: &[&str] = &[START, END, VALUE];
+
+/// A deserialized value together with where in the Eon source it was defined.
+///
+/// Use it as the type of a field to be able to point at that field in your own error messages.
+///
+/// This only works when deserializing with the Eon deserializer, e.g. with [`crate::from_str`].
+/// Serializing a [`Spanned`] serializes just the value.
+///
+/// Comparisons and hashing only consider the value, not the span.
+///
+/// ## Example
+/// ```rust
+/// #[derive(serde::Deserialize)]
+/// struct Config {
+///     port: eon::Spanned<u16>,
+/// }
+///
+/// let eon_source = "port: 8080";
+/// let config: Config = eon::from_str(eon_source).unwrap();
+///
+/// assert_eq!(*config.port.get_ref(), 8080);
+/// assert_eq!(&eon_source[config.port.span().start..config.port.span().end], "8080");
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Spanned<T> {
+    span: Span,
+    value: T,
+}
+
+impl<T> Spanned<T> {
+    /// Wrap a value together with its span.
+    pub fn new(span: Span, value: T) -> Self {
+        Self { span, value }
+    }
+
+    /// The byte range of the value in the Eon source.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// The wrapped value.
+    pub fn get_ref(&self) -> &T {
+        &self.value
+    }
+
+    /// The wrapped value.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+
+    /// Unwrap the value, discarding the span.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T: PartialEq> PartialEq for Spanned<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: Eq> Eq for Spanned<T> {}
+
+impl<T: PartialOrd> PartialOrd for Spanned<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+impl<T: Ord> Ord for Spanned<T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.value.cmp(&other.value)
+    }
+}
+
+impl<T: std::hash::Hash> std::hash::Hash for Spanned<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+    }
+}
+
+impl<T: serde::Serialize> serde::Serialize for Spanned<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.value.serialize(serializer)
+    }
+}
+
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for Spanned<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct SpannedVisitor<T>(std::marker::PhantomData<T>);
+
+        impl<'de, T: serde::Deserialize<'de>> de::Visitor<'de> for SpannedVisitor<T> {
+            type Value = Spanned<T>;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str("a spanned value (only supported by the Eon deserializer)")
+            }
+
+            fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<Spanned<T>, A::Error> {
+                expect_key(&mut map, START)?;
+                let start = map.next_value()?;
+                expect_key(&mut map, END)?;
+                let end = map.next_value()?;
+                expect_key(&mut map, VALUE)?;
+                let value = map.next_value()?;
+
+                Ok(Spanned::new(Span { start, end }, value))
+            }
+        }
+
+        deserializer.deserialize_struct(NAME, FIELDS, SpannedVisitor(std::marker::PhantomData))
+    }
+}
+
+fn expect_key<'de, A: de::MapAccess<'de>>(map: &mut A, name: &str) -> Result<(), A::Error> {
+    match map.next_key::<&str>()? {
+        Some(key) if key == name => Ok(()),
+        _ => Err(de::Error::custom(
+            "Spanned can only be deserialized by the Eon deserializer",
+        )),
+    }
+}
@@ -0,0 +1,867 @@
+//! A deserializer that reads the tokens directly, without building a [`eon_syntax::TokenTree`].
+
+use std::{borrow::Cow, str::FromStr as _};
+
+use serde::de::{self, Visitor};
+
+use eon_syntax::{
+    ParseOptions, PlacedToken, Span, TokenKind, Tokens, unescape_and_unquote_cow, unquote_bytes,
+};
+
+use crate::{Error, Timestamp};
+
+use super::{
+    deserializer::{visit_cow_str, visit_number},
+    key_deserializer::IdentifierKeyDeserializer,
+    spanned,
+};
+
+/// An error from a [`StreamDeserializer`].
+///
+/// Converts into an [`Error`] pointing into the Eon source.
+pub struct DeserializationError(Error);
+
+impl From<DeserializationError> for Error {
+    fn from(err: DeserializationError) -> Self {
+        err.0
+    }
+}
+
+impl std::error::Error for DeserializationError {}
+
+impl std::fmt::Debug for DeserializationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::fmt::Display for DeserializationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl de::Error for DeserializationError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Self(Error::custom(msg.to_string()))
+    }
+
+    fn unknown_variant(variant: &str, expected: &'static [&'static str]) -> Self {
+        Self(crate::suggestions::unknown_variant_error(variant, expected))
+    }
+
+    fn unknown_field(field: &str, expected: &'static [&'static str]) -> Self {
+        Self::custom(crate::suggestions::unknown_name_message(
+            "field", field, expected,
+        ))
+    }
+}
+
+type Result<T = (), E = DeserializationError> = std::result::Result<T, E>;
+
+/// Deserializes Eon straight from its tokens, without first building a [`eon_syntax::TokenTree`].
+///
+/// This uses much less memory than [`crate::from_str`] for large documents
+/// (only the source string is kept in memory),
+/// and can be combined with e.g. `serde_transcode` to convert Eon to other formats.
+///
+/// Compared to [`crate::from_str`], there are a few limitations:
+/// * Duplicate keys are not detected.
+/// * [`crate::Spanned`] is not supported.
+/// * A document with several top-level values (like `1, 2, 3`) must be wrapped in `[]`.
+///
+/// When deserializing into a self-describing type (like `serde_json::Value`),
+/// a variant like `"Rgb"(255, 0, 0)` becomes a map like `{"$variant": "Rgb", "$values": [255, 0, 0]}`,
+/// just like in `eon::interop::json`.
+///
+/// ## Example
+/// ```rust
+/// use serde::Deserialize as _;
+///
+/// let eon_source = "name: \"Eon\"\nports: [80, 8080]";
+/// let mut deserializer = eon::StreamDeserializer::new(eon_source);
+/// let map = std::collections::BTreeMap::<String, eon::Value>::deserialize(&mut deserializer).unwrap();
+/// deserializer.end().unwrap();
+///
+/// assert_eq!(map["ports"], eon::Value::from(vec![80.into(), 8080.into()]));
+/// ```
+pub struct StreamDeserializer<'de> {
+    source: &'de str,
+    tokens: std::iter::Peekable<Tokens<'de>>,
+
+    /// Span of the last consumed token.
+    last_span: Span,
+
+    /// Are we about to deserialize the top-level value?
+    at_root: bool,
+
+    depth: usize,
+
+    /// See [`Self::with_max_depth`].
+    max_depth: usize,
+
+    /// The first error we found, if any.
+    ///
+    /// When transcoding, our errors come back to us from the serializer without their location,
+    /// so we use this to avoid reporting the same error twice.
+    first_error: Option<(Span, String)>,
+
+    /// See [`Self::with_human_readable`].
+    human_readable: bool,
+}
+
+impl<'de> StreamDeserializer<'de> {
+    /// Deserialize the given Eon source.
+    pub fn new(eon_source: &'de str) -> Self {
+        Self {
+            source: eon_source,
+            tokens: eon_syntax::tokenize(eon_source).peekable(),
+            last_span: Span::default(),
+            at_root: true,
+            depth: 0,
+            max_depth: ParseOptions::DEFAULT_MAX_DEPTH,
+            first_error: None,
+            human_readable: true,
+        }
+    }
+
+    /// Set what [`serde::Deserializer::is_human_readable`] returns (`true` by default, like in serde).
+    ///
+    /// Set this to `false` to read documents written by a [`crate::Serializer`]
+    /// with [`crate::Serializer::human_readable`] set to `false`.
+    ///
+    /// ## Example
+    /// ```rust
+    /// use serde::Deserialize as _;
+    ///
+    /// let mut deserializer =
+    ///     eon::StreamDeserializer::new(r#""V4"([127, 0, 0, 1])"#).with_human_readable(false);
+    /// let address = std::net::IpAddr::deserialize(&mut deserializer).unwrap();
+    /// assert_eq!(address.to_string(), "127.0.0.1");
+    /// ```
+    pub fn with_human_readable(mut self, human_readable: bool) -> Self {
+        self.human_readable = human_readable;
+        self
+    }
+
+    /// Set the maximum depth of nested lists, maps, and variants,
+    /// to protect against stack overflow on deeply nested documents.
+    ///
+    /// The default is [`ParseOptions::DEFAULT_MAX_DEPTH`], same as for [`ParseOptions::max_depth`].
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Check that there is nothing left of the document.
+    ///
+    /// Call this after deserializing the value.
+    pub fn end(&mut self) -> Result<(), Error> {
+        match self.peek()? {
+            Some(token) => Err(Error::new_at(
+                self.source,
+                token.span,
+                "Expected end of file here",
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn error(&mut self, span: Span, message: impl Into<String>) -> DeserializationError {
+        let (span, message) = self
+            .first_error
+            .get_or_insert_with(|| (span, message.into()))
+            .clone();
+        DeserializationError(Error::new_at(self.source, span, message))
+    }
+
+    /// Point errors without a location at the given span.
+    fn with_span<T>(&mut self, result: Result<T>, span: Span) -> Result<T> {
+        result.map_err(|err| {
+            if err.0.span().is_some() {
+                err
+            } else {
+                let kind = err.0.kind().clone();
+                DeserializationError(self.error(span, err.0.message()).0.with_kind(kind))
+            }
+        })
+    }
+
+    /// The next token that isn't a comment.
+    fn peek(&mut self) -> Result<Option<PlacedToken<'de>>> {
+        loop {
+            match self.tokens.peek() {
+                None => return Ok(None),
+                Some(Ok(token)) if token.kind.is_comment() => {
+                    self.tokens.next();
+                }
+                Some(Ok(token)) => return Ok(Some(*token)),
+                Some(Err(err)) => {
+                    let (span, message) = (err.span(), err.message().to_owned());
+                    self.tokens.next();
+                    return Err(match span {
+                        Some(span) => self.error(span, message),
+                        None => DeserializationError(Error::custom(message)),
+                    });
+                }
+            }
+        }
+    }
+
+    fn next(&mut self) -> Result<Option<PlacedToken<'de>>> {
+        let token = self.peek()?;
+        if let Some(token) = token {
+            self.tokens.next();
+            self.last_span = token.span;
+        }
+        Ok(token)
+    }
+
+    fn peek_is(&mut self, kind: TokenKind) -> Result<bool> {
+        Ok(self.peek()?.is_some_and(|token| token.kind == kind))
+    }
+
+    /// Consume an optional comma.
+    fn skip_comma(&mut self) -> Result {
+        if self.peek_is(TokenKind::Comma)? {
+            self.next()?;
+        }
+        Ok(())
+    }
+
+    fn consume(&mut self, expected: TokenKind) -> Result {
+        match self.next()? {
+            Some(token) if token.kind == expected => Ok(()),
+            Some(token) => Err(self.error(
+                token.span,
+                format!("Expected {expected} but found {}", token.kind),
+            )),
+            None => Err(self.error(
+                self.last_span,
+                format!("Expected {expected} but reached end of input"),
+            )),
+        }
+    }
+
+    fn next_value_token(&mut self) -> Result<PlacedToken<'de>> {
+        if let Some(token) = self.next()? {
+            Ok(token)
+        } else {
+            let end = self.source.len();
+            Err(self.error(
+                Span { start: end, end },
+                "Unexpected end of input: expected a value",
+            ))
+        }
+    }
+
+    /// Is the document a bunch of `key: value` pairs without surrounding braces?
+    fn is_implicit_map(&self) -> bool {
+        let mut tokens = eon_syntax::tokenize(self.source)
+            .filter(|token| !matches!(token, Ok(token) if token.kind.is_comment()));
+        match (tokens.next(), tokens.next()) {
+            (None, _) => true, // An empty document is an empty map
+            (Some(Ok(first)), Some(Ok(second))) => {
+                second.kind == TokenKind::Colon
+                    && !matches!(first.kind, TokenKind::OpenBrace | TokenKind::OpenList)
+            }
+            _ => false,
+        }
+    }
+
+    /// Is the whole document just `null`?
+    fn is_lone_null(&self) -> bool {
+        let mut tokens = eon_syntax::tokenize(self.source)
+            .filter(|token| !matches!(token, Ok(token) if token.kind.is_comment()));
+        matches!(
+            (tokens.next(), tokens.next()),
+            (Some(Ok(token)), None) if token.kind == TokenKind::Identifier && token.slice == "null"
+        )
+    }
+
+    fn enter(&mut self, span: Span) -> Result {
+        self.depth += 1;
+        if self.max_depth < self.depth {
+            Err(self.error(
+                span,
+                "Maximum recursion depth exceeded while parsing document",
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Visit a list, map, or variant payload, and consume its closing bracket.
+    fn visit_nested<V>(
+        &mut self,
+        open: PlacedToken<'de>,
+        close: TokenKind,
+        visit: impl FnOnce(&mut Self) -> Result<V>,
+    ) -> Result<V> {
+        self.enter(open.span)?;
+        let value = visit(self)?;
+        self.consume(close)?;
+        self.depth -= 1;
+        Ok(value)
+    }
+
+    /// Is the given token a string followed by `(`, i.e. the start of a variant?
+    fn is_variant(&mut self, token: &PlacedToken<'_>) -> Result<bool> {
+        Ok(is_string(token.kind) && self.peek_is(TokenKind::OpenParen)?)
+    }
+
+    /// Consume the next value without unescaping strings or parsing numbers,
+    /// only checking that the brackets are balanced.
+    ///
+    /// Does not recurse, so deeply nested values are fine too.
+    fn skip_value(&mut self) -> Result {
+        let mut closing_brackets = Vec::new();
+        loop {
+            let Some(token) = self.next()? else {
+                return Err(match closing_brackets.last() {
+                    Some(close) => self.error(
+                        self.last_span,
+                        format!("Expected {close} but reached end of input"),
+                    ),
+                    None => self.error(
+                        Span {
+                            start: self.source.len(),
+                            end: self.source.len(),
+                        },
+                        "Unexpected end of input: expected a value",
+                    ),
+                });
+            };
+
+            match token.kind {
+                TokenKind::OpenList => closing_brackets.push(TokenKind::CloseList),
+                TokenKind::OpenBrace => closing_brackets.push(TokenKind::CloseBrace),
+                kind if is_string(kind) && self.peek_is(TokenKind::OpenParen)? => {
+                    self.next()?; // Consume the `(`
+                    closing_brackets.push(TokenKind::CloseParen);
+                }
+                kind if is_closing(kind) => match closing_brackets.pop() {
+                    Some(close) if close == kind => {}
+                    Some(close) => {
+                        return Err(
+                            self.error(token.span, format!("Expected {close} but found {kind}"))
+                        );
+                    }
+                    None => return Err(self.error(token.span, "Unbalanced brackets")),
+                },
+                TokenKind::OpenParen => {
+                    return Err(self.error(token.span, "Parentheses must be proceeded by a string"));
+                }
+                TokenKind::Include => {
+                    return Err(self.error(
+                        token.span,
+                        "@include is only supported when loading a file, e.g. with Value::from_file",
+                    ));
+                }
+                TokenKind::Colon | TokenKind::Comma if closing_brackets.is_empty() => {
+                    return Err(self.error(
+                        token.span,
+                        "Expected a value, like a map, list, number, or string",
+                    ));
+                }
+                _ => {}
+            }
+
+            if closing_brackets.is_empty() {
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn is_string(kind: TokenKind) -> bool {
+    matches!(
+        kind,
+        TokenKind::DoubleQuotedString
+            | TokenKind::SingleQuotedString
+            | TokenKind::MultilineBasicString
+            | TokenKind::MultilineLiteralString
+    )
+}
+
+fn is_closing(kind: TokenKind) -> bool {
+    matches!(
+        kind,
+        TokenKind::CloseBrace | TokenKind::CloseList | TokenKind::CloseParen
+    )
+}
+
+impl<'de> de::Deserializer<'de> for &mut StreamDeserializer<'de> {
+    type Error = DeserializationError;
+
+    fn is_human_readable(&self) -> bool {
+        self.human_readable
+    }
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        if std::mem::take(&mut self.at_root) && self.is_implicit_map() {
+            let result = visitor.visit_map(MapAccessor {
+                de: &mut *self,
+                close: None,
+            });
+            return self.with_span(
+                result,
+                Span {
+                    start: 0,
+                    end: self.source.len(),
+                },
+            );
+        }
+
+        let token = self.next_value_token()?;
+        let span = token.span;
+
+        let result = match token.kind {
+            TokenKind::Identifier => match token.slice {
+                "null" => visitor.visit_unit(),
+                "true" => visitor.visit_bool(true),
+                "false" => visitor.visit_bool(false),
+                some_other_string => {
+                    // We get here in case of map keys
+                    visitor.visit_borrowed_str(some_other_string)
+                }
+            },
+
+            TokenKind::Number => visit_number(visitor, token.slice),
+
+            TokenKind::Timestamp => match Timestamp::from_str(token.slice) {
+                Ok(_) => visitor.visit_borrowed_str(token.slice),
+                Err(err) => Err(self.error(span, err)),
+            },
+
+            TokenKind::Bytes => match unquote_bytes(token.slice) {
+                Ok(bytes) => visitor.visit_byte_buf(bytes),
+                Err(err) => Err(self.error(span, err)),
+            },
+
+            kind if is_string(kind) => {
+                if self.is_variant(&token)? {
+                    self.next()?; // Consume the `(`
+                    let name = unescape(self, &token)?;
+                    self.visit_nested(token, TokenKind::CloseParen, |de| {
+                        visitor.visit_map(VariantAsMapAccessor {
+                            de,
+                            name: Some(name.into_owned()),
+                        })
+                    })
+                } else {
+                    let string = unescape(self, &token)?;
+                    visit_cow_str(visitor, string)
+                }
+            }
+
+            TokenKind::OpenList => self.visit_nested(token, TokenKind::CloseList, |de| {
+                visitor.visit_seq(ListAccessor {
+                    de,
+                    close: TokenKind::CloseList,
+                })
+            }),
+
+            TokenKind::OpenBrace => self.visit_nested(token, TokenKind::CloseBrace, |de| {
+                visitor.visit_map(MapAccessor {
+                    de,
+                    close: Some(TokenKind::CloseBrace),
+                })
+            }),
+
+            TokenKind::Include => Err(self.error(
+                span,
+                "@include is only supported when loading a file, e.g. with Value::from_file",
+            )),
+
+            TokenKind::CloseList => Err(self.error(span, "Unbalanced brackets")),
+            TokenKind::CloseBrace => Err(self.error(span, "Unbalanced braces")),
+            TokenKind::CloseParen => Err(self.error(span, "Unbalanced parentheses")),
+            TokenKind::OpenParen => {
+                Err(self.error(span, "Parentheses must be proceeded by a string"))
+            }
+            _ => Err(self.error(
+                span,
+                "Expected a value, like a map, list, number, or string",
+            )),
+        };
+
+        self.with_span(result, span | self.last_span)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        // At the root, `null: 1` is a map, so only a lone `null` is `None`.
+        if !self.at_root || self.is_lone_null() {
+            if let Some(token) = self.peek()? {
+                if token.kind == TokenKind::Identifier && token.slice == "null" {
+                    self.at_root = false;
+                    self.next()?;
+                    return visitor.visit_none();
+                }
+            }
+        }
+
+        visitor.visit_some(self)
+    }
+
+    // `Vec<u8>` asks for a sequence, so we let it read the bytes one by one.
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        if let Some(token) = self.peek()? {
+            if token.kind == TokenKind::Bytes {
+                self.next()?;
+                self.at_root = false;
+                let bytes =
+                    unquote_bytes(token.slice).map_err(|err| self.error(token.span, err))?;
+                let result = visitor.visit_seq(de::value::SeqDeserializer::new(bytes.into_iter()));
+                return self.with_span(result, token.span);
+            }
+        }
+
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        if name == spanned::NAME && fields == spanned::FIELDS {
+            let span = self.peek()?.map_or(self.last_span, |token| token.span);
+            Err(self.error(span, "Spanned is not supported by the StreamDeserializer"))
+        } else {
+            self.deserialize_any(visitor)
+        }
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _enum_name: &'static str,
+        variant_names: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.at_root = false;
+        let token = self.next_value_token()?;
+        let span = token.span;
+
+        if !is_string(token.kind) {
+            return Err(self.error(
+                span,
+                format!(
+                    "Expected a variant name here; one of: {variant_names:?}. Got: {}",
+                    token.kind
+                ),
+            ));
+        }
+
+        let name = unescape(self, &token)?;
+        let Some(name) = variant_names.iter().find(|&&variant| variant == name) else {
+            let unknown = de::Error::unknown_variant(&name, variant_names);
+            return self.with_span(Err(unknown), span);
+        };
+
+        let has_values = self.is_variant(&token)?;
+        let result = if has_values {
+            self.next()?; // Consume the `(`
+            self.visit_nested(token, TokenKind::CloseParen, |de| {
+                visitor.visit_enum(EnumAccessor {
+                    de,
+                    name,
+                    name_span: span,
+                    has_values,
+                })
+            })
+        } else {
+            visitor.visit_enum(EnumAccessor {
+                de: &mut *self,
+                name,
+                name_span: span,
+                has_values,
+            })
+        };
+        self.with_span(result, span | self.last_span)
+    }
+
+    // Used for unknown fields, so skip them as cheaply as we can.
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        if self.at_root {
+            // Ignoring the whole document is rare, and it may be a map without braces:
+            return self.deserialize_any(visitor);
+        }
+        self.skip_value()?;
+        visitor.visit_unit()
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct newtype_struct tuple
+        tuple_struct map identifier
+    }
+}
+
+fn unescape<'de>(
+    de: &mut StreamDeserializer<'de>,
+    token: &PlacedToken<'de>,
+) -> Result<Cow<'de, str>> {
+    unescape_and_unquote_cow(token.slice).map_err(|err| {
+        de.error(
+            token.span,
+            format!("Failed to unescape quoted string: {:?}: {err}", token.slice),
+        )
+    })
+}
+
+/// The elements of a list (or of a variant payload), up to the closing bracket.
+struct ListAccessor<'a, 'de> {
+    de: &'a mut StreamDeserializer<'de>,
+    close: TokenKind,
+}
+
+impl<'de> de::SeqAccess<'de> for ListAccessor<'_, 'de> {
+    type Error = DeserializationError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        match self.de.peek()? {
+            Some(token) if token.kind == self.close => Ok(None),
+            Some(_) => {
+                let value = seed.deserialize(&mut *self.de)?;
+                self.de.skip_comma()?;
+                Ok(Some(value))
+            }
+            None => Err(self.de.error(
+                self.de.last_span,
+                format!("Expected {} but reached end of input", self.close),
+            )),
+        }
+    }
+}
+
+/// The `key: value` pairs of a map, up to the closing brace,
+/// or to the end of the document for a top-level map without braces.
+struct MapAccessor<'a, 'de> {
+    de: &'a mut StreamDeserializer<'de>,
+    close: Option<TokenKind>,
+}
+
+impl<'de> de::MapAccess<'de> for MapAccessor<'_, 'de> {
+    type Error = DeserializationError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        match (self.de.peek()?, self.close) {
+            (Some(token), Some(close)) if token.kind == close => Ok(None),
+            (Some(token), None) if is_closing(token.kind) => {
+                Err(self.de.error(token.span, "Unbalanced brackets"))
+            }
+            (Some(token), _) if token.kind == TokenKind::Identifier => {
+                self.de.next()?;
+                let result = seed.deserialize(IdentifierKeyDeserializer::new(token.slice));
+                self.de.with_span(result, token.span).map(Some)
+            }
+            (Some(_), _) => seed.deserialize(&mut *self.de).map(Some),
+            (None, None) => Ok(None),
+            (None, Some(close)) => Err(self.de.error(
+                self.de.last_span,
+                format!("Expected {close} but reached end of input"),
+            )),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        self.de.consume(TokenKind::Colon)?;
+        let value = seed.deserialize(&mut *self.de)?;
+        self.de.skip_comma()?;
+        Ok(value)
+    }
+}
+
+const VARIANT_KEY: &str = "$variant";
+const VALUES_KEY: &str = "$values";
+
+/// Presents a variant like `"Rgb"(255, 0, 0)` as `{"$variant": "Rgb", "$values": [255, 0, 0]}`,
+/// after the opening `(` has been consumed.
+struct VariantAsMapAccessor<'a, 'de> {
+    de: &'a mut StreamDeserializer<'de>,
+
+    /// Set until the name has been visited.
+    name: Option<String>,
+}
+
+impl<'de> de::MapAccess<'de> for VariantAsMapAccessor<'_, 'de> {
+    type Error = DeserializationError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        let key = if self.name.is_some() {
+            VARIANT_KEY
+        } else if self.de.peek_is(TokenKind::CloseParen)? {
+            return Ok(None);
+        } else {
+            VALUES_KEY
+        };
+        seed.deserialize(de::value::BorrowedStrDeserializer::new(key))
+            .map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        if let Some(name) = self.name.take() {
+            seed.deserialize(de::value::StringDeserializer::new(name))
+        } else {
+            seed.deserialize(ValuesDeserializer { de: &mut *self.de })
+        }
+    }
+}
+
+/// The values of a variant, as a list, up to (but not including) the closing `)`.
+struct ValuesDeserializer<'a, 'de> {
+    de: &'a mut StreamDeserializer<'de>,
+}
+
+impl<'de> de::Deserializer<'de> for ValuesDeserializer<'_, 'de> {
+    type Error = DeserializationError;
+
+    fn is_human_readable(&self) -> bool {
+        self.de.human_readable
+    }
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_seq(ListAccessor {
+            de: self.de,
+            close: TokenKind::CloseParen,
+        })
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+struct EnumAccessor<'a, 'de> {
+    de: &'a mut StreamDeserializer<'de>,
+    name: &'static str,
+    name_span: Span,
+
+    /// Was the name followed by `(…)`?
+    has_values: bool,
+}
+
+impl<'de> de::EnumAccess<'de> for EnumAccessor<'_, 'de> {
+    type Error = DeserializationError;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant)>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let value = seed.deserialize(de::value::BorrowedStrDeserializer::new(self.name))?;
+        Ok((value, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for EnumAccessor<'_, 'de> {
+    type Error = DeserializationError;
+
+    // `enum Enum { UnitVariant }`
+    fn unit_variant(self) -> Result<()> {
+        if self.has_values && !self.de.peek_is(TokenKind::CloseParen)? {
+            Err(self.de.error(
+                self.name_span,
+                format!("Expected no values for enum variant `{}`", self.name),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    // `enum Enum { NewtypeVariant(a) }`
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        self.single_value(|de| seed.deserialize(de))
+    }
+
+    // `enum Enum { TupleVariant(a, b, c) }`
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        if !self.has_values {
+            return Err(self.de.error(
+                self.name_span,
+                format!("Expected {len} values for enum variant `{}`", self.name),
+            ));
+        }
+        visitor.visit_seq(ListAccessor {
+            de: self.de,
+            close: TokenKind::CloseParen,
+        })
+    }
+
+    // `enum Enum { StructVariant{ a: … } }`
+    fn struct_variant<V>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.single_value(|de| de::Deserializer::deserialize_any(de, visitor))
+    }
+}
+
+impl<'de> EnumAccessor<'_, 'de> {
+    /// Deserialize the one and only value in the parentheses.
+    fn single_value<T>(
+        self,
+        deserialize: impl FnOnce(&mut StreamDeserializer<'de>) -> Result<T>,
+    ) -> Result<T> {
+        let error = format!(
+            "Expected exactly one value for enum variant `{}`",
+            self.name
+        );
+        if !self.has_values || self.de.peek_is(TokenKind::CloseParen)? {
+            return Err(self.de.error(self.name_span, error));
+        }
+        let value = deserialize(&mut *self.de)?;
+        self.de.skip_comma()?;
+        if self.de.peek_is(TokenKind::CloseParen)? {
+            Ok(value)
+        } else {
+            Err(self.de.error(self.name_span, error))
+        }
+    }
+}
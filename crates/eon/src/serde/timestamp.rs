@@ -0,0 +1,74 @@
+//! Serialize a date-time type, like `chrono::DateTime`, as an unquoted Eon [`Timestamp`].
+//!
+//! Use it with `#[serde(with = "eon::timestamp")]`:
+//!
+//! ```rust
+//! #[derive(serde::Deserialize, serde::Serialize)]
+//! struct Event {
+//!     #[serde(with = "eon::timestamp")]
+//!     time: chrono::DateTime<chrono::Utc>,
+//!
+//!     /// Not a timestamp, even if it looks like one:
+//!     note: String,
+//! }
+//!
+//! let event: Event = eon::from_str("time: 2024-05-01T12:30:00Z\nnote: \"2024-05-01T12:30:00Z\"").unwrap();
+//!
+//! let eon = eon::to_string(&event, &Default::default()).unwrap();
+//! assert_eq!(eon, "time: 2024-05-01T12:30:00Z\nnote: \"2024-05-01T12:30:00Z\"\n");
+//! ```
+//!
+//! The type must serialize itself as an RFC 3339 string, which `chrono::DateTime` does,
+//! and so does `time::OffsetDateTime` with `time::serde::rfc3339`.
+//! Without this, the timestamp is written as a quoted string.
+//! Either way, it can be deserialized from both quoted and unquoted timestamps.
+//!
+//! [`Timestamp`] itself always serializes as a timestamp.
+//! With serializers other than Eon's, the timestamp is written as a string.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::Timestamp;
+
+// A timestamp is serialized as a newtype struct with this magic name around its string representation,
+// which the Eon serializer recognizes and turns into a [`Timestamp`].
+pub(crate) const NAME: &str = "$__eon_private_Timestamp";
+
+/// Serialize a value that serializes as an RFC 3339 string as an Eon [`Timestamp`].
+///
+/// ## Errors
+/// If the serializer fails, or if the value is not an RFC 3339 string.
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: ?Sized + Serialize,
+    S: Serializer,
+{
+    serializer.serialize_newtype_struct(NAME, value)
+}
+
+/// Deserialize the value from a quoted or unquoted timestamp.
+///
+/// Timestamps are deserialized as strings, so this is the same as `T::deserialize`.
+///
+/// ## Errors
+/// If `T` can't be deserialized from the string.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    T::deserialize(deserializer)
+}
+
+impl Serialize for Timestamp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(self.as_str(), serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Timestamp {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let string = String::deserialize(deserializer)?;
+        string.parse().map_err(serde::de::Error::custom)
+    }
+}
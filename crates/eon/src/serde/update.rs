@@ -0,0 +1,86 @@
+//! Writing a value back into an existing Eon document, preserving comments and unknown keys.
+
+use eon_syntax::{Placement, TokenKeyValue, TokenTree, TokenValue, unescape_and_unquote};
+
+use crate::{Value, value_from_token_tree::Converter};
+
+/// Update `tree` to contain `new`, keeping as much of the existing tree as possible.
+///
+/// * Unchanged values are left exactly as they were written.
+/// * Maps are updated key-by-key. Keys that are not in `new` are kept.
+/// * Lists and variants of the same length are updated value-by-value.
+/// * Anything else is replaced, keeping the comments around it.
+pub fn update_tree(source: &str, tree: &mut TokenTree<'_>, new: Value) {
+    if Converter::new(source)
+        .value(tree)
+        .is_ok_and(|old| old == new)
+    {
+        return;
+    }
+
+    match (&mut tree.value, new) {
+        (TokenValue::Map(map), Value::Map(new_map)) => {
+            let mut previous_key: Option<String> = None;
+            for (key, value) in new_map {
+                let existing = map
+                    .key_values
+                    .iter()
+                    .position(|kv| key_value(source, &kv.key).as_ref() == Some(&key));
+
+                if let Some(index) = existing {
+                    update_tree(source, &mut map.key_values[index].value, value);
+                } else {
+                    let placement = match &previous_key {
+                        Some(previous) => Placement::After(previous),
+                        None => Placement::Start,
+                    };
+                    let key_tree = if let Value::String(key) = &key {
+                        TokenTree::key(key)
+                    } else {
+                        TokenTree::from(key.clone())
+                    };
+                    map.insert(
+                        TokenKeyValue::new(key_tree, TokenTree::from(value)),
+                        placement,
+                    );
+                }
+
+                previous_key = if let Value::String(key) = key {
+                    Some(key)
+                } else {
+                    None
+                };
+            }
+        }
+
+        (TokenValue::List(list), Value::List(new_values))
+            if list.values.len() == new_values.len() =>
+        {
+            for (tree, value) in list.values.iter_mut().zip(new_values) {
+                update_tree(source, tree, value);
+            }
+        }
+
+        (TokenValue::Variant(variant), Value::Variant(new_variant))
+            if variant.values.len() == new_variant.values.len()
+                && unescape_and_unquote(&variant.quoted_name)
+                    .is_ok_and(|name| name == new_variant.name) =>
+        {
+            for (tree, value) in variant.values.iter_mut().zip(new_variant.values) {
+                update_tree(source, tree, value);
+            }
+        }
+
+        (_, new) => {
+            tree.value = TokenTree::from(new).value;
+        }
+    }
+}
+
+/// The value of a map key, if valid.
+fn key_value(source: &str, key: &TokenTree<'_>) -> Option<Value> {
+    match &key.value {
+        TokenValue::Identifier(key) => Some(Value::String(key.to_string())),
+        _ => Converter::new(source).value(key).ok(),
+    }
+}
@@ -0,0 +1,158 @@
+//! "Did you mean …?" hints for common mistakes, shared by the [`crate::Value`] and serde paths
+//! so that both give the same error messages.
+//!
+//! Misspelled names (of struct fields, enum variants, …) are matched against the expected names
+//! using the Damerau-Levenshtein edit distance.
+
+/// What the user probably meant by an identifier that is not a keyword, like `inf` or `None`.
+///
+/// Returns `None` if we have no idea, or if the identifier already is what we would suggest.
+pub(crate) fn suggest_keyword(identifier: &str) -> Option<&'static str> {
+    let suggestion = match identifier.to_lowercase().as_str() {
+        "inf" | "infinity" => "+inf or -inf",
+        "nan" => "+nan",
+        "false" => "false",
+        "true" => "true",
+        "nil" | "null" | "none" => "null",
+        _ => return None,
+    };
+    (suggestion != identifier).then_some(suggestion)
+}
+
+/// The error message for an identifier used as a value that is not `null`, `true`, or `false`.
+pub(crate) fn unknown_keyword_message(identifier: &str) -> String {
+    if let Some(suggestion) = suggest_keyword(identifier) {
+        format!("Unknown keyword {identifier:?}. Did you mean: {suggestion}?")
+    } else {
+        format!("Unknown keyword {identifier:?}. Expected 'null', 'true', or 'false'.")
+    }
+}
+
+/// The number of single-character insertions, deletions, substitutions,
+/// or swaps of adjacent characters to turn `a` into `b`.
+///
+/// This is the "optimal string alignment" variant of the Damerau-Levenshtein distance,
+/// which counts the common typo of swapping two letters (`Bleu`) as a single edit.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for j in 0..=b.len() {
+        d[0][j] = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if 1 < i && 1 < j && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+/// The candidate that `name` is most likely a misspelling of, if any is close enough.
+///
+/// Case is ignored, so `Port` suggests `port`.
+pub(crate) fn closest<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let name_lower = name.to_lowercase();
+    let max_distance = (name.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .filter(|&candidate| candidate != name)
+        .map(|candidate| {
+            (
+                edit_distance(&name_lower, &candidate.to_lowercase()),
+                candidate,
+            )
+        })
+        .filter(|&(distance, _)| distance <= max_distance)
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// E.g. "Unknown field `prot`, expected one of: `host`, `port`. Did you mean `port`?"
+pub(crate) fn unknown_name_message(what: &str, name: &str, expected: &[&str]) -> String {
+    if expected.is_empty() {
+        return format!("Unknown {what} `{name}`, there are none");
+    }
+    let list = expected
+        .iter()
+        .map(|name| format!("`{name}`"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut message = format!("Unknown {what} `{name}`, expected one of: {list}");
+    if let Some(suggestion) = closest(name, expected.iter().copied()) {
+        message += &format!(". Did you mean `{suggestion}`?");
+    }
+    message
+}
+
+/// The [`eon_syntax::ErrorKind`] of an unknown variant, with the closest expected name first.
+pub(crate) fn unknown_variant_kind(name: &str, expected: &[&str]) -> eon_syntax::ErrorKind {
+    let closest = closest(name, expected.iter().copied());
+    let expected = closest
+        .into_iter()
+        .chain(expected.iter().copied().filter(|&e| Some(e) != closest))
+        .map(str::to_owned)
+        .collect();
+    eon_syntax::ErrorKind::UnknownVariant {
+        found: name.to_owned(),
+        expected,
+    }
+}
+
+/// An error for an unknown variant, with [`eon_syntax::ErrorKind::UnknownVariant`].
+pub(crate) fn unknown_variant_error(name: &str, expected: &[&str]) -> crate::Error {
+    crate::Error::custom(unknown_name_message("variant", name, expected))
+        .with_kind(unknown_variant_kind(name, expected))
+}
+
+#[test]
+fn test_closest() {
+    assert_eq!(edit_distance("kitten", "sitting"), 3);
+    assert_eq!(edit_distance("", "abc"), 3);
+    assert_eq!(edit_distance("same", "same"), 0);
+    assert_eq!(edit_distance("Bleu", "Blue"), 1);
+
+    let fields = ["host", "port", "max_width"];
+    assert_eq!(closest("prot", fields), Some("port"));
+    assert_eq!(closest("hots", fields), Some("host"));
+    assert_eq!(closest("prtx", fields), None, "Too different");
+    assert_eq!(closest("pot", fields), Some("port"));
+    assert_eq!(closest("Port", fields), Some("port"));
+    assert_eq!(closest("max_widht", fields), Some("max_width"));
+    assert_eq!(closest("port", fields), None, "Exact match");
+    assert_eq!(closest("zzz", fields), None);
+
+    assert_eq!(
+        unknown_name_message("variant", "Bleu", &["Red", "Blue"]),
+        "Unknown variant `Bleu`, expected one of: `Red`, `Blue`. Did you mean `Blue`?"
+    );
+    assert_eq!(
+        unknown_variant_kind("Bleu", &["Red", "Green", "Blue"]),
+        eon_syntax::ErrorKind::UnknownVariant {
+            found: "Bleu".to_owned(),
+            expected: vec!["Blue".to_owned(), "Red".to_owned(), "Green".to_owned()],
+        }
+    );
+}
+
+#[test]
+fn test_suggest_keyword() {
+    assert_eq!(suggest_keyword("inf"), Some("+inf or -inf"));
+    assert_eq!(suggest_keyword("NaN"), Some("+nan"));
+    assert_eq!(suggest_keyword("None"), Some("null"));
+    assert_eq!(suggest_keyword("True"), Some("true"));
+    assert_eq!(suggest_keyword("true"), None);
+    assert_eq!(suggest_keyword("port"), None);
+}
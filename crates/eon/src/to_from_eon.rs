@@ -0,0 +1,325 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{CommentMap, Error, FormatOptions, KeyPath, Map, Result, Value};
+
+/// Convert a Rust value into an Eon [`Value`], without going through `serde`.
+///
+/// Usually implemented with `#[derive(ToEon)]` (behind the `derive` feature),
+/// which also turns the doc-comments of the fields into Eon comments:
+///
+/// ```
+/// # #[cfg(feature = "derive")] {
+/// use eon::ToEon;
+///
+/// #[derive(ToEon)]
+/// struct Config {
+///     /// The port to listen on
+///     port: u16,
+/// }
+///
+/// assert_eq!(
+///     Config { port: 8080 }.to_eon_string(),
+///     "// The port to listen on\nport: 8080\n"
+/// );
+/// # }
+/// ```
+pub trait ToEon {
+    /// Convert `self` into an Eon [`Value`].
+    fn to_eon(&self) -> Value;
+
+    /// Add a comment for each documented field of `Self` to `comments`, at paths starting with `prefix`.
+    ///
+    /// The default implementation adds nothing.
+    fn document(_prefix: &KeyPath, _comments: &mut CommentMap) {}
+
+    /// Format `self` as an Eon document, including the comments from [`Self::document`].
+    fn to_eon_string(&self) -> String {
+        let mut comments = CommentMap::new();
+        Self::document(&KeyPath::root(), &mut comments);
+        self.to_eon()
+            .format_with_comments(&FormatOptions::default(), &comments)
+    }
+}
+
+/// Convert an Eon [`Value`] into a Rust value, without going through `serde`.
+///
+/// Usually implemented with `#[derive(FromEon)]` (behind the `derive` feature).
+///
+/// ```
+/// # #[cfg(feature = "derive")] {
+/// use eon::FromEon;
+///
+/// #[derive(FromEon, Debug, PartialEq)]
+/// struct Config {
+///     port: u16,
+///     #[eon(default)]
+///     debug: bool,
+/// }
+///
+/// let value: eon::Value = "port: 8080".parse().unwrap();
+/// assert_eq!(
+///     Config::from_eon(&value).unwrap(),
+///     Config { port: 8080, debug: false }
+/// );
+/// # }
+/// ```
+pub trait FromEon: Sized {
+    /// Convert an Eon [`Value`] into `Self`.
+    fn from_eon(value: &Value) -> Result<Self>;
+
+    /// What to use when the field holding `Self` is missing from its map, if anything.
+    ///
+    /// The default implementation returns `None`, i.e. the field is required.
+    /// For [`Option`] this returns `Some(None)`.
+    fn from_missing() -> Option<Self> {
+        None
+    }
+
+    /// Parse an Eon document into `Self`.
+    fn from_eon_str(eon_source: &str) -> Result<Self> {
+        Self::from_eon(&eon_source.parse()?)
+    }
+}
+
+impl ToEon for Value {
+    #[inline]
+    fn to_eon(&self) -> Value {
+        self.clone()
+    }
+}
+
+impl FromEon for Value {
+    #[inline]
+    fn from_eon(value: &Value) -> Result<Self> {
+        Ok(value.clone())
+    }
+}
+
+impl ToEon for str {
+    #[inline]
+    fn to_eon(&self) -> Value {
+        Value::from(self)
+    }
+}
+
+impl<T: ToEon + ?Sized> ToEon for &T {
+    #[inline]
+    fn to_eon(&self) -> Value {
+        (**self).to_eon()
+    }
+
+    fn document(prefix: &KeyPath, comments: &mut CommentMap) {
+        T::document(prefix, comments);
+    }
+}
+
+impl<T: ToEon + ?Sized> ToEon for Box<T> {
+    #[inline]
+    fn to_eon(&self) -> Value {
+        (**self).to_eon()
+    }
+
+    fn document(prefix: &KeyPath, comments: &mut CommentMap) {
+        T::document(prefix, comments);
+    }
+}
+
+impl<T: FromEon> FromEon for Box<T> {
+    #[inline]
+    fn from_eon(value: &Value) -> Result<Self> {
+        T::from_eon(value).map(Self::new)
+    }
+
+    fn from_missing() -> Option<Self> {
+        T::from_missing().map(Self::new)
+    }
+}
+
+macro_rules! impl_to_from_eon {
+    ($($t:ty),*) => {
+        $(
+            impl ToEon for $t {
+                #[inline]
+                fn to_eon(&self) -> Value {
+                    Value::from(self.clone())
+                }
+            }
+
+            impl FromEon for $t {
+                #[inline]
+                fn from_eon(value: &Value) -> Result<Self> {
+                    Self::try_from(value)
+                }
+            }
+        )*
+    };
+}
+
+impl_to_from_eon!(
+    bool, String, f32, f64, i8, i16, i32, i64, i128, u8, u16, u32, u64, u128
+);
+
+/// `None` is written as `null`, and a missing field is read as `None`.
+impl<T: ToEon> ToEon for Option<T> {
+    fn to_eon(&self) -> Value {
+        self.as_ref().map_or(Value::Null, T::to_eon)
+    }
+
+    fn document(prefix: &KeyPath, comments: &mut CommentMap) {
+        T::document(prefix, comments);
+    }
+}
+
+impl<T: FromEon> FromEon for Option<T> {
+    fn from_eon(value: &Value) -> Result<Self> {
+        match value {
+            Value::Null => Ok(None),
+            value => T::from_eon(value).map(Some),
+        }
+    }
+
+    fn from_missing() -> Option<Self> {
+        Some(None)
+    }
+}
+
+impl<T: ToEon> ToEon for Vec<T> {
+    fn to_eon(&self) -> Value {
+        self.iter().map(T::to_eon).collect()
+    }
+}
+
+impl<T: FromEon> FromEon for Vec<T> {
+    fn from_eon(value: &Value) -> Result<Self> {
+        __private::expect_list(value)?
+            .iter()
+            .enumerate()
+            .map(|(i, element)| __private::element(element, i))
+            .collect()
+    }
+}
+
+impl<T: ToEon> ToEon for BTreeMap<String, T> {
+    fn to_eon(&self) -> Value {
+        self.iter()
+            .map(|(key, value)| (key.clone(), value.to_eon()))
+            .collect()
+    }
+}
+
+impl<T: FromEon> FromEon for BTreeMap<String, T> {
+    fn from_eon(value: &Value) -> Result<Self> {
+        string_entries(value)?.collect()
+    }
+}
+
+impl<T: ToEon, S> ToEon for HashMap<String, T, S> {
+    fn to_eon(&self) -> Value {
+        // Sort the keys so that the output is deterministic:
+        let mut entries: Vec<_> = self.iter().collect();
+        entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+        entries
+            .into_iter()
+            .map(|(key, value)| (key.clone(), value.to_eon()))
+            .collect()
+    }
+}
+
+impl<T: FromEon, S: Default + std::hash::BuildHasher> FromEon for HashMap<String, T, S> {
+    fn from_eon(value: &Value) -> Result<Self> {
+        string_entries(value)?.collect()
+    }
+}
+
+fn string_entries<T: FromEon>(value: &Value) -> Result<impl Iterator<Item = Result<(String, T)>>> {
+    let map = __private::expect_map(value)?;
+    Ok(map.iter().map(|(key, value)| {
+        let key = String::try_from(key)?;
+        let value = T::from_eon(value).map_err(|err| in_context(&key, &err))?;
+        Ok((key, value))
+    }))
+}
+
+/// Prefix the message of `err` with where it happened, like `port: ` or `[2]: `, keeping its [`crate::ErrorKind`].
+fn in_context(context: impl std::fmt::Display, err: &Error) -> Error {
+    let kind = err.kind().clone();
+    Error::custom(format!("{context}: {err}")).with_kind(kind)
+}
+
+/// Helpers used by the code generated by `#[derive(ToEon, FromEon)]`.
+///
+/// Not part of the public API.
+#[doc(hidden)]
+pub mod __private {
+    use super::{Error, FromEon, Map, Result, Value, in_context};
+
+    pub fn expect_map(value: &Value) -> Result<&Map> {
+        value.as_map().ok_or_else(|| value.unexpected("a map"))
+    }
+
+    pub fn expect_list(value: &Value) -> Result<&[Value]> {
+        value.as_list().ok_or_else(|| value.unexpected("a list"))
+    }
+
+    pub fn expect_null(value: &Value) -> Result<()> {
+        if *value == Value::Null {
+            Ok(())
+        } else {
+            Err(value.unexpected("null"))
+        }
+    }
+
+    /// Read the field `key` of `map`, falling back to [`FromEon::from_missing`].
+    pub fn field<T: FromEon>(map: &Map, key: &str) -> Result<T> {
+        match map.get_str(key) {
+            Some(value) => T::from_eon(value).map_err(|err| in_context(key, &err)),
+            None => T::from_missing().ok_or_else(|| Error::custom(format!("Missing `{key}`"))),
+        }
+    }
+
+    /// Read the field `key` of `map`, falling back to `default`.
+    pub fn field_or_else<T: FromEon>(
+        map: &Map,
+        key: &str,
+        default: impl FnOnce() -> T,
+    ) -> Result<T> {
+        match map.get_str(key) {
+            Some(value) => T::from_eon(value).map_err(|err| in_context(key, &err)),
+            None => Ok(default()),
+        }
+    }
+
+    pub fn element<T: FromEon>(value: &Value, index: usize) -> Result<T> {
+        T::from_eon(value).map_err(|err| in_context(format!("[{index}]"), &err))
+    }
+
+    /// Check that a tuple or variant has exactly `len` values.
+    pub fn expect_len(values: &[Value], len: usize) -> Result<()> {
+        if values.len() == len {
+            Ok(())
+        } else {
+            Err(Error::custom(format!(
+                "Expected {len} value(s), got {}",
+                values.len()
+            )))
+        }
+    }
+
+    pub fn unknown_variant(name: &str, expected: &[&str]) -> Error {
+        crate::suggestions::unknown_variant_error(name, expected)
+    }
+
+    pub fn missing_values(name: &str) -> Error {
+        Error::custom(format!(
+            "Expected values for variant `{name}`, like `\"{name}\"(…)`"
+        ))
+    }
+
+    pub fn unexpected_values(name: &str) -> Error {
+        Error::custom(format!("Variant `{name}` has no values"))
+    }
+
+    pub fn expected_variant(value: &Value) -> Error {
+        value.unexpected("a variant")
+    }
+}
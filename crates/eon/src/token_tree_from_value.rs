@@ -5,66 +5,95 @@
 use crate::{Value, value::Variant};
 
 use eon_syntax::{
-    TokenKeyValue, TokenList, TokenMap, TokenTree, TokenValue, TokenVariant, escape_and_quote,
-    is_valid_identifier,
+    FormatOptions, NumberLiterals, TokenKeyValue, TokenList, TokenMap, TokenTree, TokenValue,
+    TokenVariant, escape_and_quote, escape_and_quote_literal, is_valid_identifier, quote_bytes,
 };
 
 impl From<Value> for TokenTree<'static> {
     fn from(value: Value) -> Self {
-        Self::from(TokenValue::from(value))
+        token_tree(value, &FormatOptions::default())
     }
 }
 
 impl From<Value> for TokenValue<'static> {
     fn from(value: Value) -> Self {
-        match value {
-            Value::Null => TokenValue::Identifier("null".into()),
-            Value::Bool(true) => TokenValue::Identifier("true".into()),
-            Value::Bool(false) => TokenValue::Identifier("false".into()),
-            Value::Number(number) => TokenValue::Number(number.to_string().into()),
-            Value::String(string) => TokenValue::QuotedString(escape_and_quote(&string).into()),
-            Value::List(list) => TokenValue::List(TokenList {
-                values: list.into_iter().map(Into::into).collect(),
-                closing_comments: vec![],
-            }),
-            Value::Map(map) => {
-                let all_keys_are_identifiers = map.iter().all(|(key, _)| {
-                    if let Value::String(key) = key {
-                        is_valid_identifier(key)
-                    } else {
-                        false
-                    }
-                });
+        token_value(value, &FormatOptions::default())
+    }
+}
 
-                TokenValue::Map(TokenMap {
-                    key_values: map
-                        .into_iter()
-                        .map(|(key, value)| {
-                            let key = if all_keys_are_identifiers {
-                                if let Value::String(key) = key {
-                                    TokenTree::from(TokenValue::Identifier(key.into()))
-                                } else {
-                                    unreachable!();
-                                }
-                            } else {
-                                TokenTree::from(key)
-                            };
+/// Only [`FormatOptions::number_literals`] and [`FormatOptions::literal_backslash_strings`] are used here,
+/// the rest is up to the formatter.
+pub(crate) fn token_tree(value: Value, options: &FormatOptions) -> TokenTree<'static> {
+    TokenTree::from(token_value(value, options))
+}
 
-                            TokenKeyValue {
-                                key,
-                                value: TokenTree::from(value),
-                            }
-                        })
-                        .collect(),
-                    closing_comments: Default::default(),
-                })
+fn token_value(value: Value, options: &FormatOptions) -> TokenValue<'static> {
+    let tree = |value| token_tree(value, options);
+    let quote = |string: &str| {
+        if options.literal_backslash_strings {
+            escape_and_quote_literal(string)
+        } else {
+            escape_and_quote(string)
+        }
+    };
+    match value {
+        Value::Null => TokenValue::Identifier("null".into()),
+        Value::Bool(true) => TokenValue::Identifier("true".into()),
+        Value::Bool(false) => TokenValue::Identifier("false".into()),
+        Value::Number(number) => match (number.literal(), options.number_literals) {
+            (Some(literal), NumberLiterals::Preserve) => {
+                TokenValue::Number(literal.to_owned().into())
             }
-            Value::Variant(Variant { name, values }) => TokenValue::Variant(TokenVariant {
-                name_span: None,
-                quoted_name: escape_and_quote(&name).into(),
-                values: values.into_iter().map(Into::into).collect(),
+            _ => TokenValue::Number(number.to_string().into()),
+        },
+        Value::Timestamp(timestamp) => TokenValue::Timestamp(timestamp.to_string().into()),
+        Value::String(string) => TokenValue::QuotedString(quote(&string).into()),
+        Value::Bytes(bytes) => TokenValue::Bytes(quote_bytes(&bytes).into()),
+        Value::List(list) => TokenValue::List(TokenList {
+            values: list.into_iter().map(tree).collect(),
+            closing_comments: vec![],
+            trailing_comma: false,
+        }),
+        Value::Map(map) => {
+            let all_keys_are_identifiers = map.iter().all(|(key, _)| {
+                if let Value::String(key) = key {
+                    is_valid_identifier(key)
+                } else {
+                    false
+                }
+            });
+
+            TokenValue::Map(TokenMap {
+                includes: vec![],
+                key_values: map
+                    .into_iter()
+                    .map(|(key, value)| {
+                        let key = if all_keys_are_identifiers {
+                            if let Value::String(key) = key {
+                                TokenTree::from(TokenValue::Identifier(key.into()))
+                            } else {
+                                unreachable!();
+                            }
+                        } else {
+                            tree(key)
+                        };
+
+                        TokenKeyValue {
+                            key,
+                            value: tree(value),
+                        }
+                    })
+                    .collect(),
                 closing_comments: Default::default(),
-            }),
+                trailing_comma: false,
+            })
         }
+        Value::Variant(Variant { name, values }) => TokenValue::Variant(TokenVariant {
+            name_span: None,
+            quoted_name: quote(&name).into(),
+            values: values.into_iter().map(tree).collect(),
+            closing_comments: Default::default(),
+            trailing_comma: false,
+        }),
     }
 }
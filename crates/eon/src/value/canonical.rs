@@ -0,0 +1,142 @@
+use std::fmt::Write as _;
+
+use crate::{Number, Value};
+
+impl Value {
+    /// A unique Eon representation of the value, for content addressing and change detection.
+    ///
+    /// Two values are equal iff their canonical formats are equal:
+    /// * Map keys are sorted (by the [`Ord`] of [`Value`]), so insertion order does not matter
+    /// * Numbers are normalized, so `1`, `1.0`, and `0x01` are all written as `1`
+    /// * All strings (including map keys) are double-quoted, and there is no whitespace or comments
+    ///
+    /// The result is valid Eon, and parses back to an equal value.
+    ///
+    /// ```
+    /// let a: eon::Value = "b: 1.0, a: [0x10, 'text']".parse().unwrap();
+    /// let b: eon::Value = "a: [16, \"text\"]\nb: 1".parse().unwrap();
+    /// assert_eq!(a.canonical_format(), r#"{"a":[16,"text"],"b":1}"#);
+    /// assert_eq!(a.canonical_format(), b.canonical_format());
+    /// ```
+    pub fn canonical_format(&self) -> String {
+        let mut out = String::new();
+        write_canonical(self, &mut out);
+        out
+    }
+
+    /// The SHA-256 of [`Self::canonical_format`].
+    ///
+    /// Stable across versions of this crate, so it can be stored and compared later,
+    /// e.g. to detect whether the effective config of a deployment changed.
+    #[cfg(feature = "digest")]
+    pub fn canonical_digest(&self) -> [u8; 32] {
+        use sha2::Digest as _;
+        sha2::Sha256::digest(self.canonical_format().as_bytes()).into()
+    }
+}
+
+fn write_canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Number(number) => write_number(number, out),
+        Value::Timestamp(timestamp) => out.push_str(timestamp.as_str()),
+        Value::String(string) => write_string(string, out),
+        Value::Bytes(bytes) => out.push_str(&eon_syntax::quote_bytes(bytes)),
+        Value::List(list) => {
+            out.push('[');
+            write_values(list, out);
+            out.push(']');
+        }
+        Value::Map(map) => {
+            let mut entries: Vec<(&Value, &Value)> = map.iter().collect();
+            entries.sort_unstable_by(|(a, _), (b, _)| a.cmp(b)); // Keys are unique, so unstable is fine
+            out.push('{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if 0 < i {
+                    out.push(',');
+                }
+                write_canonical(key, out);
+                out.push(':');
+                write_canonical(value, out);
+            }
+            out.push('}');
+        }
+        Value::Variant(variant) => {
+            write_string(&variant.name, out);
+            out.push('(');
+            write_values(&variant.values, out);
+            out.push(')');
+        }
+    }
+}
+
+fn write_values(values: &[Value], out: &mut String) {
+    for (i, value) in values.iter().enumerate() {
+        if 0 < i {
+            out.push(',');
+        }
+        write_canonical(value, out);
+    }
+}
+
+fn write_string(string: &str, out: &mut String) {
+    // Same as the double-quoted strings of `eon_syntax::escape_and_quote`:
+    write!(out, "{string:?}").ok();
+}
+
+/// Equal numbers are always written the same, e.g. `1.0` as `1`, and `-0.0` as `0`.
+fn write_number(number: &Number, out: &mut String) {
+    if let Some(n) = number.as_i128() {
+        write!(out, "{n}").ok();
+    } else if let Some(n) = number.as_u128() {
+        write!(out, "{n}").ok();
+    } else {
+        #[cfg(feature = "decimal")]
+        if let Some(decimal) = number.as_decimal() {
+            // Floats convert via their shortest representation, so they match equal decimals:
+            write!(out, "{}", decimal.normalize()).ok();
+            return;
+        }
+
+        let n = number.as_f64().unwrap_or(f64::NAN);
+        if n.is_nan() {
+            out.push_str("+nan");
+        } else if n == f64::INFINITY {
+            out.push_str("+inf");
+        } else if n == f64::NEG_INFINITY {
+            out.push_str("-inf");
+        } else {
+            out.push_str(ryu::Buffer::new().format_finite(n));
+        }
+    }
+}
+
+#[test]
+fn test_canonical_format() {
+    let parse = |source: &str| source.parse::<Value>().unwrap();
+
+    let value = parse(
+        r#"
+        z: "Rgb"(255, 0.5, -0.0)
+        a: { 2: b"ff", 1: 2024-05-01T12:30:00Z, "c d": +inf }
+        m: [null, true, 'C:\dir', +nan]
+        "#,
+    );
+    let canonical = value.canonical_format();
+    assert_eq!(
+        canonical,
+        r#"{"a":{1:2024-05-01T12:30:00Z,2:b"ff","c d":+inf},"m":[null,true,"C:\\dir",+nan],"z":"Rgb"(255,0.5,0)}"#
+    );
+    assert_eq!(parse(&canonical), value);
+
+    // Key order and number formatting don't matter:
+    assert_eq!(
+        parse("a: 1.0, b: 0xff").canonical_format(),
+        parse("b: 255, a: 1").canonical_format()
+    );
+    assert_ne!(
+        parse("a: 1").canonical_format(),
+        parse("a: \"1\"").canonical_format()
+    );
+}
@@ -0,0 +1,539 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{Error, Map, Result, Value};
+
+impl Value {
+    /// Return the number as an `i64`, iff this is a [`Value::Number`] that fits without narrowing.
+    pub fn as_i64(&self) -> Option<i64> {
+        self.as_number()?.as_i64()
+    }
+
+    /// Return the number as a `u64`, iff this is a [`Value::Number`] that fits without narrowing.
+    pub fn as_u64(&self) -> Option<u64> {
+        self.as_number()?.as_u64()
+    }
+
+    /// Return the number as an `f64`, iff this is a [`Value::Number`] that fits without narrowing.
+    pub fn as_f64(&self) -> Option<f64> {
+        self.as_number()?.as_f64()
+    }
+
+    /// Return the string iff this is a [`Value::String`].
+    ///
+    /// Same as [`Self::as_string`].
+    pub fn as_str(&self) -> Option<&str> {
+        self.as_string()
+    }
+
+    /// Return the bool value iff this is a [`Value::Bool`], else `default`.
+    pub fn as_bool_or(&self, default: bool) -> bool {
+        self.as_bool().unwrap_or(default)
+    }
+
+    /// Look up a string key iff this is a [`Value::Map`].
+    ///
+    /// For nested values, use [`Self::get_path`].
+    pub fn get(&self, key: &str) -> Option<&Self> {
+        self.as_map()?.get_str(key)
+    }
+
+    /// Convert each element of a [`Value::List`] into `T`.
+    ///
+    /// Returns `None` if this is not a list, or if any element fails to convert.
+    ///
+    /// ```
+    /// let value: eon::Value = "[1, 2, 3]".parse().unwrap();
+    /// assert_eq!(value.as_list_of::<u8>(), Some(vec![1, 2, 3]));
+    /// assert_eq!(value.as_list_of::<String>(), None);
+    /// ```
+    pub fn as_list_of<T>(&self) -> Option<Vec<T>>
+    where
+        T: for<'a> TryFrom<&'a Self>,
+    {
+        self.as_list()?
+            .iter()
+            .map(|value| T::try_from(value).ok())
+            .collect()
+    }
+
+    /// Return the bool, or an error like "Expected a bool for `debug`, got a list".
+    ///
+    /// The `context` is typically the name of the field you are loading,
+    /// which makes it a lightweight alternative to serde for quick loaders:
+    ///
+    /// ```
+    /// # fn main() -> eon::Result<()> {
+    /// let config: eon::Value = "port: 8080, debug: [true]".parse()?;
+    /// let port = config.expect_key("port")?.expect_u64("port")?;
+    /// assert_eq!(port, 8080);
+    ///
+    /// let err = config.expect_key("debug")?.expect_bool("debug").unwrap_err();
+    /// assert_eq!(err.message(), "Expected a bool for `debug`, got a list");
+    /// # Ok(()) }
+    /// ```
+    pub fn expect_bool(&self, context: &str) -> Result<bool> {
+        self.as_bool()
+            .ok_or_else(|| self.unexpected_for("a bool", context))
+    }
+
+    /// Return the number as an `i64`, or an error naming the `context`.
+    ///
+    /// See [`Self::expect_bool`].
+    pub fn expect_i64(&self, context: &str) -> Result<i64> {
+        self.as_i64()
+            .ok_or_else(|| self.unexpected_for("an integer", context))
+    }
+
+    /// Return the number as a `u64`, or an error naming the `context`.
+    ///
+    /// See [`Self::expect_bool`].
+    pub fn expect_u64(&self, context: &str) -> Result<u64> {
+        self.as_u64()
+            .ok_or_else(|| self.unexpected_for("a non-negative integer", context))
+    }
+
+    /// Return the number as an `f64`, or an error naming the `context`.
+    ///
+    /// See [`Self::expect_bool`].
+    pub fn expect_f64(&self, context: &str) -> Result<f64> {
+        self.as_f64()
+            .ok_or_else(|| self.unexpected_for("a number", context))
+    }
+
+    /// Return the string, or an error naming the `context`.
+    ///
+    /// See [`Self::expect_bool`].
+    pub fn expect_str(&self, context: &str) -> Result<&str> {
+        self.as_string()
+            .ok_or_else(|| self.unexpected_for("a string", context))
+    }
+
+    /// Return the list, or an error naming the `context`.
+    ///
+    /// See [`Self::expect_bool`].
+    pub fn expect_list(&self, context: &str) -> Result<&[Self]> {
+        self.as_list()
+            .ok_or_else(|| self.unexpected_for("a list", context))
+    }
+
+    /// Return the map, or an error naming the `context`.
+    ///
+    /// See [`Self::expect_bool`].
+    pub fn expect_map(&self, context: &str) -> Result<&Map> {
+        self.as_map()
+            .ok_or_else(|| self.unexpected_for("a map", context))
+    }
+
+    /// Look up a required string key in a map.
+    ///
+    /// Fails if this is not a map, or if the key is missing.
+    pub fn expect_key(&self, key: &str) -> Result<&Self> {
+        self.expect_map(key)?
+            .get_str(key)
+            .ok_or_else(|| Error::custom(format!("Missing `{key}`")))
+    }
+
+    /// A short description of what kind of value this is, like `"a map"`, for error messages.
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Null => "null",
+            Self::Bool(_) => "a bool",
+            Self::Number(_) => "a number",
+            Self::Timestamp(_) => "a timestamp",
+            Self::String(_) => "a string",
+            Self::Bytes(_) => "bytes",
+            Self::List(_) => "a list",
+            Self::Map(_) => "a map",
+            Self::Variant(_) => "a variant",
+        }
+    }
+
+    pub(crate) fn unexpected(&self, expected: &str) -> Error {
+        Error::custom(format!("Expected {expected}, got {}", self.kind()))
+    }
+
+    fn unexpected_for(&self, expected: &str, context: &str) -> Error {
+        Error::custom(format!(
+            "Expected {expected} for `{context}`, got {}",
+            self.kind()
+        ))
+    }
+}
+
+impl TryFrom<&Value> for bool {
+    type Error = Error;
+
+    fn try_from(value: &Value) -> Result<Self> {
+        value.as_bool().ok_or_else(|| value.unexpected("a bool"))
+    }
+}
+
+impl TryFrom<&Value> for String {
+    type Error = Error;
+
+    fn try_from(value: &Value) -> Result<Self> {
+        value
+            .as_string()
+            .map(ToOwned::to_owned)
+            .ok_or_else(|| value.unexpected("a string"))
+    }
+}
+
+impl<'a> TryFrom<&'a Value> for &'a str {
+    type Error = Error;
+
+    fn try_from(value: &'a Value) -> Result<Self> {
+        value
+            .as_string()
+            .ok_or_else(|| value.unexpected("a string"))
+    }
+}
+
+impl TryFrom<&Value> for f64 {
+    type Error = Error;
+
+    fn try_from(value: &Value) -> Result<Self> {
+        value.as_f64().ok_or_else(|| value.unexpected("a number"))
+    }
+}
+
+impl TryFrom<&Value> for f32 {
+    type Error = Error;
+
+    fn try_from(value: &Value) -> Result<Self> {
+        f64::try_from(value).map(|f| f as Self)
+    }
+}
+
+macro_rules! impl_integer_try_from_value {
+    ($($t:ty),*) => {
+        $(
+            impl TryFrom<&Value> for $t {
+                type Error = Error;
+
+                fn try_from(value: &Value) -> Result<Self> {
+                    let expected = concat!("an integer (", stringify!($t), ")");
+                    let number = value.as_number().ok_or_else(|| value.unexpected(expected))?;
+                    number
+                        .as_i128()
+                        .and_then(|n| Self::try_from(n).ok())
+                        .or_else(|| number.as_u128().and_then(|n| Self::try_from(n).ok()))
+                        .ok_or_else(|| Error::custom(format!("Expected {expected}, got {number}")))
+                }
+            }
+        )*
+    };
+}
+
+impl_integer_try_from_value!(i8, i16, i32, i64, i128, u8, u16, u32, u64, u128);
+
+// ----------------------------------------------------------------------------
+// Owned conversions
+
+impl TryFrom<Value> for String {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self> {
+        if let Value::String(string) = value {
+            Ok(string)
+        } else {
+            Err(value.unexpected("a string"))
+        }
+    }
+}
+
+macro_rules! impl_try_from_owned_value {
+    ($($t:ty),*) => {
+        $(
+            impl TryFrom<Value> for $t {
+                type Error = Error;
+
+                #[inline]
+                fn try_from(value: Value) -> Result<Self> {
+                    Self::try_from(&value)
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_owned_value!(
+    bool, f32, f64, i8, i16, i32, i64, i128, u8, u16, u32, u64, u128
+);
+
+// A blanket `impl<T> TryFrom<Value> for Option<T>` would conflict with `impl<T> From<T> for Option<T>`,
+// so we implement it for each type instead.
+macro_rules! impl_option_try_from_value {
+    ($($t:ty),*) => {
+        $(
+            /// `null` becomes `None`.
+            impl TryFrom<Value> for Option<$t> {
+                type Error = Error;
+
+                fn try_from(value: Value) -> Result<Self> {
+                    if value == Value::Null {
+                        Ok(None)
+                    } else {
+                        <$t>::try_from(value).map(Some)
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_option_try_from_value!(
+    String, bool, f32, f64, i8, i16, i32, i64, i128, u8, u16, u32, u64, u128
+);
+
+impl<T> TryFrom<Value> for Vec<T>
+where
+    T: TryFrom<Value>,
+    T::Error: std::fmt::Display,
+{
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self> {
+        let Value::List(list) = value else {
+            return Err(value.unexpected("a list"));
+        };
+        list.into_iter()
+            .enumerate()
+            .map(|(i, element)| {
+                T::try_from(element).map_err(|err| Error::custom(format!("[{i}]: {err}")))
+            })
+            .collect()
+    }
+}
+
+/// The key-value pairs of a map with string keys.
+fn string_entries<T>(value: Value) -> Result<impl Iterator<Item = Result<(String, T)>>>
+where
+    T: TryFrom<Value>,
+    T::Error: std::fmt::Display,
+{
+    let Value::Map(map) = value else {
+        return Err(value.unexpected("a map"));
+    };
+    Ok(map.into_iter().map(|(key, value)| {
+        let Value::String(key) = key else {
+            return Err(key.unexpected("a string key"));
+        };
+        match T::try_from(value) {
+            Ok(value) => Ok((key, value)),
+            Err(err) => Err(Error::custom(format!("{key}: {err}"))),
+        }
+    }))
+}
+
+impl<T> TryFrom<Value> for BTreeMap<String, T>
+where
+    T: TryFrom<Value>,
+    T::Error: std::fmt::Display,
+{
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self> {
+        string_entries(value)?.collect()
+    }
+}
+
+impl<T, S> TryFrom<Value> for HashMap<String, T, S>
+where
+    T: TryFrom<Value>,
+    T::Error: std::fmt::Display,
+    S: std::hash::BuildHasher + Default,
+{
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self> {
+        string_entries(value)?.collect()
+    }
+}
+
+impl<T: Into<Self>> From<Option<T>> for Value {
+    /// `None` becomes [`Value::Null`].
+    #[inline]
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Self::Null, Into::into)
+    }
+}
+
+impl<T: Into<Self>> From<BTreeMap<String, T>> for Value {
+    fn from(map: BTreeMap<String, T>) -> Self {
+        Self::Map(map.into_iter().map(|(k, v)| (k, v.into())).collect::<Map>())
+    }
+}
+
+impl<T: Into<Self>, S> From<HashMap<String, T, S>> for Value {
+    fn from(map: HashMap<String, T, S>) -> Self {
+        Self::Map(map.into_iter().map(|(k, v)| (k, v.into())).collect::<Map>())
+    }
+}
+
+#[test]
+fn test_typed_getters() {
+    let value: Value = r#"
+        port: 8080
+        ratio: 0.5
+        name: "server"
+        debug: true
+        ids: [1, 2, 300]
+    "#
+    .parse()
+    .unwrap();
+
+    assert_eq!(value.get("port").and_then(Value::as_i64), Some(8080));
+    assert_eq!(value.get("port").and_then(Value::as_f64), Some(8080.0));
+    assert_eq!(value.get("ratio").and_then(Value::as_f64), Some(0.5));
+    assert_eq!(value.get("ratio").and_then(Value::as_i64), None);
+    assert_eq!(value.get("name").and_then(Value::as_str), Some("server"));
+    assert!(value.get("debug").is_some_and(|v| v.as_bool_or(false)));
+    assert!(value.get("name").is_some_and(|v| v.as_bool_or(true)));
+    assert_eq!(value.get("missing"), None);
+    assert_eq!(Value::Null.get("port"), None);
+
+    let ids = value.get("ids").unwrap();
+    assert_eq!(ids.as_list_of::<u16>(), Some(vec![1, 2, 300]));
+    assert_eq!(ids.as_list_of::<u8>(), None);
+    assert_eq!(value.as_list_of::<u8>(), None);
+
+    assert_eq!(u8::try_from(&Value::from(255)).unwrap(), 255);
+    assert_eq!(
+        u8::try_from(&Value::from(256)).unwrap_err().message(),
+        "Expected an integer (u8), got 256"
+    );
+    assert_eq!(
+        i32::try_from(&Value::from("42")).unwrap_err().message(),
+        "Expected an integer (i32), got a string"
+    );
+    assert_eq!(<&str>::try_from(&Value::from("hi")).unwrap(), "hi");
+    assert_eq!(f32::try_from(&Value::from(1.5)).unwrap(), 1.5);
+    assert!(bool::try_from(&Value::Null).is_err());
+}
+
+#[test]
+fn test_try_from_value() {
+    let value: Value = r#"
+        name: "server"
+        ports: [80, 443]
+        limits: { cpu: 2, memory: 512 }
+        backup: null
+    "#
+    .parse()
+    .unwrap();
+
+    let mut map = BTreeMap::<String, Value>::try_from(value).unwrap();
+    let take = |map: &mut BTreeMap<String, Value>, key: &str| map.remove(key).unwrap();
+
+    assert_eq!(String::try_from(take(&mut map, "name")).unwrap(), "server");
+    assert_eq!(
+        Vec::<u16>::try_from(take(&mut map, "ports")).unwrap(),
+        vec![80, 443]
+    );
+    let limits = take(&mut map, "limits");
+    assert_eq!(
+        HashMap::<String, u32>::try_from(limits.clone()).unwrap(),
+        HashMap::from([("cpu".to_owned(), 2), ("memory".to_owned(), 512)])
+    );
+    assert_eq!(
+        BTreeMap::<String, u8>::try_from(limits)
+            .unwrap_err()
+            .message(),
+        "memory: Expected an integer (u8), got 512"
+    );
+    assert_eq!(
+        Option::<String>::try_from(take(&mut map, "backup")).unwrap(),
+        None
+    );
+    assert_eq!(Option::<i32>::try_from(Value::from(7)).unwrap(), Some(7));
+
+    assert_eq!(
+        Vec::<bool>::try_from(Value::from(vec![Value::Bool(true), Value::Null]))
+            .unwrap_err()
+            .message(),
+        "[1]: Expected a bool, got null"
+    );
+    assert_eq!(
+        Vec::<bool>::try_from(Value::from("x"))
+            .unwrap_err()
+            .message(),
+        "Expected a list, got a string"
+    );
+
+    let round_trip = BTreeMap::from([("a".to_owned(), Some(1)), ("b".to_owned(), None)]);
+    let value = Value::from(round_trip);
+    assert_eq!(value.get("a"), Some(&Value::from(1)));
+    assert_eq!(value.get("b"), Some(&Value::Null));
+}
+
+#[test]
+fn test_expect() {
+    let config: Value = r#"
+        name: "server"
+        port: -1
+        ratio: 0.5
+        hosts: ["a", "b"]
+    "#
+    .parse()
+    .unwrap();
+
+    assert_eq!(
+        config
+            .expect_key("name")
+            .unwrap()
+            .expect_str("name")
+            .unwrap(),
+        "server"
+    );
+    assert_eq!(
+        config
+            .expect_key("port")
+            .unwrap()
+            .expect_i64("port")
+            .unwrap(),
+        -1
+    );
+    assert_eq!(
+        config
+            .expect_key("ratio")
+            .unwrap()
+            .expect_f64("ratio")
+            .unwrap(),
+        0.5
+    );
+    assert_eq!(
+        config
+            .expect_key("hosts")
+            .unwrap()
+            .expect_list("hosts")
+            .unwrap()
+            .len(),
+        2
+    );
+
+    let message = |result: Result<&Value>| result.unwrap_err().message().to_owned();
+    assert_eq!(message(config.expect_key("missing")), "Missing `missing`");
+    assert_eq!(
+        message(Value::Null.expect_key("port")),
+        "Expected a map for `port`, got null"
+    );
+    assert_eq!(
+        config
+            .expect_key("port")
+            .unwrap()
+            .expect_u64("port")
+            .unwrap_err()
+            .message(),
+        "Expected a non-negative integer for `port`, got a number"
+    );
+    assert_eq!(
+        config
+            .expect_key("hosts")
+            .unwrap()
+            .expect_map("hosts")
+            .unwrap_err()
+            .message(),
+        "Expected a map for `hosts`, got a list"
+    );
+}
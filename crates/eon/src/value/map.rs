@@ -16,10 +16,10 @@ pub struct Map {
     map: indexmap::IndexMap<Value, Value>,
 
     // In order to implement `Hash` efficiently,
-    // we keep a running xor of the hash of all the keys and values.
+    // we keep a running xor of the hash of all the keys.
+    // The values can be mutated in-place (e.g. with `get_mut`),
+    // so their hash is computed when the map is hashed.
     hash_of_keys: u64,
-
-    hash_of_values: u64,
 }
 
 impl Map {
@@ -35,7 +35,6 @@ impl Map {
         Self {
             map: indexmap::IndexMap::with_capacity(capacity),
             hash_of_keys: 0,
-            hash_of_values: 0,
         }
     }
 
@@ -44,11 +43,156 @@ impl Map {
     /// This will replace the value if the key already exists.
     #[inline]
     pub fn insert(&mut self, key: Value, value: Value) -> Option<Value> {
+        let (index, previous) = self.map.insert_full(key, value);
+        if previous.is_none() {
+            let (key, _) = self.map.get_index(index).expect("just inserted");
+            self.hash_of_keys ^= hash_of(key); // Using XOR guarantees that it's order-independent
+        }
+        previous
+    }
+
+    /// Insert a key-value pair at the given position in the map.
+    ///
+    /// If the key already exists, its value is replaced and the pair is moved to `index`.
+    /// Either way, the key-value pairs after `index` are shifted, so this is O(N).
+    ///
+    /// ## Panics
+    /// If `index` is out of bounds, i.e. greater than the length of the map
+    /// (or equal to it, if the key already exists).
+    pub fn shift_insert(&mut self, index: usize, key: Value, value: Value) -> Option<Value> {
         let key_hash = hash_of(&key);
-        let value_hash = hash_of(&value);
-        self.hash_of_keys ^= key_hash; // Using XOR guarantees that it's order-independent
-        self.hash_of_values ^= value_hash; // Using XOR guarantees that it's order-independent
-        self.map.insert(key, value)
+        let previous = self.map.shift_insert(index, key, value);
+        if previous.is_none() {
+            self.hash_of_keys ^= key_hash;
+        }
+        previous
+    }
+
+    /// Get the entry for the given key, for in-place manipulation.
+    ///
+    /// New keys are inserted at the end of the map.
+    ///
+    /// ```
+    /// let mut map = eon::Map::new();
+    /// map.entry("retries".into()).or_insert(3.into());
+    /// map.entry("retries".into())
+    ///     .and_modify(|retries| *retries = 5.into())
+    ///     .or_insert(0.into());
+    /// assert_eq!(map.get_str("retries"), Some(&eon::Value::from(5)));
+    /// ```
+    pub fn entry(&mut self, key: Value) -> Entry<'_> {
+        let hash_of_keys = &mut self.hash_of_keys;
+        match self.map.entry(key) {
+            indexmap::map::Entry::Occupied(entry) => Entry::Occupied(OccupiedEntry {
+                entry,
+                hash_of_keys,
+            }),
+            indexmap::map::Entry::Vacant(entry) => Entry::Vacant(VacantEntry {
+                entry,
+                hash_of_keys,
+            }),
+        }
+    }
+
+    /// Remove a key-value pair from the map, returning the value.
+    ///
+    /// This preserves the order of the remaining key-value pairs, but is O(N).
+    /// It is the same as [`Self::shift_remove`].
+    #[inline]
+    pub fn remove(&mut self, key: &Value) -> Option<Value> {
+        self.shift_remove(key)
+    }
+
+    /// Remove a key-value pair from the map, returning the value.
+    ///
+    /// The last key-value pair takes the place of the removed one, so this is O(1),
+    /// but changes the order of the map. Use [`Self::shift_remove`] to preserve the order.
+    #[inline]
+    pub fn swap_remove(&mut self, key: &Value) -> Option<Value> {
+        let (key, value) = self.map.swap_remove_entry(key)?;
+        self.hash_of_keys ^= hash_of(&key);
+        Some(value)
+    }
+
+    /// Remove a key-value pair from the map, returning the value.
+    ///
+    /// This preserves the order of the remaining key-value pairs, but is O(N).
+    #[inline]
+    pub fn shift_remove(&mut self, key: &Value) -> Option<Value> {
+        let (key, value) = self.map.shift_remove_entry(key)?;
+        self.hash_of_keys ^= hash_of(&key);
+        Some(value)
+    }
+
+    /// Keep only the key-value pairs for which `keep` returns `true`.
+    ///
+    /// This preserves the order of the remaining key-value pairs.
+    pub fn retain(&mut self, mut keep: impl FnMut(&Value, &mut Value) -> bool) {
+        let hash_of_keys = &mut self.hash_of_keys;
+        self.map.retain(|key, value| {
+            let retain = keep(key, value);
+            if !retain {
+                *hash_of_keys ^= hash_of(key);
+            }
+            retain
+        });
+    }
+
+    /// Sort the key-value pairs by key, e.g. before formatting.
+    ///
+    /// This changes the insertion order, but not the equality or hash of the map.
+    pub fn sort_keys(&mut self) {
+        self.map.sort_keys();
+    }
+
+    /// Remove all key-value pairs from the map, moving them out in order.
+    pub fn drain(&mut self) -> indexmap::map::Drain<'_, Value, Value> {
+        self.hash_of_keys = 0;
+        self.map.drain(..)
+    }
+
+    /// Reserve room for at least `additional` more key-value pairs.
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.map.reserve(additional);
+    }
+
+    /// Shrink the capacity of the map as much as possible.
+    ///
+    /// This does not recurse into the values. For that, see [`Value::shrink_to_fit`].
+    #[inline]
+    pub fn shrink_to_fit(&mut self) {
+        self.map.shrink_to_fit();
+    }
+
+    /// Get a mutable reference to the value associated with the given key.
+    #[inline]
+    pub fn get_mut(&mut self, key: &Value) -> Option<&mut Value> {
+        self.map.get_mut(key)
+    }
+
+    /// The keys, in the order they were inserted.
+    ///
+    /// This order is guaranteed, and is what [`IntoIterator`] and formatting use.
+    /// When parsing, it is the order of the keys in the document.
+    /// Only [`Self::swap_remove`], [`Self::shift_insert`], and [`Self::sort_keys`]
+    /// change the order of the existing keys.
+    ///
+    /// ```
+    /// let value: eon::Value = "b: 1, a: 2".parse().unwrap();
+    /// let map = value.as_map().unwrap();
+    /// let keys: Vec<&eon::Value> = map.keys_in_insertion_order().collect();
+    /// assert_eq!(keys, [&eon::Value::from("b"), &eon::Value::from("a")]);
+    /// ```
+    #[inline]
+    pub fn keys_in_insertion_order(&self) -> indexmap::map::Keys<'_, Value, Value> {
+        self.map.keys()
+    }
+
+    fn sorted_entries(&self) -> Vec<(&Value, &Value)> {
+        let mut entries: Vec<(&Value, &Value)> = self.map.iter().collect();
+        entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+        entries
     }
 
     /// Get a reference to the value associated with the given string key.
@@ -57,21 +201,158 @@ impl Map {
     }
 }
 
+/// An entry in a [`Map`], from [`Map::entry`].
+#[derive(Debug)]
+pub enum Entry<'a> {
+    /// The key is in the map.
+    Occupied(OccupiedEntry<'a>),
+
+    /// The key is not in the map.
+    Vacant(VacantEntry<'a>),
+}
+
+impl<'a> Entry<'a> {
+    /// The key of the entry.
+    pub fn key(&self) -> &Value {
+        match self {
+            Self::Occupied(entry) => entry.key(),
+            Self::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Insert `default` if the key is not in the map, and return the value.
+    pub fn or_insert(self, default: Value) -> &'a mut Value {
+        self.or_insert_with(|| default)
+    }
+
+    /// Insert the result of `default` if the key is not in the map, and return the value.
+    pub fn or_insert_with(self, default: impl FnOnce() -> Value) -> &'a mut Value {
+        match self {
+            Self::Occupied(entry) => entry.into_mut(),
+            Self::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Insert [`Value::Null`] if the key is not in the map, and return the value.
+    pub fn or_default(self) -> &'a mut Value {
+        self.or_insert(Value::Null)
+    }
+
+    /// Modify the value if the key is in the map.
+    pub fn and_modify(mut self, modify: impl FnOnce(&mut Value)) -> Self {
+        if let Self::Occupied(entry) = &mut self {
+            modify(entry.get_mut());
+        }
+        self
+    }
+}
+
+/// An entry for a key that is in a [`Map`].
+#[derive(Debug)]
+pub struct OccupiedEntry<'a> {
+    entry: indexmap::map::OccupiedEntry<'a, Value, Value>,
+    hash_of_keys: &'a mut u64,
+}
+
+impl<'a> OccupiedEntry<'a> {
+    /// The key of the entry.
+    pub fn key(&self) -> &Value {
+        self.entry.key()
+    }
+
+    /// The value of the entry.
+    pub fn get(&self) -> &Value {
+        self.entry.get()
+    }
+
+    /// The value of the entry, for modifying it in-place.
+    pub fn get_mut(&mut self) -> &mut Value {
+        self.entry.get_mut()
+    }
+
+    /// The value of the entry, with the lifetime of the map.
+    pub fn into_mut(self) -> &'a mut Value {
+        self.entry.into_mut()
+    }
+
+    /// Replace the value, returning the old one.
+    pub fn insert(&mut self, value: Value) -> Value {
+        self.entry.insert(value)
+    }
+
+    /// Remove the key-value pair from the map, returning the value.
+    ///
+    /// Like [`Map::remove`], this preserves the order of the remaining key-value pairs.
+    pub fn remove(self) -> Value {
+        self.remove_entry().1
+    }
+
+    /// Remove the key-value pair from the map, and return it.
+    ///
+    /// Like [`Map::remove`], this preserves the order of the remaining key-value pairs.
+    pub fn remove_entry(self) -> (Value, Value) {
+        let (key, value) = self.entry.shift_remove_entry();
+        *self.hash_of_keys ^= hash_of(&key);
+        (key, value)
+    }
+}
+
+/// An entry for a key that is not in a [`Map`].
+#[derive(Debug)]
+pub struct VacantEntry<'a> {
+    entry: indexmap::map::VacantEntry<'a, Value, Value>,
+    hash_of_keys: &'a mut u64,
+}
+
+impl<'a> VacantEntry<'a> {
+    /// The key of the entry.
+    pub fn key(&self) -> &Value {
+        self.entry.key()
+    }
+
+    /// Take ownership of the key.
+    pub fn into_key(self) -> Value {
+        self.entry.into_key()
+    }
+
+    /// Insert the value at the end of the map, and return it.
+    pub fn insert(self, value: Value) -> &'a mut Value {
+        *self.hash_of_keys ^= hash_of(self.entry.key());
+        self.entry.insert(value)
+    }
+}
+
 impl PartialEq for Map {
     fn eq(&self, other: &Self) -> bool {
-        self.hash_of_keys == other.hash_of_keys
-            && self.hash_of_values == other.hash_of_values
-            && self.map == other.map
+        self.hash_of_keys == other.hash_of_keys && self.map == other.map
+    }
+}
+
+/// Maps are compared by their entries sorted by key,
+/// so that the order is independent of the insertion order, just like equality.
+impl Ord for Map {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        // Maps with sorted keys, e.g. after `sort_keys`, can be compared without sorting:
+        match (self.map.keys().is_sorted(), other.map.keys().is_sorted()) {
+            (true, true) => self.map.iter().cmp(other.map.iter()),
+            (true, false) => self.map.iter().cmp(other.sorted_entries()),
+            (false, true) => self.sorted_entries().into_iter().cmp(other.map.iter()),
+            (false, false) => self.sorted_entries().cmp(&other.sorted_entries()),
+        }
+    }
+}
+
+impl PartialOrd for Map {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
     }
 }
 
 impl Hash for Map {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        let Self {
-            hash_of_keys,
-            hash_of_values,
-            map,
-        } = self;
+        let Self { hash_of_keys, map } = self;
+        let hash_of_values = map.values().fold(0, |hash, value| hash ^ hash_of(value)); // Using XOR guarantees that it's order-independent
         hash_of_keys.hash(state);
         hash_of_values.hash(state);
         map.len().hash(state);
@@ -164,4 +445,100 @@ fn test_map() {
 
     assert_eq!(map_a, map_b);
     assert_eq!(hash_of(&map_a), hash_of(&map_b));
+
+    let mut map_c = map_a.clone();
+    let mut map_d = map_b.clone();
+    assert_eq!(map_c.swap_remove(&Value::from("b")), Some(Value::from(2)));
+    assert_eq!(map_d.shift_remove(&Value::from("b")), Some(Value::from(2)));
+    assert_eq!(map_c.swap_remove(&Value::from("b")), None);
+    assert_eq!(map_c, map_d);
+    assert_eq!(hash_of(&map_c), hash_of(&map_d));
+    assert_ne!(hash_of(&map_a), hash_of(&map_c));
+
+    let keys: Vec<&Value> = map_b.keys_in_insertion_order().collect();
+    assert_eq!(
+        keys,
+        [
+            &Value::from("d"),
+            &Value::from("c"),
+            &Value::from("b"),
+            &Value::from("a")
+        ]
+    );
+
+    assert_eq!(map_c.drain().count(), 3);
+    assert_eq!(hash_of(&map_c), hash_of(&Map::new()));
+
+    // The values are part of the hash, even when mutated in-place:
+    let mut map_e = map_a.clone();
+    *map_e.get_mut(&Value::from("a")).unwrap() = Value::from(42);
+    assert_ne!(map_e, map_a);
+    assert_ne!(hash_of(&map_e), hash_of(&map_a));
+    *map_e.get_mut(&Value::from("a")).unwrap() = Value::from(1);
+    assert_eq!(map_e, map_a);
+    assert_eq!(hash_of(&map_e), hash_of(&map_a));
+}
+
+#[test]
+fn test_map_mutation() {
+    let map_of = |source: &str| -> Map {
+        let Value::Map(map) = source.parse().unwrap() else {
+            panic!("Expected a map: {source}");
+        };
+        map
+    };
+    // The hash must be the same as for a map built from scratch:
+    let assert_same = |map: &Map, expected: &Map| {
+        assert_eq!(map, expected);
+        assert_eq!(hash_of(map), hash_of(expected));
+    };
+
+    let mut map = map_of("c: 3, a: 1, b: 2");
+
+    assert_eq!(map.remove(&Value::from("a")), Some(Value::from(1)));
+    assert_eq!(map.remove(&Value::from("a")), None);
+    assert_eq!(
+        map.keys_in_insertion_order().collect::<Vec<_>>(),
+        [&Value::from("c"), &Value::from("b")]
+    );
+    assert_same(&map, &map_of("b: 2, c: 3"));
+
+    assert_eq!(map.shift_insert(0, Value::from("a"), Value::from(1)), None);
+    assert_eq!(
+        map.shift_insert(2, Value::from("c"), Value::from(4)),
+        Some(Value::from(3))
+    );
+    assert_same(&map, &map_of("a: 1, b: 2, c: 4"));
+    assert_eq!(
+        map.keys_in_insertion_order().next(),
+        Some(&Value::from("a"))
+    );
+
+    *map.entry(Value::from("d")).or_default() = Value::from(5);
+    map.entry(Value::from("a"))
+        .and_modify(|value| *value = Value::from(0))
+        .or_insert(Value::from(42));
+    assert_same(&map, &map_of("a: 0, b: 2, c: 4, d: 5"));
+
+    if let Entry::Occupied(entry) = map.entry(Value::from("b")) {
+        assert_eq!(entry.remove(), Value::from(2));
+    } else {
+        panic!("b is in the map");
+    }
+    assert!(matches!(map.entry(Value::from("b")), Entry::Vacant(_)));
+    assert_same(&map, &map_of("a: 0, c: 4, d: 5"));
+
+    map.retain(|key, value| {
+        *value = Value::from(1);
+        key != &Value::from("c")
+    });
+    assert_same(&map, &map_of("a: 1, d: 1"));
+
+    let mut map = map_of("c: 3, a: 1, b: 2");
+    map.sort_keys();
+    assert_eq!(
+        map.keys_in_insertion_order().collect::<Vec<_>>(),
+        [&Value::from("a"), &Value::from("b"), &Value::from("c")]
+    );
+    assert_same(&map, &map_of("c: 3, a: 1, b: 2"));
 }
@@ -0,0 +1,116 @@
+use crate::Value;
+
+/// How [`Value::deep_merge`] handles a list that is in both the base and the overlay.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// The list in the overlay replaces the base list.
+    #[default]
+    Replace,
+
+    /// The elements of the overlay list are appended to the base list.
+    Append,
+
+    /// Elements with the same index are merged recursively,
+    /// and any extra elements in the overlay are appended.
+    MergeByIndex,
+}
+
+impl Value {
+    /// Merge `overlay` into `self`.
+    ///
+    /// Maps are merged recursively, with keys in the overlay taking precedence.
+    /// Lists are handled according to the [`MergeStrategy`].
+    /// Anything else in the overlay replaces what is in `self`.
+    ///
+    /// This enables layering configs, e.g. `defaults.eon` + `environment.eon` + `local.eon`.
+    ///
+    /// ## Example
+    /// ```
+    /// use eon::{MergeStrategy, Value};
+    ///
+    /// let mut config: Value = "port: 80, hosts: [\"a\"], tls: { enabled: false }".parse().unwrap();
+    /// let overlay: Value = "hosts: [\"b\"], tls: { enabled: true }".parse().unwrap();
+    /// config.deep_merge(overlay, MergeStrategy::Append);
+    ///
+    /// assert_eq!(
+    ///     config,
+    ///     "port: 80, hosts: [\"a\", \"b\"], tls: { enabled: true }".parse().unwrap()
+    /// );
+    /// ```
+    pub fn deep_merge(&mut self, overlay: Self, strategy: MergeStrategy) {
+        match (self, overlay) {
+            (Self::Map(base), Self::Map(overlay)) => {
+                for (key, value) in overlay {
+                    if let Some(existing) = base.get_mut(&key) {
+                        existing.deep_merge(value, strategy);
+                    } else {
+                        base.insert(key, value);
+                    }
+                }
+            }
+            (Self::List(base), Self::List(overlay)) => match strategy {
+                MergeStrategy::Replace => *base = overlay,
+                MergeStrategy::Append => base.extend(overlay),
+                MergeStrategy::MergeByIndex => {
+                    let mut overlay = overlay.into_iter();
+                    for (existing, value) in base.iter_mut().zip(overlay.by_ref()) {
+                        existing.deep_merge(value, strategy);
+                    }
+                    base.extend(overlay);
+                }
+            },
+            (base, overlay) => *base = overlay,
+        }
+    }
+}
+
+#[test]
+fn test_deep_merge() {
+    let base: Value = r#"
+        name: "base"
+        servers: [{ host: "a", port: 80 }, { host: "b", port: 80 }]
+        log: { level: "info", file: "log.txt" }
+    "#
+    .parse()
+    .unwrap();
+    let overlay: Value = r#"
+        servers: [{ port: 8080 }]
+        log: { level: "debug" }
+    "#
+    .parse()
+    .unwrap();
+
+    let merged = |strategy| {
+        let mut value = base.clone();
+        value.deep_merge(overlay.clone(), strategy);
+        value
+    };
+
+    let replaced = merged(MergeStrategy::Replace);
+    assert_eq!(replaced.get_path("name"), Some(&Value::from("base")));
+    assert_eq!(replaced.get_path("log.level"), Some(&Value::from("debug")));
+    assert_eq!(replaced.get_path("log.file"), Some(&Value::from("log.txt")));
+    assert_eq!(replaced.get_path("servers[0].host"), None);
+    assert_eq!(replaced.get_path("servers[1]"), None);
+
+    let appended = merged(MergeStrategy::Append);
+    assert_eq!(
+        appended.get_path("servers[0].host"),
+        Some(&Value::from("a"))
+    );
+    assert_eq!(
+        appended.get_path("servers[2].port"),
+        Some(&Value::from(8080))
+    );
+
+    let by_index = merged(MergeStrategy::MergeByIndex);
+    assert_eq!(
+        by_index.get_path("servers[0].host"),
+        Some(&Value::from("a"))
+    );
+    assert_eq!(
+        by_index.get_path("servers[0].port"),
+        Some(&Value::from(8080))
+    );
+    assert_eq!(by_index.get_path("servers[1].port"), Some(&Value::from(80)));
+}
@@ -1,20 +1,41 @@
+mod canonical;
+mod convert;
 mod map;
+mod merge;
 mod number;
+mod path;
+mod refs;
+mod size;
+mod timestamp;
 mod variant;
 
-use eon_syntax::{FormatOptions, Result, TokenTree};
+use eon_syntax::{FormatOptions, ParseOptions, Result, TokenTree, TopLevel};
 
-pub use self::{map::Map, number::Number, variant::Variant};
+use crate::CommentMap;
+
+pub use self::{
+    map::{Entry, Map, OccupiedEntry, VacantEntry},
+    merge::MergeStrategy,
+    number::Number,
+    timestamp::Timestamp,
+    variant::Variant,
+};
 
 /// Represents any Eon value.
 ///
 /// Load an Eon document into a [`Value`] using [`Value::from_str`](std::str::FromStr::from_str).
 /// Serialize a [`Value`] into an Eon string using [`Value::format`].
 ///
+/// ## Ordering
+/// Values have a total order, so they can be used as keys in e.g. a [`std::collections::BTreeMap`].
+/// Values of different types are ordered by their type, in the order they are declared here:
+/// `null < bools < numbers < timestamps < strings < bytes < lists < maps < variants`.
+/// See [`Number`] and [`Map`] for how those are ordered.
+///
 /// ## See also
 /// A [`Value`] does NOT include comments.
 /// For that, use [`eon_syntax::TokenTree`].
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Value {
     /// Special `null` value
     Null,
@@ -25,6 +46,9 @@ pub enum Value {
     /// An integer or floating point number
     Number(Number),
 
+    /// An RFC 3339 timestamp, like `2024-05-01T12:30:00Z`.
+    Timestamp(Timestamp),
+
     /// A string value, like `"Hello, world!"`
     ///
     /// Also commonly used as the key in a [`Map`].
@@ -33,6 +57,9 @@ pub enum Value {
     /// See [`Self::Variant`] for more complex sum-type (enum) variants.
     String(String),
 
+    /// Binary data, written as hex like `b"89504e47"`.
+    Bytes(Vec<u8>),
+
     /// A list of values.
     List(Vec<Value>),
 
@@ -59,11 +86,89 @@ impl Value {
         }
     }
 
+    /// An empty [`Value::Map`] with room for `capacity` key-value pairs.
+    pub fn map_with_capacity(capacity: usize) -> Self {
+        Self::Map(Map::with_capacity(capacity))
+    }
+
+    /// An empty [`Value::List`] with room for `capacity` elements.
+    pub fn list_with_capacity(capacity: usize) -> Self {
+        Self::List(Vec::with_capacity(capacity))
+    }
+
+    /// Move the value out, leaving [`Value::Null`] in its place.
+    ///
+    /// This lets you move a subtree out of a document without cloning it:
+    /// ```
+    /// # let mut value: eon::Value = "server: { port: 8080 }".parse().unwrap();
+    /// let server = value.get_path_mut("server").unwrap().take();
+    /// assert_eq!(server.get_path("port"), Some(&eon::Value::from(8080)));
+    /// assert_eq!(value.get_path("server"), Some(&eon::Value::Null));
+    /// ```
+    #[inline]
+    pub fn take(&mut self) -> Self {
+        std::mem::replace(self, Self::Null)
+    }
+
+    /// Iff this is a [`Value::List`], remove the given range of elements and move them out.
+    ///
+    /// Use `..` to drain all elements, leaving an empty list.
+    ///
+    /// # Panics
+    /// If the range is out of bounds, just like [`Vec::drain`].
+    pub fn drain_list(
+        &mut self,
+        range: impl std::ops::RangeBounds<usize>,
+    ) -> Option<std::vec::Drain<'_, Self>> {
+        if let Self::List(list) = self {
+            Some(list.drain(range))
+        } else {
+            None
+        }
+    }
+
+    /// Parse an Eon document, using the given [`ParseOptions`].
+    ///
+    /// This is like [`Value::from_str`](std::str::FromStr::from_str),
+    /// but lets you choose e.g. what to do about duplicate keys.
+    ///
+    /// A [`Value`] has no comments, so [`ParseOptions::keep_comments`] is ignored,
+    /// and comments are always skipped.
+    pub fn from_str_with_options(eon_source: &str, options: &ParseOptions) -> Result<Self> {
+        let options = options.clone().with_keep_comments(false);
+        TokenTree::parse_str_with(eon_source, &options).and_then(|tt| {
+            crate::value_from_token_tree::Converter::new(eon_source)
+                .with_duplicate_keys(options.duplicate_keys)
+                .value(&tt)
+        })
+    }
+
     /// Pretty-print a [`Value`] to an Eon string.
     ///
     /// You can parse the result with [`Value::from_str`](std::str::FromStr::from_str).
     pub fn format(&self, options: &FormatOptions) -> String {
-        TokenTree::from(self.clone()).format(options)
+        self.token_tree(options).format(options)
+    }
+
+    /// Like [`Self::format`], but with ANSI colors for printing to a terminal.
+    ///
+    /// Keys, strings, numbers, and comments each get a distinct color.
+    /// The result is NOT valid Eon.
+    pub fn format_colored(&self, options: &FormatOptions) -> String {
+        self.token_tree(options).format_colored(options)
+    }
+
+    /// Like [`Self::format`], but with comments added to the given paths.
+    ///
+    /// Comments for paths that are not in the value are ignored.
+    pub fn format_with_comments(&self, options: &FormatOptions, comments: &CommentMap) -> String {
+        let mut tree = self.token_tree(options);
+        comments.apply(&mut tree);
+        tree.format(options)
+    }
+
+    fn token_tree(&self, options: &FormatOptions) -> TokenTree<'static> {
+        crate::token_tree_from_value::token_tree(self.clone(), options)
     }
 
     /// Return the bool value iff this is a [`Value::Bool`].
@@ -84,6 +189,15 @@ impl Value {
         }
     }
 
+    /// Return the timestamp iff this is a [`Value::Timestamp`].
+    pub fn as_timestamp(&self) -> Option<&Timestamp> {
+        if let Self::Timestamp(t) = self {
+            Some(t)
+        } else {
+            None
+        }
+    }
+
     /// Return the string iff this is a [`Value::String`].
     pub fn as_string(&self) -> Option<&str> {
         if let Self::String(s) = self {
@@ -93,6 +207,15 @@ impl Value {
         }
     }
 
+    /// Return the bytes iff this is a [`Value::Bytes`].
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        if let Self::Bytes(b) = self {
+            Some(b)
+        } else {
+            None
+        }
+    }
+
     /// Return the list iff this is a [`Value::List`].
     pub fn as_list(&self) -> Option<&[Self]> {
         if let Self::List(l) = self {
@@ -119,6 +242,57 @@ impl Value {
             None
         }
     }
+
+    /// Iterate over the elements iff this is a [`Value::List`].
+    ///
+    /// For any other type of value, the iterator is empty.
+    pub fn iter_list(&self) -> std::slice::Iter<'_, Self> {
+        self.as_list().unwrap_or_default().iter()
+    }
+
+    /// Iterate over the key-value pairs iff this is a [`Value::Map`], in insertion order.
+    ///
+    /// For any other type of value, the iterator is empty.
+    ///
+    /// ```
+    /// let value: eon::Value = "a: 1, b: 2".parse().unwrap();
+    /// let keys: Vec<&str> = value.iter_map().filter_map(|(k, _)| k.as_str()).collect();
+    /// assert_eq!(keys, ["a", "b"]);
+    /// ```
+    pub fn iter_map(&self) -> impl Iterator<Item = (&Self, &Self)> {
+        self.as_map().into_iter().flatten()
+    }
+
+    /// Assert that this is a [`Value::Map`] with exactly these keys, in this order.
+    ///
+    /// Meant for tests, e.g. of golden files, that depend on the key order,
+    /// which is the insertion order (see [`Map::keys_in_insertion_order`]).
+    /// Keys that are not strings are compared using their Eon representation, e.g. `42`.
+    ///
+    /// ```
+    /// let value: eon::Value = "name: \"Eon\", version: 1".parse().unwrap();
+    /// value.assert_key_order(&["name", "version"]);
+    /// ```
+    ///
+    /// ## Panics
+    /// If this is not a map, or if the keys differ.
+    #[track_caller]
+    pub fn assert_key_order(&self, expected: &[&str]) {
+        let Some(map) = self.as_map() else {
+            panic!("Expected a map with keys {expected:?}, got: {self}");
+        };
+        let keys: Vec<String> = map
+            .keys_in_insertion_order()
+            .map(|key| match key {
+                Self::String(key) => key.clone(),
+                key => key.to_string(),
+            })
+            .collect();
+        assert_eq!(
+            keys, expected,
+            "The keys of the map are not in the expected order"
+        );
+    }
 }
 
 impl std::fmt::Display for Value {
@@ -131,10 +305,29 @@ impl std::str::FromStr for Value {
     type Err = crate::Error;
 
     fn from_str(eon_source: &str) -> Result<Self, Self::Err> {
-        TokenTree::parse_str(eon_source).and_then(|tt| Self::try_from_token_tree(eon_source, &tt))
+        Self::from_str_with_options(eon_source, &ParseOptions::default())
     }
 }
 
+/// Parse a single value, like `8080`, `"text"`, or `[1, 2, 3]`, e.g. from an environment variable or command line flag.
+///
+/// Unlike [`Value::from_str`](std::str::FromStr::from_str), this never treats the text as a map document,
+/// so `a: 1` is an error rather than a map, and `1, 2` is an error rather than a list.
+///
+/// ## Example
+/// ```
+/// assert_eq!(eon::parse_scalar("8080").unwrap(), eon::Value::from(8080));
+/// assert_eq!(eon::parse_scalar(" \"text\" ").unwrap(), eon::Value::from("text"));
+/// assert!(eon::parse_scalar("port: 8080").is_err());
+/// ```
+///
+/// ## Errors
+/// If the text is not exactly one Eon value.
+pub fn parse_scalar(text: &str) -> Result<Value> {
+    let options = ParseOptions::new().with_top_level(TopLevel::Value);
+    Value::from_str_with_options(text, &options)
+}
+
 impl From<bool> for Value {
     #[inline]
     fn from(value: bool) -> Self {
@@ -149,6 +342,13 @@ impl From<Number> for Value {
     }
 }
 
+impl From<Timestamp> for Value {
+    #[inline]
+    fn from(value: Timestamp) -> Self {
+        Self::Timestamp(value)
+    }
+}
+
 impl From<&str> for Value {
     #[inline]
     fn from(value: &str) -> Self {
@@ -170,6 +370,14 @@ impl From<Vec<Self>> for Value {
     }
 }
 
+#[cfg(feature = "decimal")]
+impl From<rust_decimal::Decimal> for Value {
+    #[inline]
+    fn from(value: rust_decimal::Decimal) -> Self {
+        Self::Number(Number::from(value))
+    }
+}
+
 impl From<Map> for Value {
     #[inline]
     fn from(value: Map) -> Self {
@@ -184,6 +392,22 @@ impl From<Variant> for Value {
     }
 }
 
+/// Builds a [`Value::List`].
+impl FromIterator<Self> for Value {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = Self>>(iter: I) -> Self {
+        Self::List(iter.into_iter().collect())
+    }
+}
+
+/// Builds a [`Value::Map`].
+impl FromIterator<(String, Self)> for Value {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = (String, Self)>>(iter: I) -> Self {
+        Self::Map(iter.into_iter().collect())
+    }
+}
+
 macro_rules! impl_value_from_number {
     ($t:ty) => {
         impl From<$t> for Value {
@@ -218,7 +442,73 @@ impl From<char> for Value {
 impl From<&[u8]> for Value {
     #[inline]
     fn from(value: &[u8]) -> Self {
-        // TODO(emilk): encode byte arrays more efficiently? Maybe a special byte-hex-encoding, like `b"ab01…"`?
-        Self::List(value.iter().map(|&b| Self::from(b)).collect())
+        Self::Bytes(value.to_vec())
     }
 }
+
+#[test]
+fn test_value_ordering() {
+    let values: Vec<Value> = [
+        "null",
+        "false",
+        "true",
+        "-1",
+        "1",
+        "+nan",
+        "2024-05-01T12:30:00Z",
+        r#""a""#,
+        r#""b""#,
+        r#"b"00""#,
+        "[]",
+        "[1, 2]",
+        "[1, 3]",
+        "{}",
+        "{a: 1, b: 2}",
+        "{a: 1, b: 3}",
+        r#""A"(1)"#,
+    ]
+    .iter()
+    .map(|s| parse_scalar(s).unwrap())
+    .collect();
+
+    for (i, a) in values.iter().enumerate() {
+        for (j, b) in values.iter().enumerate() {
+            assert_eq!(a.cmp(b), i.cmp(&j), "{a} vs {b}");
+        }
+    }
+
+    // Maps are ordered independently of the insertion order, just like equality:
+    let a = parse_scalar("{b: 2, a: 1}").unwrap();
+    let b = parse_scalar("{a: 1, b: 2}").unwrap();
+    assert_eq!(a.cmp(&b), std::cmp::Ordering::Equal);
+}
+
+#[test]
+fn test_iterators() {
+    let list: Value = (1..=3).map(Value::from).collect();
+    assert_eq!(list, parse_scalar("[1, 2, 3]").unwrap());
+    assert_eq!(list.iter_list().count(), 3);
+    assert_eq!(list.iter_map().count(), 0);
+
+    let map: Value = ["a", "b"]
+        .into_iter()
+        .map(|key| (key.to_owned(), Value::from(key.len() as u64)))
+        .collect();
+    assert_eq!(map, parse_scalar("{a: 1, b: 1}").unwrap());
+    assert_eq!(map.iter_map().count(), 2);
+    assert_eq!(map.iter_list().count(), 0);
+    assert_eq!(Value::Null.iter_list().count(), 0);
+}
+
+#[test]
+fn test_assert_key_order() {
+    let value = parse_scalar(r#"{b: 1, "quoted key": 2, 3: 3}"#).unwrap();
+    value.assert_key_order(&["b", "quoted key", "3"]);
+}
+
+#[test]
+#[should_panic(expected = "The keys of the map are not in the expected order")]
+fn test_assert_key_order_fails() {
+    let value = parse_scalar("{b: 1, a: 2}").unwrap();
+    value.assert_key_order(&["a", "b"]);
+}
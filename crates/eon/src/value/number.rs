@@ -1,10 +1,24 @@
-use std::hash::Hash;
+use std::{cmp::Ordering, hash::Hash};
+
+#[cfg(feature = "decimal")]
+use rust_decimal::prelude::ToPrimitive as _;
 
 use crate::Result;
 
 /// Represents a number (float, integer, …)
+///
+/// A number parsed from Eon remembers how it was written if that was anything out of the ordinary,
+/// like `0xff`, `1_000_000`, or `1e9`, so that formatting it again keeps the intent.
+/// See [`Self::literal`].
 #[derive(Debug, Clone)]
-pub struct Number(NumberImpl);
+pub struct Number {
+    value: NumberImpl,
+
+    /// The original source text, iff it differs notably from how we would format the number.
+    ///
+    /// Ignored by comparisons and hashing.
+    literal: Option<Box<str>>,
+}
 
 #[derive(Debug, Clone)]
 enum NumberImpl {
@@ -15,10 +29,20 @@ enum NumberImpl {
     F32(f32),
 
     F64(f64),
+
+    /// Only used for decimals whose digits would be lost by an `f64`,
+    /// so a non-integer decimal is never equal to an `f32` or `f64`.
+    #[cfg(feature = "decimal")]
+    Decimal(rust_decimal::Decimal),
 }
 
 impl PartialEq for Number {
     fn eq(&self, other: &Self) -> bool {
+        #[cfg(feature = "decimal")]
+        if self.is_decimal() || other.is_decimal() {
+            return matches!((self.as_decimal(), other.as_decimal()), (Some(a), Some(b)) if a == b);
+        }
+
         if let (Some(a), Some(b)) = (self.as_i128(), other.as_i128()) {
             a == b
         } else if let (Some(a), Some(b)) = (self.as_u128(), other.as_u128()) {
@@ -33,6 +57,89 @@ impl PartialEq for Number {
 
 impl Eq for Number {}
 
+/// Numbers are ordered by their numeric value, regardless of whether they are integers or floats,
+/// so `1 == 1.0 < 1.5 < 2`.
+///
+/// `-0.0` and `0.0` are equal, and all NaNs are equal and greater than every other number,
+/// which makes this a total order consistent with [`PartialEq`].
+impl Ord for Number {
+    fn cmp(&self, other: &Self) -> Ordering {
+        #[cfg(feature = "decimal")]
+        if self.is_decimal() || other.is_decimal() {
+            if let (Some(a), Some(b)) = (self.as_decimal(), other.as_decimal()) {
+                return a.cmp(&b);
+            }
+        }
+
+        if let (Some(a), Some(b)) = (self.as_i128(), other.as_i128()) {
+            a.cmp(&b)
+        } else if let (Some(a), Some(b)) = (self.as_u128(), other.as_u128()) {
+            a.cmp(&b)
+        } else if let (Some(a), Some(b)) = (self.as_sign_and_magnitude(), other.as_float()) {
+            cmp_integer_with_float(a, b)
+        } else if let (Some(a), Some(b)) = (self.as_float(), other.as_sign_and_magnitude()) {
+            cmp_integer_with_float(b, a).reverse()
+        } else {
+            // Two floats (or a decimal and a float that is out of the range of decimals):
+            let (a, b) = (self.as_f64_lossy(), other.as_f64_lossy());
+            match (a.is_nan(), b.is_nan()) {
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+            }
+        }
+    }
+}
+
+/// Compare an integer (as `(is_negative, magnitude)`) with a float exactly,
+/// without rounding either of them.
+fn cmp_integer_with_float((is_negative, magnitude): (bool, u128), float: f64) -> Ordering {
+    if float.is_nan() {
+        return Ordering::Less; // NaN is greater than every other number
+    }
+    match (is_negative, float < 0.0) {
+        (false, true) => return Ordering::Greater,
+        (true, false) => return Ordering::Less,
+        _ => {}
+    }
+
+    // Same sign, so compare the magnitudes:
+    let float_magnitude = float.abs();
+    let ordering = if float_magnitude >= U128_END {
+        Ordering::Less
+    } else {
+        let whole = float_magnitude.trunc();
+        // `whole` is less than 2^128, so this is exact:
+        magnitude
+            .cmp(&(whole as u128))
+            .then(if float_magnitude > whole {
+                Ordering::Less
+            } else {
+                Ordering::Equal
+            })
+    };
+
+    if is_negative {
+        ordering.reverse()
+    } else {
+        ordering
+    }
+}
+
+/// 2^128, i.e. one more than [`u128::MAX`].
+const U128_END: f64 = u128::MAX as f64;
+
+/// 2^127, i.e. one more than [`i128::MAX`].
+const I128_END: f64 = i128::MAX as f64;
+
+impl PartialOrd for Number {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl Hash for Number {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         if let Some(n) = self.as_i128() {
@@ -40,6 +147,13 @@ impl Hash for Number {
         } else if let Some(n) = self.as_u128() {
             n.hash(state);
         } else if let Some(n) = self.as_f64() {
+            #[cfg(feature = "decimal")]
+            if let NumberImpl::Decimal(decimal) = self.value {
+                // Non-integer decimals are only ever equal to other decimals:
+                decimal.normalize().hash(state);
+                return;
+            }
+
             if n == 0.0 {
                 0_u64.hash(state);
             } else if n.is_nan() {
@@ -60,21 +174,56 @@ impl Hash for Number {
 impl std::str::FromStr for Number {
     type Err = String;
 
-    fn from_str(mut string: &str) -> Result<Self, Self::Err> {
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        let mut number = Self::parse(string)?;
+        let has_notable_formatting =
+            string.contains(['_', 'e', 'E']) || string.contains("0x") || string.contains("0b");
+        if has_notable_formatting {
+            number.literal = Some(string.into());
+        }
+        Ok(number)
+    }
+}
+
+impl Number {
+    #[inline]
+    const fn new(value: NumberImpl) -> Self {
+        Self {
+            value,
+            literal: None,
+        }
+    }
+
+    /// How the number was written in the Eon source, iff that was anything out of the ordinary,
+    /// like `0xff`, `1_000_000`, or `1e9`.
+    ///
+    /// This is used when formatting the number, unless [`crate::NumberLiterals::Normalize`] is set.
+    ///
+    /// ```
+    /// let value = eon::parse_scalar("0xff").unwrap();
+    /// assert_eq!(value.as_number().unwrap().literal(), Some("0xff"));
+    /// assert_eq!(value.to_string().trim(), "0xff");
+    /// assert_eq!(value, eon::Value::from(255));
+    /// ```
+    pub fn literal(&self) -> Option<&str> {
+        self.literal.as_deref()
+    }
+
+    fn parse(mut string: &str) -> Result<Self, String> {
         if string.contains('_') {
             // We allow _ as thousands separators:
-            return Self::from_str(string.replace('_', "").as_str());
+            return Self::parse(string.replace('_', "").as_str());
         }
 
         match string {
             "+nan" => {
-                return Ok(Self(NumberImpl::F32(f32::NAN)));
+                return Ok(Self::new(NumberImpl::F32(f32::NAN)));
             }
             "-inf" => {
-                return Ok(Self(NumberImpl::F32(f32::NEG_INFINITY)));
+                return Ok(Self::new(NumberImpl::F32(f32::NEG_INFINITY)));
             }
             "+inf" => {
-                return Ok(Self(NumberImpl::F32(f32::INFINITY)));
+                return Ok(Self::new(NumberImpl::F32(f32::INFINITY)));
             }
             _ => {}
         }
@@ -105,6 +254,16 @@ impl std::str::FromStr for Number {
             let as_f64 = string.parse::<f64>().map_err(|_err| {
                 "Failed to parse float number. Expected a valid float.".to_owned()
             })?;
+            #[cfg(feature = "decimal")]
+            if !string.contains(['e', 'E']) {
+                if let Ok(decimal) = rust_decimal::Decimal::from_str_exact(string) {
+                    if decimal_from_f64(as_f64) != Some(decimal) {
+                        // The f64 would lose precision
+                        let decimal = if sign == -1 { -decimal } else { decimal };
+                        return Ok(Self::new(NumberImpl::Decimal(decimal)));
+                    }
+                }
+            }
             NumberImpl::F64(as_f64)
         } else {
             // It looks like an integer. Can we fit it in an u128?
@@ -120,11 +279,11 @@ impl std::str::FromStr for Number {
         };
 
         if sign == -1 {
-            Self(unsigned)
+            Self::new(unsigned)
                 .try_negate()
                 .ok_or_else(|| "Number too small".to_owned())
         } else {
-            Ok(Self(unsigned))
+            Ok(Self::new(unsigned))
         }
     }
 }
@@ -136,7 +295,7 @@ fn looks_like_decimal(string: &str) -> bool {
 impl Number {
     /// Returns None if the negation cannot be represented
     pub fn try_negate(&self) -> Option<Self> {
-        match self.0 {
+        match self.value {
             NumberImpl::I128(value) => {
                 if value == i128::MIN {
                     None // negation would overflow
@@ -153,13 +312,58 @@ impl Number {
             }
             NumberImpl::F32(value) => Some(NumberImpl::F32(-value)),
             NumberImpl::F64(value) => Some(NumberImpl::F64(-value)),
+            #[cfg(feature = "decimal")]
+            NumberImpl::Decimal(value) => Some(NumberImpl::Decimal(-value)),
+        }
+        .map(Self::new)
+    }
+
+    /// Is this a floating point number (as opposed to an integer)?
+    ///
+    /// `42` is an integer, while `42.0`, `+inf` and `+nan` are floats.
+    pub fn is_float(&self) -> bool {
+        #[cfg(feature = "decimal")]
+        if self.is_decimal() {
+            return true;
+        }
+        matches!(self.value, NumberImpl::F32(_) | NumberImpl::F64(_))
+    }
+
+    #[cfg(feature = "decimal")]
+    fn is_decimal(&self) -> bool {
+        matches!(self.value, NumberImpl::Decimal(_))
+    }
+
+    /// The exact decimal value of the number, iff it can be represented by a [`rust_decimal::Decimal`].
+    ///
+    /// Decimals like `0.123456789012345678901` that can't be represented exactly by an `f64`
+    /// are kept exact when the `decimal` feature is enabled.
+    /// Floats are converted via their shortest representation, so `0.1_f64` becomes exactly `0.1`.
+    ///
+    /// ```
+    /// let value = eon::parse_scalar("0.123456789012345678901").unwrap();
+    /// let decimal = value.as_number().unwrap().as_decimal().unwrap();
+    /// assert_eq!(decimal.to_string(), "0.123456789012345678901");
+    /// ```
+    #[cfg(feature = "decimal")]
+    pub fn as_decimal(&self) -> Option<rust_decimal::Decimal> {
+        match self.value {
+            NumberImpl::I128(n) => rust_decimal::Decimal::try_from_i128_with_scale(n, 0).ok(),
+            NumberImpl::U128(n) => {
+                rust_decimal::Decimal::try_from_i128_with_scale(i128::try_from(n).ok()?, 0).ok()
+            }
+            NumberImpl::F32(n) => n
+                .is_finite()
+                .then(|| decimal_from_str(ryu::Buffer::new().format_finite(n)))
+                .flatten(),
+            NumberImpl::F64(n) => decimal_from_f64(n),
+            NumberImpl::Decimal(decimal) => Some(decimal),
         }
-        .map(Self)
     }
 
     /// Returns the value iff it can be represented without narrowing.
     pub fn as_i64(&self) -> Option<i64> {
-        match self.0 {
+        match self.value {
             NumberImpl::I128(n) => i64::try_from(n).ok(),
             NumberImpl::U128(n) => i64::try_from(n).ok(),
             NumberImpl::F32(n) => {
@@ -170,12 +374,14 @@ impl Number {
                 let i = n.round() as i64;
                 if n == i as f64 { Some(i) } else { None }
             }
+            #[cfg(feature = "decimal")]
+            NumberImpl::Decimal(n) => n.is_integer().then(|| n.to_i64()).flatten(),
         }
     }
 
     /// Returns the value iff it can be represented without narrowing.
     pub fn as_u64(&self) -> Option<u64> {
-        match self.0 {
+        match self.value {
             NumberImpl::I128(n) => u64::try_from(n).ok(),
             NumberImpl::U128(n) => u64::try_from(n).ok(),
             NumberImpl::F32(n) => {
@@ -186,44 +392,70 @@ impl Number {
                 let i = n.round() as u64;
                 if n == i as f64 { Some(i) } else { None }
             }
+            #[cfg(feature = "decimal")]
+            NumberImpl::Decimal(n) => n.is_integer().then(|| n.to_u64()).flatten(),
         }
     }
 
     /// Returns the value iff it can be represented without narrowing.
     pub fn as_i128(&self) -> Option<i128> {
-        match self.0 {
+        match self.value {
             NumberImpl::I128(n) => Some(n),
             NumberImpl::U128(n) => i128::try_from(n).ok(),
-            NumberImpl::F32(n) => {
-                let i = n.round() as i128;
-                if n == i as f32 { Some(i) } else { None }
-            }
-            NumberImpl::F64(n) => {
-                let i = n.round() as i128;
-                if n == i as f64 { Some(i) } else { None }
-            }
+            NumberImpl::F32(n) => float_as_i128(f64::from(n)),
+            NumberImpl::F64(n) => float_as_i128(n),
+            #[cfg(feature = "decimal")]
+            NumberImpl::Decimal(n) => n.is_integer().then(|| n.to_i128()).flatten(),
         }
     }
 
     /// Returns the value iff it can be represented without narrowing.
     pub fn as_u128(&self) -> Option<u128> {
-        match self.0 {
+        match self.value {
             NumberImpl::I128(n) => u128::try_from(n).ok(),
             NumberImpl::U128(n) => Some(n),
-            NumberImpl::F32(n) => {
-                let i = n.round() as u128;
-                if n == i as f32 { Some(i) } else { None }
-            }
-            NumberImpl::F64(n) => {
-                let i = n.round() as u128;
-                if n == i as f64 { Some(i) } else { None }
-            }
+            NumberImpl::F32(n) => float_as_u128(f64::from(n)),
+            NumberImpl::F64(n) => float_as_u128(n),
+            #[cfg(feature = "decimal")]
+            NumberImpl::Decimal(n) => n.is_integer().then(|| n.to_u128()).flatten(),
+        }
+    }
+
+    /// The integer as `(is_negative, magnitude)`, iff it is stored as an integer.
+    fn as_sign_and_magnitude(&self) -> Option<(bool, u128)> {
+        match self.value {
+            NumberImpl::I128(n) => Some((n < 0, n.unsigned_abs())),
+            NumberImpl::U128(n) => Some((false, n)),
+            _ => None,
+        }
+    }
+
+    /// The float, iff it is stored as an `f32` or `f64`.
+    fn as_float(&self) -> Option<f64> {
+        match self.value {
+            NumberImpl::F32(n) => Some(f64::from(n)),
+            NumberImpl::F64(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    /// The closest `f64`, rounding large integers.
+    fn as_f64_lossy(&self) -> f64 {
+        match self.value {
+            NumberImpl::I128(n) => n as f64,
+            NumberImpl::U128(n) => n as f64,
+            NumberImpl::F32(n) => n as f64,
+            NumberImpl::F64(n) => n,
+            #[cfg(feature = "decimal")]
+            NumberImpl::Decimal(n) => n.to_string().parse().unwrap_or(f64::NAN), // correctly rounded
         }
     }
 
     /// Returns the value iff it can be represented without narrowing.
+    ///
+    /// The exception is decimals (with the `decimal` feature), which are rounded to the closest `f64`.
     pub fn as_f64(&self) -> Option<f64> {
-        match self.0 {
+        match self.value {
             NumberImpl::I128(n) => {
                 if n as f32 as i128 == n {
                     Some(n as f64)
@@ -232,7 +464,8 @@ impl Number {
                 }
             }
             NumberImpl::U128(n) => {
-                if n as f32 as u128 == n {
+                // `u128::MAX as f32` rounds up to infinity, which saturates back to `u128::MAX`:
+                if (n as f32).is_finite() && n as f32 as u128 == n {
                     Some(n as f64)
                 } else {
                     None
@@ -240,101 +473,152 @@ impl Number {
             }
             NumberImpl::F32(n) => Some(n as f64),
             NumberImpl::F64(n) => Some(n),
+            #[cfg(feature = "decimal")]
+            NumberImpl::Decimal(_) => Some(self.as_f64_lossy()),
         }
     }
 }
 
+/// The float as an `i128`, iff it is a whole number in range.
+fn float_as_i128(n: f64) -> Option<i128> {
+    // Casting saturates, so check the range first:
+    (n.fract() == 0.0 && -I128_END <= n && n < I128_END).then_some(n as i128)
+}
+
+/// The float as a `u128`, iff it is a whole number in range.
+fn float_as_u128(n: f64) -> Option<u128> {
+    // Casting saturates, so check the range first:
+    (n.fract() == 0.0 && 0.0 <= n && n < U128_END).then_some(n as u128)
+}
+
 impl From<i8> for Number {
     #[inline]
     fn from(value: i8) -> Self {
-        Self(NumberImpl::I128(value as _))
+        Self::new(NumberImpl::I128(value as _))
     }
 }
 
 impl From<i16> for Number {
     #[inline]
     fn from(value: i16) -> Self {
-        Self(NumberImpl::I128(value as _))
+        Self::new(NumberImpl::I128(value as _))
     }
 }
 
 impl From<i32> for Number {
     #[inline]
     fn from(value: i32) -> Self {
-        Self(NumberImpl::I128(value as _))
+        Self::new(NumberImpl::I128(value as _))
     }
 }
 
 impl From<i64> for Number {
     #[inline]
     fn from(value: i64) -> Self {
-        Self(NumberImpl::I128(value as _))
+        Self::new(NumberImpl::I128(value as _))
     }
 }
 
 impl From<i128> for Number {
     #[inline]
     fn from(value: i128) -> Self {
-        Self(NumberImpl::I128(value))
+        Self::new(NumberImpl::I128(value))
     }
 }
 
 impl From<u8> for Number {
     #[inline]
     fn from(value: u8) -> Self {
-        Self(NumberImpl::U128(value as _))
+        Self::new(NumberImpl::U128(value as _))
     }
 }
 
 impl From<u16> for Number {
     #[inline]
     fn from(value: u16) -> Self {
-        Self(NumberImpl::U128(value as _))
+        Self::new(NumberImpl::U128(value as _))
     }
 }
 
 impl From<u32> for Number {
     #[inline]
     fn from(value: u32) -> Self {
-        Self(NumberImpl::U128(value as _))
+        Self::new(NumberImpl::U128(value as _))
     }
 }
 
 impl From<u64> for Number {
     #[inline]
     fn from(value: u64) -> Self {
-        Self(NumberImpl::U128(value as _))
+        Self::new(NumberImpl::U128(value as _))
     }
 }
 
 impl From<u128> for Number {
     #[inline]
     fn from(value: u128) -> Self {
-        Self(NumberImpl::U128(value))
+        Self::new(NumberImpl::U128(value))
     }
 }
 
 impl From<f32> for Number {
     #[inline]
     fn from(value: f32) -> Self {
-        Self(NumberImpl::F32(value))
+        Self::new(NumberImpl::F32(value))
     }
 }
 
 impl From<f64> for Number {
     #[inline]
     fn from(value: f64) -> Self {
-        Self(NumberImpl::F64(value))
+        Self::new(NumberImpl::F64(value))
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl From<rust_decimal::Decimal> for Number {
+    /// Integers and decimals that an `f64` can represent exactly become normal numbers.
+    fn from(value: rust_decimal::Decimal) -> Self {
+        if value.is_integer() {
+            if let Some(n) = value.to_i128() {
+                return Self::from(n);
+            }
+        }
+        if let Some(f) = value.to_f64() {
+            if decimal_from_f64(f) == Some(value) {
+                return Self::from(f);
+            }
+        }
+        Self::new(NumberImpl::Decimal(value))
+    }
+}
+
+#[cfg(feature = "decimal")]
+fn decimal_from_str(string: &str) -> Option<rust_decimal::Decimal> {
+    rust_decimal::Decimal::from_str_exact(string)
+        .or_else(|_err| rust_decimal::Decimal::from_scientific(string))
+        .ok()
+}
+
+/// The decimal closest to the shortest representation of the float, i.e. `0.1` for `0.1_f64`.
+#[cfg(feature = "decimal")]
+fn decimal_from_f64(f: f64) -> Option<rust_decimal::Decimal> {
+    if f.is_finite() {
+        decimal_from_str(ryu::Buffer::new().format_finite(f))
+    } else {
+        None
     }
 }
 
 impl std::fmt::Display for Number {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match &self.0 {
+        match &self.value {
             NumberImpl::I128(n) => n.fmt(f),
             NumberImpl::U128(n) => n.fmt(f),
             NumberImpl::F32(n) => format_f32(*n).fmt(f),
             NumberImpl::F64(n) => format_f64(*n).fmt(f),
+            #[cfg(feature = "decimal")]
+            NumberImpl::Decimal(n) => n.fmt(f),
         }
     }
 }
@@ -395,4 +679,103 @@ mod tests {
             assert_eq!(number.as_i128(), Some(expected));
         }
     }
+
+    #[test]
+    fn test_number_ordering() {
+        use super::Number;
+
+        let sorted: Vec<Number> = [
+            f64::NEG_INFINITY.into(),
+            Number::from(i128::MIN),
+            Number::from(-1.5),
+            Number::from(-1),
+            Number::from(0),
+            Number::from(0.5_f32),
+            Number::from(1),
+            Number::from(u128::MAX),
+            f64::INFINITY.into(),
+            f64::NAN.into(),
+        ]
+        .into();
+
+        for (i, a) in sorted.iter().enumerate() {
+            for (j, b) in sorted.iter().enumerate() {
+                assert_eq!(a.cmp(b), i.cmp(&j), "{a} vs {b}");
+            }
+        }
+
+        assert_eq!(
+            Number::from(1).cmp(&Number::from(1.0)),
+            std::cmp::Ordering::Equal
+        );
+        assert_eq!(
+            Number::from(-0.0).cmp(&Number::from(0)),
+            std::cmp::Ordering::Equal
+        );
+        assert_eq!(
+            Number::from(f32::NAN).cmp(&Number::from(-f64::NAN)),
+            std::cmp::Ordering::Equal
+        );
+
+        // Integers and floats are compared exactly, even where `as f64` would round:
+        let two_pow_128 = 2.0_f64.powi(128);
+        let two_pow_127 = 2.0_f64.powi(127);
+        let cases = [
+            (Number::from(u128::MAX), Number::from(two_pow_128)),
+            (Number::from(f32::MAX), Number::from(u128::MAX)),
+            (Number::from(i128::MAX), Number::from(two_pow_127)),
+            (Number::from(-two_pow_127 - 1e30), Number::from(i128::MIN)),
+            (Number::from(u128::MAX - 1), Number::from(u128::MAX)),
+            (
+                Number::from(1_u128 << 100),
+                Number::from(2.0_f64.powi(100) + 1e15),
+            ),
+            (Number::from(-1), Number::from(-0.5)),
+        ];
+        for (smaller, larger) in cases {
+            assert!(smaller < larger, "{smaller} < {larger}");
+            assert!(larger > smaller, "{larger} > {smaller}");
+            assert_ne!(smaller, larger);
+        }
+        assert_eq!(
+            Number::from(1_u128 << 100).cmp(&Number::from(2.0_f64.powi(100))),
+            std::cmp::Ordering::Equal
+        );
+        assert_eq!(Number::from(1_u128 << 100), Number::from(2.0_f64.powi(100)));
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn test_decimal() {
+        use super::Number;
+        use rust_decimal::Decimal;
+
+        let exact = "0.123456789012345678901";
+        let number = Number::from_str(exact).unwrap();
+        assert!(number.is_float());
+        assert_eq!(number.to_string(), exact);
+        assert_eq!(number.as_decimal(), Some(Decimal::from_str(exact).unwrap()));
+        assert_ne!(number, Number::from(0.123_456_789_012_345_68));
+        assert!(number < Number::from_str("0.123456789012345678902").unwrap());
+        assert!(number > Number::from(0.1));
+        assert_eq!(
+            Number::from_str("-0.123456789012345678901").unwrap(),
+            number.try_negate().unwrap()
+        );
+
+        // Decimals that fit an f64 or an integer stay normal numbers:
+        assert_eq!(
+            Number::from_str("0.5").unwrap().as_decimal(),
+            Some(Decimal::new(5, 1))
+        );
+        assert_eq!(Number::from(Decimal::new(5, 1)), Number::from(0.5));
+        assert_eq!(Number::from(Decimal::new(500, 2)).as_i64(), Some(5));
+        assert_eq!(
+            Number::from_str("12345678901234567890.0").unwrap(),
+            Number::from(12_345_678_901_234_567_890_u64)
+        );
+
+        let value = Value::from(Decimal::from_str(exact).unwrap());
+        assert_eq!(value.to_string().trim(), exact);
+    }
 }
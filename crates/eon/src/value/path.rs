@@ -0,0 +1,194 @@
+use eon_syntax::{KeyPath, PathSegment};
+
+use crate::{Map, Number, Value};
+
+impl Value {
+    /// Look up a nested value using a path like `server.ports[2].name`.
+    ///
+    /// Keys are separated by `.`, and list indices are written in brackets.
+    /// Keys with special characters can be quoted, like `headers."Content-Type"`.
+    /// An index applied to a map looks up the integer key,
+    /// and an index applied to a variant looks up its values.
+    ///
+    /// Returns `None` if the path is invalid or does not lead to a value.
+    ///
+    /// See also [`Self::get_key_path`].
+    pub fn get_path(&self, path: &str) -> Option<&Self> {
+        self.get_key_path(&path.parse().ok()?)
+    }
+
+    /// Like [`Self::get_path`], but returns a mutable reference.
+    pub fn get_path_mut(&mut self, path: &str) -> Option<&mut Self> {
+        self.get_key_path_mut(&path.parse().ok()?)
+    }
+
+    /// Look up a nested value using an already parsed [`KeyPath`].
+    pub fn get_key_path(&self, path: &KeyPath) -> Option<&Self> {
+        path.segments
+            .iter()
+            .try_fold(self, |value, segment| value.get_segment(segment))
+    }
+
+    /// Like [`Self::get_key_path`], but returns a mutable reference.
+    pub fn get_key_path_mut(&mut self, path: &KeyPath) -> Option<&mut Self> {
+        path.segments
+            .iter()
+            .try_fold(self, |value, segment| value.get_segment_mut(segment))
+    }
+
+    fn get_segment(&self, segment: &PathSegment) -> Option<&Self> {
+        match (self, segment) {
+            (Self::Map(map), PathSegment::Key(key)) => map.get_str(key),
+            (Self::Map(map), PathSegment::Index(index)) => map.get(&index_key(*index)),
+            (Self::List(list), PathSegment::Index(index)) => list.get(*index),
+            (Self::Variant(variant), PathSegment::Index(index)) => variant.values.get(*index),
+            _ => None,
+        }
+    }
+
+    fn get_segment_mut(&mut self, segment: &PathSegment) -> Option<&mut Self> {
+        match (self, segment) {
+            (Self::Map(map), segment) => map.get_mut(&segment_key(segment)),
+            (Self::List(list), PathSegment::Index(index)) => list.get_mut(*index),
+            (Self::Variant(variant), PathSegment::Index(index)) => variant.values.get_mut(*index),
+            _ => None,
+        }
+    }
+}
+
+impl Map {
+    /// Remove the value at a path like `server.ports[2]`, and move it out.
+    ///
+    /// The removal is done with [`Map::swap_remove`] or [`Vec::swap_remove`],
+    /// so the last sibling takes the place of the removed value.
+    /// Values cannot be removed from a [`Value::Variant`], since it must have at least one value.
+    ///
+    /// Returns `None` if the path is invalid or does not lead to a value.
+    pub fn swap_remove_path(&mut self, path: &str) -> Option<Value> {
+        self.swap_remove_key_path(&path.parse().ok()?)
+    }
+
+    /// Like [`Self::swap_remove_path`], but with an already parsed [`KeyPath`].
+    pub fn swap_remove_key_path(&mut self, path: &KeyPath) -> Option<Value> {
+        let (parent_path, last) = path.split_last()?;
+        let Some((first, rest)) = parent_path.segments.split_first() else {
+            return self.swap_remove(&segment_key(last));
+        };
+        let parent = self
+            .get_mut(&segment_key(first))?
+            .get_key_path_mut(&KeyPath {
+                segments: rest.to_vec(),
+            })?;
+        match (parent, last) {
+            (Value::Map(map), segment) => map.swap_remove(&segment_key(segment)),
+            (Value::List(list), PathSegment::Index(index)) if *index < list.len() => {
+                Some(list.swap_remove(*index))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The map key that a path segment refers to.
+fn segment_key(segment: &PathSegment) -> Value {
+    match segment {
+        PathSegment::Key(key) => Value::String(key.clone()),
+        PathSegment::Index(index) => index_key(*index),
+    }
+}
+
+fn index_key(index: usize) -> Value {
+    Value::Number(Number::from(index as u64))
+}
+
+#[test]
+fn test_get_path() {
+    let mut value: Value = r#"
+        server: {
+            ports: [
+                { name: "http", port: 80 }
+                { name: "https", port: 443 }
+                { name: "ssh", port: 22 }
+            ]
+            "Content-Type": "text/plain"
+        }
+        codes: { 404: "Not found" }
+        color: "Rgb"(255, 128, 0)
+    "#
+    .parse()
+    .unwrap();
+
+    assert_eq!(
+        value.get_path("server.ports[2].name"),
+        Some(&Value::from("ssh"))
+    );
+    assert_eq!(
+        value.get_path(r#"server."Content-Type""#),
+        Some(&Value::from("text/plain"))
+    );
+    assert_eq!(
+        value.get_path("codes[404]"),
+        Some(&Value::from("Not found"))
+    );
+    assert_eq!(value.get_path("color[1]"), Some(&Value::from(128)));
+    assert_eq!(value.get_path(""), Some(&value));
+
+    assert_eq!(value.get_path("server.ports[3]"), None);
+    assert_eq!(value.get_path("server.nope"), None);
+    assert_eq!(value.get_path("server[0]"), None);
+    assert_eq!(value.get_path("server..ports"), None);
+
+    *value.get_path_mut("server.ports[0].port").unwrap() = Value::from(8080);
+    assert_eq!(
+        value.get_path("server.ports[0].port"),
+        Some(&Value::from(8080))
+    );
+}
+
+#[test]
+fn test_swap_remove_path() {
+    let value: Value = r#"
+        server: {
+            ports: [80, 443, 22]
+            host: "localhost"
+            name: "test"
+        }
+        codes: { 404: "Not found" }
+    "#
+    .parse()
+    .unwrap();
+    let Value::Map(mut map) = value else {
+        panic!("Expected a map");
+    };
+
+    assert_eq!(
+        map.swap_remove_path("server.ports[0]"),
+        Some(Value::from(80))
+    );
+    assert_eq!(
+        map.swap_remove_path("server.host"),
+        Some(Value::from("localhost"))
+    );
+    assert_eq!(
+        map.swap_remove_path("codes[404]"),
+        Some(Value::from("Not found"))
+    );
+    assert_eq!(map.swap_remove_path("server.ports[2]"), None);
+    assert_eq!(map.swap_remove_path("nope"), None);
+    assert_eq!(map.swap_remove_path(""), None);
+
+    let expected: Value = r#"
+        server: {
+            ports: [22, 443]
+            name: "test"
+        }
+        codes: {}
+    "#
+    .parse()
+    .unwrap();
+    assert_eq!(Value::Map(map.clone()), expected);
+
+    let server = map.swap_remove_path("server").unwrap();
+    assert_eq!(server.get_path("name"), Some(&Value::from("test")));
+    assert_eq!(map.keys().collect::<Vec<_>>(), [&Value::from("codes")]);
+}
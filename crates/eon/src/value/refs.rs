@@ -0,0 +1,138 @@
+use crate::{Error, Result, Value};
+
+/// The name of the variant used for references, like `"ref"("servers.default")`.
+const REF: &str = "ref";
+
+impl Value {
+    /// Replace all references like `"ref"("path.to.value")` with a copy of the value at that path.
+    ///
+    /// This lets a document define a value once, and reuse it elsewhere.
+    /// The paths are relative to the root (`self`), and use the syntax of [`Self::get_path`].
+    /// The referenced values may contain references too,
+    /// but a path may not go through another reference.
+    ///
+    /// Resolving references is opt-in, so `"ref"(…)` is just a normal variant until you call this.
+    ///
+    /// ## Example
+    /// ```
+    /// let mut config: eon::Value = r#"
+    ///     default_server: { host: "localhost", port: 8080 }
+    ///     servers: ["ref"("default_server"), { host: "example.com", port: 80 }]
+    /// "#.parse().unwrap();
+    /// config.resolve_refs().unwrap();
+    /// assert_eq!(config.get_path("servers[0].port"), Some(&eon::Value::from(8080)));
+    /// ```
+    ///
+    /// ## Errors
+    /// If a path is invalid or does not lead to a value, or if references form a cycle.
+    pub fn resolve_refs(&mut self) -> Result<()> {
+        let root = self.clone();
+        resolve_refs(self, &root, &mut vec![])
+    }
+}
+
+/// Resolve all references in `value`.
+///
+/// `stack` is the paths of the references we are currently resolving, for detecting cycles.
+fn resolve_refs(value: &mut Value, root: &Value, stack: &mut Vec<String>) -> Result<()> {
+    if let Some(path) = ref_path(value)? {
+        if stack.contains(&path) {
+            let cycle: Vec<String> = stack
+                .iter()
+                .chain([&path])
+                .map(|path| format!("{path:?}"))
+                .collect();
+            return Err(Error::custom(format!(
+                "Reference cycle: {}",
+                cycle.join(" -> ")
+            )));
+        }
+        let Some(target) = root.get_path(&path) else {
+            return Err(Error::custom(format!(
+                "{REF}({path:?}): there is no value at this path"
+            )));
+        };
+        let mut target = target.clone();
+        stack.push(path);
+        resolve_refs(&mut target, root, stack)?;
+        stack.pop();
+        *value = target;
+        return Ok(());
+    }
+
+    match value {
+        Value::Map(map) => map
+            .into_iter()
+            .try_for_each(|(_, value)| resolve_refs(value, root, stack)),
+        Value::List(list) => list
+            .iter_mut()
+            .try_for_each(|value| resolve_refs(value, root, stack)),
+        Value::Variant(variant) => variant
+            .values
+            .iter_mut()
+            .try_for_each(|value| resolve_refs(value, root, stack)),
+        Value::Null
+        | Value::Bool(_)
+        | Value::Number(_)
+        | Value::String(_)
+        | Value::Timestamp(_)
+        | Value::Bytes(_) => Ok(()),
+    }
+}
+
+/// If `value` is a reference, return its path.
+fn ref_path(value: &Value) -> Result<Option<String>> {
+    let Value::Variant(variant) = value else {
+        return Ok(None);
+    };
+    if variant.name != REF {
+        return Ok(None);
+    }
+    match variant.values.as_slice() {
+        [Value::String(path)] => Ok(Some(path.clone())),
+        _ => Err(Error::custom(format!(
+            "{REF}(…) should contain a single string path"
+        ))),
+    }
+}
+
+#[test]
+fn test_resolve_refs() {
+    let mut value: Value = r#"
+        address: { host: "localhost", port: "ref"("ports.http") }
+        ports: { http: 80 }
+        a: "ref"("address")
+        b: ["ref"("address.host"), "ref"("ports.http")]
+        c: "Some"("ref"("b[1]"))
+    "#
+    .parse()
+    .unwrap();
+    value.resolve_refs().unwrap();
+    let expected: Value = r#"
+        address: { host: "localhost", port: 80 }
+        ports: { http: 80 }
+        a: { host: "localhost", port: 80 }
+        b: ["localhost", 80]
+        c: "Some"(80)
+    "#
+    .parse()
+    .unwrap();
+    assert_eq!(value, expected);
+
+    let resolve_err = |source: &str| {
+        let mut value: Value = source.parse().unwrap();
+        value.resolve_refs().unwrap_err().message().to_owned()
+    };
+    assert_eq!(
+        resolve_err(r#"a: "ref"("nope")"#),
+        r#"ref("nope"): there is no value at this path"#
+    );
+    assert_eq!(
+        resolve_err(r#"a: "ref"("b"), b: "ref"("a")"#),
+        r#"Reference cycle: "b" -> "a" -> "b""#
+    );
+    assert_eq!(
+        resolve_err(r#"a: "ref"(1, 2)"#),
+        "ref(…) should contain a single string path"
+    );
+}
@@ -0,0 +1,150 @@
+use crate::Value;
+
+impl Value {
+    /// The total number of values in this tree, including `self`.
+    ///
+    /// Map keys are counted as values too.
+    /// For instance, `{ a: [1, 2] }` contains 5 values: the map, the key `a`, the list, and the two numbers.
+    pub fn deep_len(&self) -> usize {
+        1 + match self {
+            Self::Null
+            | Self::Bool(_)
+            | Self::Number(_)
+            | Self::Timestamp(_)
+            | Self::String(_)
+            | Self::Bytes(_) => 0,
+            Self::List(list) => list.iter().map(Self::deep_len).sum(),
+            Self::Map(map) => map
+                .iter()
+                .map(|(key, value)| key.deep_len() + value.deep_len())
+                .sum(),
+            Self::Variant(variant) => variant.values.iter().map(Self::deep_len).sum(),
+        }
+    }
+
+    /// How deeply nested this value is.
+    ///
+    /// A value that does not contain other values (like a number, or an empty list) has depth zero,
+    /// `[1, 2]` has depth one, `[[1], 2]` has depth two, etc.
+    pub fn max_depth(&self) -> usize {
+        let children_depth = match self {
+            Self::Null
+            | Self::Bool(_)
+            | Self::Number(_)
+            | Self::Timestamp(_)
+            | Self::String(_)
+            | Self::Bytes(_) => None,
+            Self::List(list) => list.iter().map(Self::max_depth).max(),
+            Self::Map(map) => map
+                .iter()
+                .map(|(key, value)| key.max_depth().max(value.max_depth()))
+                .max(),
+            Self::Variant(variant) => variant.values.iter().map(Self::max_depth).max(),
+        };
+        children_depth.map_or(0, |depth| depth + 1)
+    }
+
+    /// An estimate of how many bytes of memory this value uses, including heap allocations.
+    ///
+    /// This is useful for enforcing limits on user-provided configuration.
+    /// The estimate does not include the overhead of the memory allocator.
+    pub fn approximate_byte_size(&self) -> usize {
+        std::mem::size_of::<Self>() + self.heap_byte_size()
+    }
+
+    /// Free unused capacity of all strings, lists, and maps in this tree.
+    ///
+    /// Useful after building a large document, e.g. with [`Self::map_with_capacity`].
+    pub fn shrink_to_fit(&mut self) {
+        match self {
+            Self::Null | Self::Bool(_) | Self::Number(_) | Self::Timestamp(_) => {}
+            Self::String(string) => string.shrink_to_fit(),
+            Self::Bytes(bytes) => bytes.shrink_to_fit(),
+            Self::List(list) => {
+                list.shrink_to_fit();
+                list.iter_mut().for_each(Self::shrink_to_fit);
+            }
+            Self::Map(map) => {
+                map.shrink_to_fit();
+                // Keys are immutable, so only the values are shrunk:
+                for (_key, value) in map {
+                    value.shrink_to_fit();
+                }
+            }
+            Self::Variant(variant) => {
+                variant.name.shrink_to_fit();
+                variant.values.shrink_to_fit();
+                variant.values.iter_mut().for_each(Self::shrink_to_fit);
+            }
+        }
+    }
+
+    /// Bytes allocated on the heap by this value, not counting `self`.
+    fn heap_byte_size(&self) -> usize {
+        match self {
+            Self::Null | Self::Bool(_) | Self::Number(_) => 0,
+            Self::Timestamp(timestamp) => timestamp.as_str().len(),
+            Self::String(string) => string.capacity(),
+            Self::Bytes(bytes) => bytes.capacity(),
+            Self::List(list) => {
+                list.capacity() * std::mem::size_of::<Self>()
+                    + list.iter().map(Self::heap_byte_size).sum::<usize>()
+            }
+            Self::Map(map) => {
+                // Each entry holds a key, a value, and a hash, plus an index in the hash table:
+                let entry_size = 2 * std::mem::size_of::<Self>() + 2 * std::mem::size_of::<usize>();
+                map.capacity() * entry_size
+                    + map
+                        .iter()
+                        .map(|(key, value)| key.heap_byte_size() + value.heap_byte_size())
+                        .sum::<usize>()
+            }
+            Self::Variant(variant) => {
+                variant.name.capacity()
+                    + variant.values.capacity() * std::mem::size_of::<Self>()
+                    + variant
+                        .values
+                        .iter()
+                        .map(Self::heap_byte_size)
+                        .sum::<usize>()
+            }
+        }
+    }
+}
+
+#[test]
+fn test_size_helpers() {
+    let value: Value = r#"{ a: [1, 2], b: "Rgb"(1, [2]) }"#.parse().unwrap();
+    assert_eq!(Value::from(1).deep_len(), 1);
+    assert_eq!(value.deep_len(), 10);
+
+    assert_eq!(Value::from(1).max_depth(), 0);
+    assert_eq!(Value::List(vec![]).max_depth(), 0);
+    assert_eq!(value.max_depth(), 3);
+
+    let small = Value::from("x");
+    let big = Value::from("x".repeat(1000));
+    assert!(small.approximate_byte_size() < big.approximate_byte_size());
+    assert!(1000 < big.approximate_byte_size());
+    assert!(value.approximate_byte_size() > 10 * std::mem::size_of::<Value>());
+}
+
+#[test]
+fn test_shrink_to_fit() {
+    let mut value = Value::map_with_capacity(100);
+    if let Value::Map(map) = &mut value {
+        let mut list = Value::list_with_capacity(100);
+        if let Value::List(list) = &mut list {
+            list.push(Value::from(1));
+        }
+        map.insert(Value::from("list"), list);
+    }
+
+    let size_before = value.approximate_byte_size();
+    value.shrink_to_fit();
+    assert!(
+        value.approximate_byte_size() < size_before / 10,
+        "Expected shrink_to_fit to free most of the unused capacity"
+    );
+    assert_eq!(value, "list: [1]".parse().unwrap());
+}
@@ -0,0 +1,158 @@
+/// An RFC 3339 timestamp, like `2024-05-01T12:30:00Z` or `2024-05-01T14:30:00.5+02:00`.
+///
+/// In Eon these are written without quotes.
+///
+/// The timestamp is stored as text, so any precision and UTC offset is preserved exactly.
+/// Two timestamps are equal if their text is equal,
+/// so `12:30:00Z` and `14:30:00+02:00` are NOT considered equal, even though they are the same instant.
+///
+/// Use e.g. `chrono` or `time` to do date arithmetic:
+/// parse [`Self::as_str`] with their RFC 3339 parsers.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Timestamp(String);
+
+impl Timestamp {
+    /// The RFC 3339 text of the timestamp, like `2024-05-01T12:30:00Z`.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::str::FromStr for Timestamp {
+    type Err = String;
+
+    /// Parse an RFC 3339 timestamp.
+    ///
+    /// A lowercase `t` or `z` is accepted, but stored as uppercase.
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        let error = || format!("Invalid RFC 3339 timestamp: {string:?}");
+
+        let bytes = string.as_bytes();
+        if bytes.len() < 20 || !bytes.is_ascii() {
+            return Err(error());
+        }
+
+        let separators_ok = bytes[4] == b'-'
+            && bytes[7] == b'-'
+            && matches!(bytes[10], b'T' | b't')
+            && bytes[13] == b':'
+            && bytes[16] == b':';
+        if !separators_ok {
+            return Err(error());
+        }
+
+        let (Some(year), Some(month), Some(day), Some(hour), Some(minute), Some(second)) = (
+            parse_digits(&string[0..4]),
+            parse_digits(&string[5..7]),
+            parse_digits(&string[8..10]),
+            parse_digits(&string[11..13]),
+            parse_digits(&string[14..16]),
+            parse_digits(&string[17..19]),
+        ) else {
+            return Err(error());
+        };
+
+        let mut rest = &string[19..];
+        if let Some(fraction) = rest.strip_prefix('.') {
+            let num_digits = fraction.bytes().take_while(u8::is_ascii_digit).count();
+            if num_digits == 0 {
+                return Err(error());
+            }
+            rest = &fraction[num_digits..];
+        }
+
+        let offset_ok = match rest.as_bytes() {
+            [b'Z' | b'z'] => true,
+            [b'+' | b'-', _, _, b':', _, _] => matches!(
+                (parse_digits(&rest[1..3]), parse_digits(&rest[4..6])),
+                (Some(0..=23), Some(0..=59))
+            ),
+            _ => false,
+        };
+
+        let is_leap_year = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+        let days_in_month = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if is_leap_year => 29,
+            2 => 28,
+            _ => 0,
+        };
+
+        // Seconds can be 60 on a leap second.
+        if offset_ok && 1 <= day && day <= days_in_month && hour < 24 && minute < 60 && second <= 60
+        {
+            Ok(Self(string.to_ascii_uppercase()))
+        } else {
+            Err(error())
+        }
+    }
+}
+
+fn parse_digits(digits: &str) -> Option<u32> {
+    if digits.bytes().all(|b| b.is_ascii_digit()) {
+        digits.parse().ok()
+    } else {
+        None
+    }
+}
+
+#[test]
+fn test_timestamp() {
+    for valid in [
+        "2024-05-01T12:30:00Z",
+        "2024-05-01T12:30:00.123456Z",
+        "2024-05-01T14:30:00+02:00",
+        "2024-05-01T07:30:00.5-05:00",
+        "2024-02-29T00:00:00Z",
+        "2016-12-31T23:59:60Z",
+    ] {
+        let timestamp: Timestamp = valid.parse().unwrap();
+        assert_eq!(timestamp.as_str(), valid);
+    }
+
+    assert_eq!(
+        "2024-05-01t12:30:00z"
+            .parse::<Timestamp>()
+            .unwrap()
+            .as_str(),
+        "2024-05-01T12:30:00Z"
+    );
+
+    for invalid in [
+        "2024-05-01",
+        "2024-05-01T12:30:00",
+        "2024-05-01 12:30:00Z",
+        "2024-13-01T12:30:00Z",
+        "2023-02-29T12:30:00Z",
+        "2024-05-01T24:00:00Z",
+        "2024-05-01T12:30:00.Z",
+        "2024-05-01T12:30:00+2:00",
+        "2024-05-01T12:30:00+24:00",
+        "2024-05-01T12:30:00Zjunk",
+    ] {
+        assert!(invalid.parse::<Timestamp>().is_err(), "{invalid}");
+    }
+}
+
+#[test]
+fn test_timestamp_value() {
+    use crate::Value;
+
+    let value: Value = "when: 2024-05-01T12:30:00Z".parse().unwrap();
+    let timestamp = value.as_map().unwrap().get_str("when").unwrap();
+    assert_eq!(
+        timestamp.as_timestamp().map(Timestamp::as_str),
+        Some("2024-05-01T12:30:00Z")
+    );
+    assert_eq!(value.to_string().trim(), "when: 2024-05-01T12:30:00Z");
+
+    assert!("when: 2024-05-01T25:30:00Z".parse::<Value>().is_err());
+}
@@ -6,7 +6,7 @@ use crate::Value;
 ///
 /// For simple enum types (e.g. `enum Maybe { Yes, No }`),
 /// the variants will be represented as [`Value::String`] instead.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Variant {
     /// The name of the variant, like `Rgb`.
     pub name: String,
@@ -5,16 +5,40 @@
 
 use std::str::FromStr as _;
 
-use crate::{Error, Map, Result, Value};
+use crate::{Error, Map, MergeStrategy, Result, Value, include::Loader};
 
-use eon_syntax::{Span, TokenKeyValue, TokenTree, TokenValue, TokenVariant, unescape_and_unquote};
+use eon_syntax::{
+    DuplicateKeyPolicy, Span, TokenKeyValue, TokenMap, TokenTree, TokenValue, TokenVariant,
+    UNNAMED_SOURCE, unescape_and_unquote, unquote_bytes,
+};
 
 impl Value {
     /// Try to parse a [`TokenTree`] into a [`Value`].
     ///
     /// You must provide the full Eon source string so that we can produce good error messages.
     pub fn try_from_token_tree(eon_source: &str, tt: &TokenTree<'_>) -> Result<Self> {
-        Self::try_from_tree_value(eon_source, tt.span, &tt.value)
+        Converter::new(eon_source).value(tt)
+    }
+
+    /// Like [`Self::try_from_token_tree`], but keep going after errors, and return all of them.
+    ///
+    /// This reports every bad number, bad escape sequence, unknown keyword, and duplicate key
+    /// in the tree, so that they can all be fixed at once.
+    pub fn try_from_token_tree_all_errors(
+        eon_source: &str,
+        tt: &TokenTree<'_>,
+    ) -> Result<Self, Vec<Error>> {
+        let mut converter = Converter::new(eon_source).recovering();
+        let result = converter.value(tt);
+        let mut errors = converter.recovered_errors.take().unwrap_or_default();
+        match result {
+            Ok(value) if errors.is_empty() => Ok(value),
+            Ok(_) => Err(errors),
+            Err(err) => {
+                errors.push(err);
+                Err(errors)
+            }
+        }
     }
 
     /// Try to parse a [`TokenValue`] into a [`Value`].
@@ -25,34 +49,154 @@ impl Value {
         span: Option<Span>,
         value: &TokenValue<'_>,
     ) -> Result<Self> {
+        Converter::new(eon_source).tree_value(span, value)
+    }
+}
+
+/// Where a key in a [`Map`] was defined.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct KeyOrigin {
+    /// Index of the source, in [`Converter::source`].
+    pub source: usize,
+
+    /// Span of the key in that source.
+    pub span: Option<Span>,
+}
+
+/// Converts a [`TokenTree`] into a [`Value`].
+pub(crate) struct Converter<'a> {
+    eon_source: &'a str,
+
+    /// Index of `eon_source` in the sources of the `loader`.
+    source_index: usize,
+
+    /// Resolves `@include` directives. Without it, they are an error.
+    loader: Option<&'a mut Loader>,
+
+    duplicate_keys: DuplicateKeyPolicy,
+
+    /// If set, we are recovering from errors, and collecting them here.
+    recovered_errors: Option<Vec<Error>>,
+}
+
+impl<'a> Converter<'a> {
+    pub fn new(eon_source: &'a str) -> Self {
+        Self {
+            eon_source,
+            source_index: 0,
+            loader: None,
+            duplicate_keys: DuplicateKeyPolicy::Error,
+            recovered_errors: None,
+        }
+    }
+
+    /// Keep converting after errors, collecting them instead of returning them.
+    ///
+    /// Values that fail to convert are replaced with [`Value::Null`].
+    fn recovering(mut self) -> Self {
+        self.recovered_errors = Some(vec![]);
+        self
+    }
+
+    fn num_recovered_errors(&self) -> usize {
+        self.recovered_errors.as_ref().map_or(0, Vec::len)
+    }
+
+    /// When recovering, remember the error and return `Ok`. Otherwise return the error.
+    fn recover(&mut self, error: Error) -> Result {
+        if let Some(errors) = &mut self.recovered_errors {
+            errors.push(error);
+            Ok(())
+        } else {
+            Err(error)
+        }
+    }
+
+    pub fn with_duplicate_keys(mut self, duplicate_keys: DuplicateKeyPolicy) -> Self {
+        self.duplicate_keys = duplicate_keys;
+        self
+    }
+
+    /// Resolve includes using the given loader, where `eon_source` is its source number `source_index`.
+    pub fn with_loader(mut self, loader: &'a mut Loader, source_index: usize) -> Self {
+        self.loader = Some(loader);
+        self.source_index = source_index;
+        self
+    }
+
+    /// The `(name, contents)` of the source with the given index.
+    fn source(&self, index: usize) -> (String, String) {
+        if let Some(loader) = &self.loader {
+            loader.source(index).clone()
+        } else {
+            (UNNAMED_SOURCE.to_owned(), self.eon_source.to_owned())
+        }
+    }
+
+    pub fn value(&mut self, tt: &TokenTree<'_>) -> Result<Value> {
+        self.tree_value(tt.span, &tt.value)
+    }
+
+    fn tree_value(&mut self, span: Option<Span>, value: &TokenValue<'_>) -> Result<Value> {
         match value {
-            TokenValue::Identifier(identifier) => match identifier.as_ref() {
-                "null" => Ok(Self::Null),
-                "true" => Ok(Self::Bool(true)),
-                "false" => Ok(Self::Bool(false)),
-                _ => {
-                    let suggestion = match identifier.to_lowercase().as_str() {
-                        "inf" => Some("+inf or -inf"),
-                        "nan" => Some("+nan"),
-                        "false" => Some("false"),
-                        "true" => Some("true"),
-                        "nil" | "null" | "none" => Some("null"),
-                        _ => None,
-                    };
-
-                    let msg = if let Some(suggestion) = suggestion {
-                        format!("Unknown keyword {identifier:?}. Did you mean: {suggestion}?")
-                    } else {
-                        format!(
-                            "Unknown keyword {identifier:?}. Expected 'null', 'true', or 'false'."
-                        )
-                    };
-
-                    Err(Error::new(eon_source, span, msg))
+            TokenValue::List(list) => Ok(Value::List(
+                list.values
+                    .iter()
+                    .map(|value| self.value(value))
+                    .collect::<Result<_>>()?,
+            )),
+            TokenValue::Map(tt_map) => self.map(tt_map).map(|(map, _)| Value::Map(map)),
+            TokenValue::Variant(variant) => {
+                let TokenVariant {
+                    name_span,
+                    quoted_name,
+                    values,
+                    closing_comments: _,
+                    trailing_comma: _,
+                } = variant;
+                let name = match unescape_and_unquote(quoted_name) {
+                    Ok(name) => name,
+                    Err(err) => {
+                        self.recover(Error::new(
+                            self.eon_source,
+                            *name_span,
+                            format!("Failed to unescape string: {err}. The string: {quoted_name}"),
+                        ))?;
+                        quoted_name.to_string()
+                    }
+                };
+                let values = values
+                    .iter()
+                    .map(|token_tree| self.value(token_tree))
+                    .collect::<Result<_>>()?;
+                Ok(Value::new_variant(name, values))
+            }
+            _ => match self.leaf_value(span, value) {
+                Ok(value) => Ok(value),
+                Err(err) => {
+                    self.recover(err)?;
+                    Ok(Value::Null)
                 }
             },
+        }
+    }
+
+    /// Convert anything but a list, map, or variant.
+    fn leaf_value(&self, span: Option<Span>, value: &TokenValue<'_>) -> Result<Value> {
+        let eon_source = self.eon_source;
+        match value {
+            TokenValue::Identifier(identifier) => match identifier.as_ref() {
+                "null" => Ok(Value::Null),
+                "true" => Ok(Value::Bool(true)),
+                "false" => Ok(Value::Bool(false)),
+                _ => Err(Error::new(
+                    eon_source,
+                    span,
+                    crate::suggestions::unknown_keyword_message(identifier),
+                )),
+            },
             TokenValue::Number(string) => crate::Number::from_str(string)
-                .map(Self::Number)
+                .map(Value::Number)
                 .map_err(|err| {
                     Error::new(
                         eon_source,
@@ -60,8 +204,14 @@ impl Value {
                         format!("Failed to parse number: {err}. The string: {string:?}"),
                     )
                 }),
+            TokenValue::Timestamp(string) => crate::Timestamp::from_str(string)
+                .map(Value::Timestamp)
+                .map_err(|err| Error::new(eon_source, span, err)),
+            TokenValue::Bytes(literal) => unquote_bytes(literal)
+                .map(Value::Bytes)
+                .map_err(|err| Error::new(eon_source, span, err)),
             TokenValue::QuotedString(escaped) => unescape_and_unquote(escaped)
-                .map(Self::String)
+                .map(Value::String)
                 .map_err(|err| {
                     Error::new(
                         eon_source,
@@ -69,46 +219,99 @@ impl Value {
                         format!("Failed to unescape string: {err}. The string: {escaped}"),
                     )
                 }),
-            TokenValue::List(list) => Ok(Self::List(
-                list.values
-                    .iter()
-                    .map(|value| Self::try_from_token_tree(eon_source, value))
-                    .collect::<Result<_>>()?,
-            )),
-            TokenValue::Map(tt_map) => {
-                let mut map = Map::with_capacity(tt_map.key_values.len());
-                for TokenKeyValue { key: key_tt, value } in &tt_map.key_values {
-                    let key = match &key_tt.value {
-                        TokenValue::Identifier(key) => Self::String(key.to_string()),
-                        _ => Self::try_from_token_tree(eon_source, key_tt)?,
-                    };
-                    let value = Self::try_from_token_tree(eon_source, value)?;
-                    if map.insert(key, value).is_some() {
-                        return Err(Error::new(eon_source, key_tt.span, "Duplicate key in map"));
-                    }
+            TokenValue::List(_) | TokenValue::Map(_) | TokenValue::Variant(_) => {
+                unreachable!("Handled by Converter::tree_value")
+            }
+        }
+    }
+
+    /// Convert a map, and also return where each of its keys was defined.
+    pub fn map(&mut self, tt_map: &TokenMap<'_>) -> Result<(Map, Vec<KeyOrigin>)> {
+        let mut map = Map::with_capacity(tt_map.key_values.len());
+        let mut origins = Vec::with_capacity(tt_map.key_values.len());
+
+        for include in &tt_map.includes {
+            let Some(loader) = self.loader.as_deref_mut() else {
+                self.recover(Error::new(
+                    self.eon_source,
+                    include.span,
+                    "@include is only supported when loading a file, e.g. with Value::from_file",
+                ))?;
+                continue;
+            };
+            let (included, included_origins) = match loader.include(self.source_index, include) {
+                Ok(included) => included,
+                Err(err) => {
+                    self.recover(err)?;
+                    continue;
                 }
-                Ok(Self::Map(map))
+            };
+            for ((key, value), origin) in included.into_iter().zip(included_origins) {
+                self.insert(&mut map, &mut origins, key, value, origin)?;
             }
-            TokenValue::Variant(variant) => {
-                let TokenVariant {
-                    name_span,
-                    quoted_name,
-                    values,
-                    closing_comments: _,
-                } = variant;
-                let name = unescape_and_unquote(quoted_name).map_err(|err| {
-                    Error::new(
-                        eon_source,
-                        *name_span,
-                        format!("Failed to unescape string: {err}. The string: {quoted_name}"),
-                    )
-                })?;
-                let values = values
-                    .iter()
-                    .map(|token_tree| Self::try_from_token_tree(eon_source, token_tree))
-                    .collect::<Result<_>>()?;
-                Ok(Self::new_variant(name, values))
+        }
+
+        for TokenKeyValue { key: key_tt, value } in &tt_map.key_values {
+            let num_errors = self.num_recovered_errors();
+            let key = match &key_tt.value {
+                TokenValue::Identifier(key) => Value::String(key.to_string()),
+                _ => self.value(key_tt)?,
+            };
+            if self.num_recovered_errors() != num_errors {
+                // The key is broken, so don't report it as a duplicate of another broken key:
+                self.value(value)?;
+                continue;
+            }
+            let value = self.value(value)?;
+            let origin = KeyOrigin {
+                source: self.source_index,
+                span: key_tt.span,
+            };
+            self.insert(&mut map, &mut origins, key, value, origin)?;
+        }
+
+        Ok((map, origins))
+    }
+
+    /// Insert into a map, applying the duplicate key policy.
+    fn insert(
+        &mut self,
+        map: &mut Map,
+        origins: &mut Vec<KeyOrigin>,
+        key: Value,
+        value: Value,
+        origin: KeyOrigin,
+    ) -> Result {
+        let Some(previous_index) = map.get_index_of(&key) else {
+            map.insert(key, value);
+            origins.push(origin);
+            return Ok(());
+        };
+
+        match self.duplicate_keys {
+            DuplicateKeyPolicy::Error => {
+                self.recover(self.duplicate_key_error(origins[previous_index], origin))
+            }
+            DuplicateKeyPolicy::FirstWins => Ok(()),
+            DuplicateKeyPolicy::LastWins => {
+                map.insert(key, value);
+                Ok(())
+            }
+            DuplicateKeyPolicy::Merge => {
+                if let Some(existing) = map.get_mut(&key) {
+                    existing.deep_merge(value, MergeStrategy::Replace);
+                }
+                Ok(())
             }
         }
     }
+
+    fn duplicate_key_error(&self, previous: KeyOrigin, new: KeyOrigin) -> Error {
+        let (previous_name, previous_source) = self.source(previous.source);
+        let (name, source) = self.source(new.source);
+        Error::duplicate_key(
+            (&previous_name, &previous_source, previous.span),
+            (&name, &source, new.span),
+        )
+    }
 }
@@ -0,0 +1,156 @@
+#![cfg(feature = "derive")]
+
+use std::collections::BTreeMap;
+
+use eon::{FromEon, ToEon, Value};
+
+#[derive(Debug, PartialEq, ToEon, FromEon)]
+struct Config {
+    /// The port to listen on
+    port: u16,
+
+    /// Where to serve from
+    server: Server,
+
+    #[eon(rename = "verbosity", default)]
+    log_level: String,
+
+    #[eon(default = "default_retries")]
+    retries: u32,
+
+    #[eon(skip)]
+    cache: Vec<u8>,
+
+    nickname: Option<String>,
+    colors: Vec<Color>,
+    limits: BTreeMap<String, Limit>,
+}
+
+fn default_retries() -> u32 {
+    3
+}
+
+#[derive(Debug, PartialEq, ToEon, FromEon)]
+struct Server {
+    /// Hostname or IP
+    host: String,
+}
+
+#[derive(Debug, PartialEq, ToEon, FromEon)]
+struct Limit(u32);
+
+#[derive(Debug, PartialEq, ToEon, FromEon)]
+enum Color {
+    Red,
+    #[eon(rename = "blue")]
+    Blue,
+    Rgb(u8, u8, u8),
+    Hsl {
+        hue: f32,
+        saturation: f32,
+    },
+}
+
+#[derive(Debug, PartialEq, ToEon, FromEon)]
+struct Point(i32, i32);
+
+#[derive(Debug, PartialEq, ToEon, FromEon)]
+struct Wrapper<T>(T);
+
+#[test]
+fn test_derive_round_trip() {
+    let config = Config {
+        port: 8080,
+        server: Server {
+            host: "localhost".to_owned(),
+        },
+        log_level: "info".to_owned(),
+        retries: 5,
+        cache: vec![],
+        nickname: None,
+        colors: vec![
+            Color::Red,
+            Color::Blue,
+            Color::Rgb(1, 2, 3),
+            Color::Hsl {
+                hue: 0.5,
+                saturation: 1.0,
+            },
+        ],
+        limits: BTreeMap::from([("cpu".to_owned(), Limit(4))]),
+    };
+
+    let value = config.to_eon();
+    assert_eq!(value.get("verbosity"), Some(&Value::from("info")));
+    assert_eq!(value.get("cache"), None, "Skipped");
+    assert_eq!(Config::from_eon(&value).unwrap(), config);
+
+    let eon = config.to_eon_string();
+    assert!(
+        eon.contains("// The port to listen on\nport: 8080"),
+        "{eon}"
+    );
+    assert!(eon.contains("// Hostname or IP\n"), "{eon}");
+    assert!(eon.contains(r#""Rgb"(1, 2, 3)"#), "{eon}");
+    assert!(eon.contains(r#""blue""#), "{eon}");
+    assert_eq!(Config::from_eon_str(&eon).unwrap(), config);
+
+    assert_eq!(Point(1, -2).to_eon(), "[1, -2]".parse::<Value>().unwrap());
+    assert_eq!(Point::from_eon_str("[1, -2]").unwrap(), Point(1, -2));
+    assert_eq!(Wrapper(true).to_eon(), Value::Bool(true));
+    assert_eq!(
+        Wrapper::<Option<u8>>::from_missing(),
+        Some(Wrapper(None)),
+        "Newtypes are transparent"
+    );
+}
+
+#[test]
+fn test_derive_defaults() {
+    let config = Config::from_eon_str(
+        r#"
+        port: 80
+        server: {host: "example.com"}
+        colors: []
+        limits: {}
+        "#,
+    )
+    .unwrap();
+    assert_eq!(config.log_level, "");
+    assert_eq!(config.retries, 3);
+    assert_eq!(config.nickname, None);
+}
+
+#[test]
+fn test_derive_errors() {
+    let err = |eon: &str| Config::from_eon_str(eon).unwrap_err().message().to_owned();
+
+    assert_eq!(err("server: {host: \"x\"}"), "Missing `port`");
+    assert_eq!(
+        err("port: 80, server: {host: 42}, colors: [], limits: {}"),
+        "server: host: Expected a string, got a number"
+    );
+    assert_eq!(
+        err(r#"port: 80, server: {host: ""}, colors: ["Red", "Bleu"], limits: {}"#),
+        "colors: [1]: Unknown variant `Bleu`, expected one of: `Red`, `blue`, `Rgb`, `Hsl`. Did you mean `blue`?"
+    );
+    assert_eq!(
+        err(r#"port: 80, server: {host: ""}, colors: ["Rgb"(1, 2)], limits: {}"#),
+        "colors: [0]: Expected 3 value(s), got 2"
+    );
+    assert_eq!(
+        err(r#"port: 80, server: {host: ""}, colors: ["Rgb"], limits: {}"#),
+        r#"colors: [0]: Expected values for variant `Rgb`, like `"Rgb"(…)`"#
+    );
+
+    // UIs can offer the alternatives, closest first:
+    let err = Config::from_eon_str(r#"port: 80, server: {host: ""}, colors: ["Bleu"], limits: {}"#)
+        .unwrap_err();
+    assert_eq!(
+        err.kind(),
+        &eon::ErrorKind::UnknownVariant {
+            found: "Bleu".to_owned(),
+            expected: ["blue", "Red", "Rgb", "Hsl"].map(str::to_owned).to_vec(),
+        }
+    );
+}
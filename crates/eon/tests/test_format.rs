@@ -2,7 +2,7 @@
 //
 // `cargo insta test --all-features --accept`
 
-use eon::{Number, Value};
+use eon::{DocBuilder, FormatOptions, Number, Value};
 
 #[test]
 fn test_format() {
@@ -41,3 +41,112 @@ fn test_format() {
     let formatted = value.format(&Default::default());
     insta::assert_snapshot!(formatted);
 }
+
+#[test]
+fn test_doc_builder() {
+    let doc = DocBuilder::map()
+        .comment("// The server config")
+        .key("server")
+        .tree(
+            DocBuilder::map()
+                .key("port")
+                .value(8080)
+                .suffix_comment("// TCP port")
+                .key("Content-Type")
+                .value("text/plain"),
+        )
+        .key("ids")
+        .tree(
+            DocBuilder::list()
+                .value(1)
+                .comment("// The important one")
+                .value(2)
+                .comment("// More to come"),
+        )
+        .comment("// The end")
+        .build();
+
+    insta::assert_snapshot!(doc.format(&FormatOptions::default()), @r#"
+    // The server config
+    server: {
+    	port: 8080 // TCP port
+    	"Content-Type": "text/plain"
+    }
+    ids: [
+    	1
+
+    	// The important one
+    	2
+
+    	// More to come
+    ]
+
+    // The end
+    "#);
+}
+
+#[test]
+fn test_format_colored() {
+    let value: Value = "name: \"eon\"\nversion: 1".parse().unwrap();
+    assert_eq!(
+        value.format_colored(&FormatOptions::default()),
+        "\x1b[34;1mname\x1b[0m: \x1b[32m\"eon\"\x1b[0m\n\x1b[34;1mversion\x1b[0m: \x1b[36m1\x1b[0m\n"
+    );
+}
+
+#[test]
+fn test_number_literals() {
+    let source = "mask: 0xff\nflags: 0b1010\nbudget: 1_000_000\nspeed: 1e9\nplain: +42\n";
+    let value: Value = source.parse().unwrap();
+
+    assert_eq!(value.get("mask"), Some(&Value::from(255)));
+    assert_eq!(
+        value.format(&FormatOptions::default()),
+        "mask: 0xff\nflags: 0b1010\nbudget: 1_000_000\nspeed: 1e9\nplain: 42\n"
+    );
+
+    let options = FormatOptions::default().with_number_literals(eon::NumberLiterals::Normalize);
+    assert_eq!(
+        value.format(&options),
+        "mask: 255\nflags: 10\nbudget: 1000000\nspeed: 1000000000.0\nplain: 42\n"
+    );
+
+    // Numbers that did not come from Eon have no literal:
+    assert_eq!(Value::from(255).to_string().trim(), "255");
+}
+
+#[test]
+fn test_format_compact() {
+    let source = r#"
+        // The server config
+        server: {
+            port: 8080 // TCP port
+            "Content-Type": "text/plain"
+        }
+        ids: [1, 2, 3]
+        color: "Rgb"(255, 0, 0)
+        mask: 0xff
+        nothing: null
+        empty: {}
+    "#;
+
+    let compact = eon::reformat(source, &FormatOptions::compact()).unwrap();
+    assert_eq!(
+        compact,
+        r#"{server:{port:8080,"Content-Type":"text/plain"},ids:[1,2,3],color:"Rgb"(255,0,0),mask:0xff,nothing:null,empty:{}}"#
+    );
+
+    let value: Value = source.parse().unwrap();
+    assert_eq!(compact.parse::<Value>().unwrap(), value);
+
+    // A `Value` quotes all keys of a map if any of them needs quoting:
+    let formatted = value.format(&FormatOptions::compact());
+    assert!(!formatted.contains('\n'), "{formatted}");
+    assert_eq!(formatted.parse::<Value>().unwrap(), value);
+
+    // Multi-line strings are escaped to keep everything on one line:
+    let value: Value = "text: '''\nline 1\nline 2'''".parse().unwrap();
+    let compact = eon::reformat("text: '''\nline 1\nline 2'''", &FormatOptions::compact()).unwrap();
+    assert!(!compact.contains('\n'), "{compact}");
+    assert_eq!(compact.parse::<Value>().unwrap(), value);
+}
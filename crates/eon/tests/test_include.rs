@@ -0,0 +1,66 @@
+// Update snapshot tests by running:
+//
+// `cargo insta test --all-features --accept`
+
+use eon::{DuplicateKeyPolicy, ParseOptions, Value};
+
+#[test]
+fn test_include() {
+    let value = Value::from_file("tests/include/main.eon").unwrap();
+    assert_eq!(
+        value.to_string(),
+        r#"log_level: "info"
+retries: 3
+name: "main"
+server: { host: "localhost", port: 8080 }
+"#
+    );
+}
+
+#[test]
+fn test_include_duplicate_key() {
+    insta::assert_snapshot!(Value::from_file("tests/include/duplicate.eon").unwrap_err(), @"
+    Error: Duplicate key in map
+       ╭─[ tests/include/defaults.eon:3:1 ]
+       │
+     3 │ retries: 3
+       │ ───┬───
+       │    ╰───── First defined here
+       │
+       ├─[ tests/include/duplicate.eon:3:1 ]
+       │
+     3 │ retries: 5
+       │ ───┬───
+       │    ╰───── Defined again here
+    ───╯
+    ");
+
+    let options = ParseOptions::new().with_duplicate_keys(DuplicateKeyPolicy::LastWins);
+    let value = Value::from_file_with_options("tests/include/duplicate.eon", &options).unwrap();
+    assert_eq!(value.get_path("retries"), Some(&Value::from(5)));
+}
+
+#[test]
+fn test_include_errors() {
+    insta::assert_snapshot!(Value::from_file("tests/include/cycle_a.eon").unwrap_err(), @r#"
+    Error: Include cycle: tests/include/cycle_a.eon -> tests/include/cycle_b.eon -> tests/include/cycle_a.eon
+       ╭─[ tests/include/cycle_b.eon:1:1 ]
+       │
+     1 │ @include "cycle_a.eon"
+       │ ───────────┬──────────
+       │            ╰──────────── Included here
+    ───╯
+    "#);
+    insta::assert_snapshot!(Value::from_file("tests/include/include_list.eon").unwrap_err(), @r#"
+    Error: not_a_map.eon must contain a map to be included
+       ╭─[ tests/include/include_list.eon:1:1 ]
+       │
+     1 │ @include "not_a_map.eon"
+       │ ────────────┬───────────
+       │             ╰───────────── Included here
+    ───╯
+    "#);
+
+    let err = "@include \"other.eon\"".parse::<Value>().unwrap_err();
+    assert!(err.to_string().contains("Value::from_file"), "{err}");
+}
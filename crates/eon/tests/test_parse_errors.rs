@@ -96,12 +96,148 @@ fn test_almost_correct() {
 #[test]
 fn test_repeated_key() {
     insta::assert_snapshot!(Value::from_str("key: 1\nkey: 2").unwrap_err(), @r"
-    Error:
-       ╭─[ <unknown>:2:1 ]
+    Error: Duplicate key in map
+       ╭─[ <unknown>:1:1 ]
        │
+     1 │ key: 1
+       │ ─┬─
+       │  ╰─── First defined here
      2 │ key: 2
        │ ─┬─
-       │  ╰─── Duplicate key in map
+       │  ╰─── Defined again here
     ───╯
     ");
 }
+
+#[test]
+fn test_control_characters() {
+    let err = Value::from_str("greeting: 'Hello\u{7}!'")
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("Raw control character U+0007"), "{err}");
+
+    let options =
+        eon::ParseOptions::new().with_control_characters(eon::ControlCharacterPolicy::Allow);
+    assert_eq!(
+        Value::from_str_with_options("greeting: 'Hello\u{7}!'", &options).unwrap(),
+        Value::from_str(r#"greeting: "Hello\u{7}!""#).unwrap()
+    );
+}
+
+#[test]
+fn test_recovering_parse() {
+    use eon_syntax::{TokenTree, TokenValue};
+
+    let source = r#"
+ok1: 1
+bad1: [1, : 3]
+ok2: 2
+bad2 3
+nested: {
+    bad3: :
+    ok3: 3
+}
+ok4: "four"
+"#;
+
+    assert!(TokenTree::parse_str(source).is_err());
+
+    let (tree, errors) = TokenTree::parse_str_recovering(source);
+    let messages: Vec<String> = errors
+        .iter()
+        .map(|err| format!("{}: {}", err.line_col(source).unwrap().0, err.message()))
+        .collect();
+    insta::assert_debug_snapshot!(messages, @r#"
+    [
+        "3: Expected a value, like a map, list, number, or string",
+        "5: Expected colon ':' but found number",
+        "7: Expected a value, like a map, list, number, or string",
+    ]
+    "#);
+
+    let tree = tree.unwrap();
+    let TokenValue::Map(map) = &tree.value else {
+        panic!("Expected a map");
+    };
+    let keys: Vec<_> = map
+        .key_values
+        .iter()
+        .filter_map(|kv| kv.key.value.as_key_str())
+        .collect();
+    insta::assert_debug_snapshot!(keys, @r#"
+    [
+        "ok1",
+        "bad1",
+        "ok2",
+        "nested",
+        "ok4",
+    ]
+    "#);
+
+    // Without errors, it is the same as `parse_str`:
+    let (tree, errors) = TokenTree::parse_str_recovering("a: 1\nb: [2, 3]");
+    assert!(errors.is_empty());
+    assert_eq!(
+        tree.unwrap().format(&Default::default()),
+        TokenTree::parse_str("a: 1\nb: [2, 3]")
+            .unwrap()
+            .format(&Default::default())
+    );
+
+    // Unbalanced brackets:
+    let source = "a: [1, 2\nb: 3\n}\nc: 4";
+    let (tree, errors) = TokenTree::parse_str_recovering(source);
+    assert!(tree.is_some());
+    let messages: Vec<String> = errors
+        .iter()
+        .map(|err| format!("{}: {}", err.line_col(source).unwrap().0, err.message()))
+        .collect();
+    insta::assert_debug_snapshot!(messages, @r#"
+    [
+        "2: Expected a value, like a map, list, number, or string",
+        "3: Expected close bracket ']' but found close brace '}'",
+        "3: Unbalanced brackets",
+    ]
+    "#);
+}
+
+#[test]
+fn test_all_conversion_errors() {
+    use eon_syntax::TokenTree;
+
+    let source = r#"
+ok: 1
+number: 0x_zz
+nested: {
+    escape: "\q"
+    list: [1, nul, 3]
+}
+ok: 2
+"bad\key": 4
+"#;
+
+    let tree = TokenTree::parse_str(source).unwrap();
+    assert!(Value::try_from_token_tree(source, &tree).is_err());
+
+    let errors = Value::try_from_token_tree_all_errors(source, &tree).unwrap_err();
+    let messages: Vec<String> = errors
+        .iter()
+        .map(|err| format!("{}: {}", err.line_col(source).unwrap().0, err.message()))
+        .collect();
+    insta::assert_debug_snapshot!(messages, @r#"
+    [
+        "3: Failed to parse number: Failed to parse hexadecimal number. Expected '0x…'. The string: \"0x_zz\"",
+        "5: Failed to unescape string: Unknown escape sequence: \\q. The string: \"\\q\"",
+        "6: Unknown keyword \"nul\". Expected 'null', 'true', or 'false'.",
+        "2: Duplicate key in map",
+        "9: Failed to unescape string: Unknown escape sequence: \\k. The string: \"bad\\key\"",
+    ]
+    "#);
+
+    let source = "a: 1\nb: [2, 3]";
+    let tree = TokenTree::parse_str(source).unwrap();
+    assert_eq!(
+        Value::try_from_token_tree_all_errors(source, &tree).unwrap(),
+        Value::from_str(source).unwrap()
+    );
+}
@@ -40,3 +40,20 @@ fn test_unicode() {
     "Rust-style unicode": "🚭"
     "#);
 }
+
+#[test]
+fn test_parse_scalar() {
+    assert_eq!(eon::parse_scalar("true").unwrap(), Value::Bool(true));
+    assert_eq!(eon::parse_scalar("-1.5").unwrap(), Value::from(-1.5));
+    assert_eq!(
+        eon::parse_scalar("'C:\\temp' // Comment").unwrap(),
+        Value::from("C:\\temp")
+    );
+    assert_eq!(
+        eon::parse_scalar("[1, 2]").unwrap(),
+        Value::from(vec![1.into(), 2.into()])
+    );
+    assert!(eon::parse_scalar("").is_err());
+    assert!(eon::parse_scalar("1, 2").is_err());
+    assert!(eon::parse_scalar("key: 1").is_err());
+}
@@ -0,0 +1,55 @@
+//! Property tests: format arbitrary values, parse them back, and check that nothing changed.
+//!
+//! Minimized failures are saved next to this file in `test_proptest.proptest-regressions`
+//! and re-run first next time.
+
+use eon::{CommentMap, FormatOptions, KeyPath, Value};
+use eon_test_utils::{arb_value, assert_reformat_is_stable, assert_value_round_trips};
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn value_round_trips(value in arb_value()) {
+        assert_value_round_trips(&value);
+    }
+
+    #[test]
+    fn value_with_comments_round_trips(
+        value in arb_value(),
+        comment in "[ a-zA-Z0-9,.!?/\n]{0,40}",
+    ) {
+        let Value::Map(map) = &value else {
+            return Ok(());
+        };
+
+        let mut comments = CommentMap::new();
+        for key in map.keys() {
+            if let Value::String(key) = key {
+                comments.insert(KeyPath::root().key(key.as_str()), &comment);
+            }
+        }
+
+        let options = FormatOptions::default();
+        let formatted = value.format_with_comments(&options, &comments);
+        let reparsed: Value = formatted
+            .parse()
+            .unwrap_or_else(|err| panic!("Failed to parse the formatted value:\n{formatted}\n{err}"));
+        prop_assert_eq!(&reparsed, &value, "Value changed after formatting:\n{}", formatted);
+        assert_reformat_is_stable(&formatted, &options);
+    }
+
+    #[test]
+    fn reformat_is_stable_with_options(
+        value in arb_value(),
+        always_include_outer_braces in any::<bool>(),
+        max_line_width in 0_usize..120,
+    ) {
+        let options = FormatOptions {
+            always_include_outer_braces,
+            max_line_width,
+            ..Default::default()
+        };
+        let formatted = value.format(&options);
+        assert_reformat_is_stable(&formatted, &options);
+    }
+}
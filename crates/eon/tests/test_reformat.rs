@@ -57,10 +57,7 @@ fn test_reformat_1() {
     	"zero_variant"
     	"one_variant"(true)
     	"three_variant"(1, 2, 3)
-    	"map_variant"({
-    		"key": "value"
-    		"another_key": 42
-    	})
+    	"map_variant"({ "key": "value", "another_key": 42 })
     	"list_variant"([
     		"doc"
     		"grumpy"
@@ -107,3 +104,458 @@ fn test_reformat_2() {
     }
     ");
 }
+
+#[test]
+fn test_reformat_max_line_width() {
+    let input = r#"
+        numbers: [1, 2, 3, 4, 5, 6, 7, 8, 9, 10]
+        words: ["alpha", "beta", "gamma", "delta"]
+        "variant": "Rgb"(255, 128, 0, 255, 128, 0)
+        server: {
+            host: "localhost"
+            port: 8080
+        }
+        color: "Rgb"({r: 255, g: 0, b: 0})
+    "#;
+
+    let wide = eon::reformat(input, &Default::default()).unwrap();
+    insta::assert_snapshot!(wide, @r#"
+    numbers: [1, 2, 3, 4, 5, 6, 7, 8, 9, 10]
+    words: ["alpha", "beta", "gamma", "delta"]
+    "variant": "Rgb"(255, 128, 0, 255, 128, 0)
+    server: { host: "localhost", port: 8080 }
+    color: "Rgb"({ r: 255, g: 0, b: 0 })
+    "#);
+
+    let narrow = eon::reformat(
+        input,
+        &eon::FormatOptions::default().with_max_line_width(30),
+    )
+    .unwrap();
+    insta::assert_snapshot!(narrow, @r#"
+    numbers: [
+    	1
+    	2
+    	3
+    	4
+    	5
+    	6
+    	7
+    	8
+    	9
+    	10
+    ]
+    words: [
+    	"alpha"
+    	"beta"
+    	"gamma"
+    	"delta"
+    ]
+    "variant": "Rgb"(
+    	255
+    	128
+    	0
+    	255
+    	128
+    	0
+    )
+    server: {
+    	host: "localhost"
+    	port: 8080
+    }
+    color: "Rgb"({
+    	r: 255
+    	g: 0
+    	b: 0
+    })
+    "#);
+}
+
+#[test]
+fn test_reformat_sort_keys() {
+    let input = r#"
+        // Comment about b
+        b: 2 // Suffix comment about b
+        "C": {
+            z: 26
+            y: 25
+        }
+        /// Docs about a
+        a: 1
+    "#;
+
+    let preserved = eon::reformat(input, &Default::default()).unwrap();
+    insta::assert_snapshot!(preserved, @r#"
+    // Comment about b
+    b: 2 // Suffix comment about b
+    "C": { z: 26, y: 25 }
+
+    /// Docs about a
+    a: 1
+    "#);
+
+    let sorted = eon::reformat(
+        input,
+        &eon::FormatOptions::default().with_sort_keys(eon::SortKeys::Alphabetical),
+    )
+    .unwrap();
+    insta::assert_snapshot!(sorted, @r#"
+    "C": { y: 25, z: 26 }
+
+    /// Docs about a
+    a: 1
+
+    // Comment about b
+    b: 2 // Suffix comment about b
+    "#);
+
+    let sorted = eon::reformat(
+        input,
+        &eon::FormatOptions::default().with_sort_keys(eon::SortKeys::AlphabeticalCaseInsensitive),
+    )
+    .unwrap();
+    insta::assert_snapshot!(sorted, @r#"
+    /// Docs about a
+    a: 1
+
+    // Comment about b
+    b: 2 // Suffix comment about b
+    "C": { y: 25, z: 26 }
+    "#);
+}
+
+#[test]
+fn test_reformat_key_value_separator() {
+    let input = r#"
+        name = "Eon" // Migrated from TOML
+        server: {
+            port = 8080
+        }
+    "#;
+
+    let normalized = eon::reformat(input, &Default::default()).unwrap();
+    insta::assert_snapshot!(normalized, @r#"
+    name: "Eon" // Migrated from TOML
+    server: { port: 8080 }
+    "#);
+
+    let equals = eon::reformat(
+        input,
+        &eon::FormatOptions::default().with_key_value_separator(" = "),
+    )
+    .unwrap();
+    insta::assert_snapshot!(equals, @r#"
+    name = "Eon" // Migrated from TOML
+    server = { port = 8080 }
+    "#);
+
+    let value: eon::Value = input.parse().unwrap();
+    assert_eq!(value, normalized.parse().unwrap());
+    assert_eq!(value, equals.parse().unwrap());
+}
+
+#[test]
+fn test_reformat_hash_comments() {
+    let input = r#"
+        # Pasted from a YAML file
+        name: "Eon" #Suffix comment
+        ports: [
+            80 # http
+            443
+        ]
+    "#;
+
+    let normalized = eon::reformat(input, &Default::default()).unwrap();
+    insta::assert_snapshot!(normalized, @r#"
+    // Pasted from a YAML file
+    name: "Eon" //Suffix comment
+    ports: [
+    	80 // http
+    	443
+    ]
+    "#);
+
+    let preserved = eon::reformat(
+        input,
+        &eon::FormatOptions::default().with_hash_comments(eon::HashComments::Preserve),
+    )
+    .unwrap();
+    insta::assert_snapshot!(preserved, @r##"
+    # Pasted from a YAML file
+    name: "Eon" #Suffix comment
+    ports: [
+    	80 # http
+    	443
+    ]
+    "##);
+
+    let value: eon::Value = input.parse().unwrap();
+    assert_eq!(value, normalized.parse().unwrap());
+    assert_eq!(value, preserved.parse().unwrap());
+}
+
+#[test]
+fn test_reformat_ensure_trailing_newline() {
+    let options = eon::FormatOptions::default().with_ensure_trailing_newline(true);
+    let with = |input: &str| eon::reformat(input, &options).unwrap();
+    let without = |input: &str| eon::reformat(input, &Default::default()).unwrap();
+
+    assert_eq!(without("42"), "42");
+    assert_eq!(with("42"), "42\n");
+    assert_eq!(with("42\n\n"), "42\n");
+    assert_eq!(with("'text'"), "'text'\n");
+    assert_eq!(with("[1, 2]"), "[1, 2]\n");
+
+    // Maps already end with a newline:
+    assert_eq!(without("a: 1"), "a: 1\n");
+    assert_eq!(with("a: 1"), "a: 1\n");
+
+    // …except when wrapped in braces:
+    let braces = options.clone().with_always_include_outer_braces(true);
+    assert_eq!(eon::reformat("a: 1", &braces).unwrap(), "{\n\ta: 1\n}\n");
+
+    // An empty document stays empty:
+    assert_eq!(with(""), "");
+
+    let crlf = options.with_newline("\r\n".to_owned());
+    assert_eq!(eon::reformat("42", &crlf).unwrap(), "42\r\n");
+}
+
+#[test]
+fn test_reformat_outer_braces() {
+    let input = r#"
+// Header
+/// The name
+name: "eon"
+server: { port: 8080 } // Suffix
+
+// Footer
+"#;
+    let braces = eon::FormatOptions::default().with_always_include_outer_braces(true);
+
+    let without = eon::reformat(input, &Default::default()).unwrap();
+    insta::assert_snapshot!(without, @r#"
+    // Header
+    /// The name
+    name: "eon"
+    server: { port: 8080 } // Suffix
+
+    // Footer
+    "#);
+
+    let with = eon::reformat(input, &braces).unwrap();
+    insta::assert_snapshot!(with, @r#"
+    {
+    	// Header
+    	/// The name
+    	name: "eon"
+    	server: { port: 8080 } // Suffix
+
+    	// Footer
+    }
+    "#);
+
+    // Switching back and forth keeps the comments in place:
+    assert_eq!(eon::reformat(&with, &Default::default()).unwrap(), without);
+    assert_eq!(eon::reformat(&without, &braces).unwrap(), with);
+
+    // Comments around explicit braces:
+    let input = "// Before\n{ a: 1 } // After\n";
+    assert_eq!(
+        eon::reformat(input, &braces).unwrap(),
+        "// Before\n{\n\ta: 1\n} // After"
+    );
+    let without = eon::reformat(input, &Default::default()).unwrap();
+    assert_eq!(without, "// Before\na: 1\n\n// After\n");
+    assert_eq!(
+        eon::reformat(&without, &Default::default()).unwrap(),
+        without,
+        "Should be stable"
+    );
+}
+
+#[test]
+fn test_reformat_layout_overrides() {
+    let input = r#"
+        color: [
+            255
+            128
+            0
+            255
+        ]
+        keybindings: ["ctrl+s", "ctrl+q"]
+        themes: [
+            { accent: "Rgb"(255, 0, 0) }
+        ]
+    "#;
+
+    let options = eon::FormatOptions::default()
+        .with_layout_override("keybindings".parse().unwrap(), eon::Layout::MultiLine)
+        .with_layout_override("themes[0].accent".parse().unwrap(), eon::Layout::MultiLine);
+    let formatted = eon::reformat(input, &options).unwrap();
+    insta::assert_snapshot!(formatted, @r#"
+    color: [255, 128, 0, 255]
+    keybindings: [
+    	"ctrl+s"
+    	"ctrl+q"
+    ]
+    themes: [
+    	{
+    		accent: "Rgb"(
+    			255
+    			0
+    			0
+    		)
+    	}
+    ]
+    "#);
+
+    let options = eon::FormatOptions::default()
+        .with_max_line_width(10)
+        .with_layout_override("color".parse().unwrap(), eon::Layout::SingleLine);
+    let formatted = eon::reformat(input, &options).unwrap();
+    insta::assert_snapshot!(formatted, @r#"
+    color: [255, 128, 0, 255]
+    keybindings: [
+    	"ctrl+s"
+    	"ctrl+q"
+    ]
+    themes: [
+    	{
+    		accent: "Rgb"(
+    			255
+    			0
+    			0
+    		)
+    	}
+    ]
+    "#);
+
+    // Maps too:
+    let input = "server: { host: \"localhost\", port: 8080 }";
+    let options = eon::FormatOptions::default()
+        .with_layout_override("server".parse().unwrap(), eon::Layout::MultiLine);
+    assert_eq!(
+        eon::reformat(input, &options).unwrap(),
+        "server: {\n\thost: \"localhost\"\n\tport: 8080\n}\n"
+    );
+}
+
+#[test]
+fn test_reformat_separators() {
+    let input = r#"
+        @include "base.eon"
+        list: [
+            "a",
+            "b", // Comment about b
+        ]
+        map: {
+            a: 1
+            b: 2
+        }
+        variant: "Rgb"(
+            255
+            128 // Green
+            0
+        )
+    "#;
+
+    let comma = |trailing| {
+        eon::FormatOptions::default()
+            .with_max_line_width(20)
+            .with_separators(eon::MultilineSeparator::Comma, trailing)
+    };
+
+    let never = eon::reformat(input, &comma(eon::TrailingSeparator::Never)).unwrap();
+    insta::assert_snapshot!(never, @r#"
+    @include "base.eon",
+
+    list: [
+    	"a",
+    	"b" // Comment about b
+    ],
+    map: { a: 1, b: 2 },
+    variant: "Rgb"(
+    	255,
+    	128, // Green
+    	0
+    )
+    "#);
+
+    let always = eon::reformat(input, &comma(eon::TrailingSeparator::Always)).unwrap();
+    insta::assert_snapshot!(always, @r#"
+    @include "base.eon",
+
+    list: [
+    	"a",
+    	"b", // Comment about b
+    ],
+    map: { a: 1, b: 2 },
+    variant: "Rgb"(
+    	255,
+    	128, // Green
+    	0,
+    ),
+    "#);
+
+    let preserve = eon::reformat(input, &comma(eon::TrailingSeparator::Preserve)).unwrap();
+    insta::assert_snapshot!(preserve, @r#"
+    @include "base.eon",
+
+    list: [
+    	"a",
+    	"b", // Comment about b
+    ],
+    map: { a: 1, b: 2 },
+    variant: "Rgb"(
+    	255,
+    	128, // Green
+    	0
+    )
+    "#);
+
+    // The output parses back to the same thing:
+    for formatted in [never, always, preserve] {
+        assert_eq!(
+            eon::reformat(&formatted, &Default::default()).unwrap(),
+            eon::reformat(input, &Default::default()).unwrap()
+        );
+    }
+}
+
+#[test]
+fn test_reformat_align_suffix_comments() {
+    let input = r#"
+        // Server settings
+        port: 8080 // The port
+        host: "localhost" // The host
+        limits: {
+            max_connections: 100 // Per client
+            timeout: 30
+            retries: 3 // Before giving up
+        }
+        tags: ["a", "b"] // Optional
+    "#;
+
+    let options = eon::FormatOptions::default().with_align_suffix_comments(true);
+    let formatted = eon::reformat(input, &options).unwrap();
+    insta::assert_snapshot!(formatted, @r#"
+    // Server settings
+    port: 8080        // The port
+    host: "localhost" // The host
+    limits: {
+    	max_connections: 100 // Per client
+    	timeout: 30
+    	retries: 3           // Before giving up
+    }
+    tags: ["a", "b"]  // Optional
+    "#);
+
+    // Aligning is stable, and the default leaves a single space:
+    assert_eq!(eon::reformat(&formatted, &options).unwrap(), formatted);
+    assert!(
+        eon::reformat(&formatted, &Default::default())
+            .unwrap()
+            .contains("port: 8080 // The port")
+    );
+}
@@ -85,3 +85,1213 @@ fn test_bool_to_string() {
     true: "true"
     "#);
 }
+
+#[test]
+fn test_duplicate_keys() {
+    #[derive(serde::Deserialize, Debug, PartialEq)]
+    struct Server {
+        host: String,
+        port: u16,
+    }
+
+    let source = r#"
+        server: { host: "localhost", port: 80 }
+        server: { port: 8080 }
+    "#;
+
+    assert_eq!(
+        eon::from_str::<BTreeMap<String, Server>>(source).unwrap()["server"],
+        Server {
+            host: "localhost".to_owned(),
+            port: 80
+        },
+        "from_str keeps the first value"
+    );
+
+    let parse = |policy| {
+        let options = eon::ParseOptions::new().with_duplicate_keys(policy);
+        eon::from_str_with_options::<BTreeMap<String, Server>>(source, &options)
+    };
+
+    assert!(parse(eon::DuplicateKeyPolicy::Error).is_err());
+    assert!(
+        parse(eon::DuplicateKeyPolicy::LastWins).is_err(),
+        "host is missing"
+    );
+    assert_eq!(
+        parse(eon::DuplicateKeyPolicy::FirstWins).unwrap()["server"],
+        Server {
+            host: "localhost".to_owned(),
+            port: 80
+        }
+    );
+    assert_eq!(
+        parse(eon::DuplicateKeyPolicy::Merge).unwrap()["server"],
+        Server {
+            host: "localhost".to_owned(),
+            port: 8080
+        }
+    );
+}
+
+#[test]
+fn test_variant_payload_style() {
+    let colors = vec![
+        Color::Gray(128),
+        Color::Hsl(0, 100, 200),
+        Color::Rgb { r: 255, g: 0, b: 0 },
+    ];
+
+    let serializer =
+        eon::Serializer::new().with_variant_payload_style(eon::VariantPayloadStyle::Named);
+    let string = serializer
+        .to_string(&colors, &eon::FormatOptions::default())
+        .unwrap();
+    insta::assert_snapshot!(string);
+
+    let roundtripped: Vec<Color> = eon::from_str(&string).unwrap();
+    assert_eq!(colors, roundtripped);
+
+    // Both styles are accepted, in any key order:
+    let colors: Vec<Color> =
+        eon::from_str(r#"["Hsl"(1, 2, 3), "Hsl"({2: 3, 0: 1, 1: 2})]"#).unwrap();
+    assert_eq!(colors, vec![Color::Hsl(1, 2, 3), Color::Hsl(1, 2, 3)]);
+
+    assert!(eon::from_str::<Color>(r#""Hsl"({0: 1, 1: 2, 3: 3})"#).is_err());
+}
+
+#[test]
+fn test_timestamps() {
+    #[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq)]
+    struct Event {
+        name: String,
+        #[serde(with = "eon::timestamp")]
+        time: chrono::DateTime<chrono::Utc>,
+        #[serde(with = "eon::timestamp")]
+        local_time: chrono::DateTime<chrono::FixedOffset>,
+    }
+
+    let event: Event = eon::from_str(
+        r#"
+        name: "Launch"
+        time: 2024-05-01T12:30:00Z
+        local_time: 2024-05-01T14:30:00.5+02:00
+        "#,
+    )
+    .unwrap();
+    assert_eq!(event.time.to_rfc3339(), "2024-05-01T12:30:00+00:00");
+    assert_eq!(
+        event.local_time.to_rfc3339(),
+        "2024-05-01T14:30:00.500+02:00"
+    );
+
+    let string = eon::to_string(&event, &eon::FormatOptions::default()).unwrap();
+    insta::assert_snapshot!(string);
+
+    let roundtripped: Event = eon::from_str(&string).unwrap();
+    assert_eq!(event, roundtripped);
+
+    // Quoted timestamps work too:
+    let event: Event = eon::from_str(
+        r#"
+        name: "Launch"
+        time: "2024-05-01T12:30:00Z"
+        local_time: "2024-05-01T14:30:00+02:00"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(event.time.to_rfc3339(), "2024-05-01T12:30:00+00:00");
+
+    let err = eon::from_str::<Event>("name: \"Launch\"\ntime: 2024-02-30T12:30:00Z").unwrap_err();
+    assert!(
+        err.to_string().contains("Invalid RFC 3339 timestamp"),
+        "{err}"
+    );
+
+    // Strings are never turned into timestamps, even if they look like one:
+    let value = eon::to_value(&BTreeMap::from([("time", "2024-05-01T12:30:00Z")])).unwrap();
+    assert_eq!(
+        value.format(&eon::FormatOptions::default()),
+        "time: \"2024-05-01T12:30:00Z\"\n"
+    );
+
+    // Unless asked to:
+    let timestamp: eon::Timestamp = "2024-05-01T12:30:00Z".parse().unwrap();
+    let value = eon::to_value(&BTreeMap::from([("time", &timestamp)])).unwrap();
+    assert_eq!(
+        value.format(&eon::FormatOptions::default()),
+        "time: 2024-05-01T12:30:00Z\n"
+    );
+    assert_eq!(
+        eon::from_str::<BTreeMap<String, eon::Timestamp>>("time: 2024-05-01T12:30:00Z").unwrap()["time"],
+        timestamp
+    );
+}
+
+#[test]
+fn test_bytes() {
+    /// Like `serde_bytes::ByteBuf`
+    #[derive(Debug, PartialEq)]
+    struct Blob(Vec<u8>);
+
+    impl serde::Serialize for Blob {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_bytes(&self.0)
+        }
+    }
+
+    impl<'de> serde::Deserialize<'de> for Blob {
+        fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct BlobVisitor;
+
+            impl serde::de::Visitor<'_> for BlobVisitor {
+                type Value = Blob;
+
+                fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    f.write_str("bytes")
+                }
+
+                fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Blob, E> {
+                    Ok(Blob(v))
+                }
+            }
+
+            deserializer.deserialize_byte_buf(BlobVisitor)
+        }
+    }
+
+    #[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq)]
+    struct Image {
+        blob: Blob,
+        vec: Vec<u8>,
+    }
+
+    let image = Image {
+        blob: Blob(vec![0x89, b'P', b'N', b'G']),
+        vec: vec![1, 2],
+    };
+
+    let string = eon::to_string(&image, &eon::FormatOptions::default()).unwrap();
+    assert_eq!(string, "blob: b\"89504e47\"\nvec: [1, 2]\n");
+
+    // `Vec<u8>` can be read from both lists and bytes:
+    let roundtripped: Image = eon::from_str("blob: b\"8950_4E47\"\nvec: b\"0102\"").unwrap();
+    assert_eq!(roundtripped, image);
+
+    let value: eon::Value = eon::from_str("b\"0102\"").unwrap();
+    assert_eq!(value, eon::Value::Bytes(vec![1, 2]));
+
+    assert!(eon::from_str::<Image>("blob: b\"123\"\nvec: []").is_err());
+}
+
+#[test]
+fn test_spanned() {
+    use eon::Spanned;
+
+    #[derive(serde::Deserialize, serde::Serialize, Debug)]
+    struct Server {
+        name: Spanned<String>,
+        ports: Vec<Spanned<u16>>,
+        color: Spanned<Color>,
+        optional: Option<Spanned<bool>>,
+    }
+
+    let eon_source = r#"
+name: "web"
+ports: [80, 8080]
+color: "Rgb"({r: 255, g: 0, b: 0})
+optional: true
+"#;
+    let server: Server = eon::from_str(eon_source).unwrap();
+    let text = |span: eon::Span| &eon_source[span.start..span.end];
+
+    assert_eq!(server.name.get_ref(), "web");
+    assert_eq!(text(server.name.span()), "\"web\"");
+    assert_eq!(server.ports.len(), 2);
+    assert_eq!(text(server.ports[1].span()), "8080");
+    assert_eq!(server.ports[1].into_inner(), 8080);
+    assert_eq!(server.color.get_ref(), &Color::Rgb { r: 255, g: 0, b: 0 });
+    assert_eq!(text(server.color.span()), "\"Rgb\"({r: 255, g: 0, b: 0})");
+    assert_eq!(text(server.optional.unwrap().span()), "true");
+
+    // Serializing ignores the spans:
+    let value = eon::to_value(&server).unwrap();
+    let expected: eon::Value = eon_source.parse().unwrap();
+    assert_eq!(value, expected);
+
+    // Spanned map keys:
+    let map: BTreeMap<Spanned<String>, i32> = eon::from_str("a: 1\nbb: 2").unwrap();
+    let spans: Vec<_> = map.keys().map(|key| key.span()).collect();
+    assert_eq!(
+        spans,
+        [
+            eon::Span { start: 0, end: 1 },
+            eon::Span { start: 5, end: 7 }
+        ]
+    );
+
+    // Errors inside a spanned value still point at the value:
+    let err =
+        eon::from_str::<Server>("name: \"web\"\nports: [80, -1]\ncolor: \"Black\"").unwrap_err();
+    assert!(err.to_string().contains("-1"), "{err}");
+}
+
+#[test]
+fn test_stream_deserializer() {
+    fn stream_from_str<'de, T: serde::Deserialize<'de>>(
+        eon_source: &'de str,
+    ) -> Result<T, eon::Error> {
+        let mut deserializer = eon::StreamDeserializer::new(eon_source);
+        let value = T::deserialize(&mut deserializer)?;
+        deserializer.end()?;
+        Ok(value)
+    }
+
+    let top = Top {
+        f: 1.5,
+        i: -42,
+        s: "Hello, world!".to_owned(),
+        b: true,
+        some: Some("Some".to_owned()),
+        none: None,
+        floats: vec![1.1, f32::NEG_INFINITY],
+        nested_object: NestedObject {
+            f: 3.2,
+            i: 7,
+            s: "Nested".to_owned(),
+        },
+        colors: vec![
+            Color::Black,
+            Color::Gray(128),
+            Color::Hsl(0, 100, 200),
+            Color::Rgb { r: 255, g: 0, b: 0 },
+        ],
+        tuple: (100, "Tuple".to_owned()),
+        map: BTreeMap::from([(1, 1.1), (2, 2.2)]),
+    };
+    let string = eon::to_string(&top, &eon::FormatOptions::default()).unwrap();
+    assert_eq!(stream_from_str::<Top>(&string).unwrap(), top);
+
+    // Top-level values that are not maps:
+    assert_eq!(stream_from_str::<Vec<i32>>("[1, 2, 3]").unwrap(), [1, 2, 3]);
+    assert_eq!(stream_from_str::<i32>("// Comment\n42").unwrap(), 42);
+    assert_eq!(
+        stream_from_str::<BTreeMap<String, i32>>("{a: 1}").unwrap(),
+        BTreeMap::from([("a".to_owned(), 1)])
+    );
+    assert_eq!(
+        stream_from_str::<BTreeMap<String, i32>>("").unwrap(),
+        BTreeMap::new()
+    );
+
+    // Transcoding to JSON:
+    let eon_source = r#"
+// Comment
+name: "Eon"
+ports: [80, 8080]
+when: 2024-05-01T12:30:00Z
+color: "Rgb"(255, 0, 0)
+nested: { "quoted key": null, empty: [] }
+"#;
+    let mut json = vec![];
+    let mut deserializer = eon::StreamDeserializer::new(eon_source);
+    serde_transcode::transcode(
+        &mut deserializer,
+        &mut serde_json::Serializer::new(&mut json),
+    )
+    .unwrap();
+    deserializer.end().unwrap();
+    assert_eq!(
+        String::from_utf8(json).unwrap(),
+        r#"{"name":"Eon","ports":[80,8080],"when":"2024-05-01T12:30:00Z","color":{"$variant":"Rgb","$values":[255,0,0]},"nested":{"quoted key":null,"empty":[]}}"#
+    );
+
+    // Errors point into the source:
+    let err = stream_from_str::<Top>("f: 1.5\ni: \"not a number\"").unwrap_err();
+    insta::assert_snapshot!(err, @r#"
+    Error:
+       ╭─[ <unknown>:2:4 ]
+       │
+     2 │ i: "not a number"
+       │    ───────┬──────
+       │           ╰──────── invalid type: string "not a number", expected i32
+    ───╯
+    "#);
+    let err = stream_from_str::<BTreeMap<String, Vec<i32>>>("a: [1]\nb: [2").unwrap_err();
+    insta::assert_snapshot!(err, @"
+    Error:
+       ╭─[ <unknown>:2:5 ]
+       │
+     2 │ b: [2
+       │     ┬
+       │     ╰── Expected close bracket ']' but reached end of input
+    ───╯
+    ");
+    let deep = format!("{}{}", "[".repeat(20), "]".repeat(20));
+    let mut deserializer = eon::StreamDeserializer::new(&deep).with_max_depth(10);
+    let err = <eon::Value as serde::Deserialize>::deserialize(&mut deserializer).unwrap_err();
+    assert!(
+        err.to_string().contains("Maximum recursion depth exceeded"),
+        "{err}"
+    );
+    let err = stream_from_str::<i32>("1, 2").unwrap_err();
+    insta::assert_snapshot!(err, @"
+    Error:
+       ╭─[ <unknown>:1:2 ]
+       │
+     1 │ 1, 2
+       │  ┬
+       │  ╰── Expected end of file here
+    ───╯
+    ");
+}
+
+#[test]
+fn test_stream_deserializer_matches_from_str() {
+    #[track_caller]
+    fn check<T>(eon_source: &str, expected: &T)
+    where
+        T: serde::de::DeserializeOwned + PartialEq + std::fmt::Debug,
+    {
+        let mut deserializer = eon::StreamDeserializer::new(eon_source);
+        let streamed = T::deserialize(&mut deserializer).unwrap();
+        deserializer.end().unwrap();
+        assert_eq!(&streamed, expected, "StreamDeserializer on {eon_source:?}");
+        let parsed: T = eon::from_str(eon_source).unwrap();
+        assert_eq!(&parsed, expected, "from_str on {eon_source:?}");
+    }
+
+    check::<Option<i32>>("null", &None);
+    check::<Option<i32>>("// Nothing\nnull", &None);
+    check::<Option<i32>>("5", &Some(5));
+
+    // Keyword-like keys are still strings:
+    check::<BTreeMap<String, i32>>(
+        "null: 1\ntrue: 2\nfalse: 3",
+        &BTreeMap::from([
+            ("null".to_owned(), 1),
+            ("true".to_owned(), 2),
+            ("false".to_owned(), 3),
+        ]),
+    );
+    check::<Option<BTreeMap<String, i32>>>(
+        "null: 1",
+        &Some(BTreeMap::from([("null".to_owned(), 1)])),
+    );
+    check::<BTreeMap<bool, i32>>(
+        "{true: 1, false: 0}",
+        &BTreeMap::from([(true, 1), (false, 0)]),
+    );
+}
+
+#[test]
+fn test_stream_deserializer_borrows_strings() {
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Borrowed<'a> {
+        plain: &'a str,
+        literal: &'a str,
+        letter: char,
+        escaped: std::borrow::Cow<'a, str>,
+    }
+
+    let eon_source = r#""plain": "no escapes", literal: 'C:\dir', letter: "x", escaped: "a\tb""#;
+    let mut deserializer = eon::StreamDeserializer::new(eon_source);
+    let value = <Borrowed<'_> as serde::Deserialize>::deserialize(&mut deserializer).unwrap();
+    deserializer.end().unwrap();
+    assert_eq!(
+        value,
+        Borrowed {
+            plain: "no escapes",
+            literal: r"C:\dir",
+            letter: 'x',
+            escaped: "a\tb".into(),
+        }
+    );
+
+    // A `&str` can't be borrowed if it needs unescaping:
+    let mut deserializer = eon::StreamDeserializer::new(r#"plain: "a\tb""#);
+    let err = <std::collections::HashMap<&str, &str> as serde::Deserialize>::deserialize(
+        &mut deserializer,
+    )
+    .unwrap_err();
+    assert!(
+        err.to_string().contains("expected a borrowed string"),
+        "{err}"
+    );
+}
+
+#[test]
+fn test_stream_deserializer_skips_ignored_fields() {
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Partial {
+        name: String,
+    }
+
+    fn stream_from_str(eon_source: &str) -> Result<Partial, eon::Error> {
+        let mut deserializer = eon::StreamDeserializer::new(eon_source);
+        let value = <Partial as serde::Deserialize>::deserialize(&mut deserializer)?;
+        deserializer.end()?;
+        Ok(value)
+    }
+
+    // Ignored values are skipped without being unescaped or parsed,
+    // so even values that would fail to deserialize are fine:
+    let deep = format!("{}{}", "[".repeat(1000), "]".repeat(1000));
+    let eon_source = format!(
+        r#"
+before: {{ huge: 1e999999, bad_escape: "\q", "Rgb"(1, [2]): {{}} }}
+name: "Eon"
+after: [{deep}, b"ff", 2024-05-01T12:30:00Z]
+"#
+    );
+    assert_eq!(
+        stream_from_str(&eon_source).unwrap(),
+        Partial {
+            name: "Eon".to_owned()
+        }
+    );
+
+    // …but the brackets must still be balanced:
+    let err = stream_from_str("skipped: [1, 2}\nname: \"Eon\"").unwrap_err();
+    insta::assert_snapshot!(err, @"
+    Error:
+       ╭─[ <unknown>:1:15 ]
+       │
+     1 │ skipped: [1, 2}
+       │               ┬
+       │               ╰── Expected close bracket ']' but found close brace '}'
+    ───╯
+    ");
+}
+
+#[test]
+fn test_to_string_with_comments() {
+    #[derive(serde::Serialize)]
+    struct Server {
+        host: String,
+        ports: Vec<u16>,
+    }
+
+    #[derive(serde::Serialize)]
+    struct Config {
+        name: String,
+        server: Server,
+    }
+
+    let config = Config {
+        name: "My app".to_owned(),
+        server: Server {
+            host: "localhost".to_owned(),
+            ports: vec![8080, 8081],
+        },
+    };
+
+    let comments = eon::CommentMap::new()
+        .with_comment(eon::KeyPath::root(), "Generated default config")
+        .with_comment(
+            "server".parse().unwrap(),
+            "Where to serve from.\n\nChange with care!",
+        )
+        .with_comment("server.ports[1]".parse().unwrap(), "Fallback")
+        .with_comment("no.such.path".parse().unwrap(), "Ignored");
+
+    let string =
+        eon::to_string_with_comments(&config, &eon::FormatOptions::default(), &comments).unwrap();
+    insta::assert_snapshot!(string, @r#"
+    // Generated default config
+    name: "My app"
+
+    // Where to serve from.
+    //
+    // Change with care!
+    server: {
+    	host: "localhost"
+    	ports: [
+    		8080
+
+    		// Fallback
+    		8081
+    	]
+    }
+    "#);
+}
+
+#[test]
+fn test_field_case() {
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    enum Shape {
+        Rect { corner_radius: f32 },
+    }
+
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Window {
+        max_width: u32,
+        _private_id: u32,
+        title: String,
+        shape: Shape,
+    }
+
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    #[serde(rename_all = "kebab-case")]
+    struct Renamed {
+        max_width: u32,
+    }
+
+    let window = Window {
+        max_width: 800,
+        _private_id: 1,
+        title: "Eon".to_owned(),
+        shape: Shape::Rect { corner_radius: 4.0 },
+    };
+    let options = eon::FormatOptions::default();
+
+    for field_case in [
+        eon::FieldCase::Preserve,
+        eon::FieldCase::CamelCase,
+        eon::FieldCase::KebabCase,
+    ] {
+        let serializer = eon::Serializer::new().with_field_case(field_case);
+        let string = serializer.to_string(&window, &options).unwrap();
+        let round_tripped: Window = eon::from_str(&string).unwrap();
+        assert_eq!(round_tripped, window, "{field_case:?}");
+    }
+
+    let camel = eon::Serializer::new()
+        .with_field_case(eon::FieldCase::CamelCase)
+        .to_string(&window, &options)
+        .unwrap();
+    insta::assert_snapshot!(camel, @r#"
+    maxWidth: 800
+    _privateId: 1
+    title: "Eon"
+    shape: "Rect"({ cornerRadius: 4.0 })
+    "#);
+
+    let kebab = eon::Serializer::new()
+        .with_field_case(eon::FieldCase::KebabCase)
+        .to_string(&window, &options)
+        .unwrap();
+    insta::assert_snapshot!(kebab, @r#"
+    "max-width": 800
+    "_private-id": 1
+    "title": "Eon"
+    "shape": "Rect"({ "corner-radius": 4.0 })
+    "#);
+
+    // `#[serde(rename_all)]` round-trips as usual:
+    let renamed = Renamed { max_width: 800 };
+    let string = eon::to_string(&renamed, &options).unwrap();
+    assert_eq!(string, "\"max-width\": 800\n");
+    assert_eq!(eon::from_str::<Renamed>(&string).unwrap(), renamed);
+}
+
+#[test]
+fn test_update_document() {
+    #[derive(serde::Serialize)]
+    struct Window {
+        width: u32,
+        height: u32,
+    }
+
+    #[derive(serde::Serialize)]
+    struct Settings {
+        window: Window,
+        recent_files: Vec<String>,
+        max_undo: u32,
+    }
+
+    let existing = r#"
+// Window settings
+window: {
+    width: 800 // Pixels
+    height: 600
+    maximized: false // Not known to `Settings`
+}
+
+recent_files: [
+    // The most recent one
+    "a.txt"
+    "b.txt"
+]
+
+max_undo: 1_000 // Written with a separator
+"#;
+
+    let settings = Settings {
+        window: Window {
+            width: 1024,
+            height: 600,
+        },
+        recent_files: vec!["c.txt".to_owned(), "a.txt".to_owned()],
+        max_undo: 1000,
+    };
+
+    let updated = eon::update_document(existing, &settings).unwrap();
+    insta::assert_snapshot!(updated, @r#"
+    // Window settings
+    window: {
+    	width: 1024 // Pixels
+    	height: 600
+    	maximized: false // Not known to `Settings`
+    }
+    recent_files: [
+    	// The most recent one
+    	"c.txt"
+    	"a.txt"
+    ]
+    max_undo: 1_000 // Written with a separator
+    "#);
+
+    // Creating a new document:
+    let created = eon::update_document("", &settings).unwrap();
+    assert_eq!(
+        created,
+        eon::to_string(&settings, &eon::FormatOptions::default()).unwrap()
+    );
+
+    assert!(eon::update_document("window: {", &settings).is_err());
+}
+
+#[test]
+fn test_windows_paths() {
+    use std::path::PathBuf;
+
+    #[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq)]
+    struct Paths {
+        plain: PathBuf,
+        apostrophe: PathBuf,
+        quoted: PathBuf,
+    }
+
+    let paths = Paths {
+        plain: PathBuf::from(r"C:\Program Files\eon\eon.exe"),
+        apostrophe: PathBuf::from(r"C:\Users\O'Brien\config.eon"),
+        quoted: PathBuf::from(r#"C:\Users\"quoted"\config.eon"#),
+    };
+
+    let string = eon::to_string(&paths, &eon::FormatOptions::default()).unwrap();
+    assert_eq!(
+        string,
+        r#"plain: 'C:\Program Files\eon\eon.exe'
+apostrophe: "C:\\Users\\O'Brien\\config.eon"
+quoted: 'C:\Users\"quoted"\config.eon'
+"#
+    );
+    let round_tripped: Paths = eon::from_str(&string).unwrap();
+    assert_eq!(round_tripped, paths);
+
+    let options = eon::FormatOptions::default().with_literal_backslash_strings(true);
+    let string = eon::to_string(&paths, &options).unwrap();
+    assert_eq!(
+        string,
+        r#"plain: 'C:\Program Files\eon\eon.exe'
+apostrophe: '''C:\Users\O'Brien\config.eon'''
+quoted: 'C:\Users\"quoted"\config.eon'
+"#
+    );
+    let round_tripped: Paths = eon::from_str(&string).unwrap();
+    assert_eq!(round_tripped, paths);
+}
+
+#[test]
+fn test_tagged_enums() {
+    #[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq)]
+    #[serde(tag = "type")]
+    enum Shape {
+        Circle { radius: f32, color: Color },
+        Square { side: f32, colors: Vec<Color> },
+        Empty,
+    }
+
+    #[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq)]
+    #[serde(tag = "type", content = "value")]
+    enum Fill {
+        Solid(Color),
+        Gradient { from: Color, to: Color },
+        None,
+    }
+
+    #[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq)]
+    #[serde(untagged)]
+    enum Size {
+        Fixed(f32),
+        Named(Color),
+    }
+
+    fn round_trip<T>(value: &T, expected: &str)
+    where
+        T: serde::Serialize + serde::de::DeserializeOwned + std::fmt::Debug + PartialEq,
+    {
+        let string = eon::to_string(value, &eon::FormatOptions::default()).unwrap();
+        assert_eq!(string, expected);
+        assert_eq!(&eon::from_str::<T>(&string).unwrap(), value);
+    }
+
+    round_trip(
+        &Shape::Circle {
+            radius: 1.5,
+            color: Color::Hsl(0, 100, 200),
+        },
+        "type: \"Circle\"\nradius: 1.5\ncolor: \"Hsl\"(0, 100, 200)\n",
+    );
+    round_trip(
+        &Shape::Square {
+            side: 2.0,
+            colors: vec![Color::Black, Color::Gray(128)],
+        },
+        "type: \"Square\"\nside: 2.0\ncolors: [\n\t\"Black\"\n\t\"Gray\"(128)\n]\n",
+    );
+    round_trip(&Shape::Empty, "type: \"Empty\"\n");
+
+    round_trip(
+        &Fill::Solid(Color::Gray(7)),
+        "type: \"Solid\"\nvalue: \"Gray\"(7)\n",
+    );
+    round_trip(
+        &Fill::Gradient {
+            from: Color::Black,
+            to: Color::Hsl(1, 2, 3),
+        },
+        "type: \"Gradient\"\nvalue: {\n\tfrom: \"Black\"\n\tto: \"Hsl\"(1, 2, 3)\n}\n",
+    );
+    round_trip(&Fill::None, "type: \"None\"\n");
+
+    round_trip(
+        &vec![Size::Fixed(1.0), Size::Named(Color::Gray(3))],
+        "[\n\t1.0\n\t\"Gray\"(3)\n]",
+    );
+}
+
+#[test]
+fn test_type_hints() {
+    #[derive(serde::Deserialize, Debug, PartialEq)]
+    struct Hints {
+        byte: u8,
+        float: f32,
+        letter: char,
+        flag: bool,
+    }
+
+    let map: BTreeMap<String, i32> = eon::from_str("1: 10\n\"two\": 20\nthree: 30").unwrap();
+    assert_eq!(
+        map.keys().map(String::as_str).collect::<Vec<_>>(),
+        ["1", "three", "two"]
+    );
+
+    assert_eq!(
+        eon::from_str::<Hints>("byte: 3.0, float: 2, letter: \"ö\", flag: true").unwrap(),
+        Hints {
+            byte: 3,
+            float: 2.0,
+            letter: 'ö',
+            flag: true,
+        }
+    );
+
+    let error = |source: &str| {
+        let err = eon::from_str::<Hints>(source).unwrap_err();
+        (
+            err.message().to_owned(),
+            err.span()
+                .map(|span| source[span.start..span.end].to_owned()),
+        )
+    };
+    let valid = "float: 2, letter: \"x\", flag: true";
+    assert_eq!(
+        error(&format!("byte: 3.5, {valid}")),
+        (
+            "Expected an integer (u8), got 3.5".to_owned(),
+            Some("3.5".to_owned())
+        )
+    );
+    assert_eq!(
+        error(&format!("byte: 256, {valid}")),
+        (
+            "256 is out of range for u8".to_owned(),
+            Some("256".to_owned())
+        )
+    );
+    assert_eq!(
+        error("byte: 1, float: \"2\", letter: \"x\", flag: true"),
+        (
+            "Expected a number, got the string \"2\"".to_owned(),
+            Some("\"2\"".to_owned())
+        )
+    );
+    assert_eq!(
+        error("byte: 1, float: 2, letter: \"xy\", flag: true"),
+        (
+            "Expected a single character, got the string \"xy\"".to_owned(),
+            Some("\"xy\"".to_owned())
+        )
+    );
+    assert_eq!(
+        error("byte: 1, float: 2, letter: \"x\", flag: 1"),
+        (
+            "Expected true or false, got 1".to_owned(),
+            Some("1".to_owned())
+        )
+    );
+
+    let err = eon::from_str::<BTreeMap<String, String>>("name: null").unwrap_err();
+    assert_eq!(err.message(), "Expected a string, got null");
+
+    // Only map keys can be numbers in disguise:
+    #[derive(serde::Deserialize, Debug)]
+    #[expect(dead_code)]
+    struct Named {
+        name: String,
+    }
+    let err = eon::from_str::<Named>("name: 42").unwrap_err();
+    assert_eq!(err.message(), "Expected a string, got 42");
+}
+
+#[test]
+fn test_unknown_fields() {
+    #[derive(serde::Deserialize, Debug, PartialEq)]
+    struct Config {
+        max_width: u32,
+        servers: Vec<Server>,
+        colors: BTreeMap<String, Color>,
+    }
+
+    #[derive(serde::Deserialize, Debug, PartialEq)]
+    struct Server {
+        host: String,
+    }
+
+    let source = r#"
+maxWidth: 800
+servers: [{ host: "a", prot: 80 }]
+colors: {
+    anything: "Rgb"({ r: 1, g: 2, b: 3, alpha: 4 })
+}
+typo: true
+"#;
+
+    let (config, warnings) =
+        eon::from_str_with_warnings::<Config>(source, &eon::ParseOptions::default()).unwrap();
+    assert_eq!(config.max_width, 800);
+    let warnings: Vec<(&str, &str)> = warnings
+        .iter()
+        .map(|warning| {
+            let span = warning.span().unwrap();
+            (&source[span.start..span.end], warning.message())
+        })
+        .collect();
+    assert_eq!(
+        warnings,
+        [
+            ("prot", "Unknown field `prot`, expected one of: `host`"),
+            (
+                "alpha",
+                "Unknown field `alpha`, expected one of: `r`, `g`, `b`"
+            ),
+            (
+                "typo",
+                "Unknown field `typo`, expected one of: `max_width`, `servers`, `colors`"
+            ),
+        ]
+    );
+
+    let err = eon::from_str_strict::<Config>(source).unwrap_err();
+    assert_eq!(err.message(), "3 unknown fields");
+    insta::assert_snapshot!(err, @r#"
+    Error: 3 unknown fields
+       ╭─[ <unknown>:3:24 ]
+       │
+     3 │ servers: [{ host: "a", prot: 80 }]
+       │                        ──┬─
+       │                          ╰─── Unknown field `prot`, expected one of: `host`
+       │
+     5 │     anything: "Rgb"({ r: 1, g: 2, b: 3, alpha: 4 })
+       │                                         ──┬──
+       │                                           ╰──── Unknown field `alpha`, expected one of: `r`, `g`, `b`
+       │
+     7 │ typo: true
+       │ ──┬─
+       │   ╰─── Unknown field `typo`, expected one of: `max_width`, `servers`, `colors`
+    ───╯
+    "#);
+
+    assert!(
+        eon::from_str_strict::<Config>("max_width: 1, servers: [], colors: {}").is_ok(),
+        "No unknown fields"
+    );
+
+    // Duplicate keys are handled like in `from_str`:
+    let source = "max_width: 1, servers: [], colors: {}, max_width: 2";
+    assert_eq!(
+        eon::from_str_strict::<Config>(source).unwrap(),
+        eon::from_str::<Config>(source).unwrap()
+    );
+}
+
+#[test]
+fn test_roundtrip_check() {
+    #[derive(serde::Deserialize, serde::Serialize)]
+    struct Config {
+        name: String,
+        #[expect(clippy::option_option)]
+        retries: Option<Option<u32>>,
+        ratio: f32,
+        tags: Vec<String>,
+    }
+
+    let config = Config {
+        name: "server".to_owned(),
+        retries: Some(Some(3)),
+        ratio: f32::NAN,
+        tags: vec!["a".to_owned(), "b".to_owned()],
+    };
+    assert_eq!(eon::roundtrip_check(&config), Ok(()));
+
+    let config = Config {
+        retries: Some(None),
+        ratio: -f32::NAN,
+        ..config
+    };
+    let report = eon::roundtrip_check(&config).unwrap_err();
+    assert_eq!(report.error, None);
+    let paths: Vec<String> = report.losses.iter().map(|l| l.path.to_string()).collect();
+    assert_eq!(paths, ["retries", "ratio"]);
+    assert_eq!(report.losses[0].after, Some(eon::Value::Null));
+    insta::assert_snapshot!(report, @r#"
+    Round-trip changed 2 value(s):
+      retries: "Some"(null) -> null
+      ratio: +nan changed its bit pattern
+    "#);
+
+    #[derive(serde::Deserialize, serde::Serialize)]
+    struct Strict {
+        value: u8,
+    }
+    assert!(eon::roundtrip_check(&Strict { value: 255 }).is_ok());
+}
+
+#[test]
+fn test_keyword_suggestions() {
+    #[derive(Debug, serde::Deserialize)]
+    struct Config {
+        #[expect(dead_code)]
+        speed: f64,
+    }
+
+    assert_eq!(eon::from_str::<f64>("+inf").unwrap(), f64::INFINITY);
+
+    let err = eon::from_str::<Config>("speed: inf").unwrap_err();
+    assert_eq!(
+        err.message(),
+        "Expected a number, got inf. Did you mean: +inf or -inf?"
+    );
+    let span = err.span().unwrap();
+    assert_eq!(&"speed: inf"[span.start..span.end], "inf");
+
+    let err = eon::from_str::<Option<bool>>("True").unwrap_err();
+    assert_eq!(
+        err.message(),
+        "Expected true or false, got True. Did you mean: true?"
+    );
+
+    // Same hints as when parsing into a `Value`:
+    let err = eon::parse_scalar("inf").unwrap_err();
+    assert_eq!(
+        err.message(),
+        "Unknown keyword \"inf\". Did you mean: +inf or -inf?"
+    );
+}
+
+#[test]
+fn test_generate_template() {
+    use eon::{CommentMap, EonDocumented, KeyPath};
+
+    #[derive(serde::Serialize)]
+    struct Server {
+        host: String,
+        port: u16,
+    }
+
+    impl Default for Server {
+        fn default() -> Self {
+            Self {
+                host: "localhost".to_owned(),
+                port: 8080,
+            }
+        }
+    }
+
+    impl EonDocumented for Server {
+        fn document(prefix: &KeyPath, comments: &mut CommentMap) {
+            comments.insert(prefix.clone().key("host"), "Hostname or IP address");
+            comments.insert(prefix.clone().key("port"), "TCP port");
+        }
+    }
+
+    #[derive(Default, serde::Serialize)]
+    struct Config {
+        server: Server,
+        workers: Vec<String>,
+    }
+
+    impl EonDocumented for Config {
+        fn document(prefix: &KeyPath, comments: &mut CommentMap) {
+            let server = prefix.clone().key("server");
+            comments.insert(server.clone(), "Where to listen");
+            Server::document(&server, comments);
+            comments.insert(prefix.clone().key("workers"), "Names of the worker pools");
+        }
+    }
+
+    let template = eon::generate_template::<Config>().unwrap();
+    assert_eq!(
+        template,
+        r#"// Where to listen
+server: {
+	// Hostname or IP address
+	host: "localhost"
+
+	// TCP port
+	port: 8080
+}
+
+// Names of the worker pools
+workers: []
+"#
+    );
+    let _: eon::Value = template.parse().unwrap();
+}
+
+#[test]
+fn test_did_you_mean() {
+    #[derive(Debug, serde::Deserialize)]
+    enum Color {
+        Red,
+        Blue,
+    }
+
+    #[derive(Debug, serde::Deserialize)]
+    #[serde(deny_unknown_fields)]
+    #[expect(dead_code)]
+    struct Config {
+        color: Color,
+        max_width: u32,
+    }
+
+    let unknown_bleu = eon::ErrorKind::UnknownVariant {
+        found: "Bleu".to_owned(),
+        expected: vec!["Blue".to_owned(), "Red".to_owned()],
+    };
+
+    let err = eon::from_str::<Config>("color: \"Bleu\", max_width: 3").unwrap_err();
+    assert_eq!(
+        err.message(),
+        "Unknown variant `Bleu`, expected one of: `Red`, `Blue`. Did you mean `Blue`?"
+    );
+    assert_eq!(err.kind(), &unknown_bleu);
+
+    let err = eon::from_str::<Config>("color: \"Red\", max_widht: 3").unwrap_err();
+    assert_eq!(
+        err.message(),
+        "Unknown field `max_widht`, expected one of: `color`, `max_width`. Did you mean `max_width`?"
+    );
+
+    let mut deserializer = eon::StreamDeserializer::new("color: \"Bleu\", max_width: 3");
+    let err = <Config as serde::Deserialize<'_>>::deserialize(&mut deserializer).unwrap_err();
+    assert!(err.to_string().contains("Did you mean `Blue`?"), "{err}");
+    assert_eq!(eon::Error::from(err).kind(), &unknown_bleu);
+}
+
+#[test]
+fn test_human_readable() {
+    use std::net::{IpAddr, SocketAddr};
+
+    #[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq)]
+    struct Peer {
+        ip: IpAddr,
+        socket: SocketAddr,
+    }
+
+    let peer = Peer {
+        ip: "::1".parse().unwrap(),
+        socket: "127.0.0.1:8080".parse().unwrap(),
+    };
+
+    fn stream_from_str(eon_source: &str, human_readable: bool) -> Result<Peer, eon::Error> {
+        let mut deserializer =
+            eon::StreamDeserializer::new(eon_source).with_human_readable(human_readable);
+        let value = <Peer as serde::Deserialize>::deserialize(&mut deserializer)?;
+        deserializer.end()?;
+        Ok(value)
+    }
+
+    // Human-readable by default:
+    let readable = eon::to_string(&peer, &Default::default()).unwrap();
+    assert_eq!(readable, "ip: \"::1\"\nsocket: \"127.0.0.1:8080\"\n");
+    assert_eq!(eon::from_str::<Peer>(&readable).unwrap(), peer);
+    assert_eq!(stream_from_str(&readable, true).unwrap(), peer);
+
+    let compact = eon::Serializer::new()
+        .with_human_readable(false)
+        .to_string(&peer, &Default::default())
+        .unwrap();
+    assert!(
+        compact.starts_with("ip: \"V6\"([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1])\n"),
+        "{compact}"
+    );
+    assert_eq!(stream_from_str(&compact, false).unwrap(), peer);
+    assert!(
+        stream_from_str(&compact, true).is_err(),
+        "The compact form is not a string"
+    );
+}
+
+#[test]
+fn test_value_serde_roundtrip() {
+    let value: eon::Value = r#"
+        null: null
+        bool: true
+        int: -42
+        float: 3.5
+        string: "text"
+        timestamp: 2024-05-01T12:30:00Z
+        list: [1, "two", [3]]
+        nested: { a: { b: [] } }
+        color: "Rgb"(255, 0, 0)
+        nested_variant: "Some"("Rgb"(1, 2, 3), { t: 2024-05-01T12:30:00Z })
+    "#
+    .parse()
+    .unwrap();
+
+    // Through another self-describing format and back:
+    let json = serde_json::to_string(&value).unwrap();
+    assert!(
+        json.contains(r#""color":{"$variant":"Rgb","$values":[255,0,0]}"#),
+        "{json}"
+    );
+    assert!(
+        json.contains(r#""timestamp":{"$timestamp":"2024-05-01T12:30:00Z"}"#),
+        "{json}"
+    );
+    assert_eq!(serde_json::from_str::<eon::Value>(&json).unwrap(), value);
+
+    // Through Eon and back, including things JSON can't represent:
+    let value: eon::Value = r#"
+        big: 340282366920938463463374607431768211455
+        bytes: b"ff00"
+        1: "one"
+        [2]: "Rgb"(b"01")
+        timestamp: 2024-05-01T12:30:00Z
+        color: "Rgb"(255, 0, 0)
+    "#
+    .parse()
+    .unwrap();
+    assert_eq!(eon::to_value(&value).unwrap(), value);
+    let eon_string = eon::to_string(&value, &Default::default()).unwrap();
+    assert_eq!(eon_string.parse::<eon::Value>().unwrap(), value);
+    assert_eq!(eon::from_str::<eon::Value>(&eon_string).unwrap(), value);
+
+    // The stream deserializer keeps variants too:
+    let mut deserializer = eon::StreamDeserializer::new(r#""Rgb"(255, 0, 0)"#);
+    let color = <eon::Value as serde::Deserialize>::deserialize(&mut deserializer).unwrap();
+    assert_eq!(color, r#""Rgb"(255, 0, 0)"#.parse().unwrap());
+}
@@ -0,0 +1,85 @@
+//! Query Eon files from the command line, like `jq` does for JSON.
+//!
+//! ```sh
+//! eon get config.eon 'server.endpoints[0].url'
+//! ```
+//!
+//! See <https://github.com/emilk/eon> for more.
+
+use std::process;
+
+use clap::{Arg, ArgMatches, Command};
+use eon::Value;
+
+fn main() {
+    let matches = Command::new("eon")
+        .about("Query Eon files")
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("get")
+                .about("Print the value at a path, like `server.endpoints[0].url`")
+                .arg(
+                    Arg::new("file")
+                        .help("The Eon file to read")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::new("path")
+                        .help("Path to the value. Leave out to print the whole document")
+                        .default_value("")
+                        .index(2),
+                )
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .short('f')
+                        .help("Output format")
+                        .value_parser(["eon", "json"])
+                        .default_value("eon"),
+                )
+                .arg(
+                    Arg::new("raw")
+                        .long("raw")
+                        .short('r')
+                        .help("Print strings without quotes")
+                        .action(clap::ArgAction::SetTrue),
+                ),
+        )
+        .get_matches();
+
+    let result = match matches.subcommand() {
+        Some(("get", matches)) => get(matches),
+        _ => unreachable!("clap requires a subcommand"),
+    };
+
+    if let Err(err) = result {
+        eprintln!("{err}");
+        #[allow(clippy::exit, clippy::allow_attributes)]
+        process::exit(1);
+    }
+}
+
+/// `eon get <file> <path>`
+fn get(matches: &ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    let file = matches.get_one::<String>("file").expect("Missing file");
+    let path = matches.get_one::<String>("path").expect("Missing path");
+    let format = matches.get_one::<String>("format").expect("Missing format");
+
+    let path: eon::KeyPath = path.parse()?;
+    let document = Value::from_file(file)?;
+    let Some(value) = document.get_key_path(&path) else {
+        return Err(format!("Found nothing at `{path}` in {file}").into());
+    };
+
+    if let (true, Value::String(string)) = (matches.get_flag("raw"), value) {
+        println!("{string}");
+    } else if format == "json" {
+        println!("{}", eon::interop::json::to_json_string(value));
+    } else {
+        let formatted = value.format(&eon::FormatOptions::default());
+        println!("{}", formatted.trim_end());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,109 @@
+use syn::{Attribute, Ident, LitStr, ext::IdentExt as _};
+
+/// What to use for a field that is missing from the Eon map.
+pub enum DefaultValue {
+    /// `#[eon(default)]`: use [`Default::default`].
+    Trait,
+
+    /// `#[eon(default = "path::to::function")]`.
+    Function(syn::ExprPath),
+}
+
+/// The contents of the `#[eon(…)]` attributes on a field or enum variant.
+#[derive(Default)]
+pub struct Attributes {
+    /// `#[eon(rename = "name")]`
+    pub rename: Option<String>,
+
+    /// `#[eon(default)]` or `#[eon(default = "function")]`. Only valid on fields.
+    pub default: Option<DefaultValue>,
+
+    /// `#[eon(skip)]`. Only valid on fields.
+    pub skip: bool,
+
+    /// The doc-comment, if any.
+    pub doc: Option<String>,
+}
+
+impl Attributes {
+    pub fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut result = Self {
+            doc: doc_comment(attrs),
+            ..Default::default()
+        };
+
+        for attr in attrs {
+            if !attr.path().is_ident("eon") {
+                continue;
+            }
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    let name: LitStr = meta.value()?.parse()?;
+                    result.rename = Some(name.value());
+                    Ok(())
+                } else if meta.path.is_ident("default") {
+                    if meta.input.peek(syn::Token![=]) {
+                        let function: LitStr = meta.value()?.parse()?;
+                        result.default = Some(DefaultValue::Function(function.parse()?));
+                    } else {
+                        result.default = Some(DefaultValue::Trait);
+                    }
+                    Ok(())
+                } else if meta.path.is_ident("skip") {
+                    result.skip = true;
+                    Ok(())
+                } else {
+                    Err(meta
+                        .error("Unknown eon attribute, expected `rename`, `default`, or `skip`"))
+                }
+            })?;
+        }
+
+        Ok(result)
+    }
+
+    /// Parse the attributes of an enum variant, which only supports `rename`.
+    pub fn parse_variant(attrs: &[Attribute], span: proc_macro2::Span) -> syn::Result<Self> {
+        let result = Self::parse(attrs)?;
+        if result.default.is_some() || result.skip {
+            return Err(syn::Error::new(
+                span,
+                "Only `#[eon(rename = \"…\")]` is supported on enum variants",
+            ));
+        }
+        Ok(result)
+    }
+
+    /// The name in Eon of the field or variant called `ident` in Rust.
+    pub fn name(&self, ident: &Ident) -> String {
+        self.rename
+            .clone()
+            .unwrap_or_else(|| ident.unraw().to_string())
+    }
+}
+
+/// Join the lines of `/// doc-comments`, removing the leading space of each line.
+fn doc_comment(attrs: &[Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| {
+            let syn::Meta::NameValue(name_value) = &attr.meta else {
+                return None;
+            };
+            let syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(line),
+                ..
+            }) = &name_value.value
+            else {
+                return None;
+            };
+            let line = line.value();
+            Some(line.strip_prefix(' ').unwrap_or(&line).to_owned())
+        })
+        .collect();
+
+    let doc = lines.join("\n");
+    let doc = doc.trim();
+    (!doc.is_empty()).then(|| doc.to_owned())
+}
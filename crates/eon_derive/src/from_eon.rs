@@ -0,0 +1,159 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, FieldsNamed};
+
+use crate::attributes::{Attributes, DefaultValue};
+
+pub fn derive(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let generics = crate::add_bounds(&input.generics, &syn::parse_quote!(::eon::FromEon));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let mut from_missing = quote!();
+
+    let from_eon = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => {
+                let construct = construct_named(&quote!(Self), fields)?;
+                quote! {
+                    let __map = ::eon::__private::expect_map(value)?;
+                    Ok(#construct)
+                }
+            }
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                let ty = &fields.unnamed[0].ty;
+                from_missing = quote! {
+                    fn from_missing() -> Option<Self> {
+                        <#ty as ::eon::FromEon>::from_missing().map(Self)
+                    }
+                };
+                quote!(::eon::FromEon::from_eon(value).map(Self))
+            }
+            Fields::Unnamed(fields) => {
+                let construct = construct_tuple(&quote!(Self), fields.unnamed.len());
+                quote! {
+                    let __values = ::eon::__private::expect_list(value)?;
+                    #construct
+                }
+            }
+            Fields::Unit => quote! {
+                ::eon::__private::expect_null(value)?;
+                Ok(Self)
+            },
+        },
+
+        Data::Enum(data) => {
+            let mut names = vec![];
+            let mut unit_arms = vec![];
+            let mut data_arms = vec![];
+            for variant in &data.variants {
+                let attributes = Attributes::parse_variant(&variant.attrs, variant.ident.span())?;
+                let ident = &variant.ident;
+                let eon_name = attributes.name(ident);
+                match &variant.fields {
+                    Fields::Named(fields) => {
+                        let construct = construct_named(&quote!(Self::#ident), fields)?;
+                        data_arms.push(quote! {
+                            #eon_name => {
+                                ::eon::__private::expect_len(__values, 1)?;
+                                let __map = ::eon::__private::expect_map(&__values[0])?;
+                                Ok(#construct)
+                            }
+                        });
+                        unit_arms.push(quote! {
+                            #eon_name => Err(::eon::__private::missing_values(#eon_name))
+                        });
+                    }
+                    Fields::Unnamed(fields) => {
+                        let construct =
+                            construct_tuple(&quote!(Self::#ident), fields.unnamed.len());
+                        data_arms.push(quote!(#eon_name => { #construct }));
+                        unit_arms.push(quote! {
+                            #eon_name => Err(::eon::__private::missing_values(#eon_name))
+                        });
+                    }
+                    Fields::Unit => {
+                        unit_arms.push(quote!(#eon_name => Ok(Self::#ident)));
+                        data_arms.push(quote! {
+                            #eon_name => Err(::eon::__private::unexpected_values(#eon_name))
+                        });
+                    }
+                }
+                names.push(eon_name);
+            }
+
+            quote! {
+                const VARIANTS: &[&str] = &[#(#names),*];
+                match value {
+                    ::eon::Value::String(name) => match name.as_str() {
+                        #(#unit_arms,)*
+                        name => Err(::eon::__private::unknown_variant(name, VARIANTS)),
+                    },
+                    ::eon::Value::Variant(variant) => {
+                        let __values = variant.values.as_slice();
+                        match variant.name.as_str() {
+                            #(#data_arms,)*
+                            name => Err(::eon::__private::unknown_variant(name, VARIANTS)),
+                        }
+                    }
+                    value => Err(::eon::__private::expected_variant(value)),
+                }
+            }
+        }
+
+        Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                input,
+                "FromEon cannot be derived for unions",
+            ));
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::eon::FromEon for #name #ty_generics #where_clause {
+            fn from_eon(value: &::eon::Value) -> ::eon::Result<Self> {
+                #from_eon
+            }
+
+            #from_missing
+        }
+    })
+}
+
+/// Construct `path { … }` from the fields of the `__map: &eon::Map`.
+fn construct_named(path: &TokenStream, fields: &FieldsNamed) -> syn::Result<TokenStream> {
+    let mut initializers = vec![];
+    for field in &fields.named {
+        let attributes = Attributes::parse(&field.attrs)?;
+        let ident = field.ident.as_ref().expect("named field");
+        let eon_name = attributes.name(ident);
+        let value = if attributes.skip {
+            quote!(::core::default::Default::default())
+        } else {
+            match &attributes.default {
+                None => quote!(::eon::__private::field(__map, #eon_name)?),
+                Some(DefaultValue::Trait) => quote! {
+                    ::eon::__private::field_or_else(
+                        __map,
+                        #eon_name,
+                        ::core::default::Default::default,
+                    )?
+                },
+                Some(DefaultValue::Function(function)) => {
+                    quote!(::eon::__private::field_or_else(__map, #eon_name, #function)?)
+                }
+            }
+        };
+        initializers.push(quote!(#ident: #value));
+    }
+    Ok(quote!(#path { #(#initializers,)* }))
+}
+
+/// Construct `Ok(path(…))` from the `__values: &[eon::Value]`.
+fn construct_tuple(path: &TokenStream, len: usize) -> TokenStream {
+    let elements = (0..len).map(|i| quote!(::eon::__private::element(&__values[#i], #i)?));
+    quote! {
+        ::eon::__private::expect_len(__values, #len)?;
+        Ok(#path(#(#elements),*))
+    }
+}
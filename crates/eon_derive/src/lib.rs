@@ -0,0 +1,64 @@
+//! Derive macros for converting between Rust types and [`eon::Value`](https://docs.rs/eon/latest/eon/enum.Value.html),
+//! without going through `serde`.
+//!
+//! Use these through the `derive` feature of the `eon` crate, as `#[derive(eon::ToEon, eon::FromEon)]`.
+//!
+//! Structs with named fields become Eon maps, and their doc-comments become Eon comments.
+//! Tuple structs become lists, except newtype structs which are transparent wrappers.
+//!
+//! Enum variants without data become strings, like `"Red"`,
+//! and variants with data become Eon variants, like `"Rgb"(255, 0, 0)`,
+//! with one value per tuple field, or a single map for variants with named fields.
+//!
+//! ## Attributes
+//! * `#[eon(rename = "name")]` on a field or variant: use a different name in Eon.
+//! * `#[eon(default)]` on a field: use [`Default::default`] if the field is missing.
+//! * `#[eon(default = "path::to::function")]` on a field: call the function if the field is missing.
+//! * `#[eon(skip)]` on a field: never write it, and always read it as [`Default::default`].
+//!
+//! [`Option`] fields are `None` when missing, without needing `#[eon(default)]`.
+
+mod attributes;
+mod from_eon;
+mod to_eon;
+
+use proc_macro::TokenStream;
+use syn::{DeriveInput, parse_macro_input};
+
+/// Implement `eon::ToEon` for a struct or enum.
+///
+/// See the [crate docs](crate) for the supported attributes.
+#[proc_macro_derive(ToEon, attributes(eon))]
+pub fn derive_to_eon(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    to_eon::derive(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implement `eon::FromEon` for a struct or enum.
+///
+/// See the [crate docs](crate) for the supported attributes.
+#[proc_macro_derive(FromEon, attributes(eon))]
+pub fn derive_from_eon(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    from_eon::derive(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Add `T: bound` for each type parameter `T`.
+fn add_bounds(generics: &syn::Generics, bound: &syn::Path) -> syn::Generics {
+    let mut generics = generics.clone();
+    let type_params: Vec<syn::Ident> = generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect();
+    let where_clause = generics.make_where_clause();
+    for ident in type_params {
+        where_clause
+            .predicates
+            .push(syn::parse_quote!(#ident: #bound));
+    }
+    generics
+}
@@ -0,0 +1,168 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Fields, FieldsNamed};
+
+use crate::attributes::Attributes;
+
+pub fn derive(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let generics = crate::add_bounds(&input.generics, &syn::parse_quote!(::eon::ToEon));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let (to_eon, document) = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => {
+                let fields = named_fields(fields)?;
+                let to_eon = fields.to_map(|ident| quote!(&self.#ident));
+                (to_eon, fields.document())
+            }
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                let ty = &fields.unnamed[0].ty;
+                let to_eon = quote!(::eon::ToEon::to_eon(&self.0));
+                let document = quote! {
+                    fn document(prefix: &::eon::KeyPath, comments: &mut ::eon::CommentMap) {
+                        <#ty as ::eon::ToEon>::document(prefix, comments);
+                    }
+                };
+                (to_eon, document)
+            }
+            Fields::Unnamed(fields) => {
+                let indices = (0..fields.unnamed.len()).map(syn::Index::from);
+                let to_eon = quote! {
+                    ::eon::Value::List(vec![#(::eon::ToEon::to_eon(&self.#indices)),*])
+                };
+                (to_eon, quote!())
+            }
+            Fields::Unit => (quote!(::eon::Value::Null), quote!()),
+        },
+
+        Data::Enum(data) => {
+            let mut arms = vec![];
+            for variant in &data.variants {
+                let attributes = Attributes::parse_variant(&variant.attrs, variant.ident.span())?;
+                let ident = &variant.ident;
+                let eon_name = attributes.name(ident);
+                arms.push(match &variant.fields {
+                    Fields::Named(fields) => {
+                        let fields = named_fields(fields)?;
+                        let idents = fields.fields.iter().map(|field| &field.ident);
+                        let map = fields.to_map(|ident| quote!(#ident));
+                        quote! {
+                            Self::#ident { #(#idents,)* .. } => {
+                                ::eon::Value::new_variant(#eon_name.to_owned(), vec![#map])
+                            }
+                        }
+                    }
+                    Fields::Unnamed(fields) => {
+                        let bindings: Vec<_> = (0..fields.unnamed.len())
+                            .map(|i| format_ident!("field_{i}"))
+                            .collect();
+                        quote! {
+                            Self::#ident(#(#bindings),*) => ::eon::Value::new_variant(
+                                #eon_name.to_owned(),
+                                vec![#(::eon::ToEon::to_eon(#bindings)),*],
+                            )
+                        }
+                    }
+                    Fields::Unit => quote!(Self::#ident => ::eon::Value::from(#eon_name)),
+                });
+            }
+            if arms.is_empty() {
+                (quote!(match *self {}), quote!())
+            } else {
+                (quote!(match self { #(#arms,)* }), quote!())
+            }
+        }
+
+        Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                input,
+                "ToEon cannot be derived for unions",
+            ));
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::eon::ToEon for #name #ty_generics #where_clause {
+            fn to_eon(&self) -> ::eon::Value {
+                #to_eon
+            }
+
+            #document
+        }
+    })
+}
+
+struct NamedField<'a> {
+    ident: &'a syn::Ident,
+    ty: &'a syn::Type,
+    eon_name: String,
+    doc: Option<String>,
+}
+
+struct NamedFields<'a> {
+    /// The fields that are not `#[eon(skip)]`.
+    fields: Vec<NamedField<'a>>,
+}
+
+fn named_fields(fields: &FieldsNamed) -> syn::Result<NamedFields<'_>> {
+    let mut result = vec![];
+    for field in &fields.named {
+        let attributes = Attributes::parse(&field.attrs)?;
+        if attributes.skip {
+            continue;
+        }
+        let ident = field.ident.as_ref().expect("named field");
+        result.push(NamedField {
+            ident,
+            ty: &field.ty,
+            eon_name: attributes.name(ident),
+            doc: attributes.doc,
+        });
+    }
+    Ok(NamedFields { fields: result })
+}
+
+impl NamedFields<'_> {
+    /// An expression building a `Value::Map` of the fields,
+    /// where `access` returns a reference to the field with the given name.
+    fn to_map(&self, access: impl Fn(&syn::Ident) -> TokenStream) -> TokenStream {
+        let inserts = self.fields.iter().map(|field| {
+            let eon_name = &field.eon_name;
+            let value = access(field.ident);
+            quote! {
+                __map.insert(::eon::Value::from(#eon_name), ::eon::ToEon::to_eon(#value));
+            }
+        });
+        quote! {{
+            let mut __map = ::eon::Map::new();
+            #(#inserts)*
+            ::eon::Value::Map(__map)
+        }}
+    }
+
+    /// The `ToEon::document` method, turning doc-comments into Eon comments.
+    fn document(&self) -> TokenStream {
+        if self.fields.is_empty() {
+            return quote!();
+        }
+        let fields = self.fields.iter().map(|field| {
+            let NamedField {
+                ty, eon_name, doc, ..
+            } = field;
+            let comment = doc
+                .as_ref()
+                .map(|doc| quote!(comments.insert(path.clone(), #doc);));
+            quote! {{
+                let path = prefix.clone().key(#eon_name);
+                #comment
+                <#ty as ::eon::ToEon>::document(&path, comments);
+            }}
+        });
+        quote! {
+            fn document(prefix: &::eon::KeyPath, comments: &mut ::eon::CommentMap) {
+                #(#fields)*
+            }
+        }
+    }
+}
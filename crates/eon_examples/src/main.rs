@@ -0,0 +1,123 @@
+//! Renders the canonical example documents of the Eon README.
+//!
+//! Each example is constructed programmatically as an [`eon::Value`] and formatted
+//! with the real formatter, so the documented output can never drift from what `eon` actually produces.
+//!
+//! Usage:
+//! ```text
+//! cargo run -p eon_examples          # print all examples
+//! cargo run -p eon_examples -- check # verify that README.md contains all of them
+//! ```
+
+use std::process;
+
+use eon::{FormatOptions, Map, Value};
+
+/// An example document from the README.
+struct Example {
+    name: &'static str,
+    value: Value,
+}
+
+impl Example {
+    fn new(name: &'static str, key: &str, value: impl Into<Value>) -> Self {
+        let mut map = Map::new();
+        map.insert(Value::from(key), value.into());
+        Self {
+            name,
+            value: Value::Map(map),
+        }
+    }
+
+    fn render(&self) -> String {
+        self.value.format(&format_options())
+    }
+}
+
+/// The README uses four spaces for indentation, since tabs render inconsistently on the web.
+fn format_options() -> FormatOptions {
+    FormatOptions::default().with_indentation("    ".to_owned())
+}
+
+const COUNTRIES: [(&str, i32); 9] = [
+    ("United States", 1),
+    ("France", 33),
+    ("United Kingdom", 44),
+    ("Sweden", 46),
+    ("Germany", 49),
+    ("Australia", 61),
+    ("Japan", 81),
+    ("China", 86),
+    ("India", 91),
+];
+
+fn examples() -> Vec<Example> {
+    let country_codes: Map = COUNTRIES
+        .iter()
+        .map(|&(country, code)| (Value::from(country), Value::from(code)))
+        .collect();
+
+    let country_from_code: Map = COUNTRIES
+        .iter()
+        .map(|&(country, code)| (Value::from(code), Value::from(country)))
+        .collect();
+
+    let planets: Vec<Value> = [
+        "Mercury", "Venus", "Earth", "Mars", "Jupiter", "Saturn", "Uranus", "Neptune",
+    ]
+    .into_iter()
+    .map(Value::from)
+    .collect();
+    let short_list: Vec<Value> = [1, 2, 3].into_iter().map(Value::from).collect();
+
+    vec![
+        Example::new("long_list", "long_list", planets),
+        Example::new("short_list", "short_list", short_list),
+        Example::new("country_codes", "country_codes", country_codes),
+        Example::new("country_from_code", "country_from_code", country_from_code),
+    ]
+}
+
+/// Returns the names of all examples that are missing from the given README.
+fn missing_from_readme(readme: &str) -> Vec<&'static str> {
+    examples()
+        .into_iter()
+        .filter(|example| !readme.contains(example.render().as_str()))
+        .map(|example| example.name)
+        .collect()
+}
+
+const README: &str = include_str!("../../../README.md");
+
+fn main() {
+    let check = std::env::args().nth(1).is_some_and(|arg| arg == "check");
+
+    if check {
+        let missing = missing_from_readme(README);
+        if missing.is_empty() {
+            eprintln!("README.md is up-to-date");
+        } else {
+            for name in missing {
+                eprintln!(
+                    "README.md is missing or has an outdated version of the example {name:?}"
+                );
+            }
+            #[allow(clippy::exit, clippy::allow_attributes)]
+            process::exit(1);
+        }
+    } else {
+        for example in examples() {
+            println!("// {}:", example.name);
+            println!("{}", example.render());
+        }
+    }
+}
+
+#[test]
+fn test_readme_examples_are_up_to_date() {
+    let missing = missing_from_readme(README);
+    assert!(
+        missing.is_empty(),
+        "README.md does not match the formatter output for {missing:?}. Run `cargo run -p eon_examples` and update README.md"
+    );
+}
@@ -0,0 +1,174 @@
+//! The language features, as functions from a document to LSP JSON values.
+
+use eon_syntax::{FormatOptions, Span, TokenTree, TokenValue};
+use serde_json::{Value, json};
+
+use crate::LineIndex;
+
+/// LSP `DiagnosticSeverity.Error`.
+const SEVERITY_ERROR: u8 = 1;
+
+/// LSP `SymbolKind`s.
+mod symbol_kind {
+    pub const CONSTANT: u8 = 14;
+    pub const STRING: u8 = 15;
+    pub const NUMBER: u8 = 16;
+    pub const BOOLEAN: u8 = 17;
+    pub const ARRAY: u8 = 18;
+    pub const OBJECT: u8 = 19;
+    pub const NULL: u8 = 21;
+    pub const ENUM_MEMBER: u8 = 22;
+}
+
+/// All syntax errors in the document, as LSP `Diagnostic`s.
+pub fn diagnostics(source: &str) -> Vec<Value> {
+    let index = LineIndex::new(source);
+    let (_, errors) = TokenTree::parse_str_recovering(source);
+    errors
+        .iter()
+        .map(|error| {
+            let diagnostic = error.to_parts();
+            let span = diagnostic
+                .labels
+                .first()
+                .map_or(Span { start: 0, end: 0 }, |label| label.span);
+            json!({
+                "range": index.range(span),
+                "severity": SEVERITY_ERROR,
+                "source": "eon",
+                "message": diagnostic.message,
+            })
+        })
+        .collect()
+}
+
+/// The `TextEdit`s for formatting the whole document.
+///
+/// `options` are the LSP `FormattingOptions`.
+/// Returns no edits if the document is already formatted, or has syntax errors.
+pub fn formatting(source: &str, options: &Value) -> Vec<Value> {
+    let mut format_options = FormatOptions::default();
+    if options["insertSpaces"].as_bool() == Some(true) {
+        let tab_size = options["tabSize"].as_u64().unwrap_or(4);
+        format_options.indentation = " ".repeat(tab_size as usize);
+    }
+    format_options.ensure_trailing_newline = options["insertFinalNewline"].as_bool() == Some(true);
+
+    match eon_syntax::reformat(source, &format_options) {
+        Ok(formatted) if formatted != source => {
+            vec![json!({
+                "range": LineIndex::new(source).full_range(),
+                "newText": formatted,
+            })]
+        }
+        _ => vec![],
+    }
+}
+
+/// LSP `FoldingRange`s for all multi-line maps, lists, and variants.
+pub fn folding_ranges(source: &str) -> Vec<Value> {
+    let index = LineIndex::new(source);
+    let mut ranges = vec![];
+    if let (Some(tree), _) = TokenTree::parse_str_recovering(source) {
+        // The top-level map covers the whole file, so don't fold it:
+        for child in children(&tree) {
+            collect_folding_ranges(&index, child, &mut ranges);
+        }
+    }
+    ranges
+}
+
+fn collect_folding_ranges(index: &LineIndex<'_>, tree: &TokenTree<'_>, ranges: &mut Vec<Value>) {
+    if let Some(span) = tree.span {
+        let (start_line, _) = index.line_col(span.start);
+        let (end_line, _) = index.line_col(span.end);
+        if start_line < end_line {
+            ranges.push(json!({ "startLine": start_line, "endLine": end_line }));
+        }
+    }
+    for child in children(tree) {
+        collect_folding_ranges(index, child, ranges);
+    }
+}
+
+/// A hierarchy of LSP `DocumentSymbol`s for the keys of all maps, and the elements of all lists.
+pub fn document_symbols(source: &str) -> Vec<Value> {
+    let index = LineIndex::new(source);
+    match TokenTree::parse_str_recovering(source) {
+        (Some(tree), _) => symbols(&index, &tree),
+        (None, _) => vec![],
+    }
+}
+
+/// The symbols for the children of `tree`.
+fn symbols(index: &LineIndex<'_>, tree: &TokenTree<'_>) -> Vec<Value> {
+    match &tree.value {
+        TokenValue::Map(map) => map
+            .key_values
+            .iter()
+            .filter_map(|kv| {
+                let name = match &kv.key.value {
+                    TokenValue::Number(number) => number.to_string(),
+                    key => key.as_key_str()?.into_owned(),
+                };
+                symbol(index, &name, kv.key.span?, &kv.value)
+            })
+            .collect(),
+        TokenValue::List(list) => list
+            .values
+            .iter()
+            .enumerate()
+            .filter_map(|(i, value)| symbol(index, &format!("[{i}]"), value.span?, value))
+            .collect(),
+        TokenValue::Variant(variant) => variant
+            .values
+            .iter()
+            .enumerate()
+            .filter_map(|(i, value)| symbol(index, &i.to_string(), value.span?, value))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+fn symbol(
+    index: &LineIndex<'_>,
+    name: &str,
+    selection_span: Span,
+    value: &TokenTree<'_>,
+) -> Option<Value> {
+    let kind = match &value.value {
+        TokenValue::Identifier(identifier) => match identifier.as_ref() {
+            "true" | "false" => symbol_kind::BOOLEAN,
+            "null" => symbol_kind::NULL,
+            _ => symbol_kind::CONSTANT,
+        },
+        TokenValue::Number(_) => symbol_kind::NUMBER,
+        TokenValue::Timestamp(_) | TokenValue::Bytes(_) => symbol_kind::CONSTANT,
+        TokenValue::QuotedString(_) => symbol_kind::STRING,
+        TokenValue::List(_) => symbol_kind::ARRAY,
+        TokenValue::Map(_) => symbol_kind::OBJECT,
+        TokenValue::Variant(_) => symbol_kind::ENUM_MEMBER,
+    };
+    let span = selection_span | value.span?;
+    Some(json!({
+        "name": name,
+        "kind": kind,
+        "range": index.range(span),
+        "selectionRange": index.range(selection_span),
+        "children": symbols(index, value),
+    }))
+}
+
+/// The keys and values of a map, or the values of a list or variant.
+fn children<'t, 's>(tree: &'t TokenTree<'s>) -> Vec<&'t TokenTree<'s>> {
+    match &tree.value {
+        TokenValue::Map(map) => map
+            .key_values
+            .iter()
+            .flat_map(|kv| [&kv.key, &kv.value])
+            .collect(),
+        TokenValue::List(list) => list.values.iter().collect(),
+        TokenValue::Variant(variant) => variant.values.iter().collect(),
+        _ => vec![],
+    }
+}
@@ -0,0 +1,20 @@
+//! A language server for Eon config files.
+//!
+//! Provides diagnostics, formatting, folding ranges, and a document outline
+//! over the [Language Server Protocol](https://microsoft.github.io/language-server-protocol/).
+//!
+//! Use the `eon-lsp` binary, or embed the [`Server`] in your own tool.
+//! See <https://github.com/emilk/eon> for more.
+
+mod features;
+mod line_index;
+mod server;
+mod transport;
+mod workspace;
+
+pub use self::{
+    line_index::LineIndex,
+    server::{Server, run},
+    transport::{read_message, write_message},
+    workspace::{Symbol, WorkspaceIndex},
+};
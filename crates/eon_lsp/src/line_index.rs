@@ -0,0 +1,70 @@
+//! Converting byte offsets into LSP positions.
+
+use eon_syntax::Span;
+use serde_json::{Value, json};
+
+/// Converts byte offsets in a document into LSP positions.
+///
+/// LSP positions are zero-based lines, and characters counted in UTF-16 code units.
+pub struct LineIndex<'a> {
+    source: &'a str,
+
+    /// Byte offset of the start of each line.
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(source: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self {
+            source,
+            line_starts,
+        }
+    }
+
+    /// The zero-based `(line, character)` of the given byte offset.
+    ///
+    /// Offsets past the end are clamped to the end of the document.
+    pub fn line_col(&self, offset: usize) -> (usize, usize) {
+        let mut offset = offset.min(self.source.len());
+        while !self.source.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let line_start = self.line_starts[line];
+        let character = self.source[line_start..offset].encode_utf16().count();
+        (line, character)
+    }
+
+    /// An LSP `Position`.
+    pub fn position(&self, offset: usize) -> Value {
+        let (line, character) = self.line_col(offset);
+        json!({ "line": line, "character": character })
+    }
+
+    /// An LSP `Range`.
+    pub fn range(&self, span: Span) -> Value {
+        json!({ "start": self.position(span.start), "end": self.position(span.end) })
+    }
+
+    /// The LSP `Range` of the whole document.
+    pub fn full_range(&self) -> Value {
+        self.range(Span {
+            start: 0,
+            end: self.source.len(),
+        })
+    }
+}
+
+#[test]
+fn test_line_index() {
+    let index = LineIndex::new("a: 1\nö: \"😀x\"\n");
+    assert_eq!(index.line_col(0), (0, 0));
+    assert_eq!(index.line_col(4), (0, 4));
+    assert_eq!(index.line_col(5), (1, 0));
+    assert_eq!(index.line_col(7), (1, 1)); // after the two-byte `ö`
+    assert_eq!(index.line_col(14), (1, 6)); // after the surrogate pair `😀`
+    assert_eq!(index.line_col(1000), (2, 0));
+}
@@ -0,0 +1,14 @@
+//! Eon language server.
+//!
+//! Speaks the Language Server Protocol over stdin/stdout.
+//! See <https://github.com/emilk/eon> for more.
+
+use std::{io, process};
+
+fn main() {
+    if let Err(err) = eon_lsp::run(&mut io::stdin().lock(), &mut io::stdout().lock()) {
+        eprintln!("eon-lsp: {err}");
+        #[allow(clippy::exit, clippy::allow_attributes)]
+        process::exit(1);
+    }
+}
@@ -0,0 +1,200 @@
+//! Dispatching LSP messages to the language features.
+
+use std::{
+    collections::BTreeMap,
+    io::{self, BufRead, Write},
+};
+
+use serde_json::{Value, json};
+
+use crate::{features, read_message, write_message};
+
+/// JSON-RPC error code for unknown methods.
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// The state of the language server: the open documents.
+///
+/// Documents are synced in full on every change.
+#[derive(Default)]
+pub struct Server {
+    /// The contents of the open documents, by URI.
+    documents: BTreeMap<String, String>,
+}
+
+impl Server {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Handle one incoming message (request or notification),
+    /// returning the messages to send back (responses and notifications).
+    pub fn handle(&mut self, message: &Value) -> Vec<Value> {
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+
+        let result = match method {
+            "initialize" => json!({
+                "capabilities": {
+                    "textDocumentSync": 1, // Full
+                    "documentFormattingProvider": true,
+                    "foldingRangeProvider": true,
+                    "documentSymbolProvider": true,
+                },
+                "serverInfo": {
+                    "name": "eon-lsp",
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            }),
+            "shutdown" => Value::Null,
+
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+                return self.set_document(uri, text.to_owned());
+            }
+            "textDocument/didChange" => {
+                // With full sync, the last change is the whole new document:
+                let Some(text) = params["contentChanges"]
+                    .as_array()
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str())
+                else {
+                    return vec![];
+                };
+                return self.set_document(uri, text.to_owned());
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(uri);
+                return vec![publish_diagnostics(uri, vec![])];
+            }
+
+            "textDocument/formatting" => {
+                json!(features::formatting(self.document(uri), &params["options"]))
+            }
+            "textDocument/foldingRange" => json!(features::folding_ranges(self.document(uri))),
+            "textDocument/documentSymbol" => {
+                json!(features::document_symbols(self.document(uri)))
+            }
+
+            _ => {
+                if message.get("id").is_some() {
+                    return vec![json!({
+                        "jsonrpc": "2.0",
+                        "id": message["id"],
+                        "error": {
+                            "code": METHOD_NOT_FOUND,
+                            "message": format!("Unknown method: {method:?}"),
+                        },
+                    })];
+                } else {
+                    return vec![]; // Ignore unknown notifications, like `initialized`
+                }
+            }
+        };
+
+        vec![json!({ "jsonrpc": "2.0", "id": message["id"], "result": result })]
+    }
+
+    fn document(&self, uri: &str) -> &str {
+        self.documents.get(uri).map_or("", String::as_str)
+    }
+
+    fn set_document(&mut self, uri: &str, text: String) -> Vec<Value> {
+        let diagnostics = features::diagnostics(&text);
+        self.documents.insert(uri.to_owned(), text);
+        vec![publish_diagnostics(uri, diagnostics)]
+    }
+}
+
+fn publish_diagnostics(uri: &str, diagnostics: Vec<Value>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": { "uri": uri, "diagnostics": Value::Array(diagnostics) },
+    })
+}
+
+/// Run a language server, reading messages from `reader` and writing to `writer`,
+/// until the `exit` notification or the end of the input.
+pub fn run(reader: &mut impl BufRead, writer: &mut impl Write) -> io::Result<()> {
+    let mut server = Server::new();
+    while let Some(message) = read_message(reader)? {
+        if message["method"] == "exit" {
+            break;
+        }
+        for response in server.handle(&message) {
+            write_message(writer, &response)?;
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_server() {
+    let mut server = Server::new();
+    let uri = "file:///config.eon";
+
+    let response = server.handle(&json!({"jsonrpc": "2.0", "id": 1, "method": "initialize"}));
+    assert_eq!(response[0]["result"]["capabilities"]["textDocumentSync"], 1);
+
+    let response = server.handle(&json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": { "textDocument": { "uri": uri, "text": "a: [1, 2\nb: 3" } },
+    }));
+    let diagnostics = &response[0]["params"]["diagnostics"];
+    assert_eq!(diagnostics.as_array().unwrap().len(), 2);
+    assert_eq!(diagnostics[0]["range"]["start"]["line"], 1);
+    assert_eq!(diagnostics[0]["severity"], 1);
+
+    let source = "server: {\n  port: 8080\n  hosts: [\"a\", \"b\"]\n}\n";
+    let response = server.handle(&json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didChange",
+        "params": { "textDocument": { "uri": uri }, "contentChanges": [{ "text": source }] },
+    }));
+    assert_eq!(response[0]["params"]["diagnostics"], json!([]));
+
+    let response = server.handle(&json!({
+        "jsonrpc": "2.0",
+        "id": 2,
+        "method": "textDocument/formatting",
+        "params": { "textDocument": { "uri": uri }, "options": { "tabSize": 4, "insertSpaces": true } },
+    }));
+    assert_eq!(
+        response[0]["result"][0]["newText"],
+        "server: {\n    port: 8080\n    hosts: [\"a\", \"b\"]\n}\n"
+    );
+
+    let response = server.handle(&json!({
+        "jsonrpc": "2.0",
+        "id": 3,
+        "method": "textDocument/foldingRange",
+        "params": { "textDocument": { "uri": uri } },
+    }));
+    assert_eq!(
+        response[0]["result"],
+        json!([{ "startLine": 0, "endLine": 3 }])
+    );
+
+    let response = server.handle(&json!({
+        "jsonrpc": "2.0",
+        "id": 4,
+        "method": "textDocument/documentSymbol",
+        "params": { "textDocument": { "uri": uri } },
+    }));
+    let server_symbol = &response[0]["result"][0];
+    assert_eq!(server_symbol["name"], "server");
+    assert_eq!(server_symbol["range"]["end"]["line"], 3);
+    assert_eq!(server_symbol["children"][0]["name"], "port");
+    assert_eq!(server_symbol["children"][1]["name"], "hosts");
+    assert_eq!(server_symbol["children"][1]["children"][1]["name"], "[1]");
+
+    let response = server.handle(&json!({"jsonrpc": "2.0", "id": 5, "method": "nope"}));
+    assert_eq!(response[0]["error"]["code"], METHOD_NOT_FOUND);
+    assert!(
+        server
+            .handle(&json!({"jsonrpc": "2.0", "method": "initialized"}))
+            .is_empty()
+    );
+}
@@ -0,0 +1,69 @@
+//! Reading and writing JSON-RPC messages, framed with the `Content-Length` header used by LSP.
+
+use std::io::{self, BufRead, Write};
+
+use serde_json::Value;
+
+/// Read the next message.
+///
+/// Returns `Ok(None)` at the end of the input.
+pub fn read_message(reader: &mut impl BufRead) -> io::Result<Option<Value>> {
+    let mut content_length = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break; // End of headers
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                content_length = Some(value.trim().parse::<usize>().map_err(invalid_data)?);
+            }
+        }
+    }
+
+    let Some(content_length) = content_length else {
+        return Err(invalid_data("Missing Content-Length header"));
+    };
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(invalid_data)
+}
+
+/// Write a message, with the `Content-Length` header.
+pub fn write_message(writer: &mut impl Write, message: &Value) -> io::Result<()> {
+    let body = message.to_string();
+    write!(writer, "Content-Length: {}\r\n\r\n{body}", body.len())?;
+    writer.flush()
+}
+
+fn invalid_data(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+#[test]
+fn test_transport() {
+    let messages = [
+        serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "initialize"}),
+        serde_json::json!({"jsonrpc": "2.0", "method": "exit", "params": {"text": "ö"}}),
+    ];
+
+    let mut bytes = vec![];
+    for message in &messages {
+        write_message(&mut bytes, message).unwrap();
+    }
+
+    let mut reader = io::Cursor::new(bytes);
+    for message in &messages {
+        assert_eq!(read_message(&mut reader).unwrap().as_ref(), Some(message));
+    }
+    assert!(read_message(&mut reader).unwrap().is_none());
+
+    let mut reader = io::Cursor::new(b"Content-Type: foo\r\n\r\n{}".to_vec());
+    assert!(read_message(&mut reader).is_err());
+}
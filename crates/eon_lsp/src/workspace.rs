@@ -0,0 +1,233 @@
+//! An index of the top-level keys of all Eon files in a workspace.
+
+use std::{
+    collections::BTreeMap,
+    io,
+    path::{Path, PathBuf},
+};
+
+use eon_syntax::{Span, TokenTree, TokenValue};
+use serde_json::Value;
+
+use crate::LineIndex;
+
+/// A top-level key in one of the files of a [`WorkspaceIndex`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Symbol {
+    /// The key, unquoted.
+    pub name: String,
+
+    /// The file the key is in.
+    pub path: PathBuf,
+
+    /// Where the key is in the file.
+    pub span: Span,
+
+    /// The LSP `Range` of [`Self::span`].
+    pub range: Value,
+
+    /// The text of the `///` doc comments above the key, if any.
+    pub doc: Option<String>,
+}
+
+/// The top-level keys of all `.eon` files in a directory, for workspace-wide lookups.
+///
+/// Build it with [`Self::scan`], and keep it up to date with [`Self::update_file`]
+/// and [`Self::remove_file`] as files change.
+///
+/// ```
+/// # use eon_lsp::WorkspaceIndex;
+/// let mut index = WorkspaceIndex::default();
+/// index.update_file("base.eon", "/// The port to listen on\nport: 80");
+/// index.update_file("local.eon", "port: 8080\nhost: \"localhost\"");
+///
+/// assert_eq!(index.find("port").count(), 2);
+/// assert_eq!(index.duplicates()[0].0, "port");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct WorkspaceIndex {
+    /// The symbols of each file, in the order they appear in the file.
+    files: BTreeMap<PathBuf, Vec<Symbol>>,
+}
+
+impl WorkspaceIndex {
+    /// Index all `.eon` files in `root` and its subdirectories.
+    ///
+    /// Hidden files and directories (starting with a `.`) are skipped.
+    /// Files with syntax errors are indexed as far as they can be parsed.
+    pub fn scan(root: &Path) -> io::Result<Self> {
+        let mut index = Self::default();
+        index.scan_dir(root)?;
+        Ok(index)
+    }
+
+    fn scan_dir(&mut self, dir: &Path) -> io::Result<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            if entry.file_type()?.is_dir() {
+                self.scan_dir(&path)?;
+            } else if path.extension().is_some_and(|ext| ext == "eon") {
+                let source = std::fs::read_to_string(&path)?;
+                self.update_file(path, &source);
+            }
+        }
+        Ok(())
+    }
+
+    /// Index (or re-index) a single file with the given contents, e.g. when it is edited.
+    pub fn update_file(&mut self, path: impl Into<PathBuf>, source: &str) {
+        let path = path.into();
+        let symbols = top_level_symbols(&path, source);
+        self.files.insert(path, symbols);
+    }
+
+    /// Forget about a file, e.g. when it is deleted.
+    pub fn remove_file(&mut self, path: &Path) {
+        self.files.remove(path);
+    }
+
+    /// All indexed files, sorted by path.
+    pub fn files(&self) -> impl Iterator<Item = &Path> {
+        self.files.keys().map(PathBuf::as_path)
+    }
+
+    /// The top-level keys of the given file, in order.
+    pub fn symbols_in(&self, path: &Path) -> &[Symbol] {
+        self.files.get(path).map_or(&[], Vec::as_slice)
+    }
+
+    /// All top-level keys of all files.
+    pub fn symbols(&self) -> impl Iterator<Item = &Symbol> {
+        self.files.values().flatten()
+    }
+
+    /// Where the given key is defined, e.g. for "go to key".
+    pub fn find(&self, name: &str) -> impl Iterator<Item = &Symbol> {
+        self.symbols().filter(move |symbol| symbol.name == name)
+    }
+
+    /// The keys that contain `query`, ignoring case, e.g. for the LSP `workspace/symbol` request.
+    ///
+    /// An empty query matches all keys.
+    pub fn search(&self, query: &str) -> impl Iterator<Item = &Symbol> {
+        let query = query.to_lowercase();
+        self.symbols()
+            .filter(move |symbol| symbol.name.to_lowercase().contains(&query))
+    }
+
+    /// The keys that are defined in more than one file, sorted by name,
+    /// with all the places they are defined.
+    pub fn duplicates(&self) -> Vec<(&str, Vec<&Symbol>)> {
+        let mut by_name: BTreeMap<&str, Vec<&Symbol>> = BTreeMap::new();
+        for symbol in self.symbols() {
+            by_name.entry(&symbol.name).or_default().push(symbol);
+        }
+        by_name
+            .into_iter()
+            .filter(|(_, symbols)| symbols.iter().any(|symbol| symbol.path != symbols[0].path))
+            .collect()
+    }
+}
+
+/// The keys of the top-level map of the file, if it is a map.
+fn top_level_symbols(path: &Path, source: &str) -> Vec<Symbol> {
+    let (Some(tree), _) = TokenTree::parse_str_recovering(source) else {
+        return vec![];
+    };
+    let TokenValue::Map(map) = &tree.value else {
+        return vec![];
+    };
+    let index = LineIndex::new(source);
+    map.key_values
+        .iter()
+        .filter_map(|kv| {
+            let name = match &kv.key.value {
+                TokenValue::Number(number) => number.to_string(),
+                key => key.as_key_str()?.into_owned(),
+            };
+            let span = kv.key.span?;
+            Some(Symbol {
+                name,
+                path: path.to_path_buf(),
+                span,
+                range: index.range(span),
+                doc: kv.key.doc(),
+            })
+        })
+        .collect()
+}
+
+#[test]
+fn test_workspace_index() {
+    let mut index = WorkspaceIndex::default();
+    index.update_file(
+        "base.eon",
+        "/// The port to listen on\nport: 80\n\"log level\": \"info\"",
+    );
+    index.update_file("local.eon", "port: 8080\nhost: \"localhost\"\n");
+    index.update_file("broken.eon", "name: \"x\"\nlist: [1, 2\n");
+    index.update_file("list.eon", "[1, 2, 3]");
+
+    let port: Vec<_> = index.find("port").collect();
+    assert_eq!(port.len(), 2);
+    assert_eq!(port[0].path, Path::new("base.eon"));
+    assert_eq!(port[0].doc.as_deref(), Some("The port to listen on"));
+    assert_eq!(port[0].range["start"]["line"], 1);
+    assert_eq!(port[1].path, Path::new("local.eon"));
+    assert_eq!(port[1].doc, None);
+
+    assert_eq!(index.find("log level").count(), 1);
+    assert_eq!(
+        index.find("name").count(),
+        1,
+        "Broken files are indexed too"
+    );
+    assert!(index.symbols_in(Path::new("list.eon")).is_empty());
+
+    let names = |symbols: Vec<&Symbol>| -> Vec<String> {
+        symbols.iter().map(|symbol| symbol.name.clone()).collect()
+    };
+    assert_eq!(
+        names(index.search("O").collect()),
+        ["port", "log level", "port", "host"],
+        "Sorted by file, then by position"
+    );
+
+    let duplicates = index.duplicates();
+    assert_eq!(duplicates.len(), 1);
+    assert_eq!(duplicates[0].0, "port");
+    assert_eq!(duplicates[0].1.len(), 2);
+
+    index.remove_file(Path::new("local.eon"));
+    assert!(index.duplicates().is_empty());
+
+    // The same key twice in one file is a syntax error, not a duplicate across files:
+    index.update_file("base.eon", "port: 80\nport: 81");
+    assert!(index.duplicates().is_empty());
+}
+
+#[test]
+fn test_workspace_scan() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    let write = |path: &str, contents: &str| {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    };
+    write("a.eon", "port: 80");
+    write("sub/b.eon", "port: 8080");
+    write("sub/c.txt", "port: 1");
+    write(".hidden/d.eon", "port: 2");
+
+    let index = WorkspaceIndex::scan(root).unwrap();
+    assert_eq!(
+        index.files().collect::<Vec<_>>(),
+        [root.join("a.eon"), root.join("sub").join("b.eon")]
+    );
+    assert_eq!(index.find("port").count(), 2);
+}
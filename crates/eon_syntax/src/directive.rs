@@ -0,0 +1,156 @@
+//! Directive comments, like `//@deprecated` or `//@since: 2.1`.
+//!
+//! These are normal comments as far as the parser and formatter are concerned,
+//! but tools like linters and schema generators can read them as structured metadata.
+
+use crate::token_tree::{TokenKeyValue, TokenTree};
+
+/// A directive comment, like `//@deprecated` or `//@since: 2.1`.
+///
+/// The name and value may be separated by a colon or by whitespace,
+/// so `//@since 2.1` and `//@since: 2.1` are the same.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Directive<'s> {
+    /// The name of the directive, like `deprecated` or `since`.
+    pub name: &'s str,
+
+    /// The value after the name, if any, like `2.1`.
+    pub value: Option<&'s str>,
+}
+
+impl<'s> Directive<'s> {
+    /// Parse a comment (including the leading `//`) as a directive.
+    ///
+    /// Returns `None` if the comment is not a directive.
+    pub fn parse(comment: &'s str) -> Option<Self> {
+        let rest = comment.strip_prefix("//@")?;
+        let name_len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+            .unwrap_or(rest.len());
+        let (name, rest) = rest.split_at(name_len);
+        if name.is_empty() {
+            return None;
+        }
+
+        let value = if let Some(value) = rest.strip_prefix(':') {
+            value
+        } else if rest.is_empty() || rest.starts_with(char::is_whitespace) {
+            rest
+        } else {
+            return None;
+        };
+        let value = value.trim();
+
+        Some(Self {
+            name,
+            value: (!value.is_empty()).then_some(value),
+        })
+    }
+}
+
+impl TokenTree<'_> {
+    /// The directive comments (like `//@deprecated`) among the [`Self::prefix_comments`].
+    pub fn directives(&self) -> impl Iterator<Item = Directive<'_>> {
+        self.prefix_comments
+            .iter()
+            .filter_map(|comment| Directive::parse(comment))
+    }
+
+    /// The first directive with the given name, if any.
+    pub fn directive(&self, name: &str) -> Option<Directive<'_>> {
+        self.directives().find(|directive| directive.name == name)
+    }
+}
+
+impl TokenKeyValue<'_> {
+    /// The directive comments above the key (or between the key and the value).
+    pub fn directives(&self) -> impl Iterator<Item = Directive<'_>> {
+        self.key.directives().chain(self.value.directives())
+    }
+}
+
+#[test]
+fn test_parse_directive() {
+    let parse = Directive::parse;
+    assert_eq!(
+        parse("//@deprecated"),
+        Some(Directive {
+            name: "deprecated",
+            value: None
+        })
+    );
+    assert_eq!(
+        parse("//@since 2.1"),
+        Some(Directive {
+            name: "since",
+            value: Some("2.1")
+        })
+    );
+    assert_eq!(
+        parse("//@since: 2.1 "),
+        Some(Directive {
+            name: "since",
+            value: Some("2.1")
+        })
+    );
+    assert_eq!(
+        parse("//@see-also: other_key"),
+        Some(Directive {
+            name: "see-also",
+            value: Some("other_key")
+        })
+    );
+    assert_eq!(parse("// @deprecated"), None);
+    assert_eq!(parse("//@"), None);
+    assert_eq!(parse("//@foo=bar"), None);
+    assert_eq!(parse("/// Docs"), None);
+}
+
+#[test]
+fn test_directives_in_document() {
+    let source = r#"
+// Regular comment
+//@deprecated
+//@replaced-by: new_port
+/// The port to listen on.
+old_port: 8080
+
+new_port: 8080
+"#;
+    let tree = TokenTree::parse_str(source).unwrap();
+    let crate::TokenValue::Map(map) = &tree.value else {
+        panic!("Expected a map");
+    };
+
+    let old_port = &map.key_values[0];
+    assert_eq!(
+        old_port.directives().collect::<Vec<_>>(),
+        [
+            Directive {
+                name: "deprecated",
+                value: None
+            },
+            Directive {
+                name: "replaced-by",
+                value: Some("new_port")
+            }
+        ]
+    );
+    assert!(old_port.key.directive("deprecated").is_some());
+    assert_eq!(
+        old_port
+            .key
+            .directive("replaced-by")
+            .and_then(|directive| directive.value),
+        Some("new_port")
+    );
+    assert_eq!(old_port.key.directive("since"), None);
+
+    assert_eq!(map.key_values[1].directives().count(), 0);
+
+    // Directives are preserved by the formatter:
+    assert!(
+        tree.format(&Default::default())
+            .starts_with("// Regular comment\n//@deprecated\n//@replaced-by: new_port\n")
+    );
+}
@@ -0,0 +1,645 @@
+//! Editing a [`TokenTree`] in-place, while preserving comments.
+
+use std::borrow::Cow;
+
+use crate::{
+    path::{KeyPath, PathSegment},
+    strings::{escape_and_quote, escape_and_quote_like, is_valid_identifier},
+    token_tree::{Comment, TokenInclude, TokenKeyValue, TokenMap, TokenTree, TokenValue},
+};
+
+/// Where to place a new key-value pair when inserting it into a [`TokenMap`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Placement<'a> {
+    /// First in the map.
+    Start,
+
+    /// Last in the map.
+    End,
+
+    /// Right before the entry with the given key (and its prefix comments).
+    ///
+    /// If there is no such key, the new entry is placed last.
+    Before(&'a str),
+
+    /// Right after the entry with the given key.
+    ///
+    /// If there is no such key, the new entry is placed last.
+    After(&'a str),
+
+    /// Before the first existing key that sorts after the new key.
+    ///
+    /// This does not sort the existing keys.
+    Alphabetical,
+}
+
+impl<'s> TokenTree<'s> {
+    /// Create a map key, using an identifier if possible, and a quoted string otherwise.
+    pub fn key(name: &str) -> Self {
+        if is_valid_identifier(name) {
+            TokenValue::Identifier(Cow::Owned(name.to_owned())).into()
+        } else {
+            TokenValue::QuotedString(escape_and_quote(name).into()).into()
+        }
+    }
+
+    /// Find the subtree at the given path, like `server.ports[2]`.
+    ///
+    /// An index applied to a map looks up the integer key.
+    pub fn get_path(&self, path: &KeyPath) -> Option<&Self> {
+        path.segments
+            .iter()
+            .try_fold(self, |tree, segment| tree.get_segment(segment))
+    }
+
+    /// Find the subtree at the given path, like `server.ports[2]`.
+    pub fn get_path_mut(&mut self, path: &KeyPath) -> Option<&mut Self> {
+        path.segments
+            .iter()
+            .try_fold(self, |tree, segment| tree.get_segment_mut(segment))
+    }
+
+    /// Remove the subtree at the given path from a map or list, and return it.
+    ///
+    /// The comments above a removed map entry follow the returned subtree,
+    /// so that it can be formatted as a document of its own.
+    ///
+    /// Returns `None` if there is nothing at the path, or if it points into a variant
+    /// (which cannot have its values removed).
+    pub fn extract(&mut self, path: &KeyPath) -> Option<Self> {
+        let (parent_path, last) = path.split_last()?;
+        let parent = self.get_path_mut(&parent_path)?;
+        match (&mut parent.value, last) {
+            (TokenValue::Map(map), segment) => {
+                let index = map.index_of_segment(segment)?;
+                let TokenKeyValue { key, mut value } = map.key_values.remove(index);
+                let TokenTree {
+                    mut prefix_comments,
+                    mut doc_comments,
+                    ..
+                } = key;
+                prefix_comments.append(&mut doc_comments);
+                prefix_comments.append(&mut value.prefix_comments);
+                value.prefix_comments = prefix_comments;
+                Some(value)
+            }
+            (TokenValue::List(list), PathSegment::Index(index)) => {
+                (*index < list.values.len()).then(|| list.values.remove(*index))
+            }
+            _ => None,
+        }
+    }
+
+    /// Like [`Self::extract`], but leaves an `@include` directive for `include_path` in its place,
+    /// e.g. `server: { @include "server.eon" }`.
+    ///
+    /// Only maps can be included, so this returns `None` if the subtree is not a map.
+    pub fn extract_to_include(&mut self, path: &KeyPath, include_path: &str) -> Option<Self> {
+        if !matches!(self.get_path(path)?.value, TokenValue::Map(_)) {
+            return None;
+        }
+        let placeholder = TokenValue::Map(TokenMap {
+            includes: vec![TokenInclude::new(include_path)],
+            key_values: vec![],
+            closing_comments: vec![],
+            trailing_comma: false,
+        });
+        let target = self.get_path_mut(path)?;
+        let mut extracted = std::mem::replace(target, placeholder.into());
+        target.suffix_comment = extracted.suffix_comment.take();
+        Some(extracted)
+    }
+
+    /// Add a comment line above the value at the given path, like `server.port`.
+    ///
+    /// For a map entry, the comment is put above the key, together with any existing comments.
+    /// The comment must include the leading `//`.
+    ///
+    /// Returns `false` if there is nothing at the path.
+    pub fn add_prefix_comment(&mut self, path: &KeyPath, comment: impl Into<Comment<'s>>) -> bool {
+        if let Some((key, value)) = self.entry_mut(path) {
+            key.unwrap_or(value).prefix_comments.push(comment.into());
+            true
+        } else {
+            false
+        }
+    }
+
+    /// The text of the comments above the value at the given path, like `server.port`.
+    ///
+    /// For a map entry, this includes the normal and `///` doc comments above the key,
+    /// and any comments between the key and the value.
+    /// The leading `//`, `///`, or `#` (and one space after it) is removed from each line,
+    /// and the lines are joined with `\n`.
+    ///
+    /// Returns `None` if there is nothing at the path, or if it has no comments.
+    ///
+    /// ```
+    /// # use eon_syntax::TokenTree;
+    /// let tree = TokenTree::parse_str("server: {\n    /// The port to listen on.\n    port: 8080\n}").unwrap();
+    /// assert_eq!(
+    ///     tree.comment_at(&"server.port".parse().unwrap()).as_deref(),
+    ///     Some("The port to listen on.")
+    /// );
+    /// ```
+    pub fn comment_at(&self, path: &KeyPath) -> Option<String> {
+        let (key, value) = self.entry(path)?;
+        let lines: Vec<&str> = key
+            .into_iter()
+            .flat_map(|key| key.prefix_comments.iter().chain(&key.doc_comments))
+            .chain(&value.prefix_comments)
+            .map(|comment| comment_text(comment))
+            .collect();
+        (!lines.is_empty()).then(|| lines.join("\n"))
+    }
+
+    /// Replace the comments above the value at the given path, like `server.port`, with `text`.
+    ///
+    /// Each line of `text` becomes a `// line` comment. This replaces any `///` doc comments too,
+    /// so that [`Self::comment_at`] returns `text` afterwards.
+    /// An empty `text` removes the comments.
+    ///
+    /// Returns `false` if there is nothing at the path.
+    pub fn set_prefix_comment(&mut self, path: &KeyPath, text: &str) -> bool {
+        let Some((key, value)) = self.entry_mut(path) else {
+            return false;
+        };
+        let comments = text
+            .lines()
+            .map(|line| {
+                if line.is_empty() {
+                    Cow::Borrowed("//")
+                } else {
+                    Cow::Owned(format!("// {line}"))
+                }
+            })
+            .collect();
+
+        value.prefix_comments.clear();
+        if let Some(key) = key {
+            key.doc_comments.clear();
+            key.prefix_comments = comments;
+        } else {
+            value.prefix_comments = comments;
+        }
+        true
+    }
+
+    /// Replace the value at the given path, like `server.password`, with the string `value`.
+    ///
+    /// Only the value is replaced; its comments are kept.
+    /// If there was a string there, its quoting style is kept if `value` allows it,
+    /// e.g. a single-quoted `'…'` string stays single-quoted unless `value` contains a `'`.
+    /// This keeps the diff minimal when a tool updates a hand-written file.
+    ///
+    /// Returns `false` if there is nothing at the path.
+    ///
+    /// ```
+    /// # use eon_syntax::{FormatOptions, TokenTree};
+    /// let mut tree = TokenTree::parse_str("password: 'hunter2' // Rotated monthly").unwrap();
+    /// assert!(tree.set_string(&"password".parse().unwrap(), r"c0rrect\horse"));
+    /// assert_eq!(
+    ///     tree.format(&FormatOptions::default()),
+    ///     "password: 'c0rrect\\horse' // Rotated monthly\n"
+    /// );
+    /// ```
+    pub fn set_string(&mut self, path: &KeyPath, value: &str) -> bool {
+        let Some(target) = self.get_path_mut(path) else {
+            return false;
+        };
+        let quoted = if let TokenValue::QuotedString(original) = &target.value {
+            escape_and_quote_like(value, original)
+        } else {
+            escape_and_quote(value)
+        };
+        target.value = TokenValue::QuotedString(quoted.into());
+        true
+    }
+
+    /// The key (if in a map) and the value at the given path.
+    fn entry(&self, path: &KeyPath) -> Option<(Option<&Self>, &Self)> {
+        let Some((parent_path, last)) = path.split_last() else {
+            return Some((None, self));
+        };
+        let parent = self.get_path(&parent_path)?;
+        if let TokenValue::Map(map) = &parent.value {
+            let TokenKeyValue { key, value } = &map.key_values[map.index_of_segment(last)?];
+            Some((Some(key), value))
+        } else {
+            parent.get_segment(last).map(|value| (None, value))
+        }
+    }
+
+    /// The key (if in a map) and the value at the given path.
+    fn entry_mut(&mut self, path: &KeyPath) -> Option<(Option<&mut Self>, &mut Self)> {
+        let Some((parent_path, last)) = path.split_last() else {
+            return Some((None, self));
+        };
+        let parent = self.get_path_mut(&parent_path)?;
+        if !matches!(parent.value, TokenValue::Map(_)) {
+            return parent.get_segment_mut(last).map(|value| (None, value));
+        }
+        let TokenValue::Map(map) = &mut parent.value else {
+            return None;
+        };
+        let index = map.index_of_segment(last)?;
+        let TokenKeyValue { key, value } = &mut map.key_values[index];
+        Some((Some(key), value))
+    }
+
+    fn get_segment(&self, segment: &PathSegment) -> Option<&Self> {
+        match (&self.value, segment) {
+            (TokenValue::Map(map), segment) => map
+                .index_of_segment(segment)
+                .map(|i| &map.key_values[i].value),
+            (TokenValue::List(list), PathSegment::Index(index)) => list.values.get(*index),
+            (TokenValue::Variant(variant), PathSegment::Index(index)) => variant.values.get(*index),
+            _ => None,
+        }
+    }
+
+    fn get_segment_mut(&mut self, segment: &PathSegment) -> Option<&mut Self> {
+        match (&mut self.value, segment) {
+            (TokenValue::Map(map), segment) => map
+                .index_of_segment(segment)
+                .map(|i| &mut map.key_values[i].value),
+            (TokenValue::List(list), PathSegment::Index(index)) => list.values.get_mut(*index),
+            (TokenValue::Variant(variant), PathSegment::Index(index)) => {
+                variant.values.get_mut(*index)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<'s> TokenKeyValue<'s> {
+    /// Create a new key-value pair, without any comments.
+    pub fn new(key: TokenTree<'s>, value: TokenTree<'s>) -> Self {
+        Self { key, value }
+    }
+
+    /// Attach a comment line above the key-value pair.
+    ///
+    /// The comment must include the leading `//`.
+    pub fn with_prefix_comment(mut self, comment: impl Into<Comment<'s>>) -> Self {
+        self.key.prefix_comments.push(comment.into());
+        self
+    }
+
+    /// Attach a `///` doc comment line above the key.
+    ///
+    /// The comment must include the leading `///`.
+    pub fn with_doc_comment(mut self, comment: impl Into<Comment<'s>>) -> Self {
+        self.key.doc_comments.push(comment.into());
+        self
+    }
+
+    /// Attach a comment after the value, on the same line.
+    ///
+    /// The comment must include the leading `//`.
+    pub fn with_suffix_comment(mut self, comment: impl Into<Comment<'s>>) -> Self {
+        self.value.suffix_comment = Some(comment.into());
+        self
+    }
+}
+
+impl<'s> TokenMap<'s> {
+    /// Find the index of the key-value pair with the given string key.
+    pub fn index_of(&self, key: &str) -> Option<usize> {
+        self.key_values
+            .iter()
+            .position(|kv| kv.key.value.as_key_str().is_some_and(|k| k == key))
+    }
+
+    fn index_of_segment(&self, segment: &PathSegment) -> Option<usize> {
+        match segment {
+            PathSegment::Key(key) => self.index_of(key),
+            PathSegment::Index(index) => {
+                let index = index.to_string();
+                self.key_values.iter().position(
+                    |kv| matches!(&kv.key.value, TokenValue::Number(n) if n.as_ref() == index),
+                )
+            }
+        }
+    }
+
+    /// Get the value of the given string key.
+    pub fn get(&self, key: &str) -> Option<&TokenTree<'s>> {
+        self.index_of(key).map(|i| &self.key_values[i].value)
+    }
+
+    /// Get the value of the given string key.
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut TokenTree<'s>> {
+        self.index_of(key).map(|i| &mut self.key_values[i].value)
+    }
+
+    /// Insert a key-value pair, including any comments attached to it.
+    ///
+    /// If the key already exists, only its value is replaced (keeping the comments of the existing entry),
+    /// the `placement` is ignored, and the old value is returned.
+    ///
+    /// Otherwise the new entry is placed according to `placement`.
+    /// Blank lines in the formatted output follow the comments,
+    /// so the surrounding structure is preserved.
+    pub fn insert(
+        &mut self,
+        key_value: TokenKeyValue<'s>,
+        placement: Placement<'_>,
+    ) -> Option<TokenTree<'s>> {
+        let new_key = key_value.key.value.as_key_str().map(Cow::into_owned);
+
+        if let Some(existing) = new_key.as_deref().and_then(|key| self.index_of(key)) {
+            let TokenKeyValue { key: _, mut value } = key_value;
+            let old = &mut self.key_values[existing].value;
+            if value.suffix_comment.is_none() {
+                value.suffix_comment = old.suffix_comment.take();
+            }
+            return Some(std::mem::replace(old, value));
+        }
+
+        let index = match placement {
+            Placement::Start => 0,
+            Placement::End => self.key_values.len(),
+            Placement::Before(sibling) => self.index_of(sibling).unwrap_or(self.key_values.len()),
+            Placement::After(sibling) => self
+                .index_of(sibling)
+                .map_or(self.key_values.len(), |i| i + 1),
+            Placement::Alphabetical => new_key.as_deref().map_or(self.key_values.len(), |new| {
+                self.key_values
+                    .iter()
+                    .position(|kv| kv.key.value.as_key_str().is_some_and(|k| new < k.as_ref()))
+                    .unwrap_or(self.key_values.len())
+            }),
+        };
+
+        self.key_values.insert(index, key_value);
+        None
+    }
+
+    /// Remove the key-value pair with the given string key, including its comments.
+    pub fn remove(&mut self, key: &str) -> Option<TokenKeyValue<'s>> {
+        self.index_of(key).map(|i| self.key_values.remove(i))
+    }
+}
+
+/// The text of a comment, without the leading `//`, `///`, or `#`, and one space after it.
+fn comment_text(comment: &str) -> &str {
+    let text = comment
+        .strip_prefix('#')
+        .unwrap_or_else(|| comment.trim_start_matches('/'));
+    text.strip_prefix(' ').unwrap_or(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FormatOptions;
+
+    fn number(n: &str) -> TokenTree<'_> {
+        TokenValue::Number(n.into()).into()
+    }
+
+    #[test]
+    fn test_insert_with_placement() {
+        let source = "
+// The A
+a: 1
+
+// The C
+c: 3
+";
+        let mut tt = TokenTree::parse_str(source).unwrap();
+        let TokenValue::Map(map) = &mut tt.value else {
+            panic!("Expected a map");
+        };
+
+        map.insert(
+            TokenKeyValue::new(TokenTree::key("b"), number("2")).with_prefix_comment("// The B"),
+            Placement::Alphabetical,
+        );
+        map.insert(
+            TokenKeyValue::new(TokenTree::key("d"), number("4")),
+            Placement::After("c"),
+        );
+        map.insert(
+            TokenKeyValue::new(TokenTree::key("not an identifier"), number("0"))
+                .with_suffix_comment("// Needs quotes"),
+            Placement::Before("a"),
+        );
+        let old = map.insert(
+            TokenKeyValue::new(TokenTree::key("c"), number("33")),
+            Placement::Start,
+        );
+        assert!(old.is_some(), "c should have been replaced");
+
+        assert_eq!(
+            tt.format(&FormatOptions::default()),
+            r#""not an identifier": 0 // Needs quotes
+
+// The A
+a: 1
+
+// The B
+b: 2
+
+// The C
+c: 33
+d: 4
+"#
+        );
+    }
+
+    #[test]
+    fn test_extract() {
+        let source = "
+name: \"server\"
+
+// How to reach the server
+network: {
+    // Default port
+    port: 8080
+    hosts: [\"a\", \"b\"]
+}
+";
+        let mut tt = TokenTree::parse_str(source).unwrap();
+        let options = FormatOptions::default();
+
+        let port: KeyPath = "network.port".parse().unwrap();
+        assert_eq!(tt.get_path(&port).unwrap().format(&options), "8080");
+
+        let network = tt.extract(&"network".parse().unwrap()).unwrap();
+        assert_eq!(
+            network.format(&options),
+            r#"// How to reach the server
+// Default port
+port: 8080
+hosts: ["a", "b"]
+"#
+        );
+        assert_eq!(tt.format(&options), "name: \"server\"\n");
+
+        let mut network = network;
+        let host = network.extract(&"hosts[1]".parse().unwrap()).unwrap();
+        assert_eq!(host.format(&options), "\"b\"");
+        assert!(network.extract(&"hosts[1]".parse().unwrap()).is_none());
+        assert!(network.extract(&KeyPath::root()).is_none());
+    }
+
+    #[test]
+    fn test_add_prefix_comment() {
+        let source = "name: \"server\"\nhosts: [\"a\", \"b\"]\n";
+        let mut tt = TokenTree::parse_str(source).unwrap();
+
+        assert!(tt.add_prefix_comment(&KeyPath::root(), "// The server config"));
+        assert!(tt.add_prefix_comment(&"hosts".parse().unwrap(), "// Where to find it"));
+        assert!(tt.add_prefix_comment(&"hosts[1]".parse().unwrap(), "// The backup"));
+        assert!(!tt.add_prefix_comment(&"port".parse().unwrap(), "// Missing"));
+        assert!(!tt.add_prefix_comment(&"hosts[2]".parse().unwrap(), "// Missing"));
+
+        assert_eq!(
+            tt.format(&FormatOptions::default()),
+            r#"// The server config
+name: "server"
+
+// Where to find it
+hosts: [
+	"a"
+
+	// The backup
+	"b"
+]
+"#
+        );
+    }
+
+    #[test]
+    fn test_set_string() {
+        let source = r#"
+// Rotated by a script
+database: {
+    user: 'admin'
+    password: "old" // Keep secret
+    notes: '''
+Line one'''
+    port: 5432
+}
+"#;
+        let mut tt = TokenTree::parse_str(source).unwrap();
+        let set = |tt: &mut TokenTree<'_>, path: &str, value: &str| {
+            tt.set_string(&path.parse().unwrap(), value)
+        };
+
+        assert!(set(&mut tt, "database.user", r"DOMAIN\root"));
+        assert!(set(&mut tt, "database.password", "n3w 'secret'"));
+        assert!(set(&mut tt, "database.notes", "Line one\nLine two"));
+        assert!(set(&mut tt, "database.port", "5433"));
+        assert!(!set(&mut tt, "database.host", "localhost"));
+
+        assert_eq!(
+            tt.format(&FormatOptions::default()),
+            r#"// Rotated by a script
+database: {
+	user: 'DOMAIN\root'
+	password: "n3w 'secret'" // Keep secret
+	notes: '''
+Line one
+Line two'''
+	port: "5433"
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_comment_at() {
+        let source = r#"
+// The server config
+server: {
+    // Normal comment
+    /// Doc comment
+    port: 8080
+
+    "Content-Type": "text/plain" // Not a prefix comment
+    hosts: [
+        # The main one
+        "a"
+    ]
+}
+"#;
+        let mut tt = TokenTree::parse_str(source).unwrap();
+        let comment_at = |tt: &TokenTree<'_>, path: &str| tt.comment_at(&path.parse().unwrap());
+
+        assert_eq!(
+            comment_at(&tt, "server").as_deref(),
+            Some("The server config")
+        );
+        assert_eq!(
+            comment_at(&tt, "server.port").as_deref(),
+            Some("Normal comment\nDoc comment")
+        );
+        assert_eq!(comment_at(&tt, "server.\"Content-Type\""), None);
+        assert_eq!(
+            comment_at(&tt, "server.hosts[0]").as_deref(),
+            Some("The main one")
+        );
+        assert_eq!(comment_at(&tt, "server.missing"), None);
+
+        assert!(tt.set_prefix_comment(
+            &"server.port".parse().unwrap(),
+            "The port\n\nBelow 1024 needs root"
+        ));
+        assert!(tt.set_prefix_comment(
+            &"server.\"Content-Type\"".parse().unwrap(),
+            "Sent to clients"
+        ));
+        assert!(tt.set_prefix_comment(&"server.hosts[0]".parse().unwrap(), ""));
+        assert!(!tt.set_prefix_comment(&"server.missing".parse().unwrap(), "Nothing here"));
+        assert_eq!(
+            comment_at(&tt, "server.port").as_deref(),
+            Some("The port\n\nBelow 1024 needs root")
+        );
+
+        assert_eq!(
+            tt.format(&FormatOptions::default()),
+            r#"// The server config
+server: {
+	// The port
+	//
+	// Below 1024 needs root
+	port: 8080
+
+	// Sent to clients
+	"Content-Type": "text/plain" // Not a prefix comment
+	hosts: ["a"]
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_extract_to_include() {
+        let source = "
+name: \"server\"
+network: {
+    port: 8080
+} // The network
+";
+        let mut tt = TokenTree::parse_str(source).unwrap();
+        let options = FormatOptions::default();
+
+        assert!(
+            tt.extract_to_include(&"name".parse().unwrap(), "name.eon")
+                .is_none(),
+            "Only maps can be included"
+        );
+
+        let network = tt
+            .extract_to_include(&"network".parse().unwrap(), "network.eon")
+            .unwrap();
+        assert_eq!(network.format(&options), "port: 8080\n");
+        assert_eq!(
+            tt.format(&options),
+            "name: \"server\"\nnetwork: {\n\t@include \"network.eon\"\n} // The network\n"
+        );
+    }
+}
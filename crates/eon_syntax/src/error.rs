@@ -1,7 +1,216 @@
 use crate::span::Span;
 
-/// Represent an error during parsing
-type ErrorReport = ariadne::Report<'static, Span>;
+/// The name used for the source in errors that refer to a single, unnamed Eon source.
+pub const UNNAMED_SOURCE: &str = "<unknown>";
+
+/// A labeled span in a named source, used for errors that point at several places,
+/// possibly in different files.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Label {
+    /// The name of the source, e.g. a file path.
+    ///
+    /// Must match one of the sources given to [`Error::new_labeled`].
+    pub source_name: String,
+
+    /// Where in the source.
+    pub span: Span,
+
+    /// What to say about it, e.g. "first defined here".
+    pub message: String,
+}
+
+impl Label {
+    pub fn new(source_name: impl Into<String>, span: Span, message: impl Into<String>) -> Self {
+        Self {
+            source_name: source_name.into(),
+            span,
+            message: message.into(),
+        }
+    }
+}
+
+/// The lines of a source that an [`Error`] points at.
+///
+/// Errors keep only these lines instead of a copy of the whole source,
+/// so creating an error for a huge document is cheap.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Snippet {
+    /// The name of the source, e.g. a file path, or [`UNNAMED_SOURCE`].
+    pub source_name: String,
+
+    /// The full lines covered by the span.
+    pub text: String,
+
+    /// The byte offset of [`Self::text`] in the full source.
+    pub offset: usize,
+
+    /// The 0-based line number of the first line of [`Self::text`] in the full source.
+    pub first_line: usize,
+}
+
+impl Snippet {
+    /// The lines of `eon_source` covered by `span`.
+    pub fn new(eon_source: &str, span: Span) -> Self {
+        let start = span.start.min(eon_source.len());
+        let end = span.end.clamp(start, eon_source.len());
+
+        let (Some(before), Some(after)) = (eon_source.get(..start), eon_source.get(end..)) else {
+            // Not on a char boundary, which should never happen. Keep everything, just in case.
+            return Self {
+                source_name: UNNAMED_SOURCE.to_owned(),
+                text: eon_source.to_owned(),
+                offset: 0,
+                first_line: 0,
+            };
+        };
+
+        let offset = before.rfind('\n').map_or(0, |newline| newline + 1);
+        let line_end = after
+            .find('\n')
+            .map_or(eon_source.len(), |newline| end + newline);
+        Self {
+            source_name: UNNAMED_SOURCE.to_owned(),
+            text: eon_source[offset..line_end].to_owned(),
+            offset,
+            first_line: before[..offset].matches('\n').count(),
+        }
+    }
+}
+
+/// How serious a [`Diagnostic`] is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Severity {
+    /// Something that made the operation fail.
+    Error,
+
+    /// Something suspicious that did not stop the operation.
+    ///
+    /// [`Error::to_parts`] always returns [`Severity::Error`],
+    /// so set this yourself for e.g. the warnings from `TokenTree::parse_str_with_warnings`.
+    Warning,
+}
+
+/// A structured version of an [`Error`], for tools (like language servers) that render diagnostics themselves.
+///
+/// Created with [`Error::to_parts`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// How serious it is.
+    pub severity: Severity,
+
+    /// The main message.
+    pub message: String,
+
+    /// The places in the source(s) that the diagnostic points at.
+    ///
+    /// The first label is the primary location. Empty if the location is unknown.
+    pub labels: Vec<Label>,
+
+    /// Additional information that is not tied to a location.
+    pub notes: Vec<String>,
+
+    /// Other possible explanations of the problem.
+    ///
+    /// For instance, when the parser cannot tell whether a broken document was meant to be
+    /// a map or a list, this contains the error for the interpretation that was not picked.
+    pub alternatives: Vec<Diagnostic>,
+}
+
+/// What kind of [`Error`] it is, for tools that want to do more than show the message.
+///
+/// Returned by [`Error::kind`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// Any error without a more specific kind.
+    #[default]
+    Other,
+
+    /// A variant name that the type being deserialized does not have.
+    UnknownVariant {
+        /// The variant name in the source.
+        found: String,
+
+        /// The variant names the type does have.
+        ///
+        /// The one closest to [`Self::UnknownVariant::found`] comes first (if any is close),
+        /// followed by the rest in declaration order, e.g. for a pick-list.
+        expected: Vec<String>,
+    },
+}
+
+/// How to render an [`Error`] as text with [`Error::render`].
+///
+/// The default is what [`std::fmt::Display`] uses: no color, no wrapping, and unicode box-drawing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RenderOptions {
+    /// Color the output with ANSI escape codes, e.g. when printing to a terminal.
+    pub color: bool,
+
+    /// Word-wrap the messages to fit within this many columns, e.g. the width of the terminal.
+    ///
+    /// Only the main message of an error is wrapped; the lines of source code are kept as they are.
+    /// `None` means no wrapping.
+    pub width: Option<usize>,
+
+    /// Draw the source snippets with unicode box-drawing characters, like `│` and `╭─`.
+    ///
+    /// If `false`, only ASCII is used, like `|` and `,-`.
+    pub unicode: bool,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            color: false,
+            width: None,
+            unicode: true,
+        }
+    }
+}
+
+impl RenderOptions {
+    /// Set whether to use ANSI colors.
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Wrap messages to fit within `width` columns.
+    pub fn with_width(mut self, width: usize) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    /// Set whether to use unicode box-drawing characters.
+    pub fn with_unicode(mut self, unicode: bool) -> Self {
+        self.unicode = unicode;
+        self
+    }
+
+    fn ariadne_config(&self) -> ariadne::Config {
+        let Self {
+            color,
+            width: _,
+            unicode,
+        } = *self;
+        ariadne::Config::default()
+            .with_color(color)
+            .with_char_set(if unicode {
+                ariadne::CharSet::Unicode
+            } else {
+                ariadne::CharSet::Ascii
+            })
+    }
+
+    /// Word-wrap `message` to [`Self::width`].
+    fn wrap<'a>(&self, message: &'a str) -> std::borrow::Cow<'a, str> {
+        match self.width {
+            Some(width) => wrap_words(message, width).into(),
+            None => message.into(),
+        }
+    }
+}
 
 /// An error that can occur during parsing of an Eon file.
 pub enum Error {
@@ -9,16 +218,53 @@ pub enum Error {
         msg: String,
     },
     At {
-        source: ariadne::Source,
+        /// The lines around the span.
+        snippet: Snippet,
+
+        /// Where in the full source.
         span: Span,
+
         message: String,
     },
+
+    /// An error with several labeled spans, possibly in different sources.
+    Labeled {
+        /// `(name, contents)` of each source referred to by the labels.
+        sources: Vec<(String, String)>,
+
+        /// The main message.
+        message: String,
+
+        /// Never empty.
+        labels: Vec<Label>,
+    },
+
+    /// An error that has another possible explanation.
+    ///
+    /// Everything but [`Error::alternative`] and [`Error::to_parts`] only uses the main `error`.
+    WithAlternative {
+        /// The most likely explanation.
+        error: Box<Error>,
+
+        /// A less likely explanation.
+        alternative: Box<Error>,
+    },
+
+    /// An error with a more specific [`ErrorKind`] than [`ErrorKind::Other`].
+    ///
+    /// Everything but [`Error::kind`] only uses the inner `error`.
+    WithKind {
+        error: Box<Error>,
+
+        /// Never [`ErrorKind::Other`].
+        kind: ErrorKind,
+    },
 }
 
 impl Error {
     pub fn new_at(eon_source: &str, span: Span, message: impl Into<String>) -> Self {
         Self::At {
-            source: ariadne::Source::from(eon_source.to_owned()),
+            snippet: Snippet::new(eon_source, span),
             span,
             message: message.into(),
         }
@@ -32,28 +278,316 @@ impl Error {
         }
     }
 
+    /// An error pointing at several labeled spans in a single source.
+    ///
+    /// Falls back to [`Self::custom`] if there are no labels.
+    pub fn new_at_labels(
+        eon_source: &str,
+        message: impl Into<String>,
+        labels: impl IntoIterator<Item = (Span, String)>,
+    ) -> Self {
+        Self::new_labeled(
+            [(UNNAMED_SOURCE.to_owned(), eon_source.to_owned())],
+            message,
+            labels
+                .into_iter()
+                .map(|(span, message)| Label::new(UNNAMED_SOURCE, span, message))
+                .collect(),
+        )
+    }
+
+    /// An error pointing at several labeled spans, possibly in different sources.
+    ///
+    /// `sources` are `(name, contents)` pairs, and each [`Label::source_name`] should refer to one of them.
+    ///
+    /// Falls back to [`Self::custom`] if there are no labels.
+    pub fn new_labeled(
+        sources: impl IntoIterator<Item = (String, String)>,
+        message: impl Into<String>,
+        labels: Vec<Label>,
+    ) -> Self {
+        if labels.is_empty() {
+            Self::custom(message)
+        } else {
+            Self::Labeled {
+                sources: sources.into_iter().collect(),
+                message: message.into(),
+                labels,
+            }
+        }
+    }
+
+    /// A key that is defined more than once in the same map, pointing at both definitions.
+    ///
+    /// Each definition is given as `(source name, source, span of the key)`.
+    /// They can be in different sources, e.g. if one of them comes from an included file.
+    pub fn duplicate_key(
+        first: (&str, &str, Option<Span>),
+        again: (&str, &str, Option<Span>),
+    ) -> Self {
+        const MESSAGE: &str = "Duplicate key in map";
+
+        let ((first_name, first_source, Some(first_span)), (name, source, Some(span))) =
+            (first, again)
+        else {
+            return Self::new(again.1, again.2, MESSAGE);
+        };
+
+        let labels = vec![
+            Label::new(first_name, first_span, "First defined here"),
+            Label::new(name, span, "Defined again here"),
+        ];
+
+        let mut sources = vec![(first_name.to_owned(), first_source.to_owned())];
+        if name != first_name {
+            sources.push((name.to_owned(), source.to_owned()));
+        }
+
+        Self::new_labeled(sources, MESSAGE, labels)
+    }
+
+    /// An error with another, less likely, explanation.
+    ///
+    /// See [`Self::alternative`].
+    pub fn with_alternative(error: Self, alternative: Self) -> Self {
+        Self::WithAlternative {
+            error: Box::new(error),
+            alternative: Box::new(alternative),
+        }
+    }
+
+    /// Give the error a more specific [`ErrorKind`].
+    ///
+    /// Replaces any previous kind. [`ErrorKind::Other`] removes it.
+    pub fn with_kind(self, kind: ErrorKind) -> Self {
+        let error = match self {
+            Self::WithKind { error, .. } => *error,
+            error => error,
+        };
+        if kind == ErrorKind::Other {
+            error
+        } else {
+            Self::WithKind {
+                error: Box::new(error),
+                kind,
+            }
+        }
+    }
+
+    /// What kind of error this is.
+    pub fn kind(&self) -> &ErrorKind {
+        static OTHER: ErrorKind = ErrorKind::Other;
+        match self {
+            Self::WithKind { kind, .. } => kind,
+            Self::WithAlternative { error, .. } => error.kind(),
+            Self::Custom { .. } | Self::At { .. } | Self::Labeled { .. } => &OTHER,
+        }
+    }
+
+    /// Another, less likely, explanation of the error, if any.
+    ///
+    /// For instance, when a document could not be parsed as either a map or a list,
+    /// this is the error of the interpretation that was not picked.
+    pub fn alternative(&self) -> Option<&Self> {
+        match self {
+            Self::WithAlternative { alternative, .. } => Some(alternative),
+            Self::WithKind { error, .. } => error.alternative(),
+            _ => None,
+        }
+    }
+
+    /// Name the source of this error, e.g. with the path of the file it came from.
+    ///
+    /// Only affects errors that point into a single unnamed source.
+    pub fn with_source_name(self, name: &str) -> Self {
+        match self {
+            Self::Custom { .. } => self,
+            Self::At {
+                mut snippet,
+                span,
+                message,
+            } => {
+                if snippet.source_name == UNNAMED_SOURCE {
+                    name.clone_into(&mut snippet.source_name);
+                }
+                Self::At {
+                    snippet,
+                    span,
+                    message,
+                }
+            }
+            Self::Labeled {
+                mut sources,
+                message,
+                mut labels,
+            } => {
+                for (source_name, _) in &mut sources {
+                    if source_name == UNNAMED_SOURCE {
+                        *source_name = name.to_owned();
+                    }
+                }
+                for label in &mut labels {
+                    if label.source_name == UNNAMED_SOURCE {
+                        label.source_name = name.to_owned();
+                    }
+                }
+                Self::Labeled {
+                    sources,
+                    message,
+                    labels,
+                }
+            }
+            Self::WithAlternative { error, alternative } => Self::WithAlternative {
+                error: Box::new(error.with_source_name(name)),
+                alternative: Box::new(alternative.with_source_name(name)),
+            },
+            Self::WithKind { error, kind } => Self::WithKind {
+                error: Box::new(error.with_source_name(name)),
+                kind,
+            },
+        }
+    }
+
     pub fn custom(message: impl Into<String>) -> Self {
         Self::Custom {
             msg: message.into(),
         }
     }
 
+    /// The main message of the error, without any source code context.
+    pub fn message(&self) -> &str {
+        match self {
+            Self::Custom { msg } => msg,
+            Self::At { message, .. } | Self::Labeled { message, .. } => message,
+            Self::WithAlternative { error, .. } | Self::WithKind { error, .. } => error.message(),
+        }
+    }
+
+    /// Where the error is, if known.
+    ///
+    /// For errors pointing at several places, this is the primary one.
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            Self::Custom { .. } => None,
+            Self::At { span, .. } => Some(*span),
+            Self::Labeled { labels, .. } => labels.first().map(|label| label.span),
+            Self::WithAlternative { error, .. } | Self::WithKind { error, .. } => error.span(),
+        }
+    }
+
+    /// The 1-based line and column (counted in characters) of the start of [`Self::span`].
+    ///
+    /// `eon_source` must be the source that the span refers to.
+    /// Returns `None` if the span is unknown or outside of `eon_source`.
+    pub fn line_col(&self, eon_source: &str) -> Option<(usize, usize)> {
+        let offset = self.span()?.start;
+        let before = eon_source.get(..offset)?;
+        let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+        let line = before.matches('\n').count() + 1;
+        let column = before[line_start..].chars().count() + 1;
+        Some((line, column))
+    }
+
+    /// The parts of the error, for rendering it yourself instead of using the preformatted [`std::fmt::Display`].
+    pub fn to_parts(&self) -> Diagnostic {
+        let labels = match self {
+            Self::Custom { .. } => vec![],
+            Self::At {
+                snippet,
+                span,
+                message,
+            } => vec![Label::new(&snippet.source_name, *span, message)],
+            Self::Labeled { labels, .. } => labels.clone(),
+            Self::WithAlternative { error, alternative } => {
+                let mut diagnostic = error.to_parts();
+                diagnostic.alternatives.push(alternative.to_parts());
+                return diagnostic;
+            }
+            Self::WithKind { error, .. } => return error.to_parts(),
+        };
+        Diagnostic {
+            severity: Severity::Error,
+            message: self.message().to_owned(),
+            labels,
+            notes: vec![],
+            alternatives: vec![],
+        }
+    }
+
+    /// Render the error with the default [`RenderOptions`], optionally with ANSI colors.
     pub fn to_string_with_color(&self, color: bool) -> String {
+        self.render(&RenderOptions::default().with_color(color))
+    }
+
+    /// Render the error as text, with source snippets pointing at where it is.
+    ///
+    /// This is what [`std::fmt::Display`] does, but with control over colors, width, and characters,
+    /// so that command line tools can match the style of their other diagnostics.
+    ///
+    /// ```
+    /// # use eon_syntax::{RenderOptions, TokenTree};
+    /// let err = TokenTree::parse_str("a: [1, 2").unwrap_err();
+    /// let rendered = err.render(&RenderOptions::default().with_unicode(false).with_width(80));
+    /// assert!(rendered.is_ascii());
+    /// ```
+    pub fn render(&self, options: &RenderOptions) -> String {
         match self {
-            Self::Custom { msg } => msg.to_owned(),
+            Self::Custom { msg } => options.wrap(msg).into_owned(),
+            Self::WithAlternative { error, .. } | Self::WithKind { error, .. } => {
+                error.render(options)
+            }
             Self::At {
-                source,
+                snippet,
                 span,
                 message,
             } => {
-                let report = ErrorReport::build(ariadne::ReportKind::Error, *span)
-                    .with_label(ariadne::Label::new(*span).with_message(message))
-                    .with_config(ariadne::Config::default().with_color(color))
+                // ariadne only applies `Source::with_display_line_offset` to the location in the header,
+                // not to the line numbers in the margin, so put the snippet on the right line instead:
+                let padding = "\n".repeat(snippet.first_line);
+                let offset = |pos: usize| pos.saturating_sub(snippet.offset) + padding.len();
+                let span = (
+                    snippet.source_name.clone(),
+                    offset(span.start)..offset(span.end),
+                );
+                let report = ariadne::Report::build(ariadne::ReportKind::Error, span.clone())
+                    .with_label(ariadne::Label::new(span).with_message(options.wrap(message)))
+                    .with_config(options.ariadne_config())
                     .finish();
 
+                let source = ariadne::Source::from(format!("{padding}{}", snippet.text));
+
                 let mut utf8 = vec![];
                 let mut cursor = std::io::Cursor::new(&mut utf8);
-                match report.write(source, &mut cursor) {
+                match report.write((snippet.source_name.clone(), source), &mut cursor) {
+                    Ok(_) => {
+                        strip_trailing_whitespace_on_each_line(&String::from_utf8_lossy(&utf8))
+                    }
+                    Err(_) => message.to_owned(),
+                }
+            }
+            Self::Labeled {
+                sources,
+                message,
+                labels,
+            } => {
+                let to_span =
+                    |label: &Label| (label.source_name.clone(), label.span.start..label.span.end);
+
+                let Some(first) = labels.first() else {
+                    return message.to_owned();
+                };
+
+                let report = ariadne::Report::build(ariadne::ReportKind::Error, to_span(first))
+                    .with_message(options.wrap(message))
+                    .with_labels(labels.iter().map(|label| {
+                        ariadne::Label::new(to_span(label)).with_message(&label.message)
+                    }))
+                    .with_config(options.ariadne_config())
+                    .finish();
+
+                let mut utf8 = vec![];
+                let mut cursor = std::io::Cursor::new(&mut utf8);
+                match report.write(ariadne::sources(sources.clone()), &mut cursor) {
                     Ok(_) => {
                         strip_trailing_whitespace_on_each_line(&String::from_utf8_lossy(&utf8))
                     }
@@ -81,9 +615,186 @@ impl std::error::Error for Error {}
 /// A type alias for a result that uses the [`Error`] type defined above.
 pub type Result<T = (), E = Error> = std::result::Result<T, E>;
 
+/// Greedily break the lines of `text` at spaces so that they fit within `width` characters.
+///
+/// Words longer than `width` are kept whole.
+fn wrap_words(text: &str, width: usize) -> String {
+    let mut out = String::with_capacity(text.len());
+    for (i, line) in text.lines().enumerate() {
+        if 0 < i {
+            out.push('\n');
+        }
+        let mut column = 0;
+        for word in line.split(' ') {
+            let word_width = word.chars().count();
+            if 0 < column && width < column + 1 + word_width {
+                out.push('\n');
+                column = 0;
+            } else if 0 < column {
+                out.push(' ');
+                column += 1;
+            }
+            out.push_str(word);
+            column += word_width;
+        }
+    }
+    out
+}
+
 fn strip_trailing_whitespace_on_each_line(s: &str) -> String {
     s.lines()
         .map(|line| line.trim_end())
         .collect::<Vec<_>>()
         .join("\n")
 }
+
+#[test]
+fn test_labels_in_multiple_sources() {
+    let err = Error::new_labeled(
+        [
+            ("base.eon".to_owned(), "port: 80\n".to_owned()),
+            ("local.eon".to_owned(), "port: 8080\n".to_owned()),
+        ],
+        "Duplicate key",
+        vec![
+            Label::new("base.eon", Span { start: 0, end: 4 }, "First defined here"),
+            Label::new("local.eon", Span { start: 0, end: 4 }, "Overridden here"),
+        ],
+    );
+    let rendered = err.to_string();
+    assert!(rendered.contains("base.eon:1:1"), "{rendered}");
+    assert!(rendered.contains("local.eon:1:1"), "{rendered}");
+    assert!(rendered.contains("First defined here"), "{rendered}");
+    assert!(rendered.contains("Overridden here"), "{rendered}");
+}
+
+#[test]
+fn test_error_parts() {
+    let source = "a: 1\nbb: [1, 2 3]\n";
+    let err = Error::new_at(source, Span { start: 15, end: 16 }, "Expected a comma");
+    assert_eq!(err.message(), "Expected a comma");
+    assert_eq!(err.span(), Some(Span { start: 15, end: 16 }));
+    assert_eq!(err.line_col(source), Some((2, 11)));
+    assert_eq!(
+        err.to_parts(),
+        Diagnostic {
+            severity: Severity::Error,
+            message: "Expected a comma".to_owned(),
+            labels: vec![Label::new(
+                UNNAMED_SOURCE,
+                Span { start: 15, end: 16 },
+                "Expected a comma"
+            )],
+            notes: vec![],
+            alternatives: vec![],
+        }
+    );
+
+    let err = Error::custom("Something went wrong");
+    assert_eq!(err.message(), "Something went wrong");
+    assert_eq!(err.span(), None);
+    assert_eq!(err.line_col(source), None);
+    assert!(err.to_parts().labels.is_empty());
+
+    let err = Error::new_at_labels(
+        "ä: 1\nä: 2",
+        "Duplicate key",
+        [
+            (Span { start: 0, end: 2 }, "First".to_owned()),
+            (Span { start: 6, end: 8 }, "Again".to_owned()),
+        ],
+    );
+    assert_eq!(err.message(), "Duplicate key");
+    assert_eq!(err.span(), Some(Span { start: 0, end: 2 }));
+    assert_eq!(err.line_col("ä: 1\nä: 2"), Some((1, 1)));
+    assert_eq!(err.to_parts().labels.len(), 2);
+}
+
+#[test]
+fn test_error_snippet() {
+    let source = "a: 1\nbb: [1, 2 3]\nc: 3\n";
+    let err = Error::new_at(source, Span { start: 15, end: 16 }, "Expected a comma");
+    let Error::At { snippet, .. } = &err else {
+        panic!("Expected Error::At");
+    };
+    assert_eq!(snippet.text, "bb: [1, 2 3]");
+    assert_eq!(snippet.offset, 5);
+    assert_eq!(snippet.first_line, 1);
+
+    let rendered = err.with_source_name("config.eon").to_string();
+    assert!(rendered.contains("config.eon:2:11"), "{rendered}");
+    assert!(rendered.contains(" 2 │ bb: [1, 2 3]"), "{rendered}");
+    assert!(!rendered.contains("a: 1"), "{rendered}");
+}
+
+#[test]
+fn test_error_alternative() {
+    use crate::TokenTree;
+
+    // Once a `key:` has been seen, the map error wins, even if the list attempt got further:
+    let err = TokenTree::parse_str("a: 1\nb 2").unwrap_err();
+    let alternative = err.alternative().unwrap();
+    assert_ne!(err.message(), alternative.message());
+    assert!(alternative.alternative().is_none());
+
+    let diagnostic = err.to_parts();
+    assert_eq!(diagnostic.message, err.message());
+    assert_eq!(diagnostic.alternatives.len(), 1);
+    assert_eq!(diagnostic.alternatives[0].message, alternative.message());
+}
+
+#[test]
+fn test_error_kind() {
+    let kind = ErrorKind::UnknownVariant {
+        found: "Bleu".to_owned(),
+        expected: vec!["Blue".to_owned(), "Red".to_owned()],
+    };
+    let err = Error::new_at(
+        "color: \"Bleu\"",
+        Span { start: 7, end: 13 },
+        "Unknown variant",
+    );
+    assert_eq!(err.kind(), &ErrorKind::Other);
+
+    let err = err.with_kind(kind.clone()).with_source_name("config.eon");
+    assert_eq!(err.kind(), &kind);
+    assert_eq!(err.message(), "Unknown variant");
+    assert_eq!(err.span(), Some(Span { start: 7, end: 13 }));
+    assert!(err.to_string().contains("config.eon:1:8"), "{err}");
+
+    let err = Error::with_alternative(err, Error::custom("Something else"));
+    assert_eq!(err.kind(), &kind);
+
+    assert_eq!(
+        Error::custom("Oops")
+            .with_kind(kind)
+            .with_kind(ErrorKind::Other)
+            .kind(),
+        &ErrorKind::Other
+    );
+}
+
+#[test]
+fn test_render_options() {
+    let source = "a: 1\nbb: [1, 2 3]\n";
+    let err = Error::new_at(source, Span { start: 15, end: 16 }, "Expected a comma");
+
+    let default = err.render(&RenderOptions::default());
+    assert_eq!(default, err.to_string());
+    assert!(default.contains('│'), "{default}");
+
+    let ascii = err.render(&RenderOptions::default().with_unicode(false));
+    assert!(ascii.is_ascii(), "{ascii}");
+    assert!(ascii.contains("Expected a comma"), "{ascii}");
+
+    let colored = err.render(&RenderOptions::default().with_color(true));
+    assert!(colored.contains('\u{1b}'), "{colored}");
+    assert_eq!(colored, err.to_string_with_color(true));
+
+    let err = Error::custom("The quick brown fox jumps over the lazy dog");
+    assert_eq!(
+        err.render(&RenderOptions::default().with_width(16)),
+        "The quick brown\nfox jumps over\nthe lazy dog"
+    );
+    assert_eq!(wrap_words("a verylongword b", 4), "a\nverylongword\nb");
+}
@@ -1,11 +1,21 @@
 //! Serialize a [`TokenTree`] to an Eon string.
 
-use crate::token_tree::{TokenKeyValue, TokenList, TokenMap, TokenTree, TokenValue, TokenVariant};
+use std::collections::BTreeMap;
+
+use crate::{
+    Error, KeyPath, PathSegment, Result,
+    strings::{escape_and_quote, unescape_and_unquote},
+    token_tree::{
+        Comment, TokenInclude, TokenKeyValue, TokenList, TokenMap, TokenTree, TokenValue,
+        TokenVariant,
+    },
+};
 
 /// How to format an Eon document.
 ///
 /// If you mess up the options too much (e.g. set the indentation to something that is not whitespace)
 /// you might end up with a document that is not valid Eon syntax.
+/// Use [`Self::validate`] to catch such mistakes.
 #[derive(Clone, Debug)]
 pub struct FormatOptions {
     /// `"\t"`
@@ -18,12 +28,229 @@ pub struct FormatOptions {
     pub space_before_suffix_comment: String,
 
     /// `": "`
+    ///
+    /// Can also be e.g. `" = "`, for documents migrated from TOML or INI files.
+    /// Both `:` and `=` are accepted when parsing,
+    /// so reformatting normalizes all key-value pairs to this separator.
     pub key_value_separator: String,
 
     /// Surround the top-level map in { } with an extra level of indentation.
+    ///
+    /// The braces wrap the document as it is, so comments are placed like this:
+    /// * Comments above the first entry (like a file header) stay inside the braces, above that entry.
+    /// * Comments after the last entry stay inside the braces, before the `}`.
+    /// * Comments before an explicit `{` in the source stay above the `{`,
+    ///   and a comment after an explicit `}` stays after the `}`.
+    ///
+    /// Without outer braces, a comment after an explicit `}` has nothing to follow,
+    /// so it is written on its own line at the end of the document.
     pub always_include_outer_braces: bool,
+
+    /// End the output with a [`Self::newline`], unless it is empty.
+    ///
+    /// A top-level map without outer braces always ends with a newline,
+    /// but a document that is just `42` or `[1, 2, 3]` does not, unless this is set.
+    /// Set this when writing files, which by POSIX convention end with a newline.
+    pub ensure_trailing_newline: bool,
+
+    /// Lists, maps, and variants are put on a single line if they fit within this many columns,
+    /// including indentation, keys, and separators.
+    ///
+    /// Tabs are counted as [`TAB_WIDTH`] columns.
+    ///
+    /// Lists, maps, and variants containing comments, multi-line strings, or non-empty maps or lists
+    /// are always broken across multiple lines, as is the top-level map.
+    pub max_line_width: usize,
+
+    /// In what order to write the key-value pairs of maps.
+    pub sort_keys: SortKeys,
+
+    /// Force the layout of the lists, maps, and variants at specific paths,
+    /// e.g. always put `keybindings` on multiple lines, or `color` on a single line.
+    ///
+    /// This overrides the [`Self::max_line_width`] heuristic.
+    pub layout_overrides: BTreeMap<KeyPath, Layout>,
+
+    /// Whether to put commas between the values of multi-line lists, maps, and variants.
+    pub multiline_separator: MultilineSeparator,
+
+    /// Whether to put a comma after the last value of multi-line lists, maps, and variants.
+    ///
+    /// Only used with [`MultilineSeparator::Comma`].
+    pub trailing_separator: TrailingSeparator,
+
+    /// Whether to keep numbers written like `0xff` or `1_000_000` as they were written.
+    pub number_literals: NumberLiterals,
+
+    /// Write strings with both backslashes and apostrophes, like the Windows path `C:\Users\O'Brien`,
+    /// as `'''C:\Users\O'Brien'''` instead of `"C:\\Users\\O'Brien"`.
+    ///
+    /// Like [`Self::number_literals`], this only affects formatting a value, like an `eon::Value`,
+    /// since a [`TokenTree`] keeps the quotes of its strings.
+    /// See [`crate::escape_and_quote_literal`].
+    pub literal_backslash_strings: bool,
+
+    /// Pad the `// comments` after the values of a multi-line list, map, or variant
+    /// so that they all start in the same column:
+    ///
+    /// ```text
+    /// port: 8080        // The port to listen on
+    /// host: "localhost" // The host to bind to
+    /// ```
+    pub align_suffix_comments: bool,
+
+    /// Whether to write `# comments` as `// comments`.
+    pub hash_comments: HashComments,
+
+    /// Write the whole document on a single line, with as little whitespace as possible
+    /// and without any comments, like `{a:1,b:[1,2,3]}`.
+    ///
+    /// Useful for embedding Eon in logs, command line arguments, and HTTP headers.
+    /// Strings with newlines in them are re-quoted with escapes.
+    ///
+    /// Only [`Self::sort_keys`], [`Self::number_literals`], and [`Self::literal_backslash_strings`]
+    /// affect compact output.
+    /// See also [`Self::compact()`].
+    pub compact: bool,
+}
+
+/// Whether to put commas between the values of multi-line lists, maps, and variants.
+///
+/// Single-line lists and variants always use commas, like `[1, 2, 3]`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MultilineSeparator {
+    /// No commas, since each value is on its own line anyway.
+    #[default]
+    None,
+
+    /// A comma after each value (except the last, see [`TrailingSeparator`]).
+    Comma,
+}
+
+/// Whether to put a comma after the last value of a multi-line list, map, or variant.
+///
+/// Only used with [`MultilineSeparator::Comma`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TrailingSeparator {
+    /// Always end with a comma, which makes for smaller diffs when adding values.
+    Always,
+
+    /// Never end with a comma.
+    #[default]
+    Never,
+
+    /// End with a comma if the source document did.
+    Preserve,
 }
 
+/// Whether to keep numbers written like `0xff`, `0b1010`, `1_000_000`, or `1e9` as they were written.
+///
+/// A [`TokenTree`] always keeps the source text of its numbers,
+/// so this only affects formatting a value that was parsed from Eon, like an `eon::Value`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NumberLiterals {
+    /// Write numbers the way they were written in the source document.
+    #[default]
+    Preserve,
+
+    /// Write all numbers as plain decimals, like `255` and `1000000`.
+    Normalize,
+}
+
+/// Whether to keep `# comments` (as in YAML and TOML) as they were written.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HashComments {
+    /// Write `# comment` as `// comment`, the Eon way.
+    #[default]
+    Normalize,
+
+    /// Write `# comment` as it was written.
+    Preserve,
+}
+
+/// How to lay out a list, map, or variant. See [`FormatOptions::layout_overrides`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layout {
+    /// Put all values on one line, like `[1, 2, 3]` or `{ a: 1, b: 2 }`, no matter how long it gets.
+    ///
+    /// Values that contain comments, multi-line strings, or non-empty maps or lists
+    /// are still broken across multiple lines.
+    SingleLine,
+
+    /// Put each value on its own line.
+    MultiLine,
+}
+
+/// In what order the formatter writes the key-value pairs of a map.
+///
+/// Comments stay attached to the key-value pair they belong to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SortKeys {
+    /// Keep the order of the source document.
+    #[default]
+    Preserve,
+
+    /// Sort keys alphabetically, by their unquoted string value.
+    ///
+    /// `"B"` comes before `a`.
+    /// Keys that are not strings (e.g. numbers) are compared by their source text.
+    /// Keys that are lists, maps, or variants are put last, in their original order.
+    Alphabetical,
+
+    /// Like [`Self::Alphabetical`], but ignores case, so that `a` comes before `"B"`.
+    AlphabeticalCaseInsensitive,
+}
+
+/// The key-value pairs of a map in the order they should be written.
+fn sorted_key_values<'a, 's>(
+    key_values: &'a [TokenKeyValue<'s>],
+    sort_keys: SortKeys,
+) -> Vec<&'a TokenKeyValue<'s>> {
+    let mut sorted: Vec<&TokenKeyValue<'_>> = key_values.iter().collect();
+    match sort_keys {
+        SortKeys::Preserve => {}
+        SortKeys::Alphabetical => {
+            sorted.sort_by_cached_key(|key_value| key_sort_text(&key_value.key.value));
+        }
+        SortKeys::AlphabeticalCaseInsensitive => {
+            sorted.sort_by_cached_key(|key_value| {
+                key_sort_text(&key_value.key.value).map(|text| text.to_lowercase())
+            });
+        }
+    }
+    sorted
+}
+
+/// What to sort a map key by.
+///
+/// `Err` (which sorts after any `Ok`) for keys that are lists, maps, or variants.
+fn key_sort_text(key: &TokenValue<'_>) -> Result<String, ()> {
+    // Strings are compared by their value, so that `key` and `"key"` are equivalent:
+    if let Some(string) = key.as_key_str() {
+        return Ok(string.into_owned());
+    }
+    match key {
+        TokenValue::Number(text) | TokenValue::Timestamp(text) | TokenValue::Bytes(text) => {
+            Ok(text.to_string())
+        }
+        _ => Err(()),
+    }
+}
+
+/// The [`PathSegment`] that refers to the value of the given map key, if any.
+fn key_path_segment(key: &TokenValue<'_>) -> Option<PathSegment> {
+    if let Some(key) = key.as_key_str() {
+        Some(PathSegment::Key(key.into_owned()))
+    } else if let TokenValue::Number(number) = key {
+        number.parse().ok().map(PathSegment::Index)
+    } else {
+        None
+    }
+}
+
+/// How many columns a tab character is assumed to occupy when measuring line widths.
+pub const TAB_WIDTH: usize = 4;
+
 impl Default for FormatOptions {
     fn default() -> Self {
         Self {
@@ -34,6 +261,17 @@ impl Default for FormatOptions {
             space_before_suffix_comment: " ".to_owned(),
             key_value_separator: ": ".to_owned(),
             always_include_outer_braces: false,
+            ensure_trailing_newline: false,
+            max_line_width: 80,
+            sort_keys: SortKeys::Preserve,
+            layout_overrides: BTreeMap::new(),
+            multiline_separator: MultilineSeparator::None,
+            trailing_separator: TrailingSeparator::Never,
+            number_literals: NumberLiterals::Preserve,
+            literal_backslash_strings: false,
+            align_suffix_comments: false,
+            hash_comments: HashComments::Normalize,
+            compact: false,
         }
     }
 }
@@ -44,6 +282,17 @@ impl FormatOptions {
         Self::default()
     }
 
+    /// Options for writing the whole document on a single line, without comments,
+    /// like `{a:1,b:[1,2,3]}`.
+    ///
+    /// This is the default options with the `compact` field set.
+    pub fn compact() -> Self {
+        Self {
+            compact: true,
+            ..Self::default()
+        }
+    }
+
     /// Set the indentation string.
     pub fn with_indentation(mut self, indentation: String) -> Self {
         self.indentation = indentation;
@@ -55,18 +304,169 @@ impl FormatOptions {
         self.newline = newline;
         self
     }
+
+    /// Set whether to surround the top-level map in { }.
+    ///
+    /// See [`Self::always_include_outer_braces`].
+    pub fn with_always_include_outer_braces(mut self, always_include_outer_braces: bool) -> Self {
+        self.always_include_outer_braces = always_include_outer_braces;
+        self
+    }
+
+    /// Set whether to always end the output with a newline.
+    ///
+    /// See [`Self::ensure_trailing_newline`].
+    pub fn with_ensure_trailing_newline(mut self, ensure_trailing_newline: bool) -> Self {
+        self.ensure_trailing_newline = ensure_trailing_newline;
+        self
+    }
+
+    /// Set the maximum line width.
+    pub fn with_max_line_width(mut self, max_line_width: usize) -> Self {
+        self.max_line_width = max_line_width;
+        self
+    }
+
+    /// Set in what order to write the key-value pairs of maps.
+    pub fn with_sort_keys(mut self, sort_keys: SortKeys) -> Self {
+        self.sort_keys = sort_keys;
+        self
+    }
+
+    /// Force the layout of the list or variant at the given path.
+    ///
+    /// See [`Self::layout_overrides`].
+    pub fn with_layout_override(mut self, path: KeyPath, layout: Layout) -> Self {
+        self.layout_overrides.insert(path, layout);
+        self
+    }
+
+    /// Set whether to put commas between (and after) the values of multi-line lists, maps, and variants.
+    pub fn with_separators(
+        mut self,
+        multiline_separator: MultilineSeparator,
+        trailing_separator: TrailingSeparator,
+    ) -> Self {
+        self.multiline_separator = multiline_separator;
+        self.trailing_separator = trailing_separator;
+        self
+    }
+
+    /// Set whether to keep numbers like `0xff` as they were written.
+    pub fn with_number_literals(mut self, number_literals: NumberLiterals) -> Self {
+        self.number_literals = number_literals;
+        self
+    }
+
+    /// Set whether to write strings like `C:\Users\O'Brien` as `'''C:\Users\O'Brien'''`.
+    ///
+    /// See [`Self::literal_backslash_strings`].
+    pub fn with_literal_backslash_strings(mut self, literal_backslash_strings: bool) -> Self {
+        self.literal_backslash_strings = literal_backslash_strings;
+        self
+    }
+
+    /// Set what to put between a key and its value, e.g. `": "` or `" = "`.
+    ///
+    /// See [`Self::key_value_separator`].
+    pub fn with_key_value_separator(mut self, key_value_separator: impl Into<String>) -> Self {
+        self.key_value_separator = key_value_separator.into();
+        self
+    }
+
+    /// Set whether to align the suffix comments of multi-line lists, maps, and variants.
+    ///
+    /// See [`Self::align_suffix_comments`].
+    pub fn with_align_suffix_comments(mut self, align_suffix_comments: bool) -> Self {
+        self.align_suffix_comments = align_suffix_comments;
+        self
+    }
+
+    /// Set whether to write `# comments` as `// comments`.
+    pub fn with_hash_comments(mut self, hash_comments: HashComments) -> Self {
+        self.hash_comments = hash_comments;
+        self
+    }
+
+    /// Check that these options will produce valid Eon.
+    ///
+    /// ## Errors
+    /// * If the indentation or the space before suffix comments is not whitespace.
+    /// * If the key-value separator is not a `:` or `=` surrounded by optional whitespace.
+    /// * If the newline is not `"\n"` or `"\r\n"`.
+    pub fn validate(&self) -> Result<()> {
+        let Self {
+            indentation,
+            newline,
+            space_before_suffix_comment,
+            key_value_separator,
+            always_include_outer_braces: _,
+            ensure_trailing_newline: _,
+            max_line_width: _,
+            sort_keys: _,
+            layout_overrides: _,
+            multiline_separator: _,
+            trailing_separator: _,
+            number_literals: _,
+            literal_backslash_strings: _,
+            align_suffix_comments: _,
+            hash_comments: _,
+            compact: _,
+        } = self;
+
+        if !indentation.chars().all(char::is_whitespace) {
+            return Err(Error::custom(format!(
+                "Invalid FormatOptions: indentation must be whitespace, got {indentation:?}"
+            )));
+        }
+        if newline != "\n" && newline != "\r\n" {
+            return Err(Error::custom(format!(
+                "Invalid FormatOptions: newline must be \"\\n\" or \"\\r\\n\", got {newline:?}"
+            )));
+        }
+        if !space_before_suffix_comment.chars().all(char::is_whitespace) {
+            return Err(Error::custom(format!(
+                "Invalid FormatOptions: space_before_suffix_comment must be whitespace, got {space_before_suffix_comment:?}"
+            )));
+        }
+        if !matches!(key_value_separator.trim(), ":" | "=") {
+            return Err(Error::custom(format!(
+                "Invalid FormatOptions: key_value_separator must be a ':' or '=' surrounded by optional whitespace, got {key_value_separator:?}"
+            )));
+        }
+        Ok(())
+    }
 }
 
 impl TokenTree<'_> {
+    /// Like [`Self::format`], but with ANSI colors for printing to a terminal.
+    ///
+    /// See [`crate::colorize`].
+    pub fn format_colored(&self, options: &FormatOptions) -> String {
+        crate::colorize(&self.format(options))
+    }
+
     /// Format as an Eon string.
     pub fn format(&self, options: &FormatOptions) -> String {
         let mut f = Formatter::new(options);
 
+        if f.options.compact {
+            f.compact_value(&self.value);
+            return f.finish();
+        }
+
         if !f.options.always_include_outer_braces {
             if let TokenValue::Map(map) = &self.value {
                 f.indented_comments(&self.prefix_comments);
                 f.map_content(map);
-                f.suffix_comment(&self.suffix_comment);
+                if let Some(comment) = &self.suffix_comment {
+                    // There is no closing brace to put it after, so write it like a closing comment:
+                    let has_entries = !map.includes.is_empty() || !map.key_values.is_empty();
+                    if has_entries && map.closing_comments.is_empty() {
+                        f.newline();
+                    }
+                    f.indented_comments(std::slice::from_ref(comment));
+                }
                 return f.finish();
             }
         }
@@ -80,6 +480,24 @@ struct Formatter<'o> {
     options: &'o FormatOptions,
     indent: usize,
     out: String,
+
+    /// The path to the value currently being formatted.
+    ///
+    /// Only tracked if there are any [`FormatOptions::layout_overrides`].
+    path: KeyPath,
+
+    /// How many levels deep we are inside a value that has no [`KeyPath`],
+    /// e.g. the value of a map key that is a list.
+    num_unaddressable: usize,
+
+    /// For each multi-line block we are in, where its suffix comments start in [`Self::out`],
+    /// as `(byte offset, column)`.
+    ///
+    /// Only tracked if [`FormatOptions::align_suffix_comments`] is set.
+    suffix_comments: Vec<Vec<(usize, usize)>>,
+
+    /// Are we about to format the top-level value?
+    at_root: bool,
 }
 
 impl<'o> Formatter<'o> {
@@ -88,15 +506,102 @@ impl<'o> Formatter<'o> {
             options,
             indent: 0,
             out: String::new(),
+            path: KeyPath::root(),
+            num_unaddressable: 0,
+            suffix_comments: vec![],
+            at_root: true,
+        }
+    }
+
+    /// Enter a child of the current value. Must be followed by a call to [`Self::pop_path`].
+    fn push_path(&mut self, segment: impl FnOnce() -> Option<PathSegment>) {
+        if self.options.layout_overrides.is_empty() {
+            return;
+        }
+        if self.num_unaddressable == 0 {
+            if let Some(segment) = segment() {
+                self.path.segments.push(segment);
+                return;
+            }
         }
+        self.num_unaddressable += 1;
     }
 
-    fn finish(self) -> String {
+    fn pop_path(&mut self) {
+        if self.options.layout_overrides.is_empty() {
+            return;
+        }
+        if self.num_unaddressable == 0 {
+            self.path.segments.pop();
+        } else {
+            self.num_unaddressable -= 1;
+        }
+    }
+
+    /// Start a multi-line block, whose suffix comments are aligned by [`Self::end_block`].
+    fn begin_block(&mut self) {
+        if self.options.align_suffix_comments {
+            self.suffix_comments.push(vec![]);
+        }
+    }
+
+    /// Pad the suffix comments of the current block so that they all start in the same column.
+    fn end_block(&mut self) {
+        let Some(comments) = self.suffix_comments.pop() else {
+            return;
+        };
+        let Some(max_column) = comments.iter().map(|&(_, column)| column).max() else {
+            return;
+        };
+
+        // Back to front, so that padding a comment doesn't move the ones before it:
+        for &(offset, column) in comments.iter().rev() {
+            self.out
+                .insert_str(offset, &" ".repeat(max_column - column));
+        }
+    }
+
+    /// Should there be a comma after value number `index` of `len` in a multi-line list, map, or variant?
+    ///
+    /// `trailing_comma` is whether the source had a comma after the last value.
+    fn wants_comma(&self, index: usize, len: usize, trailing_comma: bool) -> bool {
+        match self.options.multiline_separator {
+            MultilineSeparator::None => false,
+            MultilineSeparator::Comma => {
+                index + 1 < len
+                    || match self.options.trailing_separator {
+                        TrailingSeparator::Always => true,
+                        TrailingSeparator::Never => false,
+                        TrailingSeparator::Preserve => trailing_comma,
+                    }
+            }
+        }
+    }
+
+    /// Should a list, map, or variant of the given single-line width be put on a single line?
+    fn single_line(&self, width: usize) -> bool {
+        let layout = if self.num_unaddressable == 0 {
+            self.options.layout_overrides.get(&self.path)
+        } else {
+            None
+        };
+        match layout {
+            Some(Layout::SingleLine) => true,
+            Some(Layout::MultiLine) => false,
+            None => self.fits_on_line(width),
+        }
+    }
+
+    fn finish(mut self) -> String {
         debug_assert_eq!(
             self.indent, 0,
             "Formatter finished with non-zero indent of {}",
             self.indent
         );
+        if self.options.ensure_trailing_newline && !self.out.is_empty() && !self.out.ends_with('\n')
+        {
+            self.newline();
+        }
         self.out
     }
 
@@ -104,45 +609,88 @@ impl<'o> Formatter<'o> {
         self.out.push_str(&self.options.newline);
     }
 
+    /// The width of the current (last) line of the output, in columns.
+    fn current_column(&self) -> usize {
+        let line = self.out.rsplit('\n').next().unwrap_or_default();
+        line.chars()
+            .map(|c| if c == '\t' { TAB_WIDTH } else { 1 })
+            .sum()
+    }
+
+    /// Would something of the given width fit on the current line?
+    fn fits_on_line(&self, width: usize) -> bool {
+        self.current_column() + width <= self.options.max_line_width
+    }
+
     fn add_indent(&mut self) {
         for _ in 0..self.indent {
             self.out.push_str(&self.options.indentation);
         }
     }
 
-    fn indented_comments(&mut self, comments: &[&str]) {
-        for &comment in comments {
+    fn indented_comments(&mut self, comments: &[Comment<'_>]) {
+        for comment in comments {
             self.add_indent();
-            self.out.push_str(comment);
+            self.comment(comment);
             self.newline();
         }
     }
 
     fn indented_value(&mut self, value: &TokenTree<'_>) {
+        self.indented_value_with_separator(value, |_| {});
+    }
+
+    /// Format a value on its own line(s), calling `separator` after the value, before any suffix comment.
+    fn indented_value_with_separator(
+        &mut self,
+        value: &TokenTree<'_>,
+        separator: impl FnOnce(&mut Self),
+    ) {
         let TokenTree {
             prefix_comments,
+            doc_comments,
             value,
             suffix_comment,
             span: _,
         } = value;
         self.indented_comments(prefix_comments);
+        self.indented_comments(doc_comments);
         self.add_indent();
         self.value(value);
-        self.suffix_comment(suffix_comment);
+        separator(self);
+        self.suffix_comment(suffix_comment.as_deref());
     }
 
-    #[expect(clippy::ref_option_ref)]
-    fn suffix_comment(&mut self, suffix_comment: &Option<&str>) {
+    fn suffix_comment(&mut self, suffix_comment: Option<&str>) {
         if let Some(suffix_comment) = suffix_comment {
+            if self.options.align_suffix_comments {
+                let position = (self.out.len(), self.current_column());
+                if let Some(comments) = self.suffix_comments.last_mut() {
+                    comments.push(position);
+                }
+            }
             self.out.push(' ');
-            self.out.push_str(suffix_comment);
+            self.comment(suffix_comment);
+        }
+    }
+
+    fn comment(&mut self, comment: &str) {
+        match comment.strip_prefix('#') {
+            Some(text) if self.options.hash_comments == HashComments::Normalize => {
+                self.out.push_str("//");
+                self.out.push_str(text);
+            }
+            _ => self.out.push_str(comment),
         }
     }
 
     fn value(&mut self, value: &TokenValue<'_>) {
+        let is_root = std::mem::take(&mut self.at_root);
         match value {
             TokenValue::Identifier(slice)
             | TokenValue::Number(slice)
+            | TokenValue::Timestamp(slice)
+            | TokenValue::Bytes(slice)
             | TokenValue::QuotedString(slice) => {
                 self.out.push_str(slice);
             }
@@ -150,7 +698,7 @@ impl<'o> Formatter<'o> {
                 self.list(list);
             }
             TokenValue::Map(map) => {
-                self.map(map);
+                self.map(map, is_root);
             }
             TokenValue::Variant(variant) => {
                 self.variant(variant);
@@ -158,10 +706,83 @@ impl<'o> Formatter<'o> {
         }
     }
 
+    /// Write a value on a single line, without comments or whitespace.
+    fn compact_value(&mut self, value: &TokenValue<'_>) {
+        match value {
+            TokenValue::Identifier(slice)
+            | TokenValue::Number(slice)
+            | TokenValue::Timestamp(slice)
+            | TokenValue::Bytes(slice) => {
+                self.out.push_str(slice);
+            }
+            TokenValue::QuotedString(slice) => {
+                if slice.contains('\n') {
+                    // A multi-line string:
+                    match unescape_and_unquote(slice) {
+                        Ok(raw) => self.out.push_str(&escape_and_quote(&raw)),
+                        Err(_) => self.out.push_str(slice), // Shouldn't happen for a parsed string
+                    }
+                } else {
+                    self.out.push_str(slice);
+                }
+            }
+            TokenValue::List(list) => {
+                self.out.push('[');
+                self.compact_values(&list.values);
+                self.out.push(']');
+            }
+            TokenValue::Map(map) => {
+                let TokenMap {
+                    includes,
+                    key_values,
+                    closing_comments: _,
+                    trailing_comma: _,
+                } = map;
+                self.out.push('{');
+                for (i, include) in includes.iter().enumerate() {
+                    if 0 < i {
+                        self.out.push(',');
+                    }
+                    self.out.push_str("@include ");
+                    self.out.push_str(&include.quoted_path);
+                }
+                let sorted = sorted_key_values(key_values, self.options.sort_keys);
+                for (i, key_value) in sorted.into_iter().enumerate() {
+                    if 0 < i || !includes.is_empty() {
+                        self.out.push(',');
+                    }
+                    self.compact_value(&key_value.key.value);
+                    self.out.push(':');
+                    self.compact_value(&key_value.value.value);
+                }
+                self.out.push('}');
+            }
+            TokenValue::Variant(variant) => {
+                self.out.push_str(&variant.quoted_name);
+                if !variant.values.is_empty() {
+                    self.out.push('(');
+                    self.compact_values(&variant.values);
+                    self.out.push(')');
+                }
+            }
+        }
+    }
+
+    /// Comma-separated values, on a single line.
+    fn compact_values(&mut self, values: &[TokenTree<'_>]) {
+        for (i, value) in values.iter().enumerate() {
+            if 0 < i {
+                self.out.push(',');
+            }
+            self.compact_value(&value.value);
+        }
+    }
+
     fn list(&mut self, list: &TokenList<'_>) {
         let TokenList {
             values,
             closing_comments,
+            trailing_comma: _,
         } = list;
 
         if list.values.is_empty() && closing_comments.is_empty() {
@@ -169,7 +790,10 @@ impl<'o> Formatter<'o> {
             return;
         }
 
-        if should_format_list_on_one_line(list) {
+        if closing_comments.is_empty()
+            && values.iter().all(is_simple)
+            && self.single_line(single_line_width("[", values, "]"))
+        {
             self.out.push('[');
             for (i, value) in values.iter().enumerate() {
                 self.value(&value.value);
@@ -193,15 +817,34 @@ impl<'o> Formatter<'o> {
         let TokenList {
             values,
             closing_comments,
+            trailing_comma,
         } = list;
+        self.values_content(values, closing_comments, *trailing_comma);
+    }
 
+    /// The inside of a multi-line list or variant.
+    fn values_content(
+        &mut self,
+        values: &[TokenTree<'_>],
+        closing_comments: &[Comment<'_>],
+        trailing_comma: bool,
+    ) {
+        self.begin_block();
         for (i, value) in values.iter().enumerate() {
             if 0 < i && !value.prefix_comments.is_empty() {
                 self.newline();
             }
-            self.indented_value(value);
+            self.push_path(|| Some(PathSegment::Index(i)));
+            let comma = self.wants_comma(i, values.len(), trailing_comma);
+            self.indented_value_with_separator(value, |f| {
+                if comma {
+                    f.out.push(',');
+                }
+            });
+            self.pop_path();
             self.newline();
         }
+        self.end_block();
 
         if !closing_comments.is_empty() {
             if !values.is_empty() {
@@ -211,17 +854,50 @@ impl<'o> Formatter<'o> {
         }
     }
 
-    fn map(&mut self, map: &TokenMap<'_>) {
+    /// `is_root` is set for the braces around the whole document, which are never put on a single line.
+    /// Only valid for keys and values that are [`is_simple`].
+    fn single_line_map(&mut self, key_values: &[TokenKeyValue<'_>]) {
+        self.out.push_str("{ ");
+        let sorted = sorted_key_values(key_values, self.options.sort_keys);
+        for (i, TokenKeyValue { key, value }) in sorted.into_iter().enumerate() {
+            if 0 < i {
+                self.out.push_str(", "); // We use commas for single-line maps, just for extra readability
+            }
+            self.value(&key.value);
+            self.out.push_str(&self.options.key_value_separator);
+            self.value(&value.value);
+        }
+        self.out.push_str(" }");
+    }
+
+    fn map(&mut self, map: &TokenMap<'_>, is_root: bool) {
         let TokenMap {
+            includes,
             key_values,
             closing_comments,
+            trailing_comma: _,
         } = map;
 
-        if key_values.is_empty() && closing_comments.is_empty() {
+        if includes.is_empty() && key_values.is_empty() && closing_comments.is_empty() {
             self.out.push_str("{}");
             return;
         }
 
+        if !is_root
+            && includes.is_empty()
+            && closing_comments.is_empty()
+            && key_values.iter().all(|kv| {
+                kv.key.doc_comments.is_empty() && is_simple(&kv.key) && is_simple(&kv.value)
+            })
+            && self.single_line(single_line_map_width(
+                key_values,
+                &self.options.key_value_separator,
+            ))
+        {
+            self.single_line_map(key_values);
+            return;
+        }
+
         self.out.push('{');
         self.indent += 1;
         self.newline();
@@ -233,35 +909,83 @@ impl<'o> Formatter<'o> {
 
     fn map_content(&mut self, map: &TokenMap<'_>) {
         let TokenMap {
+            includes,
             key_values,
             closing_comments,
+            trailing_comma,
         } = map;
 
-        for (i, key_value) in key_values.iter().enumerate() {
-            if 0 < i && !key_value.key.prefix_comments.is_empty() {
+        let num_entries = includes.len() + key_values.len();
+
+        self.begin_block();
+
+        for (i, include) in includes.iter().enumerate() {
+            if 0 < i && !include.prefix_comments.is_empty() {
                 self.newline();
             }
-            self.indented_key_value(key_value);
+            let comma = self.wants_comma(i, num_entries, *trailing_comma);
+            self.indented_include(include, comma);
+            self.newline();
+        }
+
+        if !includes.is_empty() && !key_values.is_empty() {
             self.newline();
         }
 
+        let sorted = sorted_key_values(key_values, self.options.sort_keys);
+        for (i, key_value) in sorted.into_iter().enumerate() {
+            if 0 < i
+                && !(key_value.key.prefix_comments.is_empty()
+                    && key_value.key.doc_comments.is_empty())
+            {
+                self.newline();
+            }
+            let comma = self.wants_comma(includes.len() + i, num_entries, *trailing_comma);
+            self.indented_key_value(key_value, comma);
+            self.newline();
+        }
+        self.end_block();
+
         if !closing_comments.is_empty() {
-            if !key_values.is_empty() {
+            if !includes.is_empty() || !key_values.is_empty() {
                 self.newline();
             }
             self.indented_comments(closing_comments);
         }
     }
 
-    fn indented_key_value(&mut self, key_value: &TokenKeyValue<'_>) {
+    fn indented_include(&mut self, include: &TokenInclude<'_>, comma: bool) {
+        let TokenInclude {
+            span: _,
+            prefix_comments,
+            quoted_path,
+            suffix_comment,
+        } = include;
+        self.indented_comments(prefix_comments);
+        self.add_indent();
+        self.out.push_str("@include ");
+        self.out.push_str(quoted_path);
+        if comma {
+            self.out.push(',');
+        }
+        self.suffix_comment(suffix_comment.as_deref());
+    }
+
+    fn indented_key_value(&mut self, key_value: &TokenKeyValue<'_>, comma: bool) {
         let TokenKeyValue { key, value } = key_value;
         self.indented_comments(&key.prefix_comments);
+        self.indented_comments(&key.doc_comments);
         self.indented_comments(&value.prefix_comments);
         self.add_indent();
         self.value(&key.value);
         self.out.push_str(&self.options.key_value_separator);
+        self.push_path(|| key_path_segment(&key.value));
         self.value(&value.value);
-        self.suffix_comment(&value.suffix_comment);
+        self.pop_path();
+        if comma {
+            self.out.push(',');
+        }
+        self.suffix_comment(value.suffix_comment.as_deref());
     }
 
     fn variant(&mut self, variant: &TokenVariant<'_>) {
@@ -270,6 +994,7 @@ impl<'o> Formatter<'o> {
             quoted_name,
             values,
             closing_comments,
+            trailing_comma,
         } = variant;
 
         if values.is_empty() && closing_comments.is_empty() {
@@ -277,7 +1002,10 @@ impl<'o> Formatter<'o> {
             return;
         }
 
-        if should_format_variant_on_one_line(variant) {
+        if closing_comments.is_empty()
+            && values.iter().all(is_simple)
+            && self.single_line(single_line_width(quoted_name, values, ")") + 1)
+        {
             self.out.push_str(quoted_name);
             self.out.push('(');
             for (i, value) in values.iter().enumerate() {
@@ -298,6 +1026,22 @@ impl<'o> Formatter<'o> {
             if map.key_values.is_empty() && map.closing_comments.is_empty() {
                 self.out.push_str(quoted_name);
                 self.out.push_str("({ })");
+            } else if map.includes.is_empty()
+                && map.closing_comments.is_empty()
+                && map.key_values.iter().all(|kv| {
+                    kv.key.doc_comments.is_empty() && is_simple(&kv.key) && is_simple(&kv.value)
+                })
+                && self.single_line(
+                    quoted_name.chars().count()
+                        + "()".len()
+                        + single_line_map_width(&map.key_values, &self.options.key_value_separator),
+                )
+            {
+                // A short map variant, like `"Rgb"({ r: 255, g: 0, b: 0 })`.
+                self.out.push_str(quoted_name);
+                self.out.push('(');
+                self.single_line_map(&map.key_values);
+                self.out.push(')');
             } else {
                 // A single map variant, like `"VariantName"({ key: value, … })`.
                 // Here we avoid double-indenting for nicer/more compact output.
@@ -305,7 +1049,9 @@ impl<'o> Formatter<'o> {
                 self.out.push_str("({");
                 self.indent += 1;
                 self.newline();
+                self.push_path(|| Some(PathSegment::Index(0)));
                 self.map_content(map);
+                self.pop_path();
                 self.indent -= 1;
                 self.add_indent();
                 self.out.push_str("})");
@@ -321,6 +1067,22 @@ impl<'o> Formatter<'o> {
             if list.values.is_empty() && list.closing_comments.is_empty() {
                 self.out.push_str(quoted_name);
                 self.out.push_str("([ ])");
+            } else if list.closing_comments.is_empty()
+                && list.values.iter().all(is_simple)
+                && self.single_line(
+                    quoted_name.chars().count() + single_line_width("([", &list.values, "])"),
+                )
+            {
+                // A short list variant, like `"V4"([127, 0, 0, 1])`.
+                self.out.push_str(quoted_name);
+                self.out.push_str("([");
+                for (i, value) in list.values.iter().enumerate() {
+                    self.value(&value.value);
+                    if i + 1 < list.values.len() {
+                        self.out.push_str(", ");
+                    }
+                }
+                self.out.push_str("])");
             } else {
                 // A single list variant, like `"VariantName"({ key: value, … })`.
                 // Here we avoid double-indenting for nicer/more compact output.
@@ -328,7 +1090,9 @@ impl<'o> Formatter<'o> {
                 self.out.push_str("([");
                 self.indent += 1;
                 self.newline();
+                self.push_path(|| Some(PathSegment::Index(0)));
                 self.list_content(list);
+                self.pop_path();
                 self.indent -= 1;
                 self.add_indent();
                 self.out.push_str("])");
@@ -338,20 +1102,7 @@ impl<'o> Formatter<'o> {
             self.out.push('(');
             self.indent += 1;
             self.newline();
-            for (i, value) in values.iter().enumerate() {
-                if 0 < i && !value.prefix_comments.is_empty() {
-                    self.newline();
-                }
-                self.newline();
-            }
-
-            if !closing_comments.is_empty() {
-                if !values.is_empty() {
-                    self.newline();
-                }
-                self.indented_comments(closing_comments);
-            }
-
+            self.values_content(values, closing_comments, *trailing_comma);
             self.indent -= 1;
             self.add_indent();
             self.out.push(')');
@@ -359,54 +1110,49 @@ impl<'o> Formatter<'o> {
     }
 }
 
-fn should_format_list_on_one_line(list: &TokenList<'_>) -> bool {
-    let TokenList {
-        values,
-        closing_comments,
-    } = list;
-    closing_comments.is_empty() && should_format_values_on_one_line(values)
+/// The width of a map like `{ a: 1, b: 2 }` on a single line.
+///
+/// Only valid for keys and values that are [`is_simple`].
+fn single_line_map_width(key_values: &[TokenKeyValue<'_>], key_value_separator: &str) -> usize {
+    let separator_width = key_value_separator.chars().count();
+    let entries_width: usize = key_values
+        .iter()
+        .map(|kv| simple_width(&kv.key.value) + separator_width + simple_width(&kv.value.value))
+        .sum();
+    let commas_width = 2 * key_values.len().saturating_sub(1);
+    "{ ".len() + entries_width + commas_width + " }".len()
 }
 
-fn should_format_variant_on_one_line(variant: &TokenVariant<'_>) -> bool {
-    let TokenVariant {
-        name_span: _,
-        quoted_name: _,
-        values,
-        closing_comments,
-    } = variant;
-    closing_comments.is_empty() && should_format_values_on_one_line(values)
+/// The width of `values` if they are formatted on a single line,
+/// separated by commas and surrounded by `open` and `close`.
+///
+/// Only valid for values that are [`is_simple`].
+fn single_line_width(open: &str, values: &[TokenTree<'_>], close: &str) -> usize {
+    let values_width: usize = values.iter().map(|value| simple_width(&value.value)).sum();
+    let separators_width = 2 * values.len().saturating_sub(1);
+    open.chars().count() + values_width + separators_width + close.chars().count()
 }
 
-fn should_format_values_on_one_line(values: &[TokenTree<'_>]) -> bool {
-    if !values.iter().all(is_simple) {
-        return false;
-    }
-
-    if values.len() <= 4 && values.iter().all(|tt| tt.value.is_number()) {
-        return true; // e.g. [1 2 3 4]
-    }
-
-    if values.len() > 4 {
-        return false;
-    }
-
-    let mut estimated_width = 0;
-    for value in values {
-        if let TokenValue::QuotedString(string) = &value.value {
-            estimated_width += string.len();
-        } else {
-            estimated_width += 5;
-        }
-        estimated_width += 2;
+/// The width of a value that [`is_simple`].
+fn simple_width(value: &TokenValue<'_>) -> usize {
+    match value {
+        TokenValue::Identifier(slice)
+        | TokenValue::Number(slice)
+        | TokenValue::Timestamp(slice)
+        | TokenValue::Bytes(slice)
+        | TokenValue::QuotedString(slice) => slice.chars().count(),
+        TokenValue::List(_) | TokenValue::Map(_) => 2,
+        TokenValue::Variant(variant) => variant.quoted_name.chars().count(),
     }
-
-    estimated_width < 60
 }
 
 fn is_simple(value: &TokenTree<'_>) -> bool {
     if value.prefix_comments.is_empty() && value.suffix_comment.is_none() {
         match &value.value {
-            TokenValue::Identifier(_) | TokenValue::Number(_) => true,
+            TokenValue::Identifier(_)
+            | TokenValue::Number(_)
+            | TokenValue::Timestamp(_)
+            | TokenValue::Bytes(_) => true,
 
             TokenValue::QuotedString(string) => !string.contains('\n'),
 
@@ -414,16 +1160,19 @@ fn is_simple(value: &TokenTree<'_>) -> bool {
                 let TokenList {
                     values,
                     closing_comments,
+                    trailing_comma: _,
                 } = list;
                 values.is_empty() && closing_comments.is_empty()
             }
 
             TokenValue::Map(map) => {
                 let TokenMap {
+                    includes,
                     key_values,
                     closing_comments,
+                    trailing_comma: _,
                 } = map;
-                key_values.is_empty() && closing_comments.is_empty()
+                includes.is_empty() && key_values.is_empty() && closing_comments.is_empty()
             }
 
             TokenValue::Variant(variant) => {
@@ -432,6 +1181,7 @@ fn is_simple(value: &TokenTree<'_>) -> bool {
                     quoted_name: _,
                     values,
                     closing_comments,
+                    trailing_comma: _,
                 } = variant;
                 values.is_empty() && closing_comments.is_empty()
             }
@@ -440,3 +1190,43 @@ fn is_simple(value: &TokenTree<'_>) -> bool {
         false
     }
 }
+
+#[test]
+fn test_validate_format_options() {
+    assert!(FormatOptions::default().validate().is_ok());
+    assert!(
+        FormatOptions::default()
+            .with_indentation("  ".to_owned())
+            .with_newline("\r\n".to_owned())
+            .validate()
+            .is_ok()
+    );
+
+    let error = |options: FormatOptions| options.validate().unwrap_err().to_string();
+    assert_eq!(
+        error(FormatOptions::default().with_indentation("--".to_owned())),
+        r#"Invalid FormatOptions: indentation must be whitespace, got "--""#
+    );
+    assert_eq!(
+        error(FormatOptions::default().with_newline("\r".to_owned())),
+        r#"Invalid FormatOptions: newline must be "\n" or "\r\n", got "\r""#
+    );
+    assert!(
+        FormatOptions::default()
+            .with_key_value_separator(" = ")
+            .validate()
+            .is_ok()
+    );
+    assert_eq!(
+        error(FormatOptions::default().with_key_value_separator(" -> ")),
+        r#"Invalid FormatOptions: key_value_separator must be a ':' or '=' surrounded by optional whitespace, got " -> ""#
+    );
+    assert!(
+        FormatOptions {
+            space_before_suffix_comment: "x".to_owned(),
+            ..Default::default()
+        }
+        .validate()
+        .is_err()
+    );
+}
@@ -0,0 +1,210 @@
+//! Classifying tokens for syntax highlighting.
+
+use logos::Logos as _;
+
+use crate::{span::Span, token_kind::TokenKind};
+
+/// What kind of thing a token is, for syntax highlighting.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HighlightKind {
+    /// A map key, quoted or not.
+    Key,
+
+    /// A string value, including bytes like `b"89504e47"`.
+    String,
+
+    /// A number, including special values like `+inf`, and timestamps.
+    Number,
+
+    /// `true`, `false`, `null`, and `@include`.
+    Keyword,
+
+    /// `// A comment`
+    Comment,
+
+    /// Brackets, braces, parentheses, colons and commas.
+    Punctuation,
+
+    /// The name of a variant, like the `"Rgb"` in `"Rgb"(255, 0, 0)`.
+    VariantName,
+
+    /// Something that is not a valid token.
+    Invalid,
+}
+
+impl HighlightKind {
+    /// The ANSI escape code used by [`colorize`] for this kind, if any.
+    pub fn ansi_color(self) -> Option<&'static str> {
+        match self {
+            Self::Key => Some("\x1b[34;1m"),       // Bold blue
+            Self::String => Some("\x1b[32m"),      // Green
+            Self::Number => Some("\x1b[36m"),      // Cyan
+            Self::Keyword => Some("\x1b[35m"),     // Magenta
+            Self::Comment => Some("\x1b[90m"),     // Gray
+            Self::VariantName => Some("\x1b[33m"), // Yellow
+            Self::Invalid => Some("\x1b[31;4m"),   // Underlined red
+            Self::Punctuation => None,
+        }
+    }
+}
+
+/// The ANSI escape code that resets the color.
+const ANSI_RESET: &str = "\x1b[0m";
+
+/// Classify all the tokens in the source, for syntax highlighting.
+///
+/// This only looks at the tokens, so it works on documents with syntax errors too.
+/// The spans are in order, and never overlap. Whitespace is not included.
+///
+/// ## Example
+/// ```
+/// use eon_syntax::{HighlightKind, highlight};
+///
+/// let kinds: Vec<HighlightKind> = highlight("port: 8080").into_iter().map(|(_, kind)| kind).collect();
+/// assert_eq!(kinds, [HighlightKind::Key, HighlightKind::Punctuation, HighlightKind::Number]);
+/// ```
+pub fn highlight(source: &str) -> Vec<(Span, HighlightKind)> {
+    let tokens: Vec<(Span, Option<TokenKind>)> = TokenKind::lexer(source)
+        .spanned()
+        .map(|(kind, span)| {
+            let span = Span {
+                start: span.start,
+                end: span.end,
+            };
+            (span, kind.ok())
+        })
+        .collect();
+
+    // The next token that is not a comment:
+    let next_kind = |i: usize| {
+        tokens[i + 1..]
+            .iter()
+            .map(|(_, kind)| *kind)
+            .find(|kind| !kind.is_some_and(TokenKind::is_comment))
+            .flatten()
+    };
+
+    tokens
+        .iter()
+        .enumerate()
+        .map(|(i, &(span, kind))| {
+            let kind = match kind {
+                None => HighlightKind::Invalid,
+                Some(kind) => match kind {
+                    TokenKind::Comment | TokenKind::HashComment => HighlightKind::Comment,
+                    TokenKind::OpenList
+                    | TokenKind::CloseList
+                    | TokenKind::OpenBrace
+                    | TokenKind::CloseBrace
+                    | TokenKind::OpenParen
+                    | TokenKind::CloseParen
+                    | TokenKind::Colon
+                    | TokenKind::Comma => HighlightKind::Punctuation,
+                    TokenKind::Include => HighlightKind::Keyword,
+                    TokenKind::Number | TokenKind::Timestamp => HighlightKind::Number,
+                    TokenKind::Bytes => HighlightKind::String,
+                    TokenKind::Identifier
+                    | TokenKind::DoubleQuotedString
+                    | TokenKind::SingleQuotedString
+                    | TokenKind::MultilineBasicString
+                    | TokenKind::MultilineLiteralString => match next_kind(i) {
+                        Some(TokenKind::Colon) => HighlightKind::Key,
+                        Some(TokenKind::OpenParen) if kind != TokenKind::Identifier => {
+                            HighlightKind::VariantName
+                        }
+                        _ if kind == TokenKind::Identifier => HighlightKind::Keyword,
+                        _ => HighlightKind::String,
+                    },
+                },
+            };
+            (span, kind)
+        })
+        .collect()
+}
+
+/// Add ANSI color codes to an Eon source, for printing it to a terminal.
+///
+/// See [`HighlightKind::ansi_color`] for the colors used.
+/// Whitespace and punctuation are left uncolored.
+pub fn colorize(source: &str) -> String {
+    let mut colored = String::with_capacity(2 * source.len());
+    let mut end_of_previous = 0;
+    for (span, kind) in highlight(source) {
+        colored.push_str(&source[end_of_previous..span.start]);
+        let text = &source[span.start..span.end];
+        if let Some(color) = kind.ansi_color() {
+            colored.push_str(color);
+            colored.push_str(text);
+            colored.push_str(ANSI_RESET);
+        } else {
+            colored.push_str(text);
+        }
+        end_of_previous = span.end;
+    }
+    colored.push_str(&source[end_of_previous..]);
+    colored
+}
+
+#[test]
+fn test_colorize() {
+    assert_eq!(
+        colorize("a: [1, \"b\"] // c\n"),
+        "\x1b[34;1ma\x1b[0m: [\x1b[36m1\x1b[0m, \x1b[32m\"b\"\x1b[0m] \x1b[90m// c\x1b[0m\n"
+    );
+}
+
+#[test]
+fn test_highlight() {
+    let source = r#"
+// Comment
+@include "other.eon"
+key: true
+"quoted key": "Rgb"(255, 0, 0)
+list: [null, 'literal', b"ff", 2024-05-01T12:30:00Z, -1.5]
+bad: $
+"#;
+
+    let highlighted: Vec<(&str, HighlightKind)> = highlight(source)
+        .into_iter()
+        .map(|(span, kind)| (&source[span.start..span.end], kind))
+        .collect();
+
+    use HighlightKind::{Comment, Invalid, Key, Keyword, Number, Punctuation, String, VariantName};
+    assert_eq!(
+        highlighted,
+        [
+            ("// Comment", Comment),
+            ("@include", Keyword),
+            ("\"other.eon\"", String),
+            ("key", Key),
+            (":", Punctuation),
+            ("true", Keyword),
+            ("\"quoted key\"", Key),
+            (":", Punctuation),
+            ("\"Rgb\"", VariantName),
+            ("(", Punctuation),
+            ("255", Number),
+            (",", Punctuation),
+            ("0", Number),
+            (",", Punctuation),
+            ("0", Number),
+            (")", Punctuation),
+            ("list", Key),
+            (":", Punctuation),
+            ("[", Punctuation),
+            ("null", Keyword),
+            (",", Punctuation),
+            ("'literal'", String),
+            (",", Punctuation),
+            ("b\"ff\"", String),
+            (",", Punctuation),
+            ("2024-05-01T12:30:00Z", Number),
+            (",", Punctuation),
+            ("-1.5", Number),
+            ("]", Punctuation),
+            ("bad", Key),
+            (":", Punctuation),
+            ("$", Invalid),
+        ]
+    );
+}
@@ -28,26 +28,149 @@
 //! This can be useful for e.g. reading "docstrings" from an `.eon` file,
 //! or for automate the editing an `.eon` file while preserving comments and formatting.
 
+mod directive;
+mod edit;
 mod error;
 mod format;
+mod highlight;
 mod parse;
+mod parse_options;
+mod path;
+mod progress;
+mod reparse;
 mod span;
 mod strings;
+
+#[cfg(feature = "telemetry")]
+pub mod telemetry;
+
 mod token_kind;
 mod token_tree;
+mod validate;
 
 pub use crate::{
-    error::{Error, Result},
-    format::FormatOptions,
+    directive::Directive,
+    edit::Placement,
+    error::{
+        Diagnostic, Error, ErrorKind, Label, RenderOptions, Result, Severity, Snippet,
+        UNNAMED_SOURCE,
+    },
+    format::{
+        FormatOptions, HashComments, Layout, MultilineSeparator, NumberLiterals, SortKeys,
+        TAB_WIDTH, TrailingSeparator,
+    },
+    highlight::{HighlightKind, colorize, highlight},
+    parse::{PlacedToken, Tokens, tokenize},
+    parse_options::{
+        ControlCharacterPolicy, DuplicateKeyPolicy, EmptyDocumentPolicy, ParseOptions, TopLevel,
+    },
+    path::{KeyPath, PathSegment},
+    progress::ParseProgress,
+    reparse::TextEdit,
     span::Span,
-    strings::{escape_and_quote, is_valid_identifier, unescape_and_unquote},
-    token_tree::{TokenKeyValue, TokenList, TokenMap, TokenTree, TokenValue, TokenVariant},
+    strings::{
+        escape_and_quote, escape_and_quote_literal, is_valid_identifier, quote_bytes,
+        unescape_and_unquote, unescape_and_unquote_cow, unquote_bytes,
+    },
+    token_kind::TokenKind,
+    token_tree::{
+        Comment, TokenInclude, TokenKeyValue, TokenList, TokenMap, TokenTree, TokenValue,
+        TokenVariant, is_doc_comment,
+    },
+    validate::validate,
+};
+
+// Parsed documents and errors can be shared freely between threads:
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Error>();
+    assert_send_sync::<FormatOptions>();
+    assert_send_sync::<KeyPath>();
+    assert_send_sync::<ParseOptions>();
+    assert_send_sync::<ParseProgress>();
+    assert_send_sync::<TokenTree<'static>>();
 };
 
 /// Parses an Eon file and re-indents and formats it in a pretty way.
 ///
 /// ## Errors
-/// Returns an error if the source is not valid Eon syntax.
+/// Returns an error if the source is not valid Eon syntax,
+/// or if the [`FormatOptions`] are invalid (see [`FormatOptions::validate`]).
 pub fn reformat(eon_source: &str, options: &FormatOptions) -> Result<String> {
+    options.validate()?;
     TokenTree::parse_str(eon_source).map(|value| value.format(options))
 }
+
+/// Parse a single `key: value` pair, like `port: 8080`.
+///
+/// This is useful for reading e.g. an override from an environment variable or a command line flag.
+/// Comments are allowed, and are attached to the key and value like in a full document.
+///
+/// ## Example
+/// ```
+/// let kv = eon_syntax::parse_key_value("port: 8080 // The default").unwrap();
+/// assert_eq!(kv.key.value.as_key_str().unwrap(), "port");
+/// assert!(matches!(&kv.value.value, eon_syntax::TokenValue::Number(number) if number == "8080"));
+/// assert_eq!(kv.value.suffix_comment.as_deref(), Some("// The default"));
+/// ```
+///
+/// ## Errors
+/// If the line is not exactly one `key: value` pair.
+pub fn parse_key_value(line: &str) -> Result<TokenKeyValue<'_>> {
+    let options = ParseOptions::new().with_top_level(TopLevel::Map);
+    let tree = parse::parse_top_str_as(line, &options)?;
+    let TokenValue::Map(map) = tree.value else {
+        return Err(Error::new(line, tree.span, "Expected a key-value pair"));
+    };
+    if let Some(include) = map.includes.first() {
+        return Err(Error::new(
+            line,
+            include.span,
+            "Expected a key-value pair, not an @include",
+        ));
+    }
+    let mut key_values = map.key_values.into_iter();
+    match (key_values.next(), key_values.next()) {
+        (Some(kv), None) => Ok(kv),
+        (None, _) => Err(Error::new_at(
+            line,
+            Span {
+                start: 0,
+                end: line.len(),
+            },
+            "Expected a key-value pair",
+        )),
+        (Some(_), Some(second)) => Err(Error::new(
+            line,
+            second.key.span,
+            "Expected a single key-value pair",
+        )),
+    }
+}
+
+#[test]
+fn test_parse_key_value() {
+    let kv = parse_key_value("  \"quoted key\": [1, 2]  ").unwrap();
+    assert_eq!(kv.key.value.as_key_str().unwrap(), "quoted key");
+    assert_eq!(kv.value.format(&FormatOptions::default()), "[1, 2]");
+
+    assert_eq!(
+        parse_key_value("").unwrap_err().message(),
+        "Expected a key-value pair"
+    );
+    assert_eq!(
+        parse_key_value("a: 1, b: 2").unwrap_err().message(),
+        "Expected a single key-value pair"
+    );
+    assert_eq!(
+        parse_key_value("{ a: 1 }")
+            .unwrap()
+            .key
+            .value
+            .as_key_str()
+            .unwrap(),
+        "a"
+    );
+    assert!(parse_key_value("42").is_err());
+    assert!(parse_key_value("a: ").is_err());
+}
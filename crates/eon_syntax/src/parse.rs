@@ -2,13 +2,20 @@
 
 use crate::{
     error::{Error, Result},
+    parse_options::{ParseOptions, TopLevel},
+    progress::{PROGRESS_INTERVAL, ParseProgress},
     span::Span,
     token_kind::TokenKind,
-    token_tree::{TokenKeyValue, TokenList, TokenMap, TokenTree, TokenValue, TokenVariant},
+    token_tree::{
+        Comment, TokenInclude, TokenKeyValue, TokenList, TokenMap, TokenTree, TokenValue,
+        TokenVariant, is_doc_comment,
+    },
 };
 
 /// Protect against stack overflow in our recursive descent parser.
-const MAX_RECURSION_DEPTH: usize = 128;
+///
+/// Each level of nesting is two levels of recursion.
+pub(crate) const MAX_RECURSION_DEPTH: usize = 128;
 
 #[derive(Clone, Copy, Debug)]
 pub struct PlacedToken<'s> {
@@ -49,18 +56,33 @@ impl<'s> PlacedTokenResult<'s> {
 
 pub struct PlacedTokenIter<'s> {
     iter: logos::SpannedIter<'s, TokenKind>,
+
+    /// Read `;` as `,`. See [`ParseOptions::legacy_separators`].
+    legacy_separators: bool,
+
+    /// Skip all comments. See [`ParseOptions::keep_comments`].
+    skip_comments: bool,
 }
 
 impl<'s> Iterator for PlacedTokenIter<'s> {
     type Item = PlacedTokenResult<'s>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let (result, span) = self.iter.next()?;
+        let (result, span) = loop {
+            let (result, span) = self.iter.next()?;
+            if !(self.skip_comments && result.is_ok_and(TokenKind::is_comment)) {
+                break (result, span);
+            }
+        };
         let span = Span {
             start: span.start,
             end: span.end,
         };
         let slice = self.iter.slice();
+        let result = match (result, slice) {
+            (Err(_), ";") if self.legacy_separators => Ok(TokenKind::Comma),
+            (result, _) => result,
+        };
         if let Ok(token) = result {
             Some(PlacedTokenResult {
                 span,
@@ -81,6 +103,35 @@ impl<'s> Iterator for PlacedTokenIter<'s> {
     }
 }
 
+/// The tokens of an Eon source, including comments.
+///
+/// Created with [`tokenize`].
+pub struct Tokens<'s> {
+    iter: PlacedTokenIter<'s>,
+}
+
+impl<'s> Iterator for Tokens<'s> {
+    type Item = Result<PlacedToken<'s>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(PlacedTokenResult::ok)
+    }
+}
+
+/// Split an Eon source into tokens, without building a [`TokenTree`].
+///
+/// This is the lowest level of parsing, useful for e.g. streaming deserializers and syntax highlighters.
+pub fn tokenize(source: &str) -> Tokens<'_> {
+    use logos::Logos as _;
+    Tokens {
+        iter: PlacedTokenIter {
+            iter: TokenKind::lexer(source).spanned(),
+            legacy_separators: false,
+            skip_comments: false,
+        },
+    }
+}
+
 pub struct PeekableIter<'s> {
     source: &'s str,
     iter: PlacedTokenIter<'s>,
@@ -90,6 +141,24 @@ pub struct PeekableIter<'s> {
     peeked: Option<Option<PlacedTokenResult<'s>>>,
 
     last_span: Span,
+
+    /// Number of tokens returned by [`Self::next()`] so far.
+    num_consumed: usize,
+
+    /// If set, we are recovering from errors, and collecting them here.
+    recovered_errors: Option<Vec<Error>>,
+
+    /// If set, report how far we've come here.
+    progress: Option<ParseProgress>,
+
+    /// Have we consumed the `:` of a `key: value` pair in the top-level map?
+    saw_top_level_colon: bool,
+
+    /// When to next report to [`Self::progress`].
+    next_progress_report: usize,
+
+    /// Give up when recursing this deep. See [`ParseOptions::max_depth`].
+    max_recursion_depth: usize,
 }
 
 impl<'s> PeekableIter<'s> {
@@ -99,9 +168,78 @@ impl<'s> PeekableIter<'s> {
             source,
             iter: PlacedTokenIter {
                 iter: TokenKind::lexer(source).spanned(),
+                legacy_separators: false,
+                skip_comments: false,
             },
             peeked: None,
             last_span: Span { start: 0, end: 0 },
+            num_consumed: 0,
+            recovered_errors: None,
+            progress: None,
+            next_progress_report: 0,
+            saw_top_level_colon: false,
+            max_recursion_depth: MAX_RECURSION_DEPTH,
+        }
+    }
+
+    /// Start lexing at the given byte offset, instead of at the start of the source.
+    fn starting_at(mut self, offset: usize) -> Self {
+        use logos::Logos as _;
+        let mut lexer = TokenKind::lexer(self.source);
+        lexer.bump(offset);
+        self.iter = PlacedTokenIter {
+            iter: lexer.spanned(),
+            legacy_separators: self.iter.legacy_separators,
+            skip_comments: self.iter.skip_comments,
+        };
+        self.last_span = Span {
+            start: offset,
+            end: offset,
+        };
+        self
+    }
+
+    /// Read `;` as `,`. See [`ParseOptions::legacy_separators`].
+    fn with_legacy_separators(mut self, legacy_separators: bool) -> Self {
+        self.iter.legacy_separators = legacy_separators;
+        self
+    }
+
+    /// Skip all comments. See [`ParseOptions::keep_comments`].
+    fn with_keep_comments(mut self, keep_comments: bool) -> Self {
+        self.iter.skip_comments = !keep_comments;
+        self
+    }
+
+    /// See [`ParseOptions::max_depth`].
+    fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_recursion_depth = 2 * max_depth;
+        self
+    }
+
+    /// Report how far we've come to the given [`ParseProgress`].
+    fn with_progress(mut self, progress: Option<&ParseProgress>) -> Self {
+        self.progress = progress.cloned();
+        self
+    }
+
+    /// Keep parsing after errors, collecting them instead of returning them.
+    fn recovering(mut self) -> Self {
+        self.recovered_errors = Some(vec![]);
+        self
+    }
+
+    fn is_recovering(&self) -> bool {
+        self.recovered_errors.is_some()
+    }
+
+    /// When recovering, remember the error and return `Ok`. Otherwise return the error.
+    fn recover(&mut self, error: Error) -> Result {
+        if let Some(errors) = &mut self.recovered_errors {
+            errors.push(error);
+            Ok(())
+        } else {
+            Err(error)
         }
     }
 
@@ -147,6 +285,14 @@ impl<'s> Iterator for PeekableIter<'s> {
         };
         if let Some(next) = &next {
             self.last_span = next.span;
+            self.num_consumed += 1;
+
+            if let Some(progress) = &self.progress {
+                if self.next_progress_report <= next.span.end {
+                    progress.report(next.span.end);
+                    self.next_progress_report = next.span.end + PROGRESS_INTERVAL;
+                }
+            }
         }
         next
     }
@@ -157,65 +303,63 @@ impl<'s> TokenTree<'s> {
     pub fn parse_str(source: &'s str) -> Result<Self> {
         parse_top_str(source)
     }
+
+    /// Like [`Self::parse_str`], but reports how far it has come to the given [`ParseProgress`].
+    ///
+    /// This is useful for showing a progress bar when loading huge files.
+    pub fn parse_str_with_progress(source: &'s str, progress: &ParseProgress) -> Result<Self> {
+        progress.start(source.len());
+        let result = parse_top_str_with_progress(source, Some(progress));
+        progress.report(source.len());
+        result
+    }
+
+    /// Parse a full Eon file, but keep going after syntax errors, and return all of them.
+    ///
+    /// After an error, the parser skips ahead to the next line, comma, or closing bracket,
+    /// and continues from there. The returned tree contains everything that could be parsed,
+    /// and is `None` only if the document could not be parsed at all.
+    ///
+    /// If there are no errors, this is the same as [`Self::parse_str`].
+    ///
+    /// This is useful for editors and linters that want to show every problem in a file at once.
+    pub fn parse_str_recovering(source: &'s str) -> (Option<Self>, Vec<Error>) {
+        parse_top_str_recovering(source)
+    }
 }
 
 /// Parse a full Eon file.
 fn parse_top_str(eon_source: &str) -> Result<TokenTree<'_>> {
+    parse_top_str_with_progress(eon_source, None)
+}
+
+fn parse_top_str_with_progress<'s>(
+    eon_source: &'s str,
+    progress: Option<&ParseProgress>,
+) -> Result<TokenTree<'s>> {
+    parse_top_auto(|| PeekableIter::new(eon_source).with_progress(progress))
+}
+
+/// Parse a full Eon file as a map, or failing that, as a value or list of values.
+///
+/// `new_tokens` is called once per attempt.
+fn parse_top_auto<'s>(new_tokens: impl Fn() -> PeekableIter<'s>) -> Result<TokenTree<'s>> {
     // Usually an Eon file contains a bunch of `key: value` pairs, without any
     // surrounding braces, so we optimize for that case:
-    let mut tokens_a = PeekableIter::new(eon_source);
-    match parse_map_contents(&mut tokens_a, 0) {
-        Ok(map) => {
-            check_for_trailing_tokens(&mut tokens_a)?;
-            let value = TokenTree {
-                span: Some(Span {
-                    start: 0,
-                    end: eon_source.len(),
-                }),
-                prefix_comments: vec![],
-                value: TokenValue::Map(map),
-                suffix_comment: None,
-            };
-            Ok(value)
-        }
+    let mut tokens_a = new_tokens();
+    match parse_top_map(&mut tokens_a) {
+        Ok(tree) => Ok(tree),
         Err(err_a) => {
             // Maybe the use did wrap the file in {}, or maybe it is not an map?
-            let mut tokens_b = PeekableIter::new(eon_source);
-
-            match parse_list_contents(&mut tokens_b, 0) {
-                Ok(list) => {
-                    check_for_trailing_tokens(&mut tokens_b)?;
+            let mut tokens_b = new_tokens();
 
-                    let TokenList {
-                        values,
-                        closing_comments,
-                    } = list;
-
-                    if values.len() == 1 {
-                        // A file containing a single value, e.g. `42` or `{…}`,
-                        Ok(values.into_iter().next().expect("Can't fail"))
-                    } else {
-                        // A file containing many values, e.g. `1, 2, 3` or `{…}, {…}`,
-                        Ok(TokenTree {
-                            span: Some(Span {
-                                start: 0,
-                                end: eon_source.len(),
-                            }),
-                            prefix_comments: Default::default(),
-                            value: TokenValue::List(TokenList {
-                                values,
-                                closing_comments,
-                            }),
-                            suffix_comment: Default::default(),
-                        })
-                    }
-                }
+            match parse_top_list(&mut tokens_b) {
+                Ok(tree) => Ok(tree),
                 Err(err_b) => {
-                    // Return the error of the path that processed the most tokens, i.e. got further:
-                    if tokens_a.span_of_previous().end < tokens_b.span_of_previous().end {
-                        Err(err_b)
+                    if prefer_map_error(&tokens_a, &tokens_b) {
+                        Err(Error::with_alternative(err_a, err_b))
                     } else {
-                        Err(err_a)
+                        Err(Error::with_alternative(err_b, err_a))
                     }
                 }
             }
@@ -223,6 +367,202 @@ fn parse_top_str(eon_source: &str) -> Result<TokenTree<'_>> {
     }
 }
 
+/// When parsing the top level both as a map (`tokens_a`) and as a list (`tokens_b`) failed,
+/// should we report the error of the map?
+///
+/// Once a top-level `key:` has been seen, the document is clearly meant to be a map.
+/// Otherwise, pick the attempt that consumed the most tokens, preferring the map on a tie.
+fn prefer_map_error(tokens_a: &PeekableIter<'_>, tokens_b: &PeekableIter<'_>) -> bool {
+    tokens_a.saw_top_level_colon || tokens_b.num_consumed <= tokens_a.num_consumed
+}
+
+/// Parse a full Eon file, with the top level and separators of the given [`ParseOptions`].
+pub(crate) fn parse_top_str_as<'s>(
+    eon_source: &'s str,
+    options: &ParseOptions,
+) -> Result<TokenTree<'s>> {
+    let new_tokens = || {
+        PeekableIter::new(eon_source)
+            .with_legacy_separators(options.legacy_separators)
+            .with_keep_comments(options.keep_comments)
+            .with_max_depth(options.max_depth)
+    };
+    let mut tokens = new_tokens();
+    match options.top_level {
+        TopLevel::Auto => parse_top_auto(new_tokens),
+        TopLevel::Map => {
+            let starts_with_brace = tokens
+                .find(|token| !matches!(token.kind, Ok(kind) if kind.is_comment()))
+                .is_some_and(|token| matches!(token.kind, Ok(TokenKind::OpenBrace)));
+            let mut tokens = new_tokens();
+            if starts_with_brace {
+                let tree = parse_top_list(&mut tokens)?;
+                if matches!(tree.value, TokenValue::Map(_)) {
+                    Ok(tree)
+                } else {
+                    Err(Error::new(eon_source, tree.span, "Expected a map"))
+                }
+            } else {
+                parse_top_map(&mut tokens)
+            }
+        }
+        TopLevel::Value => {
+            let list = parse_list_contents(&mut tokens, 0)?;
+            check_for_trailing_tokens(&mut tokens)?;
+            let mut values = list.values.into_iter();
+            match (values.next(), values.next()) {
+                (Some(value), None) => Ok(value),
+                (_, second) => Err(tokens.error_at(
+                    second
+                        .and_then(|value| value.span)
+                        .unwrap_or(tokens.end_span()),
+                    "Expected a single value",
+                )),
+            }
+        }
+        TopLevel::List => {
+            let tree = parse_top_list(&mut tokens)?;
+            if matches!(tree.value, TokenValue::List(_)) {
+                Ok(tree)
+            } else {
+                Err(Error::new(eon_source, tree.span, "Expected a list"))
+            }
+        }
+    }
+}
+
+/// Like [`parse_top_str`], but keeps going after errors.
+fn parse_top_str_recovering(eon_source: &str) -> (Option<TokenTree<'_>>, Vec<Error>) {
+    // First find out if this is a map or list document, by seeing which gets further:
+    let mut tokens_a = PeekableIter::new(eon_source);
+    let err_a = match parse_top_map(&mut tokens_a) {
+        Ok(tree) => return (Some(tree), vec![]),
+        Err(err) => err,
+    };
+    let mut tokens_b = PeekableIter::new(eon_source);
+    let err_b = match parse_top_list(&mut tokens_b) {
+        Ok(tree) => return (Some(tree), vec![]),
+        Err(err) => err,
+    };
+    let is_list = !prefer_map_error(&tokens_a, &tokens_b);
+
+    let mut tokens = PeekableIter::new(eon_source).recovering();
+    let result = if is_list {
+        parse_top_list(&mut tokens)
+    } else {
+        parse_top_map(&mut tokens)
+    };
+    let mut errors = tokens.recovered_errors.take().unwrap_or_default();
+
+    match result {
+        Ok(tree) if !errors.is_empty() => (Some(tree), errors),
+        Ok(_) => (None, vec![if is_list { err_b } else { err_a }]),
+        Err(err) => {
+            errors.push(err);
+            (None, errors)
+        }
+    }
+}
+
+/// Parse a file containing `key: value` pairs, optionally wrapped in `{}`.
+fn parse_top_map<'s>(tokens: &mut PeekableIter<'s>) -> Result<TokenTree<'s>> {
+    let mut map = parse_map_contents(tokens, 0)?;
+
+    if tokens.is_recovering() {
+        // Skip any unbalanced closing brackets, and keep going:
+        while let Some(token) = tokens.next() {
+            tokens.recover(Error::new_at(
+                tokens.source,
+                token.span,
+                "Unbalanced brackets",
+            ))?;
+            let TokenMap {
+                includes,
+                key_values,
+                closing_comments,
+                trailing_comma,
+            } = parse_map_contents(tokens, 0)?;
+            map.includes.extend(includes);
+            map.key_values.extend(key_values);
+            map.closing_comments = closing_comments;
+            map.trailing_comma = trailing_comma;
+        }
+    } else {
+        check_for_trailing_tokens(tokens)?;
+    }
+
+    Ok(TokenTree {
+        span: Some(Span {
+            start: 0,
+            end: tokens.source.len(),
+        }),
+        prefix_comments: vec![],
+        doc_comments: vec![],
+        value: TokenValue::Map(map),
+        suffix_comment: None,
+    })
+}
+
+/// Parse a file containing a single value, or a comma-separated list of values.
+fn parse_top_list<'s>(tokens: &mut PeekableIter<'s>) -> Result<TokenTree<'s>> {
+    let list = parse_list_contents(tokens, 0)?;
+    check_for_trailing_tokens(tokens)?;
+
+    let TokenList {
+        values,
+        closing_comments,
+        trailing_comma,
+    } = list;
+
+    if values.len() == 1 {
+        // A file containing a single value, e.g. `42` or `{…}`,
+        Ok(values.into_iter().next().expect("Can't fail"))
+    } else {
+        // A file containing many values, e.g. `1, 2, 3` or `{…}, {…}`,
+        Ok(TokenTree {
+            span: Some(Span {
+                start: 0,
+                end: tokens.source.len(),
+            }),
+            prefix_comments: Default::default(),
+            doc_comments: Default::default(),
+            value: TokenValue::List(TokenList {
+                values,
+                closing_comments,
+                trailing_comma,
+            }),
+            suffix_comment: Default::default(),
+        })
+    }
+}
+
+/// Parse the single value in `source[range]`, without any surrounding comments.
+///
+/// The spans are relative to the whole `source`.
+/// Used for incremental reparsing.
+pub(crate) fn parse_value_at(
+    source: &str,
+    range: std::ops::Range<usize>,
+    recurse_depth: usize,
+) -> Result<TokenTree<'_>> {
+    if range.end < range.start
+        || !source.is_char_boundary(range.start)
+        || !source.is_char_boundary(range.end)
+    {
+        return Err(Error::custom("Invalid range"));
+    }
+
+    let mut tokens = PeekableIter::new(&source[..range.end]).starting_at(range.start);
+    let tree = parse_token_tree(&mut tokens, recurse_depth)?;
+    check_for_trailing_tokens(&mut tokens)?;
+
+    if tree.prefix_comments.is_empty() && tree.suffix_comment.is_none() {
+        Ok(tree)
+    } else {
+        Err(tokens.error_at(tokens.span_of_previous(), "Expected just a value"))
+    }
+}
+
 fn check_for_trailing_tokens(tokens: &mut PeekableIter<'_>) -> Result {
     if let Some(token) = tokens.next() {
         let token = token.ok()?;
@@ -238,6 +578,7 @@ fn parse_list_contents<'s>(
     recurse_depth: usize,
 ) -> Result<TokenList<'s>> {
     let mut values = vec![];
+    let mut trailing_comma = false;
 
     loop {
         let prefix_comments = parse_comments(tokens);
@@ -251,27 +592,56 @@ fn parse_list_contents<'s>(
             return Ok(TokenList {
                 values,
                 closing_comments: prefix_comments,
+                trailing_comma,
             });
         }
 
-        let mut value = parse_token_tree(tokens, recurse_depth + 1)?;
-
-        {
-            let mut prefix_comments = prefix_comments;
-            prefix_comments.append(&mut value.prefix_comments);
-            value.prefix_comments = prefix_comments;
+        let num_consumed = tokens.num_consumed;
+        match parse_list_value(tokens, prefix_comments, recurse_depth) {
+            Ok((value, comma)) => {
+                values.push(value);
+                trailing_comma = comma;
+            }
+            Err(err) => {
+                tokens.recover(err)?;
+                skip_to_next_entry(tokens, num_consumed);
+                trailing_comma = false;
+            }
         }
+    }
+}
 
-        if tokens
-            .peek()
-            .is_some_and(|peeked| matches!(peeked.kind, Ok(TokenKind::Comma)))
-        {
-            // Consume optional comma
-            tokens.next();
-            value.suffix_comment = parse_suffix_comment(tokens)?;
-        }
+/// Parse a value in a list, and the comma after it (if any).
+///
+/// Also returns whether there was a comma.
+fn parse_list_value<'s>(
+    tokens: &mut PeekableIter<'s>,
+    mut prefix_comments: Vec<Comment<'s>>,
+    recurse_depth: usize,
+) -> Result<(TokenTree<'s>, bool)> {
+    let mut value = parse_token_tree(tokens, recurse_depth + 1)?;
 
-        values.push(value);
+    prefix_comments.append(&mut value.prefix_comments);
+    value.prefix_comments = prefix_comments;
+
+    let comma = parse_optional_comma(tokens, &mut value)?;
+    Ok((value, comma))
+}
+
+/// Consume an optional comma after a value, and any suffix comment after it.
+fn parse_optional_comma<'s>(
+    tokens: &mut PeekableIter<'s>,
+    value: &mut TokenTree<'s>,
+) -> Result<bool> {
+    if tokens
+        .peek()
+        .is_some_and(|peeked| matches!(peeked.kind, Ok(TokenKind::Comma)))
+    {
+        tokens.next();
+        value.suffix_comment = parse_suffix_comment(tokens)?;
+        Ok(true)
+    } else {
+        Ok(false)
     }
 }
 
@@ -280,7 +650,9 @@ fn parse_map_contents<'s>(
     tokens: &mut PeekableIter<'s>,
     recurse_depth: usize,
 ) -> Result<TokenMap<'s>> {
+    let mut includes = vec![];
     let mut key_values = vec![];
+    let mut trailing_comma = false;
 
     loop {
         let prefix_comments = parse_comments(tokens);
@@ -292,41 +664,169 @@ fn parse_map_contents<'s>(
             )
         }) {
             return Ok(TokenMap {
+                includes,
                 key_values,
                 closing_comments: prefix_comments,
+                trailing_comma,
             });
         }
 
-        let mut key = parse_token_tree(tokens, recurse_depth + 1)?;
-        debug_assert!(
-            key.prefix_comments.is_empty(),
-            "We should have already consumed these"
-        );
-        key.prefix_comments = prefix_comments;
-
-        consume_token(tokens, TokenKind::Colon)?;
-
-        let mut value = parse_token_tree(tokens, recurse_depth + 1)?;
-
         if tokens
             .peek()
-            .is_some_and(|peeked| matches!(peeked.kind, Ok(TokenKind::Comma)))
+            .is_some_and(|peeked| matches!(peeked.kind, Ok(TokenKind::Include)))
         {
-            // Consume optional comma
-            tokens.next();
-            value.suffix_comment = parse_suffix_comment(tokens)?;
+            let num_consumed = tokens.num_consumed;
+            let include_span = tokens.span_of_next();
+            tokens.next(); // Consume `@include`
+            let include = if key_values.is_empty() {
+                parse_include(tokens, include_span, prefix_comments)
+            } else {
+                Err(tokens.error_at(
+                    include_span,
+                    "@include must come before any key-value pairs in a map",
+                ))
+            };
+            match include {
+                Ok((include, comma)) => {
+                    includes.push(include);
+                    trailing_comma = comma;
+                }
+                Err(err) => {
+                    tokens.recover(err)?;
+                    skip_to_next_entry(tokens, num_consumed);
+                    trailing_comma = false;
+                }
+            }
+            continue;
+        }
+
+        let num_consumed = tokens.num_consumed;
+        match parse_key_value(tokens, prefix_comments, recurse_depth) {
+            Ok((key_value, comma)) => {
+                key_values.push(key_value);
+                trailing_comma = comma;
+            }
+            Err(err) => {
+                tokens.recover(err)?;
+                skip_to_next_entry(tokens, num_consumed);
+                trailing_comma = false;
+            }
+        }
+    }
+}
+
+/// Parse a `key: value` pair in a map, and the comma after it (if any).
+///
+/// Also returns whether there was a comma.
+fn parse_key_value<'s>(
+    tokens: &mut PeekableIter<'s>,
+    prefix_comments: Vec<Comment<'s>>,
+    recurse_depth: usize,
+) -> Result<(TokenKeyValue<'s>, bool)> {
+    let mut key = parse_token_tree(tokens, recurse_depth + 1)?;
+    debug_assert!(
+        key.prefix_comments.is_empty(),
+        "We should have already consumed these"
+    );
+    (key.prefix_comments, key.doc_comments) = split_doc_comments(prefix_comments);
+
+    consume_token(tokens, TokenKind::Colon)?;
+    if recurse_depth == 0 {
+        tokens.saw_top_level_colon = true;
+    }
+
+    let mut value = parse_token_tree(tokens, recurse_depth + 1)?;
+    let comma = parse_optional_comma(tokens, &mut value)?;
+
+    Ok((TokenKeyValue { key, value }, comma))
+}
+
+/// After an error in an entry of a map or list, skip ahead to what looks like the start of the next entry:
+/// the next line, the next comma, or the closing bracket.
+///
+/// Always skips at least one token (unless at a closing bracket),
+/// so that we don't get stuck on the same error.
+fn skip_to_next_entry(tokens: &mut PeekableIter<'_>, num_consumed_before_error: usize) {
+    let mut depth = 0_usize;
+    loop {
+        let made_progress = num_consumed_before_error < tokens.num_consumed;
+        let Some(peeked) = tokens.peek() else {
+            return;
+        };
+        let kind = peeked.kind.as_ref().ok().copied();
+        let span = peeked.span;
+
+        let is_closing = matches!(
+            kind,
+            Some(TokenKind::CloseBrace | TokenKind::CloseList | TokenKind::CloseParen)
+        );
+        if depth == 0 && is_closing {
+            return; // Let the caller handle it
+        }
+        let on_new_line = tokens.source[tokens.span_of_previous().end..span.start].contains('\n');
+        if depth == 0 && made_progress && on_new_line {
+            return;
         }
 
-        key_values.push(TokenKeyValue { key, value });
+        tokens.next();
+
+        match kind {
+            Some(TokenKind::OpenBrace | TokenKind::OpenList | TokenKind::OpenParen) => depth += 1,
+            _ if is_closing => depth -= 1,
+            Some(TokenKind::Comma) if depth == 0 => return,
+            _ => {}
+        }
     }
 }
 
+/// Parse the path of an `@include` directive, after the `@include` token has been consumed.
+///
+/// Also returns whether there was a comma after it.
+fn parse_include<'s>(
+    tokens: &mut PeekableIter<'s>,
+    include_span: Span,
+    prefix_comments: Vec<Comment<'s>>,
+) -> Result<(TokenInclude<'s>, bool)> {
+    let Some(token) = tokens.next() else {
+        return Err(tokens.error_at(
+            include_span,
+            "Expected a quoted path after @include, like `@include \"other.eon\"`",
+        ));
+    };
+    let token = token.ok()?;
+    if !matches!(
+        token.kind,
+        TokenKind::DoubleQuotedString | TokenKind::SingleQuotedString
+    ) {
+        return Err(tokens.error_at(
+            token.span,
+            "Expected a quoted path after @include, like `@include \"other.eon\"`",
+        ));
+    }
+
+    let comma = tokens
+        .peek()
+        .is_some_and(|peeked| matches!(peeked.kind, Ok(TokenKind::Comma)));
+    if comma {
+        // Consume optional comma
+        tokens.next();
+    }
+
+    let include = TokenInclude {
+        span: Some(include_span | token.span),
+        prefix_comments,
+        quoted_path: token.slice.into(),
+        suffix_comment: parse_suffix_comment(tokens)?,
+    };
+    Ok((include, comma))
+}
+
 /// Parse a value, including prefix and suffix comments.
 fn parse_token_tree<'s>(
     tokens: &mut PeekableIter<'s>,
     recurse_depth: usize,
 ) -> Result<TokenTree<'s>> {
-    if recurse_depth >= MAX_RECURSION_DEPTH {
+    if recurse_depth >= tokens.max_recursion_depth {
         return Err(tokens.error_at(
             tokens.span_of_previous(),
             "Maximum recursion depth exceeded while parsing document",
@@ -359,6 +859,8 @@ fn parse_token_tree<'s>(
         }
         TokenKind::Identifier => TokenValue::Identifier(token.slice.into()),
         TokenKind::Number => TokenValue::Number(token.slice.into()),
+        TokenKind::Timestamp => TokenValue::Timestamp(token.slice.into()),
+        TokenKind::Bytes => TokenValue::Bytes(token.slice.into()),
         TokenKind::DoubleQuotedString
         | TokenKind::SingleQuotedString
         | TokenKind::MultilineBasicString
@@ -375,6 +877,7 @@ fn parse_token_tree<'s>(
                 let TokenList {
                     values,
                     closing_comments,
+                    trailing_comma,
                 } = parse_list_contents(tokens, recurse_depth + 1)?;
 
                 consume_token(tokens, TokenKind::CloseParen)?;
@@ -384,19 +887,28 @@ fn parse_token_tree<'s>(
                     quoted_name: token.slice.into(),
                     values,
                     closing_comments,
+                    trailing_comma,
                 })
             } else {
                 // Just a string, not a variant
                 TokenValue::QuotedString(token.slice.into())
             }
         }
-        TokenKind::Comment => unreachable!("We should have already consumed comments"),
+        TokenKind::Comment | TokenKind::HashComment => {
+            unreachable!("We should have already consumed comments")
+        }
         TokenKind::CloseList => Err(tokens.error_at(token.span, "Unbalanced brackets"))?,
         TokenKind::CloseBrace => Err(tokens.error_at(token.span, "Unbalanced braces"))?,
         TokenKind::CloseParen => Err(tokens.error_at(token.span, "Unbalanced parentheses"))?,
         TokenKind::OpenParen => {
             Err(tokens.error_at(token.span, "Parentheses must be proceeded by a string"))?
         }
+        TokenKind::Include => {
+            return Err(tokens.error_at(
+                token.span,
+                "@include is only allowed inside a map, before any key-value pairs",
+            ));
+        }
         TokenKind::Colon | TokenKind::Comma => {
             return Err(tokens.error_at(
                 token.span,
@@ -412,12 +924,40 @@ fn parse_token_tree<'s>(
     Ok(TokenTree {
         span: Some(span),
         prefix_comments,
+        doc_comments: vec![],
         value,
         suffix_comment,
     })
 }
 
+/// Split off the trailing `///` doc comments.
+fn split_doc_comments(mut comments: Vec<Comment<'_>>) -> (Vec<Comment<'_>>, Vec<Comment<'_>>) {
+    let num_docs = comments
+        .iter()
+        .rev()
+        .take_while(|comment| is_doc_comment(comment))
+        .count();
+    let doc_comments = comments.split_off(comments.len() - num_docs);
+    (comments, doc_comments)
+}
+
 fn consume_token(tokens: &mut PeekableIter<'_>, expected_token: TokenKind) -> Result {
+    if tokens.is_recovering() {
+        if let Some(&PlacedTokenResult {
+            span,
+            kind: Ok(kind @ (TokenKind::CloseBrace | TokenKind::CloseList | TokenKind::CloseParen)),
+            ..
+        }) = tokens.peek()
+        {
+            if kind != expected_token {
+                // Leave the closing bracket for whoever opened it:
+                return Err(
+                    tokens.error_at(span, format!("Expected {expected_token} but found {kind}"))
+                );
+            }
+        }
+    }
+
     if let Some(token) = tokens.next() {
         let token = token.ok()?;
         if token.kind == expected_token {
@@ -436,12 +976,12 @@ fn consume_token(tokens: &mut PeekableIter<'_>, expected_token: TokenKind) -> Re
     }
 }
 
-fn parse_suffix_comment<'s>(tokens: &mut PeekableIter<'s>) -> Result<Option<&'s str>> {
+fn parse_suffix_comment<'s>(tokens: &mut PeekableIter<'s>) -> Result<Option<Comment<'s>>> {
     let previous_token_span = tokens.span_of_previous();
     let Some(token) = tokens.peek() else {
         return Ok(None);
     };
-    if !matches!(token.kind, Ok(TokenKind::Comment)) {
+    if !matches!(token.kind, Ok(kind) if kind.is_comment()) {
         return Ok(None);
     }
     let comment_span = token.span;
@@ -455,23 +995,19 @@ fn parse_suffix_comment<'s>(tokens: &mut PeekableIter<'s>) -> Result<Option<&'s
 
         if let Some(token) = tokens.next() {
             let token = token.ok()?;
-            debug_assert_eq!(
-                token.kind,
-                TokenKind::Comment,
-                "Bug in parse_suffix_comment"
-            );
-            Ok(Some(token.slice))
+            debug_assert!(token.kind.is_comment(), "Bug in parse_suffix_comment");
+            Ok(Some(token.slice.into()))
         } else {
             Ok(None) // shouldn't be possible
         }
     }
 }
 
-fn parse_comments<'s>(tokens: &mut PeekableIter<'s>) -> Vec<&'s str> {
+fn parse_comments<'s>(tokens: &mut PeekableIter<'s>) -> Vec<Comment<'s>> {
     let mut comments = vec![];
     while let Some(token) = tokens.peek() {
-        if matches!(token.kind, Ok(TokenKind::Comment)) {
-            comments.push(token.slice);
+        if matches!(token.kind, Ok(kind) if kind.is_comment()) {
+            comments.push(token.slice.into());
             tokens.next(); // Consume the comment token
         } else {
             break; // No more comments
@@ -505,6 +1041,7 @@ mod tests {
         let TokenTree {
             span: _,
             prefix_comments,
+            doc_comments: _,
             value,
             suffix_comment,
         } = value;
@@ -513,11 +1050,14 @@ mod tests {
         assert_eq!(suffix_comment, None);
 
         if let TokenValue::Map(TokenMap {
+            includes: _,
             key_values,
             closing_comments,
+            trailing_comma,
         }) = value
         {
             assert_eq!(key_values.len(), 2);
+            assert!(!trailing_comma);
 
             {
                 let TokenKeyValue { key, value } = &key_values[0];
@@ -554,7 +1094,7 @@ mod tests {
                     panic!("Expected a String for key2, got {key:?}");
                 }
                 assert_eq!(value.prefix_comments, ["// Prefix comment D."]);
-                assert_eq!(value.suffix_comment, Some("// Suffix comment"));
+                assert_eq!(value.suffix_comment.as_deref(), Some("// Suffix comment"));
             }
 
             assert_eq!(
@@ -565,4 +1105,64 @@ mod tests {
             panic!("Expected a map value, got {value:?}");
         }
     }
+
+    #[test]
+    fn test_parse_doc_comments() {
+        let input = r#"
+        /// Not a doc comment, because it is followed by a normal comment.
+        // Normal comment.
+        /// The port to listen on.
+        ///
+        /// Must be above 1024.
+        port: 8080
+
+        //// Four slashes is a normal comment.
+        host: "localhost"
+        "#;
+
+        let tree = parse_top_str(input).unwrap();
+        let TokenValue::Map(map) = &tree.value else {
+            panic!("Expected a map, got {tree:?}");
+        };
+
+        let port = &map.key_values[0].key;
+        assert_eq!(
+            port.prefix_comments,
+            [
+                "/// Not a doc comment, because it is followed by a normal comment.",
+                "// Normal comment."
+            ]
+        );
+        assert_eq!(
+            port.doc_comments,
+            [
+                "/// The port to listen on.",
+                "///",
+                "/// Must be above 1024."
+            ]
+        );
+        assert_eq!(
+            port.doc().as_deref(),
+            Some("The port to listen on.\n\nMust be above 1024.")
+        );
+
+        let host = &map.key_values[1].key;
+        assert_eq!(
+            host.prefix_comments,
+            ["//// Four slashes is a normal comment."]
+        );
+        assert!(host.doc_comments.is_empty());
+        assert_eq!(host.doc(), None);
+
+        // Formatting preserves the order of the comments:
+        assert_eq!(
+            tree.format(&Default::default()),
+            parse_top_str(&tree.format(&Default::default()))
+                .unwrap()
+                .format(&Default::default())
+        );
+        assert!(tree.format(&Default::default()).starts_with(
+            "/// Not a doc comment, because it is followed by a normal comment.\n// Normal comment.\n/// The port"
+        ));
+    }
 }
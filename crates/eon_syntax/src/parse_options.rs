@@ -0,0 +1,633 @@
+//! Options for how to interpret a parsed Eon document.
+
+use std::collections::{HashMap, hash_map::Entry};
+
+use logos::Logos as _;
+
+use crate::{
+    error::{Error, Result, UNNAMED_SOURCE},
+    parse::{MAX_RECURSION_DEPTH, parse_top_str_as},
+    span::Span,
+    strings::unquote_bytes,
+    token_kind::TokenKind,
+    token_tree::{TokenKeyValue, TokenMap, TokenTree, TokenValue},
+};
+
+/// What to do when the same key appears more than once in a map.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DuplicateKeyPolicy {
+    /// Duplicate keys are an error.
+    #[default]
+    Error,
+
+    /// Keep the first value, ignoring any later ones.
+    FirstWins,
+
+    /// Keep the last value, overriding any earlier ones.
+    ///
+    /// The entry stays in the position of the first one.
+    LastWins,
+
+    /// If both values are maps, merge them recursively.
+    ///
+    /// Otherwise, the last value wins.
+    Merge,
+}
+
+/// What to do about raw control characters in strings, like an invisible `U+0007 BELL`
+/// that was pasted into a config file.
+///
+/// Tabs and newlines are always allowed.
+/// Other control characters can always be written as escape sequences in double-quoted strings,
+/// e.g. `"\u{7}"`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ControlCharacterPolicy {
+    /// Raw control characters in strings are an error.
+    #[default]
+    Error,
+
+    /// Allow raw control characters in strings, but report a warning.
+    ///
+    /// The warnings are returned by [`TokenTree::parse_str_with_warnings`].
+    Warn,
+
+    /// Allow raw control characters in strings.
+    Allow,
+}
+
+/// What to do about a document that is empty, or only contains comments.
+///
+/// Such a document is parsed as an empty map, but some applications want to tell
+/// an empty (e.g. truncated) file apart from an explicit `{}`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EmptyDocumentPolicy {
+    /// An empty document is an empty map.
+    #[default]
+    Allow,
+
+    /// An empty document is an empty map, but report a warning.
+    ///
+    /// The warnings are returned by [`TokenTree::parse_str_with_warnings`].
+    Warn,
+
+    /// An empty document is an error.
+    Error,
+}
+
+/// What the top level of a document is expected to be.
+///
+/// By default the parser first tries to parse the document as a map,
+/// and then as a value (or list of values), and reports the error of whichever got further.
+/// If you know the shape of your documents, forcing it gives more precise errors,
+/// and avoids parsing the source twice.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TopLevel {
+    /// Try a map first, then a value or list of values.
+    #[default]
+    Auto,
+
+    /// `key: value` pairs, optionally wrapped in `{ }`.
+    Map,
+
+    /// A single value of any kind, like `42`, `[1, 2]`, or `{ a: 1 }`.
+    Value,
+
+    /// A list, either in brackets like `[1, 2, 3]`, or without them like `1, 2, 3`.
+    List,
+}
+
+/// How to parse an Eon document.
+///
+/// Used by [`TokenTree::parse_str_with`].
+#[derive(Clone, Debug)]
+pub struct ParseOptions {
+    /// What to do when the same key appears more than once in a map.
+    pub duplicate_keys: DuplicateKeyPolicy,
+
+    /// What to do about raw control characters in strings.
+    pub control_characters: ControlCharacterPolicy,
+
+    /// What to do about a document that is empty, or only contains comments.
+    pub empty_document: EmptyDocumentPolicy,
+
+    /// What the top level of the document is expected to be.
+    pub top_level: TopLevel,
+
+    /// Accept `;` between values, like the old `con` format did.
+    ///
+    /// It is read as `,`, so reformatting the document normalizes it.
+    /// Off by default, since it is not valid Eon.
+    ///
+    /// `key = value` is always accepted, see [`crate::FormatOptions::key_value_separator`].
+    pub legacy_separators: bool,
+
+    /// Keep the comments of the document in the [`TokenTree`] (the default).
+    ///
+    /// Turn this off when you only care about the data, e.g. when converting to a value,
+    /// to skip the comments already when lexing and save some allocations.
+    pub keep_comments: bool,
+
+    /// The maximum depth of nested lists, maps, and variants.
+    ///
+    /// A lone value like `42` has a depth of 1, and `[[42]]` has a depth of 3.
+    /// Deeper documents are an error, which protects against stack overflows,
+    /// since each level of nesting uses a bit of the stack.
+    ///
+    /// The default is [`Self::DEFAULT_MAX_DEPTH`].
+    /// If you raise this a lot, parse on a thread with a bigger stack,
+    /// e.g. using [`std::thread::Builder::stack_size`].
+    pub max_depth: usize,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            duplicate_keys: Default::default(),
+            control_characters: Default::default(),
+            empty_document: Default::default(),
+            top_level: Default::default(),
+            legacy_separators: false,
+            keep_comments: true,
+            max_depth: Self::DEFAULT_MAX_DEPTH,
+        }
+    }
+}
+
+impl ParseOptions {
+    /// The default for [`Self::max_depth`].
+    ///
+    /// Enough for any hand-written document, and safe with the default stack size of a thread.
+    pub const DEFAULT_MAX_DEPTH: usize = MAX_RECURSION_DEPTH / 2;
+
+    /// Create a new [`ParseOptions`] with the default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set what to do about duplicate keys in maps.
+    pub fn with_duplicate_keys(mut self, duplicate_keys: DuplicateKeyPolicy) -> Self {
+        self.duplicate_keys = duplicate_keys;
+        self
+    }
+
+    /// Set what to do about raw control characters in strings.
+    pub fn with_control_characters(mut self, control_characters: ControlCharacterPolicy) -> Self {
+        self.control_characters = control_characters;
+        self
+    }
+
+    /// Set what to do about a document that is empty, or only contains comments.
+    pub fn with_empty_document(mut self, empty_document: EmptyDocumentPolicy) -> Self {
+        self.empty_document = empty_document;
+        self
+    }
+
+    /// Set what the top level of the document is expected to be.
+    pub fn with_top_level(mut self, top_level: TopLevel) -> Self {
+        self.top_level = top_level;
+        self
+    }
+
+    /// Set whether to accept `;` instead of `,`.
+    ///
+    /// See [`Self::legacy_separators`].
+    pub fn with_legacy_separators(mut self, legacy_separators: bool) -> Self {
+        self.legacy_separators = legacy_separators;
+        self
+    }
+
+    /// Set whether to keep the comments of the document.
+    ///
+    /// See [`Self::keep_comments`].
+    pub fn with_keep_comments(mut self, keep_comments: bool) -> Self {
+        self.keep_comments = keep_comments;
+        self
+    }
+
+    /// Set the maximum depth of nested lists, maps, and variants.
+    ///
+    /// See [`Self::max_depth`].
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+}
+
+impl<'s> TokenTree<'s> {
+    /// Parse a full Eon file, and apply the given [`ParseOptions`].
+    ///
+    /// Unlike [`Self::parse_str`], which preserves the document as written,
+    /// this resolves duplicate keys according to [`ParseOptions::duplicate_keys`],
+    /// and checks strings for control characters according to [`ParseOptions::control_characters`].
+    /// The top level of the document is parsed according to [`ParseOptions::top_level`],
+    /// [`ParseOptions::legacy_separators`] allows `;`,
+    /// comments are dropped unless [`ParseOptions::keep_comments`] is set,
+    /// and nesting deeper than [`ParseOptions::max_depth`] is an error.
+    pub fn parse_str_with(source: &'s str, options: &ParseOptions) -> Result<Self> {
+        Self::parse_str_with_warnings(source, options).map(|(tree, _warnings)| tree)
+    }
+
+    /// Like [`Self::parse_str_with`], but also returns any warnings,
+    /// e.g. from [`ControlCharacterPolicy::Warn`] or [`EmptyDocumentPolicy::Warn`].
+    pub fn parse_str_with_warnings(
+        source: &'s str,
+        options: &ParseOptions,
+    ) -> Result<(Self, Vec<Error>)> {
+        let mut tree = parse_top_str_as(source, options)?;
+        let mut warnings = vec![];
+
+        if options.control_characters != ControlCharacterPolicy::Allow {
+            if let Some(error) = control_characters_error(source) {
+                match options.control_characters {
+                    ControlCharacterPolicy::Error => return Err(error),
+                    ControlCharacterPolicy::Warn => warnings.push(error),
+                    ControlCharacterPolicy::Allow => {}
+                }
+            }
+        }
+
+        if options.empty_document != EmptyDocumentPolicy::Allow && is_empty_source(source) {
+            let error = Error::custom("The document is empty");
+            match options.empty_document {
+                EmptyDocumentPolicy::Error => return Err(error),
+                EmptyDocumentPolicy::Warn => warnings.push(error),
+                EmptyDocumentPolicy::Allow => {}
+            }
+        }
+
+        resolve_duplicates(source, &mut tree, options.duplicate_keys)?;
+        Ok((tree, warnings))
+    }
+}
+
+/// Does the source contain nothing but whitespace and comments?
+///
+/// Unlike [`TokenTree::is_empty_document`], this does not consider `{}` to be empty.
+fn is_empty_source(source: &str) -> bool {
+    TokenKind::lexer(source).all(|token| token.is_ok_and(TokenKind::is_comment))
+}
+
+/// Points out all raw control characters (except tabs and newlines) in the strings of the source.
+fn control_characters_error(source: &str) -> Option<Error> {
+    let mut first = None;
+    let mut labels = vec![];
+
+    for (token, span) in TokenKind::lexer(source).spanned() {
+        let is_string = matches!(
+            token,
+            Ok(TokenKind::DoubleQuotedString
+                | TokenKind::SingleQuotedString
+                | TokenKind::MultilineBasicString
+                | TokenKind::MultilineLiteralString)
+        );
+        if !is_string {
+            continue;
+        }
+
+        for (i, c) in source[span.clone()].char_indices() {
+            if c.is_control() && !matches!(c, '\t' | '\n' | '\r') {
+                let start = span.start + i;
+                let span = Span {
+                    start,
+                    end: start + c.len_utf8(),
+                };
+                labels.push((
+                    span,
+                    format!("Raw control character U+{:04X}", u32::from(c)),
+                ));
+                first.get_or_insert(c);
+            }
+        }
+    }
+
+    let first = first?;
+    Some(Error::new_at_labels(
+        source,
+        format!(
+            "Strings contain raw control characters. Use an escape sequence in a double-quoted string instead, like \"\\u{{{:x}}}\"",
+            u32::from(first)
+        ),
+        labels,
+    ))
+}
+
+/// Keys that are considered equal: `key` and `"key"` are the same.
+#[derive(PartialEq, Eq, Hash)]
+enum KeyIdentity {
+    String(String),
+    Number(String),
+    Timestamp(String),
+    Bytes(Vec<u8>),
+}
+
+fn key_identity(key: &TokenValue<'_>) -> Option<KeyIdentity> {
+    match key {
+        TokenValue::Number(number) => Some(KeyIdentity::Number(number.to_string())),
+        TokenValue::Timestamp(timestamp) => Some(KeyIdentity::Timestamp(timestamp.to_string())),
+        TokenValue::Bytes(bytes) => unquote_bytes(bytes).ok().map(KeyIdentity::Bytes),
+        _ => key
+            .as_key_str()
+            .map(|key| KeyIdentity::String(key.into_owned())),
+    }
+}
+
+fn resolve_duplicates(
+    source: &str,
+    tree: &mut TokenTree<'_>,
+    policy: DuplicateKeyPolicy,
+) -> Result {
+    match &mut tree.value {
+        TokenValue::Identifier(_)
+        | TokenValue::Number(_)
+        | TokenValue::Timestamp(_)
+        | TokenValue::Bytes(_)
+        | TokenValue::QuotedString(_) => Ok(()),
+        TokenValue::List(list) => list
+            .values
+            .iter_mut()
+            .try_for_each(|value| resolve_duplicates(source, value, policy)),
+        TokenValue::Variant(variant) => variant
+            .values
+            .iter_mut()
+            .try_for_each(|value| resolve_duplicates(source, value, policy)),
+        TokenValue::Map(map) => {
+            let mut key_values = std::mem::take(&mut map.key_values);
+            for kv in &mut key_values {
+                resolve_duplicates(source, &mut kv.value, policy)?;
+            }
+            insert_resolved(source, map, key_values, policy)
+        }
+    }
+}
+
+/// Insert key-value pairs whose values have already been resolved
+/// into a map that has no duplicate keys.
+fn insert_resolved<'s>(
+    source: &str,
+    map: &mut TokenMap<'s>,
+    key_values: Vec<TokenKeyValue<'s>>,
+    policy: DuplicateKeyPolicy,
+) -> Result {
+    let mut index_of_key: HashMap<KeyIdentity, usize> = map
+        .key_values
+        .iter()
+        .enumerate()
+        .filter_map(|(index, kv)| Some((key_identity(&kv.key.value)?, index)))
+        .collect();
+
+    for kv in key_values {
+        let Some(key) = key_identity(&kv.key.value) else {
+            map.key_values.push(kv);
+            continue;
+        };
+        match index_of_key.entry(key) {
+            Entry::Vacant(entry) => {
+                entry.insert(map.key_values.len());
+                map.key_values.push(kv);
+            }
+            Entry::Occupied(entry) => {
+                insert_duplicate(source, &mut map.key_values[*entry.get()], kv, policy)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn insert_duplicate<'s>(
+    source: &str,
+    existing: &mut TokenKeyValue<'s>,
+    kv: TokenKeyValue<'s>,
+    policy: DuplicateKeyPolicy,
+) -> Result {
+    match policy {
+        DuplicateKeyPolicy::Error => Err(Error::duplicate_key(
+            (UNNAMED_SOURCE, source, existing.key.span),
+            (UNNAMED_SOURCE, source, kv.key.span),
+        )),
+        DuplicateKeyPolicy::FirstWins => Ok(()),
+        DuplicateKeyPolicy::LastWins => {
+            replace_value(&mut existing.value, kv.value);
+            Ok(())
+        }
+        DuplicateKeyPolicy::Merge => {
+            match (&mut existing.value.value, kv.value.value) {
+                (TokenValue::Map(existing_map), TokenValue::Map(new_map)) => {
+                    insert_resolved(source, existing_map, new_map.key_values, policy)?;
+                    existing_map.includes.extend(new_map.includes);
+                    existing_map
+                        .closing_comments
+                        .extend(new_map.closing_comments);
+                }
+                (_, value) => {
+                    let value = TokenTree { value, ..kv.value };
+                    replace_value(&mut existing.value, value);
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+fn replace_value<'s>(existing: &mut TokenTree<'s>, mut value: TokenTree<'s>) {
+    if value.suffix_comment.is_none() {
+        value.suffix_comment = existing.suffix_comment.take();
+    }
+    *existing = value;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FormatOptions;
+
+    fn parse_with(source: &str, duplicate_keys: DuplicateKeyPolicy) -> Result<String> {
+        let options = ParseOptions::new().with_duplicate_keys(duplicate_keys);
+        TokenTree::parse_str_with(source, &options).map(|tt| tt.format(&FormatOptions::default()))
+    }
+
+    #[test]
+    fn test_duplicate_key_policy() {
+        let source = r#"
+a: 1
+server: { host: "a", port: 80 }
+a: 2
+"server": { port: 8080, tls: true }
+"#;
+
+        assert!(parse_with(source, DuplicateKeyPolicy::Error).is_err());
+        assert_eq!(
+            parse_with(source, DuplicateKeyPolicy::FirstWins).unwrap(),
+            "a: 1\nserver: { host: \"a\", port: 80 }\n"
+        );
+        assert_eq!(
+            parse_with(source, DuplicateKeyPolicy::LastWins).unwrap(),
+            "a: 2\nserver: { port: 8080, tls: true }\n"
+        );
+        assert_eq!(
+            parse_with(source, DuplicateKeyPolicy::Merge).unwrap(),
+            "a: 2\nserver: { host: \"a\", port: 8080, tls: true }\n"
+        );
+
+        // Without options, the document is preserved as written:
+        assert!(TokenTree::parse_str(source).is_ok());
+    }
+
+    #[test]
+    fn test_empty_document_policy() {
+        let parse = |source, policy| {
+            let options = ParseOptions::new().with_empty_document(policy);
+            TokenTree::parse_str_with_warnings(source, &options)
+        };
+
+        for source in ["", "  \n", "// Just a comment\n"] {
+            let (tree, warnings) = parse(source, EmptyDocumentPolicy::Allow).unwrap();
+            assert!(tree.is_empty_document());
+            assert!(warnings.is_empty());
+
+            let (_, warnings) = parse(source, EmptyDocumentPolicy::Warn).unwrap();
+            assert_eq!(warnings.len(), 1);
+
+            let err = parse(source, EmptyDocumentPolicy::Error).unwrap_err();
+            assert_eq!(err.message(), "The document is empty");
+        }
+
+        for source in ["{}", "a: 1", "[]", "// Comment\n{ }"] {
+            let (tree, warnings) = parse(source, EmptyDocumentPolicy::Warn).unwrap();
+            assert!(warnings.is_empty(), "{source:?}");
+            assert!(parse(source, EmptyDocumentPolicy::Error).is_ok());
+            assert_eq!(tree.is_empty_document(), source.contains('{'), "{source:?}");
+        }
+    }
+
+    #[test]
+    fn test_top_level() {
+        let parse = |source, top_level| {
+            let options = ParseOptions::new().with_top_level(top_level);
+            TokenTree::parse_str_with(source, &options)
+                .map(|tt| tt.format(&FormatOptions::default()))
+        };
+
+        assert_eq!(parse("a: 1", TopLevel::Map).unwrap(), "a: 1\n");
+        assert_eq!(parse("{ a: 1 }", TopLevel::Map).unwrap(), "a: 1\n");
+        assert_eq!(
+            parse("// Comment\n{ a: 1 }", TopLevel::Map).unwrap(),
+            "// Comment\na: 1\n"
+        );
+        assert!(parse("[1, 2]", TopLevel::Map).is_err());
+        assert_eq!(
+            parse("{ a: 1 }, { b: 2 }", TopLevel::Map)
+                .unwrap_err()
+                .message(),
+            "Expected a map"
+        );
+
+        assert_eq!(parse("42", TopLevel::Value).unwrap(), "42");
+        assert_eq!(parse("[1, 2]", TopLevel::Value).unwrap(), "[1, 2]");
+        assert!(parse("a: 1", TopLevel::Value).is_err());
+        let err = parse("1, 2", TopLevel::Value).unwrap_err();
+        assert_eq!(err.message(), "Expected a single value");
+
+        assert_eq!(parse("1, 2", TopLevel::List).unwrap(), "[1, 2]");
+        assert_eq!(parse("[1, 2]", TopLevel::List).unwrap(), "[1, 2]");
+        let err = parse("42", TopLevel::List).unwrap_err();
+        assert_eq!(err.message(), "Expected a list");
+
+        // With a known shape, the error is about that shape:
+        let err = parse("a: 1\nb: ]", TopLevel::Map).unwrap_err();
+        assert!(parse("a: 1\nb: ]", TopLevel::Auto).is_err());
+        assert_eq!(err.message(), "Unbalanced brackets");
+    }
+
+    #[test]
+    fn test_legacy_separators() {
+        let source = "name = 'a=b;c';\nports = [80; 443];\nserver = { host = \"a\"; tls = true }\n";
+        let options = ParseOptions::new().with_legacy_separators(true);
+
+        let tree = TokenTree::parse_str_with(source, &options).unwrap();
+        assert_eq!(
+            tree.format(&FormatOptions::default()),
+            "name: 'a=b;c'\nports: [80, 443]\nserver: { host: \"a\", tls: true }\n"
+        );
+
+        // Not valid Eon without the option:
+        let err = TokenTree::parse_str_with(source, &ParseOptions::default()).unwrap_err();
+        assert!(
+            err.message().contains("Invalid token: ';'"),
+            "{}",
+            err.message()
+        );
+    }
+
+    #[test]
+    fn test_keep_comments() {
+        let source = "// Prefix\na: [1, 2] // Suffix\n# Hash\nb: { c: 3 // Inner\n}\n// Closing\n";
+        let without = ParseOptions::new().with_keep_comments(false);
+
+        let tree = TokenTree::parse_str_with(source, &ParseOptions::default()).unwrap();
+        assert!(tree.format(&FormatOptions::default()).contains("// Inner"));
+
+        let tree = TokenTree::parse_str_with(source, &without).unwrap();
+        assert_eq!(
+            tree.format(&FormatOptions::default()),
+            "a: [1, 2]\nb: { c: 3 }\n"
+        );
+
+        // The spans still point into the source:
+        let TokenValue::Map(map) = &tree.value else {
+            panic!("Expected a map");
+        };
+        let span = map.key_values[1].value.span.unwrap();
+        assert_eq!(&source[span.start..span.end], "{ c: 3 // Inner\n}");
+    }
+
+    #[test]
+    fn test_control_character_policy() {
+        let source = "bell: 'ding\u{7}'\ntab: \"a\tb\"\nescaped: \"\\u{7}\"\n";
+        let parse = |policy| {
+            let options = ParseOptions::new().with_control_characters(policy);
+            TokenTree::parse_str_with_warnings(source, &options)
+        };
+
+        let err = parse(ControlCharacterPolicy::Error).unwrap_err();
+        let err = err.to_string_with_color(false);
+        assert!(err.contains("Raw control character U+0007"), "{err}");
+        assert!(err.contains(r#"like "\u{7}""#), "{err}");
+
+        let (_, warnings) = parse(ControlCharacterPolicy::Warn).unwrap();
+        assert_eq!(warnings.len(), 1);
+
+        let (_, warnings) = parse(ControlCharacterPolicy::Allow).unwrap();
+        assert!(warnings.is_empty());
+
+        // Tabs, newlines, and escape sequences are fine:
+        let source = "tab: \"a\tb\"\nmultiline: \"\"\"\r\nline\r\n\"\"\"\nescaped: \"\\u{7}\"\n";
+        assert!(TokenTree::parse_str_with(source, &ParseOptions::default()).is_ok());
+    }
+
+    #[test]
+    fn test_max_depth() {
+        let nested = |depth: usize| format!("{}42{}", "[".repeat(depth - 1), "]".repeat(depth - 1));
+        fn parse(source: &str, max_depth: usize) -> Result<TokenTree<'_>> {
+            let options = ParseOptions::new()
+                .with_top_level(TopLevel::Value)
+                .with_max_depth(max_depth);
+            TokenTree::parse_str_with(source, &options)
+        }
+
+        assert!(parse("42", 1).is_ok());
+        assert!(parse("[[42]]", 3).is_ok());
+        assert!(parse("[[42]]", 2).is_err());
+        assert!(parse("{a: \"Rgb\"(1)}", 3).is_ok());
+        assert!(parse("{a: \"Rgb\"(1)}", 2).is_err());
+
+        let deep = nested(ParseOptions::DEFAULT_MAX_DEPTH + 16);
+        let err = parse(&deep, ParseOptions::DEFAULT_MAX_DEPTH).unwrap_err();
+        assert!(
+            err.message().contains("Maximum recursion depth exceeded"),
+            "{}",
+            err.message()
+        );
+        assert!(parse(&deep, ParseOptions::DEFAULT_MAX_DEPTH + 16).is_ok());
+    }
+}
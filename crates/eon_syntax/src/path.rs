@@ -0,0 +1,207 @@
+//! Paths into a document, like `server.ports[2].name`.
+
+use crate::strings::{escape_and_quote, is_valid_identifier, unescape_and_unquote};
+
+/// One step in a [`KeyPath`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PathSegment {
+    /// A string key in a map, like `.name` or `."quoted name"`.
+    Key(String),
+
+    /// An index into a list, like `[2]`.
+    ///
+    /// When applied to a map, this looks up the integer key.
+    Index(usize),
+}
+
+/// A path into a nested document, like `server.ports[2].name`.
+///
+/// Keys are separated by `.`, and indices are written in brackets.
+/// Keys that contain special characters can be quoted, like `headers."Content-Type"`.
+///
+/// The empty path refers to the root of the document.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct KeyPath {
+    /// The steps of the path, from the root and inwards.
+    pub segments: Vec<PathSegment>,
+}
+
+impl KeyPath {
+    /// The empty path, referring to the root.
+    pub fn root() -> Self {
+        Self::default()
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Extend the path with a map key.
+    pub fn key(mut self, key: impl Into<String>) -> Self {
+        self.segments.push(PathSegment::Key(key.into()));
+        self
+    }
+
+    /// Extend the path with a list index.
+    pub fn index(mut self, index: usize) -> Self {
+        self.segments.push(PathSegment::Index(index));
+        self
+    }
+
+    /// The path to the parent, and the last segment, unless this is the root.
+    pub fn split_last(&self) -> Option<(Self, &PathSegment)> {
+        let (last, parent) = self.segments.split_last()?;
+        Some((
+            Self {
+                segments: parent.to_vec(),
+            },
+            last,
+        ))
+    }
+}
+
+impl std::str::FromStr for KeyPath {
+    type Err = String;
+
+    fn from_str(path: &str) -> Result<Self, Self::Err> {
+        let mut segments = vec![];
+        let mut rest = path.trim();
+
+        while !rest.is_empty() {
+            if let Some(after_bracket) = rest.strip_prefix('[') {
+                let Some(end) = after_bracket.find(']') else {
+                    return Err(format!("Missing closing ']' in path {path:?}"));
+                };
+                let inside = after_bracket[..end].trim();
+                rest = &after_bracket[end + 1..];
+
+                if inside.starts_with('"') || inside.starts_with('\'') {
+                    segments.push(PathSegment::Key(unescape_and_unquote(inside)?));
+                } else {
+                    let index = inside.parse().map_err(|_err| {
+                        format!("Expected a non-negative integer index, got {inside:?}")
+                    })?;
+                    segments.push(PathSegment::Index(index));
+                }
+            } else {
+                if !segments.is_empty() {
+                    let Some(after_dot) = rest.strip_prefix('.') else {
+                        return Err(format!(
+                            "Expected '.' or '[' in path {path:?}, got {rest:?}"
+                        ));
+                    };
+                    rest = after_dot;
+                }
+
+                if rest.starts_with('"') {
+                    let end = closing_quote(rest)
+                        .ok_or_else(|| format!("Missing closing quote in path {path:?}"))?;
+                    segments.push(PathSegment::Key(unescape_and_unquote(&rest[..=end])?));
+                    rest = &rest[end + 1..];
+                } else {
+                    let end = rest.find(['.', '[']).unwrap_or(rest.len());
+                    let key = &rest[..end];
+                    if key.is_empty() {
+                        return Err(format!("Empty key in path {path:?}"));
+                    }
+                    segments.push(PathSegment::Key(key.to_owned()));
+                    rest = &rest[end..];
+                }
+            }
+        }
+
+        Ok(Self { segments })
+    }
+}
+
+/// Byte index of the quote closing a double-quoted string at the start of `s`.
+fn closing_quote(s: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (i, c) in s.char_indices().skip(1) {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == '"' {
+            return Some(i);
+        }
+    }
+    None
+}
+
+impl std::fmt::Display for KeyPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            match segment {
+                PathSegment::Key(key) => {
+                    if 0 < i {
+                        f.write_str(".")?;
+                    }
+                    if is_valid_identifier(key) {
+                        f.write_str(key)?;
+                    } else {
+                        f.write_str(&escape_and_quote(key))?;
+                    }
+                }
+                PathSegment::Index(index) => write!(f, "[{index}]")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<&str> for PathSegment {
+    fn from(key: &str) -> Self {
+        Self::Key(key.to_owned())
+    }
+}
+
+impl From<usize> for PathSegment {
+    fn from(index: usize) -> Self {
+        Self::Index(index)
+    }
+}
+
+impl FromIterator<PathSegment> for KeyPath {
+    fn from_iter<I: IntoIterator<Item = PathSegment>>(iter: I) -> Self {
+        Self {
+            segments: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[test]
+fn test_key_path() {
+    use std::str::FromStr as _;
+
+    let path = KeyPath::from_str("server.ports[2].name").unwrap();
+    assert_eq!(
+        path,
+        KeyPath::root()
+            .key("server")
+            .key("ports")
+            .index(2)
+            .key("name")
+    );
+    assert_eq!(path.to_string(), "server.ports[2].name");
+
+    let path = KeyPath::from_str(r#"headers."Content-Type"[0]["a b"]"#).unwrap();
+    assert_eq!(
+        path,
+        KeyPath::root()
+            .key("headers")
+            .key("Content-Type")
+            .index(0)
+            .key("a b")
+    );
+    assert_eq!(path.to_string(), r#"headers."Content-Type"[0]."a b""#);
+
+    assert_eq!(KeyPath::from_str("").unwrap(), KeyPath::root());
+    assert_eq!(
+        KeyPath::from_str("[1][2]").unwrap(),
+        KeyPath::root().index(1).index(2)
+    );
+    assert!(KeyPath::from_str("a..b").is_err());
+    assert!(KeyPath::from_str("a[x]").is_err());
+    assert!(KeyPath::from_str("a[1").is_err());
+}
@@ -0,0 +1,101 @@
+//! Reporting how far the parser has come, e.g. for showing a progress bar when loading huge files.
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+/// How many bytes to parse between each progress update.
+pub(crate) const PROGRESS_INTERVAL: usize = 64 * 1024;
+
+/// A handle for following the progress of [`crate::TokenTree::parse_str_with_progress`].
+///
+/// Clone it, hand one clone to the parser, and poll the other one from e.g. a UI thread:
+///
+/// ```
+/// let source = "numbers: [1, 2, 3]";
+/// let progress = eon_syntax::ParseProgress::new();
+/// let tree = std::thread::scope(|scope| {
+///     let parser_progress = progress.clone();
+///     let parser = scope.spawn(move || {
+///         eon_syntax::TokenTree::parse_str_with_progress(source, &parser_progress)
+///     });
+///     while !parser.is_finished() {
+///         // Update your progress bar with `progress.fraction()` here
+///         std::thread::yield_now();
+///     }
+///     parser.join().unwrap()
+/// });
+/// assert!(tree.is_ok());
+/// assert_eq!(progress.fraction(), 1.0);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ParseProgress {
+    inner: Arc<Progress>,
+}
+
+#[derive(Debug, Default)]
+struct Progress {
+    bytes_parsed: AtomicUsize,
+    total_bytes: AtomicUsize,
+}
+
+impl ParseProgress {
+    /// A new handle, with no progress.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// How many bytes of the source have been parsed so far.
+    ///
+    /// This is updated every 64 kB or so, and never goes backwards.
+    pub fn bytes_parsed(&self) -> usize {
+        self.inner.bytes_parsed.load(Ordering::Relaxed)
+    }
+
+    /// The size of the source being parsed, or zero if parsing has not started.
+    pub fn total_bytes(&self) -> usize {
+        self.inner.total_bytes.load(Ordering::Relaxed)
+    }
+
+    /// How far the parser has come, from 0 to 1.
+    pub fn fraction(&self) -> f32 {
+        let total_bytes = self.total_bytes();
+        if total_bytes == 0 {
+            0.0
+        } else {
+            self.bytes_parsed() as f32 / total_bytes as f32
+        }
+    }
+
+    pub(crate) fn start(&self, total_bytes: usize) {
+        self.inner.bytes_parsed.store(0, Ordering::Relaxed);
+        self.inner.total_bytes.store(total_bytes, Ordering::Relaxed);
+    }
+
+    pub(crate) fn report(&self, bytes_parsed: usize) {
+        self.inner
+            .bytes_parsed
+            .fetch_max(bytes_parsed, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn test_parse_progress() {
+    let source = "key: 42\n".repeat(20_000);
+    assert!(2 * PROGRESS_INTERVAL < source.len());
+
+    let progress = ParseProgress::new();
+    assert_eq!(progress.fraction(), 0.0);
+
+    let tree = crate::TokenTree::parse_str_with_progress(&source, &progress).unwrap();
+    assert!(matches!(tree.value, crate::TokenValue::Map(_)));
+    assert_eq!(progress.total_bytes(), source.len());
+    assert_eq!(progress.bytes_parsed(), source.len());
+    assert_eq!(progress.fraction(), 1.0);
+
+    // Progress is also reported on error:
+    let progress = ParseProgress::new();
+    assert!(crate::TokenTree::parse_str_with_progress("a: [", &progress).is_err());
+    assert_eq!(progress.fraction(), 1.0);
+}
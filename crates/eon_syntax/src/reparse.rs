@@ -0,0 +1,254 @@
+//! Incremental reparsing, for editors and language servers.
+
+use std::ops::Range;
+
+use crate::{
+    error::Result,
+    parse::parse_value_at,
+    span::Span,
+    token_tree::{TokenTree, TokenValue},
+};
+
+/// An edit of a source text: replace the bytes in [`Self::range`] with [`Self::replacement`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextEdit {
+    /// The byte range in the old source that is replaced.
+    pub range: Range<usize>,
+
+    /// The new text.
+    pub replacement: String,
+}
+
+impl TextEdit {
+    pub fn new(range: Range<usize>, replacement: impl Into<String>) -> Self {
+        Self {
+            range,
+            replacement: replacement.into(),
+        }
+    }
+
+    /// Apply the edit to the old source, returning the new source.
+    ///
+    /// # Panics
+    /// If the range is out of bounds, or not on a character boundary.
+    pub fn apply(&self, old_source: &str) -> String {
+        let mut new_source = old_source.to_owned();
+        new_source.replace_range(self.range.clone(), &self.replacement);
+        new_source
+    }
+
+    /// The range of the replacement in the new source.
+    fn new_range(&self) -> Range<usize> {
+        self.range.start..self.range.start + self.replacement.len()
+    }
+
+    /// Move an offset in the old source to where it is in the new source.
+    ///
+    /// Only valid for offsets outside the edited range.
+    fn shift(&self, offset: usize) -> usize {
+        if self.range.end <= offset {
+            offset - self.range.len() + self.replacement.len()
+        } else {
+            offset
+        }
+    }
+}
+
+impl<'s> TokenTree<'s> {
+    /// Update a parsed document after an edit of its source, only reparsing the part that changed.
+    ///
+    /// `self` must be what [`Self::parse_str`] returned for the old source,
+    /// and `new_source` must be the old source with `edit` applied.
+    ///
+    /// The innermost value (or key) that surrounds the edit is reparsed,
+    /// and the spans of everything after it are moved.
+    /// If that is not possible, e.g. because the edit is at the top level of the document,
+    /// the whole `new_source` is parsed instead.
+    ///
+    /// The result is the same as calling [`Self::parse_str`] on `new_source`,
+    /// except that the unchanged parts may still borrow from the old source.
+    ///
+    /// This is useful for language servers, which cannot afford a full reparse on every keystroke.
+    pub fn reparse(mut self, new_source: &'s str, edit: &TextEdit) -> Result<Self> {
+        let is_consistent = edit.range.start <= edit.range.end
+            && new_source.get(edit.new_range()) == Some(edit.replacement.as_str());
+
+        if is_consistent {
+            let mut path = vec![];
+            if let Some(new_subtree) = reparse_innermost(&self, new_source, edit, 1, &mut path) {
+                shift_spans(&mut self, edit);
+                let node = path
+                    .iter()
+                    .fold(&mut self, |node, &i| children_mut(node).swap_remove(i));
+                node.span = new_subtree.span;
+                node.value = new_subtree.value;
+                return Ok(self);
+            }
+        }
+
+        Self::parse_str(new_source)
+    }
+}
+
+/// Find the innermost descendant of `tree` that surrounds the edit and can be reparsed on its own.
+///
+/// Returns the new subtree, and pushes the path to it (as indices into [`children`]) to `path`.
+fn reparse_innermost<'s>(
+    tree: &TokenTree<'_>,
+    new_source: &'s str,
+    edit: &TextEdit,
+    recurse_depth: usize,
+    path: &mut Vec<usize>,
+) -> Option<TokenTree<'s>> {
+    for (i, child) in children(tree).into_iter().enumerate() {
+        let Some(span) = child.span else {
+            continue;
+        };
+
+        // The first and last token must be untouched, so the edit can't leak out of the child:
+        if span.start < edit.range.start && edit.range.end < span.end {
+            path.push(i);
+            if let Some(new_subtree) =
+                reparse_innermost(child, new_source, edit, recurse_depth + 2, path)
+            {
+                return Some(new_subtree);
+            }
+            let new_range = span.start..edit.shift(span.end);
+            if let Ok(new_subtree) = parse_value_at(new_source, new_range, recurse_depth) {
+                return Some(new_subtree);
+            }
+            path.pop();
+            return None;
+        }
+    }
+    None
+}
+
+/// Move all spans after the edit.
+fn shift_spans(tree: &mut TokenTree<'_>, edit: &TextEdit) {
+    if let Some(span) = &mut tree.span {
+        if span.end < edit.range.start {
+            return; // Nothing to do for this whole subtree
+        }
+        shift_span(span, edit);
+    }
+
+    match &mut tree.value {
+        TokenValue::Map(map) => {
+            for include in &mut map.includes {
+                if let Some(span) = &mut include.span {
+                    shift_span(span, edit);
+                }
+            }
+        }
+        TokenValue::Variant(variant) => {
+            if let Some(span) = &mut variant.name_span {
+                shift_span(span, edit);
+            }
+        }
+        _ => {}
+    }
+
+    for child in children_mut(tree) {
+        shift_spans(child, edit);
+    }
+}
+
+fn shift_span(span: &mut Span, edit: &TextEdit) {
+    span.start = edit.shift(span.start);
+    span.end = edit.shift(span.end);
+}
+
+/// The keys and values of a map, or the values of a list or variant.
+fn children<'t, 's>(tree: &'t TokenTree<'s>) -> Vec<&'t TokenTree<'s>> {
+    match &tree.value {
+        TokenValue::Map(map) => map
+            .key_values
+            .iter()
+            .flat_map(|kv| [&kv.key, &kv.value])
+            .collect(),
+        TokenValue::List(list) => list.values.iter().collect(),
+        TokenValue::Variant(variant) => variant.values.iter().collect(),
+        _ => vec![],
+    }
+}
+
+/// Like [`children`], but mutable.
+fn children_mut<'t, 's>(tree: &'t mut TokenTree<'s>) -> Vec<&'t mut TokenTree<'s>> {
+    match &mut tree.value {
+        TokenValue::Map(map) => map
+            .key_values
+            .iter_mut()
+            .flat_map(|kv| [&mut kv.key, &mut kv.value])
+            .collect(),
+        TokenValue::List(list) => list.values.iter_mut().collect(),
+        TokenValue::Variant(variant) => variant.values.iter_mut().collect(),
+        _ => vec![],
+    }
+}
+
+#[test]
+fn test_reparse() {
+    let old_source = r#"
+// The server
+server: {
+    port: 8080
+    hosts: ["localhost", "example.com"]
+    color: "Rgb"(255, 0, 0)
+}
+after: [1, 2, 3] // Suffix
+"#;
+
+    let check = |find: &str, replacement: &str| {
+        let start = old_source.find(find).unwrap();
+        let edit = TextEdit::new(start..start + find.len(), replacement);
+        let new_source = edit.apply(old_source);
+
+        let old_tree = TokenTree::parse_str(old_source).unwrap();
+        let reparsed = old_tree.reparse(&new_source, &edit);
+        let parsed = TokenTree::parse_str(&new_source);
+        assert_eq!(
+            format!("{reparsed:?}"),
+            format!("{parsed:?}"),
+            "Replacing {find:?} with {replacement:?}"
+        );
+    };
+
+    {
+        // Unchanged parts should still borrow from the old source:
+        let start = old_source.find("8080").unwrap();
+        let edit = TextEdit::new(start + 1..start + 3, "");
+        let new_source = edit.apply(old_source);
+        let tree = TokenTree::parse_str(old_source).unwrap();
+        let tree = tree.reparse(&new_source, &edit).unwrap();
+        let TokenValue::Map(map) = &tree.value else {
+            panic!("Expected a map");
+        };
+        let TokenValue::Identifier(key) = &map.key_values[1].key.value else {
+            panic!("Expected an identifier");
+        };
+        assert!(old_source.as_bytes().as_ptr_range().contains(&key.as_ptr()));
+
+        let port = tree.get_path(&"server.port".parse().unwrap()).unwrap();
+        assert_eq!(
+            port.span,
+            Some(Span {
+                start,
+                end: start + 2
+            })
+        );
+    }
+
+    check("80", "90");
+    check("8080", "[1, 2]");
+    check("\"localhost\", ", "");
+    check("\"example.com\"", "\"a\" // comment\n");
+    check("port: 8080", "port: 8080, new_key: true");
+    check("0, 0", "0, 128");
+    check("2, 3", "");
+    check("server", "other_server");
+    check("// The server", "// The client");
+    check("}", "");
+    check("8080", "[1, 2");
+    check("\"localhost\"", "\"unclosed");
+}
@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 use crate::Result;
 
 fn is_keyword(string: &str) -> bool {
@@ -57,16 +59,118 @@ pub fn escape_and_quote(raw: &str) -> String {
     }
 }
 
+/// Like [`escape_and_quote`], but prefer `'''C:\Users\O'Brien'''` over `"C:\\Users\\O'Brien"`.
+///
+/// Strings with both backslashes and apostrophes, like Windows paths, are written as
+/// single-line multiline literal strings instead of escaping every backslash.
+///
+/// Used for [`crate::FormatOptions::literal_backslash_strings`].
+pub fn escape_and_quote_literal(raw: &str) -> String {
+    let can_be_multiline_literal = raw.contains('\\')
+        && raw.contains('\'')
+        && !raw.contains("'''")
+        && !raw.starts_with('\'')
+        && !raw.ends_with('\'')
+        && !raw.chars().any(char::is_control);
+    if can_be_multiline_literal {
+        format!("'''{raw}'''")
+    } else {
+        escape_and_quote(raw)
+    }
+}
+
 fn double_quote(raw: &str) -> String {
     format!("{raw:?}")
 }
 
+/// Like [`escape_and_quote`], but keep the quoting style of `original` (a quoted string) if `raw` allows it.
+///
+/// Used when changing a string in a document, to keep the diff minimal.
+pub(crate) fn escape_and_quote_like(raw: &str, original: &str) -> String {
+    let has_control =
+        |allowed: &[char]| raw.chars().any(|c| c.is_control() && !allowed.contains(&c));
+
+    if original.starts_with("'''") {
+        if !raw.contains("'''") && !raw.ends_with('\'') && !has_control(&['\n', '\t']) {
+            // The newline right after the opening quotes is not part of the string:
+            return if original.starts_with("'''\n") || raw.starts_with('\n') {
+                format!("'''\n{raw}'''")
+            } else {
+                format!("'''{raw}'''")
+            };
+        }
+    } else if original.starts_with('\'') {
+        if !raw.contains('\'') && !has_control(&[]) {
+            return format!("'{raw}'");
+        }
+    } else if original.starts_with(r#"""""#) {
+        if !raw.contains(r#"""""#)
+            && !raw.ends_with('"')
+            && !raw.contains('\\')
+            && !has_control(&['\n', '\t'])
+        {
+            return format!(r#""""{raw}""""#);
+        }
+    } else if original.starts_with('"') {
+        return double_quote(raw);
+    }
+    escape_and_quote(raw)
+}
+
+/// Format bytes as an Eon bytes literal, like `b"89504e47"`.
+pub fn quote_bytes(bytes: &[u8]) -> String {
+    use std::fmt::Write as _;
+
+    let mut out = String::with_capacity(2 * bytes.len() + 3);
+    out.push_str("b\"");
+    for byte in bytes {
+        write!(out, "{byte:02x}").ok();
+    }
+    out.push('"');
+    out
+}
+
+/// Decode an Eon bytes literal, like `b"89504e47"`, ignoring any `_` separators.
+pub fn unquote_bytes(literal: &str) -> Result<Vec<u8>, String> {
+    let Some(hex) = literal
+        .strip_prefix("b\"")
+        .and_then(|rest| rest.strip_suffix('"'))
+    else {
+        return Err("Bytes must be written like b\"89504e47\"".to_owned());
+    };
+
+    let digits = hex
+        .chars()
+        .filter(|&c| c != '_')
+        .map(|c| {
+            c.to_digit(16)
+                .ok_or_else(|| format!("Invalid hex digit {c:?} in bytes"))
+        })
+        .collect::<Result<Vec<u32>, String>>()?;
+
+    if digits.len() % 2 != 0 {
+        return Err("Bytes must have an even number of hex digits".to_owned());
+    }
+
+    Ok(digits
+        .chunks_exact(2)
+        .map(|pair| ((pair[0] << 4) | pair[1]) as u8)
+        .collect())
+}
+
 /// Remove the quotes and unescape the string.
 pub fn unescape_and_unquote(escaped: &str) -> Result<String, String> {
+    unescape_and_unquote_cow(escaped).map(Cow::into_owned)
+}
+
+/// Like [`unescape_and_unquote`], but borrows from the input when there is nothing to unescape,
+/// which is the case for most strings.
+pub fn unescape_and_unquote_cow(escaped: &str) -> Result<Cow<'_, str>, String> {
     if escaped.contains('\r') {
         // Handle Windows newlines by stripping all `\r` characters,
         // turning `\r\n` into `\n`.
-        return unescape_and_unquote(&escaped.replace('\r', ""));
+        return unescape_and_unquote_cow(&escaped.replace('\r', ""))
+            .map(|unescaped| Cow::Owned(unescaped.into_owned()));
     }
 
     if let Some(suffix) = escaped.strip_prefix("'''") {
@@ -77,11 +181,9 @@ pub fn unescape_and_unquote(escaped: &str) -> Result<String, String> {
                     .to_owned(),
             );
         };
-        if let Some(stripped_newline) = contents.strip_prefix('\n') {
-            Ok(stripped_newline.to_owned())
-        } else {
-            Ok(contents.to_owned())
-        }
+        Ok(Cow::Borrowed(
+            contents.strip_prefix('\n').unwrap_or(contents),
+        ))
     } else if let Some(suffix) = escaped.strip_prefix("'") {
         // single-quoted literal string. No escape sequences.
         let Some(contents) = suffix.strip_suffix("'") else {
@@ -92,14 +194,14 @@ pub fn unescape_and_unquote(escaped: &str) -> Result<String, String> {
         if contents.contains('\n') {
             Err("Single-quoted literal string may contain newlines".to_owned())
         } else {
-            Ok(contents.to_owned())
+            Ok(Cow::Borrowed(contents))
         }
     } else if let Some(suffix) = escaped.strip_prefix(r#"""""#) {
         // Multiline double-quoted string. Can contain escape sequences.
         let Some(contents) = suffix.strip_suffix(r#"""""#) else {
             return Err("Missing ending of multiline double-quoted string".to_owned());
         };
-        unescape(contents)
+        unescape_cow(contents)
     } else if let Some(suffix) = escaped.strip_prefix('"') {
         // Simple double-quoted string. Can contain escape sequences.
         let Some(contents) = suffix.strip_suffix('"') else {
@@ -108,13 +210,21 @@ pub fn unescape_and_unquote(escaped: &str) -> Result<String, String> {
         if contents.contains('\n') {
             Err("Double-quoted string may contain newlines".to_owned())
         } else {
-            unescape(contents)
+            unescape_cow(contents)
         }
     } else {
         Err("String must start with a quote (single or double)".to_owned())
     }
 }
 
+fn unescape_cow(s: &str) -> Result<Cow<'_, str>, String> {
+    if s.contains('\\') {
+        unescape(s).map(Cow::Owned)
+    } else {
+        Ok(Cow::Borrowed(s))
+    }
+}
+
 fn unescape(s: &str) -> Result<String, String> {
     let mut chars = s.chars().peekable();
 
@@ -247,6 +357,19 @@ fn test_escape() {
         escape_and_quote(r#"C:\System32\foo.dll"#),
         r#"'C:\System32\foo.dll'"#
     );
+    assert_eq!(
+        escape_and_quote(r#"C:\Users\O'Brien\notes.txt"#),
+        r#""C:\\Users\\O'Brien\\notes.txt""#
+    );
+    assert_eq!(
+        escape_and_quote_literal(r#"C:\Users\O'Brien\notes.txt"#),
+        r#"'''C:\Users\O'Brien\notes.txt'''"#
+    );
+    assert_eq!(escape_and_quote_literal(r#"C:\It's'"#), r#""C:\\It's'""#);
+    assert_eq!(
+        escape_and_quote_literal(r#"C:\System32\foo.dll"#),
+        r#"'C:\System32\foo.dll'"#
+    );
     assert_eq!(
         escape_and_quote(r#"I use "quotes" in this string"#),
         r#"'I use "quotes" in this string'"#
@@ -257,6 +380,30 @@ fn test_escape() {
     );
 }
 
+#[test]
+fn test_escape_like() {
+    let roundtrips = |raw: &str, original: &str| -> String {
+        let quoted = escape_and_quote_like(raw, original);
+        assert_eq!(unescape_and_unquote(&quoted).unwrap(), raw, "{quoted}");
+        quoted
+    };
+
+    // Keep the style if possible:
+    assert_eq!(roundtrips("new", "'old'"), "'new'");
+    assert_eq!(roundtrips(r"C:\new", "'C:\\old'"), r"'C:\new'");
+    assert_eq!(roundtrips("it's", "'old'"), r#""it's""#);
+    assert_eq!(roundtrips(r#"say "hi""#, r#""old""#), r#""say \"hi\"""#);
+    assert_eq!(roundtrips("a\nb", "'''old'''"), "'''a\nb'''");
+    assert_eq!(roundtrips("a\nb", "'''\nold'''"), "'''\na\nb'''");
+    assert_eq!(roundtrips("\na", "'''old'''"), "'''\n\na'''");
+    assert_eq!(roundtrips("a\nb", r#""""old""""#), "\"\"\"a\nb\"\"\"");
+
+    // Fall back to the default style:
+    assert_eq!(roundtrips("a\nb", "'old'"), r#""a\nb""#);
+    assert_eq!(roundtrips(r"C:\new", r#""""old""""#), r"'C:\new'");
+    assert_eq!(roundtrips("new", "not a string"), r#""new""#);
+}
+
 #[test]
 fn test_unescape() {
     assert_eq!(
@@ -281,3 +428,31 @@ fn test_unescape() {
         "Multiline\n  String\n",
     );
 }
+
+#[test]
+fn test_unescape_borrows() {
+    let is_borrowed =
+        |escaped: &str| matches!(unescape_and_unquote_cow(escaped), Ok(Cow::Borrowed(_)));
+    assert!(is_borrowed(r#""no escapes""#));
+    assert!(is_borrowed(r"'C:\dir'"));
+    assert!(is_borrowed("'''\nliteral'''"));
+    assert!(!is_borrowed(r#""new\nline""#));
+    assert!(!is_borrowed("\"\"\"windows\r\nnewlines\"\"\""));
+    assert_eq!(
+        unescape_and_unquote_cow(r#""tab\t""#).unwrap(),
+        unescape_and_unquote(r#""tab\t""#).unwrap()
+    );
+}
+
+#[test]
+fn test_bytes() {
+    assert_eq!(quote_bytes(&[]), r#"b"""#);
+    assert_eq!(quote_bytes(&[0x89, b'P', b'N', b'G']), r#"b"89504e47""#);
+    assert_eq!(unquote_bytes(r#"b"""#).unwrap(), Vec::<u8>::new());
+    assert_eq!(
+        unquote_bytes(r#"b"8950_4E47""#).unwrap(),
+        vec![0x89, b'P', b'N', b'G']
+    );
+    assert!(unquote_bytes(r#"b"895""#).is_err());
+    assert!(unquote_bytes(r#"b"89xy""#).is_err());
+}
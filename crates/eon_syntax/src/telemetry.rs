@@ -0,0 +1,167 @@
+//! Counting allocations and nodes while parsing and formatting, e.g. for tracking memory regressions.
+//!
+//! Eon has no unsafe code, so it cannot count allocations on its own.
+//! Instead you plug in your own counting global allocator with [`set_allocation_counter`].
+
+use std::sync::OnceLock;
+
+use crate::{FormatOptions, Result, TokenTree, TokenValue};
+
+/// Something that counts heap allocations, usually a global allocator wrapping [`std::alloc::System`].
+///
+/// Register it with [`set_allocation_counter`].
+pub trait AllocationCounter: Send + Sync {
+    /// The total number of allocations made so far.
+    fn allocations(&self) -> u64;
+
+    /// The number of bytes currently allocated.
+    fn current_bytes(&self) -> u64;
+
+    /// The highest [`Self::current_bytes`] since the last call to [`Self::reset_peak`].
+    fn peak_bytes(&self) -> u64;
+
+    /// Reset [`Self::peak_bytes`] to [`Self::current_bytes`].
+    fn reset_peak(&self);
+}
+
+static ALLOCATION_COUNTER: OnceLock<&'static dyn AllocationCounter> = OnceLock::new();
+
+/// Use this counter for the allocation counts of [`Telemetry`].
+///
+/// Returns `false` if a counter was already set, in which case that one is kept.
+pub fn set_allocation_counter(counter: &'static dyn AllocationCounter) -> bool {
+    ALLOCATION_COUNTER.set(counter).is_ok()
+}
+
+/// What it cost to parse or format a document.
+///
+/// The allocation counts are global, so they include any allocations made by other threads in the meantime.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Telemetry {
+    /// The number of heap allocations made.
+    ///
+    /// `None` if no counter was set with [`set_allocation_counter`].
+    pub allocations: Option<u64>,
+
+    /// The most heap memory in use at any point, above what was in use at the start.
+    ///
+    /// `None` if no counter was set with [`set_allocation_counter`].
+    pub peak_bytes: Option<u64>,
+
+    /// The number of nodes in the [`TokenTree`] that was parsed or formatted, including map keys.
+    pub nodes: usize,
+}
+
+impl Telemetry {
+    /// Run `f`, counting its allocations.
+    fn measure<T>(f: impl FnOnce() -> T) -> (T, Self) {
+        let Some(counter) = ALLOCATION_COUNTER.get() else {
+            return (f(), Self::default());
+        };
+
+        counter.reset_peak();
+        let allocations_before = counter.allocations();
+        let bytes_before = counter.current_bytes();
+
+        let result = f();
+
+        let telemetry = Self {
+            allocations: Some(counter.allocations().saturating_sub(allocations_before)),
+            peak_bytes: Some(counter.peak_bytes().saturating_sub(bytes_before)),
+            nodes: 0,
+        };
+        (result, telemetry)
+    }
+}
+
+impl<'s> TokenTree<'s> {
+    /// Like [`Self::parse_str`], but also measure what it cost.
+    pub fn parse_str_with_telemetry(source: &'s str) -> (Result<Self>, Telemetry) {
+        let (result, mut telemetry) = Telemetry::measure(|| Self::parse_str(source));
+        if let Ok(tree) = &result {
+            telemetry.nodes = tree.node_count();
+        }
+        (result, telemetry)
+    }
+
+    /// Like [`Self::format`], but also measure what it cost.
+    pub fn format_with_telemetry(&self, options: &FormatOptions) -> (String, Telemetry) {
+        let (formatted, mut telemetry) = Telemetry::measure(|| self.format(options));
+        telemetry.nodes = self.node_count();
+        (formatted, telemetry)
+    }
+
+    /// The number of nodes in this tree, including itself and all map keys.
+    pub fn node_count(&self) -> usize {
+        let children = match &self.value {
+            TokenValue::Map(map) => map
+                .key_values
+                .iter()
+                .map(|kv| kv.key.node_count() + kv.value.node_count())
+                .sum(),
+            TokenValue::List(list) => list.values.iter().map(Self::node_count).sum(),
+            TokenValue::Variant(variant) => variant.values.iter().map(Self::node_count).sum(),
+            TokenValue::Identifier(_)
+            | TokenValue::Number(_)
+            | TokenValue::Timestamp(_)
+            | TokenValue::Bytes(_)
+            | TokenValue::QuotedString(_) => 0,
+        };
+        1 + children
+    }
+}
+
+#[test]
+fn test_telemetry() {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    /// Counts what it is told to, since we can't install a global allocator in a test.
+    #[derive(Default)]
+    struct FakeCounter {
+        allocations: AtomicU64,
+        current_bytes: AtomicU64,
+        peak_bytes: AtomicU64,
+    }
+
+    impl AllocationCounter for FakeCounter {
+        fn allocations(&self) -> u64 {
+            // Pretend every query comes after one more 100-byte allocation:
+            let current = self.current_bytes.fetch_add(100, Ordering::Relaxed) + 100;
+            self.peak_bytes.fetch_max(current, Ordering::Relaxed);
+            self.allocations.fetch_add(1, Ordering::Relaxed) + 1
+        }
+
+        fn current_bytes(&self) -> u64 {
+            self.current_bytes.load(Ordering::Relaxed)
+        }
+
+        fn peak_bytes(&self) -> u64 {
+            self.peak_bytes.load(Ordering::Relaxed)
+        }
+
+        fn reset_peak(&self) {
+            self.peak_bytes
+                .store(self.current_bytes(), Ordering::Relaxed);
+        }
+    }
+
+    let source = "a: [1, 2]\nb: \"Rgb\"(1, 2, 3)\n";
+    let (tree, telemetry) = TokenTree::parse_str_with_telemetry(source);
+    let tree = tree.unwrap();
+    assert_eq!(telemetry.nodes, 1 + 2 + 2 + 2 + 3);
+    assert_eq!(telemetry.allocations, None, "No counter set yet");
+
+    static COUNTER: OnceLock<FakeCounter> = OnceLock::new();
+    assert!(set_allocation_counter(
+        COUNTER.get_or_init(FakeCounter::default)
+    ));
+    assert!(!set_allocation_counter(
+        COUNTER.get_or_init(FakeCounter::default)
+    ));
+
+    let (formatted, telemetry) = tree.format_with_telemetry(&FormatOptions::default());
+    assert_eq!(formatted, source);
+    assert_eq!(telemetry.nodes, 10);
+    assert_eq!(telemetry.allocations, Some(1));
+    assert_eq!(telemetry.peak_bytes, Some(100));
+}
@@ -7,6 +7,13 @@ pub enum TokenKind {
     #[regex("//[^\n]*")]
     Comment,
 
+    /// `# Some comment`, like in YAML and TOML.
+    ///
+    /// Accepted so that snippets pasted from other formats just work.
+    /// Written as a `// comment` by the formatter, unless [`crate::HashComments::Preserve`] is set.
+    #[regex("#[^\n]*")]
+    HashComment,
+
     /// `[`
     #[token("[")]
     OpenList,
@@ -31,22 +38,44 @@ pub enum TokenKind {
     #[token(")")]
     CloseParen,
 
-    /// `:`
+    /// `:`, or `=` like in `key = value`.
+    ///
+    /// Both separate a key from its value, and mean exactly the same thing.
     #[token(":")]
+    #[token("=")]
     Colon,
 
     /// `,`
     #[token(",")]
     Comma,
 
+    /// `@include`, followed by the path to another Eon file.
+    #[token("@include")]
+    Include,
+
     /// Can be an map key, or "false", "true", "null"
     #[regex("[a-zA-Z_][a-zA-Z0-9_]*")]
     Identifier,
 
+    /// An RFC 3339 timestamp, like `2024-05-01T12:30:00Z` or `2024-05-01T14:30:00.5+02:00`.
+    ///
+    /// This is longer than any [`Self::Number`] it starts with, so it takes precedence.
+    #[regex(
+        r"[0-9]{4}-[0-9]{2}-[0-9]{2}[Tt][0-9]{2}:[0-9]{2}:[0-9]{2}(\.[0-9]+)?([Zz]|[+\-][0-9]{2}:[0-9]{2})"
+    )]
+    Timestamp,
+
     /// Anything that starts with a sign (+/-), a digit (0-9), or a period (decimal separator).
     #[regex("[+\\-0-9\\.][0-9a-zA-Z\\.+\\-_]*")]
     Number,
 
+    /// Hex-encoded bytes, like `b"89504e47"`.
+    ///
+    /// Can contain `_` as a visual separator.
+    /// Invalid hex digits are reported when the bytes are decoded.
+    #[regex(r#"b"[^"\n]*""#)]
+    Bytes,
+
     /// `"this"`
     ///
     /// Processes escaped characters like `\"`, `\\`, `\n`, etc.
@@ -69,10 +98,18 @@ pub enum TokenKind {
     MultilineLiteralString,
 }
 
+impl TokenKind {
+    /// Is this a `// comment` or a `# comment`?
+    pub fn is_comment(self) -> bool {
+        matches!(self, Self::Comment | Self::HashComment)
+    }
+}
+
 impl std::fmt::Display for TokenKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Comment => write!(f, "// comment"),
+            Self::HashComment => write!(f, "# comment"),
             Self::OpenList => write!(f, "open bracket '['"),
             Self::CloseList => write!(f, "close bracket ']'"),
             Self::OpenBrace => write!(f, "open brace '{{'"),
@@ -81,8 +118,11 @@ impl std::fmt::Display for TokenKind {
             Self::CloseParen => write!(f, "close parenthesis ')'"),
             Self::Colon => write!(f, "colon ':'"),
             Self::Comma => write!(f, "comma ','"),
+            Self::Include => write!(f, "@include"),
             Self::Identifier => write!(f, "identifier"),
+            Self::Timestamp => write!(f, "timestamp"),
             Self::Number => write!(f, "number"),
+            Self::Bytes => write!(f, r#"b"bytes""#),
             Self::DoubleQuotedString => write!(f, r#""basic string""#),
             Self::SingleQuotedString => write!(f, r"'literal string'"),
             Self::MultilineBasicString => write!(f, r#"""multiline basic string"""#),
@@ -95,13 +135,20 @@ impl std::fmt::Display for TokenKind {
 fn test_parse_tokens() {
     let input = r#"
     // Comment
+    # Hash comment
     key: value
+    other = value
+    @include "other.eon"
     [ { },]
     42
     123_456
     +inf
     +1.e3-42
     0xdeadbeef
+    2024-05-01T12:30:00Z
+    2024-05-01T14:30:00.123+02:00
+    2024-05-01
+    b"8950_4e47"
     "basic \n \"string\""
     'single quoted string'
     """
@@ -116,9 +163,15 @@ string'''
 
     let expect = [
         (TokenKind::Comment, "// Comment"),
+        (TokenKind::HashComment, "# Hash comment"),
         (TokenKind::Identifier, "key"),
         (TokenKind::Colon, ":"),
         (TokenKind::Identifier, "value"),
+        (TokenKind::Identifier, "other"),
+        (TokenKind::Colon, "="),
+        (TokenKind::Identifier, "value"),
+        (TokenKind::Include, "@include"),
+        (TokenKind::DoubleQuotedString, r#""other.eon""#),
         (TokenKind::OpenList, "["),
         (TokenKind::OpenBrace, "{"),
         (TokenKind::CloseBrace, "}"),
@@ -129,6 +182,10 @@ string'''
         (TokenKind::Number, "+inf"),
         (TokenKind::Number, "+1.e3-42"),
         (TokenKind::Number, "0xdeadbeef"),
+        (TokenKind::Timestamp, "2024-05-01T12:30:00Z"),
+        (TokenKind::Timestamp, "2024-05-01T14:30:00.123+02:00"),
+        (TokenKind::Number, "2024-05-01"),
+        (TokenKind::Bytes, r#"b"8950_4e47""#),
         (TokenKind::DoubleQuotedString, r#""basic \n \"string\"""#),
         (TokenKind::SingleQuotedString, "'single quoted string'"),
         (
@@ -5,17 +5,29 @@
 
 use std::borrow::Cow;
 
-use crate::span::Span;
+use crate::{
+    span::Span,
+    strings::{escape_and_quote, unescape_and_unquote, unescape_and_unquote_cow},
+};
 
 /// `// A comment`.
 ///
 /// The string includes the slashes, but not the trailing newline (if any).
-pub type Comment<'s> = &'s str;
+/// Parsed comments borrow from the source; comments added by tools are often owned.
+pub type Comment<'s> = Cow<'s, str>;
+
+/// Is this a `/// doc comment`?
+///
+/// Like in Rust, a comment starting with four or more slashes is a normal comment.
+pub fn is_doc_comment(comment: &str) -> bool {
+    comment.starts_with("///") && !comment.starts_with("////")
+}
 
 /// A tree of tokens, representing the structure of the Eon source code, including comments.
 ///
 /// This is actually something between a Concrete Syntax Tree (CST) and an Abstract Syntax Tree (AST),
-/// in that it preserves comments, but discards whitespace and some optional tokens (like commas after list items),
+/// in that it preserves comments, but discards whitespace and some optional tokens (like commas after list items,
+/// except for whether or not there is a trailing comma),
 /// though those can be inferred from the [`Span`] of the tokens, together with the original
 /// Eon file
 #[derive(Debug)]
@@ -32,6 +44,19 @@ pub struct TokenTree<'s> {
     /// ```
     pub prefix_comments: Vec<Comment<'s>>,
 
+    /// `///` doc comments directly above a map key, documenting it.
+    ///
+    /// ```ignore
+    /// // A normal comment
+    /// /// The port to listen on.
+    /// port: 8080
+    /// ```
+    ///
+    /// Only the doc comments after the last normal comment are included here.
+    /// These always come after the [`Self::prefix_comments`].
+    /// Only map keys have doc comments.
+    pub doc_comments: Vec<Comment<'s>>,
+
     /// The actual value.
     pub value: TokenValue<'s>,
 
@@ -51,13 +76,42 @@ pub struct TokenKeyValue<'s> {
     pub value: TokenTree<'s>,
 }
 
+/// An `@include "other.eon"` directive in a [`TokenMap`].
+///
+/// The contents of the included file (which must be a map) is merged into the map,
+/// as if its key-value pairs had been written before those of the including map.
+#[derive(Debug)]
+pub struct TokenInclude<'s> {
+    /// The span of the whole directive in the source code, if known.
+    pub span: Option<Span>,
+
+    /// Comments on proceeding lines.
+    pub prefix_comments: Vec<Comment<'s>>,
+
+    /// The quoted path, like `"other.eon"`.
+    ///
+    /// Relative paths are relative to the directory of the including file.
+    pub quoted_path: Cow<'s, str>,
+
+    /// Comment after the directive on the same line.
+    pub suffix_comment: Option<Comment<'s>>,
+}
+
 /// An object, like `{ key: value, … }`.
 #[derive(Debug)]
 pub struct TokenMap<'s> {
+    /// Any `@include` directives. These always come before the key-value pairs.
+    pub includes: Vec<TokenInclude<'s>>,
+
     pub key_values: Vec<TokenKeyValue<'s>>,
 
     /// Any comments after the last `key: value` pair, before the closing `}`.
     pub closing_comments: Vec<Comment<'s>>,
+
+    /// Was there a comma after the last entry?
+    ///
+    /// Used by [`crate::TrailingSeparator::Preserve`].
+    pub trailing_comma: bool,
 }
 
 /// A list, like `[ a, b, c, … ]`.
@@ -67,6 +121,11 @@ pub struct TokenList<'s> {
 
     /// Any comments after the last value, before the closing `]`.
     pub closing_comments: Vec<Comment<'s>>,
+
+    /// Was there a comma after the last value?
+    ///
+    /// Used by [`crate::TrailingSeparator::Preserve`].
+    pub trailing_comma: bool,
 }
 
 /// A sum-type (enum) variant
@@ -83,6 +142,11 @@ pub struct TokenVariant<'s> {
 
     /// Any comments after the last value, before the closing `]`.
     pub closing_comments: Vec<Comment<'s>>,
+
+    /// Was there a comma after the last value?
+    ///
+    /// Used by [`crate::TrailingSeparator::Preserve`].
+    pub trailing_comma: bool,
 }
 
 /// The different types of tokens (number, list, …).
@@ -94,6 +158,12 @@ pub enum TokenValue<'s> {
     /// Anything that starts with a sign (+/-) or a digit (0-9).
     Number(Cow<'s, str>),
 
+    /// An RFC 3339 timestamp, like `2024-05-01T12:30:00Z`.
+    Timestamp(Cow<'s, str>),
+
+    /// Hex-encoded bytes, including the `b` prefix and the quotes, like `b"89504e47"`.
+    Bytes(Cow<'s, str>),
+
     /// Includes the actual quotes of the string, both opening and closing.
     ///
     /// Can be on of:
@@ -117,6 +187,34 @@ impl TokenValue<'_> {
     pub fn is_number(&self) -> bool {
         matches!(self, Self::Number(_))
     }
+
+    /// If this is an identifier or a quoted string, return its string value.
+    ///
+    /// This is useful for comparing map keys, where `key` and `"key"` are equivalent.
+    pub fn as_key_str(&self) -> Option<Cow<'_, str>> {
+        match self {
+            Self::Identifier(identifier) => Some(Cow::Borrowed(identifier.as_ref())),
+            Self::QuotedString(quoted) => unescape_and_unquote_cow(quoted).ok(),
+            _ => None,
+        }
+    }
+}
+
+impl TokenInclude<'_> {
+    /// Create an `@include` directive for the given path.
+    pub fn new(path: &str) -> Self {
+        Self {
+            span: None,
+            prefix_comments: vec![],
+            quoted_path: Cow::Owned(escape_and_quote(path)),
+            suffix_comment: None,
+        }
+    }
+
+    /// The unquoted path of the included file.
+    pub fn path(&self) -> Result<String, String> {
+        unescape_and_unquote(&self.quoted_path)
+    }
 }
 
 impl<'s> From<TokenValue<'s>> for TokenTree<'s> {
@@ -124,8 +222,200 @@ impl<'s> From<TokenValue<'s>> for TokenTree<'s> {
         TokenTree {
             span: None,
             prefix_comments: vec![],
+            doc_comments: vec![],
             value,
             suffix_comment: None,
         }
     }
 }
+
+impl TokenTree<'_> {
+    /// The text of the [`Self::doc_comments`], without the leading `///`.
+    ///
+    /// Returns `None` if there are no doc comments.
+    pub fn doc(&self) -> Option<String> {
+        if self.doc_comments.is_empty() {
+            return None;
+        }
+        let lines: Vec<&str> = self
+            .doc_comments
+            .iter()
+            .map(|comment| {
+                let text = comment.strip_prefix("///").unwrap_or(comment);
+                text.strip_prefix(' ').unwrap_or(text)
+            })
+            .collect();
+        Some(lines.join("\n"))
+    }
+
+    /// Is this an empty map, without any keys or includes?
+    ///
+    /// This is what an empty file, or a file with only comments, is parsed as.
+    /// Note that a document consisting of just `{}` is parsed to the same thing.
+    /// Use [`crate::EmptyDocumentPolicy`] to tell the two apart.
+    pub fn is_empty_document(&self) -> bool {
+        matches!(&self.value, TokenValue::Map(map) if map.includes.is_empty() && map.key_values.is_empty())
+    }
+}
+
+impl TokenTree<'_> {
+    /// Copy everything that is borrowed from the source, so that the tree no longer borrows from it.
+    ///
+    /// This lets you keep a parsed document, comments and all, in long-lived application state,
+    /// or send it to another thread.
+    ///
+    /// ```
+    /// # use eon_syntax::TokenTree;
+    /// let tree: TokenTree<'static> = {
+    ///     let source = String::from("port: 8080 // The default");
+    ///     TokenTree::parse_str(&source).unwrap().into_owned()
+    /// };
+    /// assert_eq!(tree.format(&Default::default()), "port: 8080 // The default\n");
+    /// ```
+    pub fn into_owned(self) -> TokenTree<'static> {
+        let Self {
+            span,
+            prefix_comments,
+            doc_comments,
+            value,
+            suffix_comment,
+        } = self;
+        TokenTree {
+            span,
+            prefix_comments: owned_comments(prefix_comments),
+            doc_comments: owned_comments(doc_comments),
+            value: value.into_owned(),
+            suffix_comment: suffix_comment.map(owned),
+        }
+    }
+}
+
+impl TokenValue<'_> {
+    /// See [`TokenTree::into_owned`].
+    pub fn into_owned(self) -> TokenValue<'static> {
+        match self {
+            Self::Identifier(identifier) => TokenValue::Identifier(owned(identifier)),
+            Self::Number(number) => TokenValue::Number(owned(number)),
+            Self::Timestamp(timestamp) => TokenValue::Timestamp(owned(timestamp)),
+            Self::Bytes(bytes) => TokenValue::Bytes(owned(bytes)),
+            Self::QuotedString(quoted) => TokenValue::QuotedString(owned(quoted)),
+            Self::List(list) => TokenValue::List(list.into_owned()),
+            Self::Map(map) => TokenValue::Map(map.into_owned()),
+            Self::Variant(variant) => TokenValue::Variant(variant.into_owned()),
+        }
+    }
+}
+
+impl TokenKeyValue<'_> {
+    /// See [`TokenTree::into_owned`].
+    pub fn into_owned(self) -> TokenKeyValue<'static> {
+        TokenKeyValue {
+            key: self.key.into_owned(),
+            value: self.value.into_owned(),
+        }
+    }
+}
+
+impl TokenInclude<'_> {
+    /// See [`TokenTree::into_owned`].
+    pub fn into_owned(self) -> TokenInclude<'static> {
+        let Self {
+            span,
+            prefix_comments,
+            quoted_path,
+            suffix_comment,
+        } = self;
+        TokenInclude {
+            span,
+            prefix_comments: owned_comments(prefix_comments),
+            quoted_path: owned(quoted_path),
+            suffix_comment: suffix_comment.map(owned),
+        }
+    }
+}
+
+impl TokenMap<'_> {
+    /// See [`TokenTree::into_owned`].
+    pub fn into_owned(self) -> TokenMap<'static> {
+        let Self {
+            includes,
+            key_values,
+            closing_comments,
+            trailing_comma,
+        } = self;
+        TokenMap {
+            includes: includes.into_iter().map(TokenInclude::into_owned).collect(),
+            key_values: key_values
+                .into_iter()
+                .map(TokenKeyValue::into_owned)
+                .collect(),
+            closing_comments: owned_comments(closing_comments),
+            trailing_comma,
+        }
+    }
+}
+
+impl TokenList<'_> {
+    /// See [`TokenTree::into_owned`].
+    pub fn into_owned(self) -> TokenList<'static> {
+        let Self {
+            values,
+            closing_comments,
+            trailing_comma,
+        } = self;
+        TokenList {
+            values: values.into_iter().map(TokenTree::into_owned).collect(),
+            closing_comments: owned_comments(closing_comments),
+            trailing_comma,
+        }
+    }
+}
+
+impl TokenVariant<'_> {
+    /// See [`TokenTree::into_owned`].
+    pub fn into_owned(self) -> TokenVariant<'static> {
+        let Self {
+            name_span,
+            quoted_name,
+            values,
+            closing_comments,
+            trailing_comma,
+        } = self;
+        TokenVariant {
+            name_span,
+            quoted_name: owned(quoted_name),
+            values: values.into_iter().map(TokenTree::into_owned).collect(),
+            closing_comments: owned_comments(closing_comments),
+            trailing_comma,
+        }
+    }
+}
+
+fn owned(string: Cow<'_, str>) -> Cow<'static, str> {
+    Cow::Owned(string.into_owned())
+}
+
+fn owned_comments(comments: Vec<Comment<'_>>) -> Vec<Comment<'static>> {
+    comments.into_iter().map(owned).collect()
+}
+
+#[test]
+fn test_into_owned() {
+    let source = String::from(
+        "@include \"x.eon\"\n// Settings\n/// The port\nport: 8080 // Default\ncolor: \"Rgb\"(1, 2, 3)\n",
+    );
+    let expected = TokenTree::parse_str(&source)
+        .unwrap()
+        .format(&Default::default());
+
+    let owned = TokenTree::parse_str(&source).unwrap().into_owned();
+    drop(source);
+
+    let formatted = std::thread::Builder::new()
+        .name("format_owned".to_owned())
+        .spawn(move || owned.format(&Default::default()))
+        .unwrap()
+        .join()
+        .unwrap();
+    assert_eq!(formatted, expected);
+}
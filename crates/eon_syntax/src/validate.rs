@@ -0,0 +1,100 @@
+//! Checking an Eon document, and reporting all of its syntax errors.
+
+use crate::{error::Error, parse_options::ParseOptions, token_tree::TokenTree};
+
+/// Check that the given source is a valid Eon document according to the given [`ParseOptions`].
+///
+/// This accepts exactly the documents that [`TokenTree::parse_str_with`] accepts,
+/// and reports the same first error.
+/// After that, it keeps going like [`TokenTree::parse_str_recovering`],
+/// and reports any later syntax errors too,
+/// which makes it suitable for giving feedback on user-submitted documents.
+///
+/// Like the parser, this does not check the contents of numbers and strings.
+pub fn validate(source: &str, options: &ParseOptions) -> Result<(), Vec<Error>> {
+    // The comments are not needed for checking the document:
+    let options = options.clone().with_keep_comments(false);
+    let Err(error) = TokenTree::parse_str_with(source, &options) else {
+        return Ok(());
+    };
+
+    let end = error.span().map_or(usize::MAX, |span| span.end);
+    let (_, recovered_errors) = TokenTree::parse_str_recovering(source);
+
+    let mut errors = vec![error];
+    errors.extend(
+        recovered_errors
+            .into_iter()
+            .filter(|error| error.span().is_some_and(|span| end <= span.start)),
+    );
+    Err(errors)
+}
+
+#[test]
+fn test_validate_agrees_with_parser() {
+    let documents = [
+        "",
+        "key: value",
+        "// Just a comment",
+        "42",
+        "1, 2, 3",
+        "{ a: 1 }",
+        "{ a: 1 } { b: 2 }",
+        "a: [1, 2, { b: \"Rgb\"(1, 2, 3) }]",
+        "@include \"other.eon\"\na: 1",
+        "a: 1\n@include \"other.eon\"",
+        "a: { @include 'x.eon', b: 2 }",
+        "@include 42",
+        "a: ",
+        "a 1",
+        "[1, 2",
+        "a: 1 }",
+        "a: (1)",
+        "a: \"Rgb\"(1, 2",
+        "kebab-case: 1",
+        "a: 1 # 2 $",
+        ": 1",
+        &"[".repeat(1000),
+    ];
+
+    for document in documents {
+        let parsed = crate::TokenTree::parse_str(document);
+        let validated = validate(document, &ParseOptions::default());
+        assert_eq!(
+            parsed.is_ok(),
+            validated.is_ok(),
+            "Disagreement about {document:?}: {parsed:?} vs {validated:?}"
+        );
+        if let (Err(parse_error), Err(validation_errors)) = (parsed, validated) {
+            assert_eq!(
+                parse_error.to_string(),
+                validation_errors[0].to_string(),
+                "Different errors for {document:?}"
+            );
+        }
+    }
+
+    let errors = validate("a: $\nb: %", &ParseOptions::default()).unwrap_err();
+    assert_eq!(errors.len(), 2, "Both invalid tokens should be reported");
+}
+
+#[test]
+fn test_validate_with_options() {
+    use crate::{DuplicateKeyPolicy, TopLevel};
+
+    let default = ParseOptions::default();
+
+    assert!(validate("a: 1; b: 2", &default).is_err());
+    assert!(validate("a: 1; b: 2", &default.clone().with_legacy_separators(true)).is_ok());
+
+    assert!(validate("[1, 2]", &default).is_ok());
+    assert!(validate("[1, 2]", &default.clone().with_top_level(TopLevel::Map)).is_err());
+
+    assert!(validate("a: [[[1]]]", &default).is_ok());
+    assert!(validate("a: [[[1]]]", &default.clone().with_max_depth(3)).is_err());
+
+    let errors = validate("a: 1\na: 2", &default).unwrap_err();
+    assert_eq!(errors[0].message(), "Duplicate key in map");
+    let first_wins = default.with_duplicate_keys(DuplicateKeyPolicy::FirstWins);
+    assert!(validate("a: 1\na: 2", &first_wins).is_ok());
+}
@@ -0,0 +1,115 @@
+//! [`proptest`] strategies for generating arbitrary Eon values.
+
+use eon::{Map, Number, Timestamp, Value};
+use proptest::{collection, prelude::*};
+
+/// Any [`Value`], including nested lists, maps, and variants.
+///
+/// Combine with [`crate::assert_value_round_trips`] to find values that do not survive formatting:
+/// ```
+/// use proptest::prelude::*;
+///
+/// proptest!(|(value in eon_test_utils::arb_value())| {
+///     eon_test_utils::assert_value_round_trips(&value);
+/// });
+/// ```
+pub fn arb_value() -> impl Strategy<Value = Value> {
+    arb_scalar().prop_recursive(4, 64, 8, |inner| {
+        prop_oneof![
+            collection::vec(inner.clone(), 0..8).prop_map(Value::List),
+            collection::vec((arb_key(), inner.clone()), 0..8)
+                .prop_map(|pairs| Value::Map(pairs.into_iter().collect::<Map>())),
+            (arb_variant_name(), collection::vec(inner, 0..4))
+                .prop_map(|(name, values)| Value::new_variant(name, values)),
+        ]
+    })
+}
+
+/// A [`Value`] that is not a list, map, or variant.
+pub fn arb_scalar() -> impl Strategy<Value = Value> {
+    prop_oneof![
+        Just(Value::Null),
+        any::<bool>().prop_map(Value::Bool),
+        arb_number().prop_map(Value::Number),
+        arb_timestamp().prop_map(Value::Timestamp),
+        arb_string().prop_map(Value::String),
+        collection::vec(any::<u8>(), 0..16).prop_map(Value::Bytes),
+    ]
+}
+
+/// Any [`Number`]: integers of all sizes, and floats including `±inf`, `nan`, and `-0.0`.
+pub fn arb_number() -> impl Strategy<Value = Number> {
+    prop_oneof![
+        any::<i64>().prop_map(Number::from),
+        any::<u64>().prop_map(Number::from),
+        any::<i128>().prop_map(Number::from),
+        any::<u128>().prop_map(Number::from),
+        // Floats are always parsed as `f64`, so an `f32` only round-trips once widened:
+        any::<f32>().prop_map(|float| Number::from(f64::from(float))),
+        any::<f64>().prop_map(Number::from),
+        prop_oneof![
+            Just(f64::INFINITY),
+            Just(f64::NEG_INFINITY),
+            Just(f64::NAN),
+            Just(-0.0),
+        ]
+        .prop_map(Number::from),
+    ]
+}
+
+/// A valid RFC 3339 [`Timestamp`], with or without fractional seconds and UTC offset.
+pub fn arb_timestamp() -> impl Strategy<Value = Timestamp> {
+    let date = (0_u32..10_000, 1_u32..=12, 1_u32..=28);
+    let time = (0_u32..24, 0_u32..60, 0_u32..60);
+    let fraction = proptest::option::of("[0-9]{1,9}");
+    let offset = proptest::option::of((any::<bool>(), 0_u32..24, 0_u32..60));
+    (date, time, fraction, offset).prop_map(
+        |((year, month, day), (hour, minute, second), fraction, offset)| {
+            let mut text =
+                format!("{year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}");
+            if let Some(fraction) = fraction {
+                text += &format!(".{fraction}");
+            }
+            if let Some((negative, hours, minutes)) = offset {
+                let sign = if negative { '-' } else { '+' };
+                text += &format!("{sign}{hours:02}:{minutes:02}");
+            } else {
+                text.push('Z');
+            }
+            text.parse().expect("generated an invalid timestamp")
+        },
+    )
+}
+
+/// A string that exercises quoting and escaping:
+/// identifiers, keywords, quotes, backslashes, newlines, control characters, and non-ASCII.
+pub fn arb_string() -> impl Strategy<Value = String> {
+    prop_oneof![
+        "[a-zA-Z_][a-zA-Z0-9_]{0,8}",
+        Just("true".to_owned()),
+        Just("null".to_owned()),
+        Just("+inf".to_owned()),
+        "[ \"'`\\\\\n\t/a-z]{0,16}",
+        any::<String>(),
+    ]
+}
+
+/// A map key. Usually a string, but it can also be a number, timestamp, or bytes.
+///
+/// Never `null`, `true`, or `false`: those are identifiers, and identifier keys are read back as strings.
+pub fn arb_key() -> impl Strategy<Value = Value> {
+    prop_oneof![
+        8 => arb_string().prop_map(Value::String),
+        1 => arb_number().prop_map(Value::Number),
+        1 => arb_timestamp().prop_map(Value::Timestamp),
+        1 => collection::vec(any::<u8>(), 0..16).prop_map(Value::Bytes),
+    ]
+}
+
+/// The name of a [`eon::Variant`], which is any string.
+pub fn arb_variant_name() -> impl Strategy<Value = String> {
+    prop_oneof![
+        3 => "[A-Z][a-zA-Z0-9]{0,8}",
+        1 => arb_string(),
+    ]
+}
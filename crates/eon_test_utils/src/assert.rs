@@ -0,0 +1,78 @@
+//! Assertions for Eon documents.
+
+use eon::{FormatOptions, Value};
+
+/// Assert that the source parses as a [`Value`],
+/// and that formatting and re-parsing that value gives back the same value.
+#[track_caller]
+pub fn assert_round_trips(source: &str) {
+    let value: Value = source
+        .parse()
+        .unwrap_or_else(|err| panic!("Failed to parse:\n{source}\n{err}"));
+    let formatted = value.format(&FormatOptions::default());
+    let reparsed: Value = formatted
+        .parse()
+        .unwrap_or_else(|err| panic!("Failed to parse the formatted value:\n{formatted}\n{err}"));
+    assert_eq!(
+        reparsed, value,
+        "Value changed after formatting:\n{formatted}"
+    );
+}
+
+/// Assert that formatting the value and parsing the result gives back the same value,
+/// and that reformatting the formatted source changes nothing.
+///
+/// Useful together with [`crate::arb_value`] (behind the `proptest` feature).
+#[track_caller]
+pub fn assert_value_round_trips(value: &Value) {
+    let options = FormatOptions::default();
+    let formatted = value.format(&options);
+    let reparsed: Value = formatted
+        .parse()
+        .unwrap_or_else(|err| panic!("Failed to parse the formatted value:\n{formatted}\n{err}"));
+    assert_eq!(
+        &reparsed, value,
+        "Value changed after formatting:\n{formatted}"
+    );
+    assert_reformat_is_stable(&formatted, &options);
+}
+
+/// Assert that reformatting the source is stable, i.e. that formatting a second time changes nothing.
+#[track_caller]
+pub fn assert_reformat_is_stable(source: &str, options: &FormatOptions) {
+    let once = eon::reformat(source, options)
+        .unwrap_or_else(|err| panic!("Failed to reformat:\n{source}\n{err}"));
+    let twice = eon::reformat(&once, options)
+        .unwrap_or_else(|err| panic!("Failed to reformat the formatted source:\n{once}\n{err}"));
+    assert_eq!(twice, once, "Formatting is not stable");
+}
+
+/// Assert that the source fails to parse as a [`Value`],
+/// with an error message containing `expected_message`.
+///
+/// Returns the full error, for further inspection.
+#[track_caller]
+pub fn assert_parse_error(source: &str, expected_message: &str) -> eon::Error {
+    match source.parse::<Value>() {
+        Ok(value) => panic!("Expected an error, but parsed:\n{source}\nas {value:?}"),
+        Err(err) => {
+            assert!(
+                err.message().contains(expected_message),
+                "Expected an error containing {expected_message:?}, got: {:?}",
+                err.message()
+            );
+            err
+        }
+    }
+}
+
+#[test]
+fn test_asserts() {
+    assert_round_trips("a: [1, 2.5, \"three\", \"Four\"(4)]");
+    assert_reformat_is_stable("{a:1,b:[]}", &FormatOptions::default());
+    assert_value_round_trips(&Value::new_variant(
+        "Rgb".to_owned(),
+        vec![Value::from(255), Value::String("multi\nline".to_owned())],
+    ));
+    assert_parse_error("a: [1, 2", "Expected");
+}
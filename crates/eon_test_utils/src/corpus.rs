@@ -0,0 +1,96 @@
+//! Sets of Eon documents to test against.
+
+use std::path::{Path, PathBuf};
+
+/// An Eon document of a corpus.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CorpusFile {
+    /// The path of the file, relative to the corpus directory, e.g. `strings.eon`.
+    pub name: String,
+
+    /// The contents of the file.
+    pub source: String,
+}
+
+macro_rules! embedded_corpus {
+    ($dir:literal: $($name:literal),* $(,)?) => {
+        vec![$(CorpusFile {
+            name: $name.to_owned(),
+            source: include_str!(concat!("../corpus/", $dir, "/", $name)).to_owned(),
+        }),*]
+    };
+}
+
+/// Valid Eon documents, covering all the syntax, formatted with the default [`eon::FormatOptions`].
+pub fn valid_corpus() -> Vec<CorpusFile> {
+    embedded_corpus!("valid": "basic.eon", "comments.eon", "numbers.eon", "strings.eon", "variants.eon")
+}
+
+/// Eon documents that fail to parse as an [`eon::Value`], each with a different mistake.
+pub fn invalid_corpus() -> Vec<CorpusFile> {
+    embedded_corpus!("invalid":
+        "duplicate_key.eon",
+        "invalid_key.eon",
+        "unclosed_list.eon",
+        "unknown_keyword.eon",
+    )
+}
+
+/// Load all `.eon` files in a directory and its subdirectories, sorted by name.
+///
+/// ## Errors
+/// If the directory or any of its files could not be read.
+pub fn load_corpus(dir: impl AsRef<Path>) -> std::io::Result<Vec<CorpusFile>> {
+    let dir = dir.as_ref();
+    let mut paths = vec![];
+    collect_eon_files(dir, &mut paths)?;
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let source = std::fs::read_to_string(&path)?;
+            let name = path
+                .strip_prefix(dir)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .replace('\\', "/");
+            Ok(CorpusFile { name, source })
+        })
+        .collect()
+}
+
+fn collect_eon_files(dir: &Path, paths: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_eon_files(&path, paths)?;
+        } else if path.extension().is_some_and(|ext| ext == "eon") {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_corpus() {
+    for file in valid_corpus() {
+        crate::assert_round_trips(&file.source);
+        let formatted = eon::reformat(&file.source, &eon::FormatOptions::default()).unwrap();
+        assert_eq!(formatted, file.source, "{} is not formatted", file.name);
+    }
+
+    for file in invalid_corpus() {
+        assert!(
+            file.source.parse::<eon::Value>().is_err(),
+            "{} should not parse",
+            file.name
+        );
+    }
+
+    let loaded = load_corpus(concat!(env!("CARGO_MANIFEST_DIR"), "/corpus")).unwrap();
+    let names: Vec<&str> = loaded.iter().map(|file| file.name.as_str()).collect();
+    assert_eq!(names.len(), valid_corpus().len() + invalid_corpus().len());
+    assert!(names.contains(&"valid/strings.eon"));
+    assert!(names.contains(&"invalid/unclosed_list.eon"));
+}
@@ -0,0 +1,29 @@
+//! Helpers for testing code that reads and writes Eon.
+//!
+//! * [`valid_corpus`] and [`invalid_corpus`]: a shared set of Eon documents to test against,
+//!   and [`load_corpus`] for loading your own directory of `.eon` files.
+//! * [`normalize`] and [`snapshot_settings`]: for snapshot testing with `insta`.
+//! * [`assert_round_trips`], [`assert_value_round_trips`], [`assert_reformat_is_stable`], and [`assert_parse_error`].
+//! * `arb_value` and friends (behind the `proptest` feature): strategies for property-based testing.
+//!
+//! Meant to be used as a `[dev-dependencies]`.
+//! See <https://github.com/emilk/eon> for more.
+
+#[cfg(feature = "proptest")]
+mod arbitrary;
+mod assert;
+mod corpus;
+mod snapshot;
+
+#[cfg(feature = "proptest")]
+pub use self::arbitrary::{
+    arb_key, arb_number, arb_scalar, arb_string, arb_timestamp, arb_value, arb_variant_name,
+};
+
+pub use self::{
+    assert::{
+        assert_parse_error, assert_reformat_is_stable, assert_round_trips, assert_value_round_trips,
+    },
+    corpus::{CorpusFile, invalid_corpus, load_corpus, valid_corpus},
+    snapshot::{normalize, snapshot_settings, strip_ansi},
+};
@@ -0,0 +1,68 @@
+//! Helpers for snapshot testing with `insta`.
+
+/// Remove all ANSI escape sequences, e.g. the colors of [`eon::Value::format_colored`]
+/// or of a colored error message.
+pub fn strip_ansi(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            stripped.push(c);
+        } else if chars.next() == Some('[') {
+            // Skip parameters up until (and including) the final byte, e.g. the `m` in `\x1b[31;1m`:
+            for c in chars.by_ref() {
+                if ('\x40'..='\x7e').contains(&c) {
+                    break;
+                }
+            }
+        }
+    }
+    stripped
+}
+
+/// Make a string stable for snapshotting across platforms and terminals.
+///
+/// Strips ANSI escape sequences, turns `\r\n` into `\n`, and removes trailing whitespace on each line.
+pub fn normalize(text: &str) -> String {
+    let stripped = strip_ansi(text).replace("\r\n", "\n");
+    let mut normalized = String::with_capacity(stripped.len());
+    for line in stripped.split_inclusive('\n') {
+        normalized.push_str(line.trim_end());
+        if line.ends_with('\n') {
+            normalized.push('\n');
+        }
+    }
+    normalized
+}
+
+/// `insta` settings for snapshotting each file of a corpus.
+///
+/// The snapshots are named after the file (see [`crate::CorpusFile::name`]),
+/// and have the source of the file as their description.
+///
+/// ## Example
+/// ```no_run
+/// for file in eon_test_utils::valid_corpus() {
+///     let value: eon::Value = file.source.parse().unwrap();
+///     eon_test_utils::snapshot_settings(&file).bind(|| {
+///         insta::assert_debug_snapshot!(value);
+///     });
+/// }
+/// ```
+pub fn snapshot_settings(file: &crate::CorpusFile) -> insta::Settings {
+    let mut settings = insta::Settings::clone_current();
+    settings.set_snapshot_suffix(file.name.trim_end_matches(".eon").replace('/', "__"));
+    settings.set_description(file.source.clone());
+    settings.set_omit_expression(true);
+    settings
+}
+
+#[test]
+fn test_normalize() {
+    assert_eq!(strip_ansi("\x1b[34;1mkey\x1b[0m: 1"), "key: 1");
+    assert_eq!(strip_ansi("no colors ö"), "no colors ö");
+    assert_eq!(
+        normalize("Error:  \r\n  \x1b[31mkey\x1b[0m \n\nend"),
+        "Error:\n  key\n\nend"
+    );
+}
@@ -0,0 +1,145 @@
+//! Converting documents in the legacy Con format into Eon.
+
+use eon_syntax::FormatOptions;
+
+/// Convert a document in the legacy Con format (the predecessor of Eon) into Eon, preserving comments.
+///
+/// The differences that are converted are:
+/// * `key = value` becomes `key: value`
+/// * `'single-quoted strings'`, which support escape sequences in Con, become `"double-quoted strings"`
+/// * Unquoted choices, like `mode = Fast` or `color = Rgb(255, 0, 0)`, get quoted: `mode: "Fast"`
+///
+/// Everything else is kept as-is, and the result is then formatted with the given options.
+///
+/// ## Errors
+/// If the converted document is not valid Eon.
+pub fn con_to_eon(con_source: &str, options: &FormatOptions) -> eon_syntax::Result<String> {
+    let mut eon = String::with_capacity(con_source.len());
+    let mut rest = con_source;
+
+    while let Some(c) = rest.chars().next() {
+        let len = if rest.starts_with("//") {
+            rest.find('\n').unwrap_or(rest.len())
+        } else if rest.starts_with("\"\"\"") || rest.starts_with("'''") {
+            let quotes = &rest[..3];
+            rest[3..].find(quotes).map_or(rest.len(), |end| end + 6)
+        } else if rest.starts_with("b\"") {
+            1 + quoted_len(&rest[1..])
+        } else if c == '"' {
+            quoted_len(rest)
+        } else if c == '\'' {
+            let len = quoted_len(rest);
+            if let Some(content) = rest[1..len].strip_suffix('\'') {
+                eon.push_str(&double_quoted(content));
+                rest = &rest[len..];
+                continue;
+            }
+            len // Unclosed: keep it as-is, and let the Eon parser report it
+        } else if c == '=' {
+            eon.push(':');
+            rest = &rest[1..];
+            continue;
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let len = rest
+                .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                .unwrap_or(rest.len());
+            let identifier = &rest[..len];
+            let is_key = rest[len..]
+                .trim_start_matches([' ', '\t'])
+                .starts_with([':', '=']);
+            if is_key || matches!(identifier, "true" | "false" | "null") {
+                eon.push_str(identifier);
+            } else {
+                // An unquoted choice, or the name of a variant, like `Rgb(…)`:
+                eon.push('"');
+                eon.push_str(identifier);
+                eon.push('"');
+            }
+            rest = &rest[len..];
+            continue;
+        } else if c.is_ascii_digit() || matches!(c, '+' | '-' | '.') {
+            // A number (or timestamp), which may contain letters, like `1e6` or `0xff`:
+            rest.find(|c: char| !c.is_ascii_alphanumeric() && !matches!(c, '.' | '+' | '-' | '_'))
+                .unwrap_or(rest.len())
+        } else {
+            c.len_utf8()
+        };
+
+        eon.push_str(&rest[..len]);
+        rest = &rest[len..];
+    }
+
+    eon_syntax::reformat(&eon, options)
+}
+
+/// The length of the quoted string at the start of `s`, including the quotes.
+///
+/// If the string is not closed on the same line, this is the length up to the end of the line.
+fn quoted_len(s: &str) -> usize {
+    let quote = s.chars().next().unwrap_or('"');
+    let mut escaped = false;
+    for (i, c) in s.char_indices().skip(1) {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == quote {
+            return i + 1;
+        } else if c == '\n' {
+            return i;
+        }
+    }
+    s.len()
+}
+
+/// Convert the contents of a Con single-quoted string into an Eon double-quoted string.
+fn double_quoted(content: &str) -> String {
+    let mut quoted = String::with_capacity(content.len() + 2);
+    quoted.push('"');
+    let mut chars = content.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('\'') => quoted.push('\''),
+                Some(escaped) => {
+                    quoted.push('\\');
+                    quoted.push(escaped);
+                }
+                None => quoted.push_str("\\\\"),
+            },
+            '"' => quoted.push_str("\\\""),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[test]
+fn test_con_to_eon() {
+    let con = r#"
+// The name
+name = 'It\'s "quoted"\n'
+mode = Fast // A choice
+color = Rgb(255, 0, 0)
+items = [Small, 'b', "c", true, null, -1.5e3]
+nested = {
+    path: 'C:\\temp'
+    bytes: b"ff00"
+}
+"#;
+
+    let eon = con_to_eon(con, &FormatOptions::default()).unwrap();
+    assert_eq!(
+        eon,
+        r#"// The name
+name: "It's \"quoted\"\n"
+mode: "Fast" // A choice
+color: "Rgb"(255, 0, 0)
+items: ["Small", "b", "c", true, null, -1.5e3]
+nested: { path: "C:\\temp", bytes: b"ff00" }
+"#
+    );
+
+    assert!(con_to_eon("a = 'unclosed", &FormatOptions::default()).is_err());
+}
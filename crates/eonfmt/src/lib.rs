@@ -0,0 +1,271 @@
+//! Library part of the Eon formatter.
+//!
+//! Exposes how `eonfmt` selects which files to format,
+//! so that other tools can select the exact same set of files.
+//!
+//! Also exposes [`con_to_eon`], for converting documents in the legacy Con format,
+//! and [`normalize_strings`], for cleaning up inconsistently quoted strings.
+//! See <https://github.com/emilk/eon> for more.
+
+use std::path::{Path, PathBuf};
+
+use ignore::{WalkBuilder, overrides::OverrideBuilder};
+
+mod con;
+mod normalize_strings;
+
+pub use con::con_to_eon;
+pub use normalize_strings::normalize_strings;
+
+/// The name of the Eon-specific ignore file.
+///
+/// It uses the same syntax as `.gitignore`, and is respected in addition to it.
+pub const EON_IGNORE_FILENAME: &str = ".eonignore";
+
+/// How to select files in [`collect_files`].
+#[derive(Clone, Debug)]
+pub struct WalkOptions {
+    /// Only collect files with this extension, e.g. `eon`.
+    pub extension: String,
+
+    /// Also walk hidden files and directories, i.e. those starting with a `.`.
+    ///
+    /// Off by default, like in `ripgrep` and `fd`.
+    /// Note that this is a change from earlier versions of `eonfmt`,
+    /// which walked hidden files and directories too.
+    pub hidden: bool,
+
+    /// Follow symbolic links to directories.
+    ///
+    /// Off by default.
+    pub follow_links: bool,
+
+    /// Skip files and directories matching any of these globs, e.g. `target/` or `*.generated.eon`.
+    ///
+    /// Uses the same syntax as `.gitignore`.
+    pub exclude: Vec<String>,
+}
+
+impl Default for WalkOptions {
+    fn default() -> Self {
+        Self {
+            extension: "eon".to_owned(),
+            hidden: false,
+            follow_links: false,
+            exclude: vec![],
+        }
+    }
+}
+
+impl WalkOptions {
+    /// Only collect files with this extension, e.g. `eon`.
+    pub fn with_extension(mut self, extension: impl Into<String>) -> Self {
+        self.extension = extension.into();
+        self
+    }
+
+    /// Also walk hidden files and directories, i.e. those starting with a `.`.
+    pub fn with_hidden(mut self, hidden: bool) -> Self {
+        self.hidden = hidden;
+        self
+    }
+
+    /// Follow symbolic links to directories.
+    pub fn with_follow_links(mut self, follow_links: bool) -> Self {
+        self.follow_links = follow_links;
+        self
+    }
+
+    /// Skip files and directories matching this glob, e.g. `target/` or `*.generated.eon`.
+    pub fn with_exclude(mut self, glob: impl Into<String>) -> Self {
+        self.exclude.push(glob.into());
+        self
+    }
+}
+
+/// Find all files to format in `root`.
+///
+/// If `root` is a file, it is returned as-is, regardless of its extension.
+/// If `root` is a directory, it is walked recursively,
+/// skipping files ignored by `.gitignore` and [`EON_IGNORE_FILENAME`] files,
+/// as well as those excluded by the [`WalkOptions`].
+///
+/// The files are returned in the order they were found.
+/// Entries that can't be read, like a directory without read permissions, are passed to `on_error`
+/// and skipped, so that one bad entry doesn't stop the whole walk.
+///
+/// ## Errors
+/// If `root` does not exist, or one of [`WalkOptions::exclude`] is not a valid glob.
+pub fn collect_files(
+    root: &Path,
+    options: &WalkOptions,
+    mut on_error: impl FnMut(ignore::Error),
+) -> Result<Vec<PathBuf>, ignore::Error> {
+    if root.is_file() {
+        return Ok(vec![root.to_path_buf()]);
+    }
+    if !root.is_dir() {
+        return Err(ignore::Error::from(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("Path does not exist: {}", root.display()),
+        )));
+    }
+
+    let mut overrides = OverrideBuilder::new(root);
+    for glob in &options.exclude {
+        // Overrides are whitelists, unless negated:
+        overrides.add(&format!("!{glob}"))?;
+    }
+
+    let walker = WalkBuilder::new(root)
+        .git_ignore(true)
+        .git_exclude(true)
+        .git_global(true)
+        .hidden(!options.hidden)
+        .follow_links(options.follow_links)
+        .overrides(overrides.build()?)
+        .add_custom_ignore_filename(EON_IGNORE_FILENAME)
+        .build();
+
+    let mut files = vec![];
+    for entry in walker {
+        match entry {
+            Ok(entry) => {
+                let entry_path = entry.into_path();
+                if entry_path.is_file() && has_extension(&entry_path, &options.extension) {
+                    files.push(entry_path);
+                }
+            }
+            Err(err) => on_error(err),
+        }
+    }
+    Ok(files)
+}
+
+fn has_extension(entry_path: &Path, extension: &str) -> bool {
+    if let Some(ext) = entry_path.extension() {
+        ext == extension
+    } else {
+        false
+    }
+}
+
+#[cfg(test)]
+#[expect(clippy::needless_pass_by_value)] // Used as an `on_error` callback
+fn no_errors(err: ignore::Error) {
+    panic!("Unexpected error while walking: {err}");
+}
+
+#[test]
+fn test_collect_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    let files = [
+        "a.eon",
+        "b.txt",
+        "sub/c.eon",
+        "ignored/d.eon",
+        "sub/generated.eon",
+    ];
+    for file in files {
+        let path = root.join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, "").unwrap();
+    }
+    std::fs::write(root.join(EON_IGNORE_FILENAME), "ignored/\ngenerated.eon\n").unwrap();
+
+    let mut found: Vec<String> = collect_files(root, &WalkOptions::default(), no_errors)
+        .unwrap()
+        .iter()
+        .map(|path| path.strip_prefix(root).unwrap().display().to_string())
+        .collect();
+    found.sort();
+
+    let txt = collect_files(
+        root,
+        &WalkOptions::default().with_extension("txt"),
+        no_errors,
+    )
+    .unwrap();
+    let single_file =
+        collect_files(&root.join("b.txt"), &WalkOptions::default(), no_errors).unwrap();
+    let missing = collect_files(&root.join("missing"), &WalkOptions::default(), no_errors);
+
+    assert_eq!(found, ["a.eon", "sub/c.eon"]);
+    assert_eq!(txt, [root.join("b.txt")]);
+    assert_eq!(single_file, [root.join("b.txt")]);
+    assert!(missing.is_err());
+}
+
+#[test]
+fn test_collect_files_with_options() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    for file in [
+        "a.eon",
+        ".hidden.eon",
+        ".config/b.eon",
+        "target/c.eon",
+        "sub/d.eon",
+    ] {
+        let path = root.join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, "").unwrap();
+    }
+
+    let collect = |options: &WalkOptions| {
+        let mut found: Vec<String> = collect_files(root, options, no_errors)
+            .unwrap()
+            .iter()
+            .map(|path| path.strip_prefix(root).unwrap().display().to_string())
+            .collect();
+        found.sort();
+        found
+    };
+
+    assert_eq!(
+        collect(&WalkOptions::default()),
+        ["a.eon", "sub/d.eon", "target/c.eon"]
+    );
+    assert_eq!(
+        collect(&WalkOptions::default().with_hidden(true)),
+        [
+            ".config/b.eon",
+            ".hidden.eon",
+            "a.eon",
+            "sub/d.eon",
+            "target/c.eon"
+        ]
+    );
+    assert_eq!(
+        collect(
+            &WalkOptions::default()
+                .with_exclude("target/")
+                .with_exclude("d.eon")
+        ),
+        ["a.eon"]
+    );
+    assert!(
+        collect_files(root, &WalkOptions::default().with_exclude("["), no_errors).is_err(),
+        "Invalid glob"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_collect_files_skips_bad_entries() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    std::fs::write(root.join("a.eon"), "").unwrap();
+    std::fs::create_dir(root.join("sub")).unwrap();
+    std::fs::write(root.join("sub/b.eon"), "").unwrap();
+    std::os::unix::fs::symlink(root, root.join("sub/loop")).unwrap();
+
+    let mut errors = vec![];
+    let options = WalkOptions::default().with_follow_links(true);
+    let mut found = collect_files(root, &options, |err| errors.push(err)).unwrap();
+    found.sort();
+
+    assert_eq!(errors.len(), 1, "The symlink loop should be reported");
+    assert_eq!(found, [root.join("a.eon"), root.join("sub/b.eon")]);
+}
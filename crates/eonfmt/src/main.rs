@@ -3,14 +3,21 @@
 //! Formats Eon files according to the Eon syntax.
 //! See <https://github.com/emilk/eon> for more.
 
-use std::{fs, path::Path, process};
+use std::{
+    fs,
+    io::Write as _,
+    path::Path,
+    process,
+    time::{Duration, Instant},
+};
 
-use clap::{Arg, Command};
-use ignore::WalkBuilder;
+use clap::{Arg, ArgMatches, Command};
 
 fn main() {
     let matches = Command::new("Eon formatter")
         .about("Format Eon files")
+        .subcommand_negates_reqs(true)
+        .args_conflicts_with_subcommands(true)
         .arg(
             Arg::new("files")
                 .help("Files or directories to format")
@@ -24,6 +31,14 @@ fn main() {
                 .help("Check if files are formatted without modifying them")
                 .action(clap::ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("list-different")
+                .long("list-different")
+                .short('l')
+                .help("Only print the paths of files that would be reformatted, without modifying them")
+                .conflicts_with("timings")
+                .action(clap::ArgAction::SetTrue),
+        )
         .arg(
             Arg::new("extension")
                 .long("ext")
@@ -32,14 +47,81 @@ fn main() {
                 .default_value("eon")
                 .value_name("EXT"),
         )
+        .arg(
+            Arg::new("hidden")
+                .long("hidden")
+                .help("Also format hidden files, and walk hidden directories (skipped by default)")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("follow-links")
+                .long("follow-links")
+                .short('L')
+                .help("Follow symbolic links when walking directories")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("exclude")
+                .long("exclude")
+                .help("Skip files and directories matching this glob, e.g. 'target/'. Can be repeated")
+                .value_name("GLOB")
+                .action(clap::ArgAction::Append),
+        )
+        .arg(
+            Arg::new("normalize-strings")
+                .long("normalize-strings")
+                .help("Rewrite all strings to the same quoting style, e.g. 'single' to \"double\"")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("outer-braces")
+                .long("outer-braces")
+                .help("Surround the top-level map in { }, with an extra level of indentation")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("timings")
+                .long("timings")
+                .help("Print per-file and total timing, bytes processed, and throughput")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .subcommand(extract_command())
+        .subcommand(convert_command())
         .get_matches();
 
+    if let Some((subcommand, matches)) = matches.subcommand() {
+        let result = match subcommand {
+            "extract" => extract(matches),
+            "convert" => convert(matches),
+            _ => unreachable!("Unknown subcommand: {subcommand}"),
+        };
+        let exit_code = match result {
+            Ok(()) => 0,
+            Err(err) => {
+                eprintln!("{err}");
+                1
+            }
+        };
+        #[allow(clippy::exit, clippy::allow_attributes)]
+        process::exit(exit_code);
+    }
+
     let paths: Vec<&str> = matches
         .get_many::<String>("files")
         .expect("Missing file paths")
         .map(|s| s.as_str())
         .collect();
-    let check_mode = matches.get_flag("check");
+    let list_different = matches.get_flag("list-different");
+    let check_mode = matches.get_flag("check") || list_different;
+    let print_timings = matches.get_flag("timings");
+    let format: FormatFn = if matches.get_flag("normalize-strings") {
+        eonfmt::normalize_strings
+    } else {
+        eon_syntax::reformat
+    };
+    let options = eon_syntax::FormatOptions::default()
+        .with_ensure_trailing_newline(true)
+        .with_always_include_outer_braces(matches.get_flag("outer-braces"));
     let extension = matches
         .get_one::<String>("extension")
         .expect("Missing extension")
@@ -49,47 +131,59 @@ fn main() {
 
     let mut file_paths = Vec::new();
 
+    let mut walk_options = eonfmt::WalkOptions::default()
+        .with_extension(extension)
+        .with_hidden(matches.get_flag("hidden"))
+        .with_follow_links(matches.get_flag("follow-links"));
+    for glob in matches.get_many::<String>("exclude").into_iter().flatten() {
+        walk_options = walk_options.with_exclude(glob);
+    }
     for path_str in paths {
-        let path = Path::new(path_str);
-
-        if path.is_file() {
-            file_paths.push(path.to_path_buf());
-        } else if path.is_dir() {
-            let walker = WalkBuilder::new(path)
-                .git_ignore(true)
-                .git_exclude(true)
-                .git_global(true)
-                .hidden(false)
-                .build();
-
-            for entry in walker {
-                match entry {
-                    Ok(entry) => {
-                        let entry_path = entry.path();
-                        if entry_path.is_file() && has_extension(entry_path, extension) {
-                            file_paths.push(path.to_path_buf());
-                        }
-                    }
-                    Err(err) => {
-                        eprintln!("Error walking directory: {err}");
-                        exit_code = 1;
-                    }
-                }
-            }
-        } else {
-            eprintln!("Path does not exist: {}", path.display());
+        let mut report = |err: ignore::Error| {
+            eprintln!("Error walking directory: {err}");
             exit_code = 1;
+        };
+        match eonfmt::collect_files(Path::new(path_str), &walk_options, &mut report) {
+            Ok(files) => file_paths.extend(files),
+            Err(err) => report(err),
         }
     }
 
     let mut num_files_changed = 0;
+    let mut total_bytes = 0;
+    let mut total_duration = Duration::ZERO;
 
     for path in &file_paths {
-        match process_file(path, check_mode) {
-            Ok(false) => {}
-            Ok(true) => {
+        let start = Instant::now();
+        let result = process_file(path, check_mode, format, &options);
+        let duration = start.elapsed();
+
+        if let Ok(stats) = &result {
+            total_bytes += stats.num_bytes;
+            total_duration += duration;
+            if print_timings {
+                eprintln!(
+                    "{}: {}",
+                    path.display(),
+                    format_timing(stats.num_bytes, duration)
+                );
+            }
+        }
+
+        match result {
+            Ok(FileStats {
+                needs_formatting: false,
+                ..
+            }) => {}
+            Ok(FileStats {
+                needs_formatting: true,
+                ..
+            }) => {
                 num_files_changed += 1;
-                if check_mode {
+                if list_different {
+                    println!("{}", path.display());
+                    exit_code = 1;
+                } else if check_mode {
                     eprintln!("Would format: {}", path.display());
                     exit_code = 1;
                 } else {
@@ -105,7 +199,9 @@ fn main() {
 
     let num_files_found = file_paths.len();
 
-    if check_mode {
+    if list_different {
+        // Only the paths of the files are printed, so the output can be piped into e.g. `xargs`.
+    } else if check_mode {
         if num_files_changed > 0 {
             eprintln!("{num_files_changed}/{num_files_found} file(s) would be reformatted");
             exit_code = 1;
@@ -120,22 +216,130 @@ fn main() {
         );
     }
 
+    if print_timings {
+        eprintln!(
+            "Total: {} file(s), {}",
+            num_files_found,
+            format_timing(total_bytes, total_duration)
+        );
+    }
+
     #[allow(clippy::exit, clippy::allow_attributes)]
     process::exit(exit_code);
 }
 
-fn has_extension(entry_path: &Path, extension: &str) -> bool {
-    if let Some(ext) = entry_path.extension() {
-        ext == extension
+fn extract_command() -> Command {
+    Command::new("extract")
+        .about("Extract a subtree (with its comments) into a document of its own")
+        .arg(
+            Arg::new("file")
+                .help("The Eon file to extract from")
+                .required(true)
+                .index(1),
+        )
+        .arg(
+            Arg::new("path")
+                .help("Path to the subtree, like `server.ports[2]`")
+                .required(true)
+                .index(2),
+        )
+        .arg(
+            Arg::new("out")
+                .long("out")
+                .short('o')
+                .help("Where to write the extracted document. Defaults to stdout")
+                .value_name("FILE"),
+        )
+        .arg(
+            Arg::new("remove")
+                .long("remove")
+                .help("Remove the subtree from the original file")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("include")
+                .long("include")
+                .help("Replace the subtree in the original file with an @include of the --out file")
+                .requires("out")
+                .conflicts_with("remove")
+                .action(clap::ArgAction::SetTrue),
+        )
+}
+
+fn convert_command() -> Command {
+    Command::new("convert")
+        .about(
+            "Convert a document in another format into Eon, or an Eon document into another format",
+        )
+        .arg(
+            Arg::new("from")
+                .long("from")
+                .help("The format of the input file, which is converted into Eon")
+                .value_parser(["con", "json", "toml", "yaml"])
+                .value_name("FORMAT"),
+        )
+        .arg(
+            Arg::new("to")
+                .long("to")
+                .help("Convert the input Eon file into this format. Values JSON can't represent, like +inf or bytes, are an error")
+                .value_parser(["json"])
+                .conflicts_with("from")
+                .value_name("FORMAT"),
+        )
+        .group(
+            clap::ArgGroup::new("direction")
+                .args(["from", "to"])
+                .required(true),
+        )
+        .arg(
+            Arg::new("file")
+                .help("The file to convert")
+                .required(true)
+                .index(1),
+        )
+        .arg(
+            Arg::new("out")
+                .long("out")
+                .short('o')
+                .help("Where to write the converted document. Defaults to stdout")
+                .value_name("FILE"),
+        )
+}
+
+/// Format a duration together with the number of bytes processed and the resulting throughput.
+fn format_timing(num_bytes: usize, duration: Duration) -> String {
+    let seconds = duration.as_secs_f64();
+    let megabytes = num_bytes as f64 / 1_000_000.0;
+    if 0.0 < seconds {
+        format!(
+            "{:.2} ms, {num_bytes} bytes, {:.1} MB/s",
+            1000.0 * seconds,
+            megabytes / seconds
+        )
     } else {
-        false
+        format!("{:.2} ms, {num_bytes} bytes", 1000.0 * seconds)
     }
 }
 
-fn process_file(path: &Path, check_mode: bool) -> Result<bool, Box<dyn std::error::Error>> {
+struct FileStats {
+    /// Did the file need reformatting?
+    needs_formatting: bool,
+
+    /// Size of the file before formatting.
+    num_bytes: usize,
+}
+
+/// Turns the contents of a file into its formatted contents.
+type FormatFn = fn(&str, &eon_syntax::FormatOptions) -> eon_syntax::Result<String>;
+
+fn process_file(
+    path: &Path,
+    check_mode: bool,
+    format: FormatFn,
+    options: &eon_syntax::FormatOptions,
+) -> Result<FileStats, Box<dyn std::error::Error>> {
     let content = fs::read_to_string(path)?;
-    let options = eon_syntax::FormatOptions::default();
-    let formatted = eon_syntax::reformat(&content, &options)?;
+    let formatted = format(&content, options)?;
 
     let needs_formatting = content != formatted;
 
@@ -143,5 +347,220 @@ fn process_file(path: &Path, check_mode: bool) -> Result<bool, Box<dyn std::erro
         fs::write(path, formatted)?;
     }
 
-    Ok(needs_formatting)
+    Ok(FileStats {
+        needs_formatting,
+        num_bytes: content.len(),
+    })
+}
+
+/// `eonfmt extract <file> <path>`
+fn extract(matches: &ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    let file = matches.get_one::<String>("file").expect("Missing file");
+    let path = matches.get_one::<String>("path").expect("Missing path");
+    let path: eon_syntax::KeyPath = path.parse()?;
+    let options = eon_syntax::FormatOptions::default().with_ensure_trailing_newline(true);
+    options.validate()?;
+
+    let out = matches.get_one::<String>("out");
+    let include = matches.get_flag("include");
+
+    let content = fs::read_to_string(file)?;
+    let mut tree = eon_syntax::TokenTree::parse_str(&content)?;
+    let subtree =
+        match out {
+            Some(out) if include => {
+                let include_path = relative_include_path(Path::new(file), Path::new(out))?;
+                tree.extract_to_include(&path, &include_path).ok_or_else(|| {
+                format!("Found no map to extract at `{path}` in {file}. Only maps can be included")
+            })?
+            }
+            _ => tree
+                .extract(&path)
+                .ok_or_else(|| format!("Found nothing to extract at `{path}` in {file}"))?,
+        };
+
+    let extracted = subtree.format(&options);
+
+    if let Some(out) = out {
+        fs::write(out, extracted)?;
+        eprintln!("Extracted `{path}` to {out}");
+    } else {
+        print!("{extracted}");
+    }
+
+    if matches.get_flag("remove") {
+        fs::write(file, tree.format(&options))?;
+        eprintln!("Removed `{path}` from {file}");
+    } else if include {
+        fs::write(file, tree.format(&options))?;
+        eprintln!("Replaced `{path}` in {file} with an @include");
+    }
+
+    Ok(())
+}
+
+/// `eonfmt convert --from <format> <file>` or `eonfmt convert --to <format> <file>`
+fn convert(matches: &ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    let file = matches.get_one::<String>("file").expect("Missing file");
+    if matches.contains_id("to") {
+        return convert_to_json(file, matches.get_one::<String>("out"));
+    }
+    let from = matches.get_one::<String>("from").expect("Missing format");
+
+    let content = fs::read_to_string(file)?;
+    let options = eon_syntax::FormatOptions::default().with_ensure_trailing_newline(true);
+    options.validate()?;
+
+    let converted = if from == "con" {
+        // Converted as text, to preserve the comments:
+        eonfmt::con_to_eon(&content, &options)
+            .map_err(|err| err.with_source_name(file).to_string())?
+    } else {
+        let value = match from.as_str() {
+            "json" => eon::interop::json::from_json_str(&content)?,
+            "toml" => eon::interop::toml::from_toml_str(&content)?,
+            "yaml" => eon::interop::yaml::from_yaml_str(&content)?,
+            _ => unreachable!("Unknown format: {from}"),
+        };
+        value.format(&options)
+    };
+    if let Some(out) = matches.get_one::<String>("out") {
+        fs::write(out, converted)?;
+        eprintln!("Converted {file} to {out}");
+    } else {
+        print!("{converted}");
+    }
+
+    Ok(())
+}
+
+/// `eonfmt convert --to json <file>`
+///
+/// Streams the tokens straight into the JSON writer, so that huge documents can be converted
+/// without building the whole document in memory.
+fn convert_to_json(file: &str, out: Option<&String>) -> Result<(), Box<dyn std::error::Error>> {
+    let content = fs::read_to_string(file)?;
+    check_json_compatible(&content).map_err(|err| err.with_source_name(file).to_string())?;
+
+    let writer: Box<dyn std::io::Write> = if let Some(out) = out {
+        Box::new(fs::File::create(out)?)
+    } else {
+        Box::new(std::io::stdout().lock())
+    };
+    let mut writer = std::io::BufWriter::new(writer);
+
+    let mut deserializer = eon::StreamDeserializer::new(&content);
+    let mut serializer = serde_json::Serializer::pretty(&mut writer);
+    serde_transcode::transcode(&mut deserializer, &mut serializer)
+        .map_err(|err| format!("{file}: {err}"))?;
+    deserializer.end()?;
+    writeln!(writer)?;
+    writer.flush()?;
+
+    if let Some(out) = out {
+        eprintln!("Converted {file} to {out}");
+    }
+
+    Ok(())
+}
+
+/// Check that plain JSON can represent every value in the document,
+/// so that [`convert_to_json`] never loses data silently.
+///
+/// Streaming means we can't use the `$number`/`$bytes`/`$map` encoding of `eon::interop::json`,
+/// so instead we report non-finite numbers, integers that don't fit in 64 bits, bytes, and non-string keys.
+///
+/// Only looks at one token at a time, so this works for huge documents too.
+fn check_json_compatible(source: &str) -> eon_syntax::Result<()> {
+    use eon_syntax::TokenKind;
+
+    let mut previous: Option<eon_syntax::PlacedToken<'_>> = None;
+    for token in eon_syntax::tokenize(source) {
+        let token = token?;
+        let problem = match token.kind {
+            kind if kind.is_comment() => continue,
+            TokenKind::Number => {
+                let number: eon::Number = token
+                    .slice
+                    .parse()
+                    .map_err(|err| eon_syntax::Error::new_at(source, token.span, err))?;
+                if number.is_float() {
+                    let finite = number.as_f64().is_some_and(f64::is_finite);
+                    (!finite).then_some("JSON has no infinity or NaN")
+                } else {
+                    let fits = number.as_i64().is_some() || number.as_u64().is_some();
+                    (!fits).then_some("JSON can't represent integers that don't fit in 64 bits")
+                }
+            }
+            TokenKind::Bytes => Some("JSON has no bytes"),
+            TokenKind::Colon => previous
+                .as_ref()
+                .filter(|key| {
+                    !matches!(
+                        key.kind,
+                        TokenKind::Identifier
+                            | TokenKind::DoubleQuotedString
+                            | TokenKind::SingleQuotedString
+                            | TokenKind::MultilineBasicString
+                            | TokenKind::MultilineLiteralString
+                    )
+                })
+                .map(|_| "JSON keys must be strings"),
+            _ => None,
+        };
+        if let Some(problem) = problem {
+            let span = match (token.kind, &previous) {
+                (TokenKind::Colon, Some(key)) => key.span,
+                _ => token.span,
+            };
+            return Err(eon_syntax::Error::new_at(
+                source,
+                span,
+                format!("Can't convert to JSON without losing data: {problem}"),
+            ));
+        }
+        previous = Some(token);
+    }
+    Ok(())
+}
+
+/// The path to use in an `@include` in `file` in order to include `included`.
+///
+/// Included paths are relative to the directory of the including file,
+/// so this climbs out of it with `..` if needed, like `../shared/server.eon`.
+/// Both directories must exist, but `included` itself doesn't need to.
+fn relative_include_path(file: &Path, included: &Path) -> std::io::Result<String> {
+    let canonical_dir = |path: &Path| {
+        let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty());
+        dir.unwrap_or_else(|| Path::new(".")).canonicalize()
+    };
+    let dir = canonical_dir(file)?;
+    let included_dir = canonical_dir(included)?;
+    let file_name = included.file_name().ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("Not a file path: {}", included.display()),
+        )
+    })?;
+
+    let num_common = dir
+        .components()
+        .zip(included_dir.components())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    // Always use `/`, so that the document works on all platforms:
+    let mut parts: Vec<String> = dir
+        .components()
+        .skip(num_common)
+        .map(|_| "..".to_owned())
+        .collect();
+    parts.extend(
+        included_dir
+            .components()
+            .skip(num_common)
+            .map(|component| component.as_os_str().to_string_lossy().into_owned()),
+    );
+    parts.push(file_name.to_string_lossy().into_owned());
+    Ok(parts.join("/"))
 }
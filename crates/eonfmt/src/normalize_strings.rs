@@ -0,0 +1,108 @@
+//! Rewriting all strings of a document to the same quoting style.
+
+use std::borrow::Cow;
+
+use eon_syntax::{
+    Error, FormatOptions, Result, TokenTree, TokenValue, escape_and_quote, unescape_and_unquote,
+};
+
+/// Rewrite all quoted strings in an Eon document to the canonical quoting style
+/// chosen by [`escape_and_quote`], and format the result with the given options.
+///
+/// This applies to string values, quoted map keys, and variant names,
+/// so that e.g. `'hello'` and `"""hello"""` both become `"hello"`.
+/// Comments are preserved.
+///
+/// Each string is checked to have the same contents after re-quoting,
+/// and the whole document is checked to load into the same [`eon::Value`] as before.
+///
+/// ## Errors
+/// If the document is not valid Eon, or if normalizing it would change its meaning.
+pub fn normalize_strings(eon_source: &str, options: &FormatOptions) -> Result<String> {
+    let mut tree = TokenTree::parse_str(eon_source)?;
+    normalize_tree(&mut tree);
+    let normalized = tree.format(options);
+
+    // Documents with e.g. `@include` cannot be loaded on their own, so only compare those that can:
+    if let Ok(before) = eon_source.parse::<eon::Value>() {
+        if normalized.parse::<eon::Value>().ok() != Some(before) {
+            return Err(Error::custom(
+                "Normalizing the strings would change the meaning of the document",
+            ));
+        }
+    }
+
+    Ok(normalized)
+}
+
+fn normalize_tree(tree: &mut TokenTree<'_>) {
+    match &mut tree.value {
+        TokenValue::QuotedString(quoted) => normalize_quoted(quoted),
+        TokenValue::List(list) => {
+            for value in &mut list.values {
+                normalize_tree(value);
+            }
+        }
+        TokenValue::Map(map) => {
+            for key_value in &mut map.key_values {
+                normalize_tree(&mut key_value.key);
+                normalize_tree(&mut key_value.value);
+            }
+        }
+        TokenValue::Variant(variant) => {
+            normalize_quoted(&mut variant.quoted_name);
+            for value in &mut variant.values {
+                normalize_tree(value);
+            }
+        }
+        TokenValue::Identifier(_)
+        | TokenValue::Number(_)
+        | TokenValue::Timestamp(_)
+        | TokenValue::Bytes(_) => {}
+    }
+}
+
+/// Re-quote the string, unless that would change its contents.
+fn normalize_quoted(quoted: &mut Cow<'_, str>) {
+    let Ok(raw) = unescape_and_unquote(quoted) else {
+        return; // Leave invalid strings for the user to fix
+    };
+    let requoted = escape_and_quote(&raw);
+    if unescape_and_unquote(&requoted).as_ref() == Ok(&raw) {
+        *quoted = Cow::Owned(requoted);
+    }
+}
+
+#[test]
+fn test_normalize_strings() {
+    let source = r#"
+// A comment
+single: 'hello'
+double: "world"
+multiline: """one line"""
+path: 'C:\Users'
+'quoted key': "Rgb"(1, 2, 3)
+color: 'Red'
+list: ['a', "b", '''c''']
+"#;
+    let normalized = normalize_strings(source, &FormatOptions::default()).unwrap();
+    assert_eq!(
+        normalized,
+        r#"// A comment
+single: "hello"
+double: "world"
+multiline: "one line"
+path: 'C:\Users'
+"quoted key": "Rgb"(1, 2, 3)
+color: "Red"
+list: ["a", "b", "c"]
+"#
+    );
+
+    assert_eq!(
+        normalize_strings(&normalized, &FormatOptions::default()).unwrap(),
+        normalized,
+        "Normalizing is idempotent"
+    );
+    assert!(normalize_strings("key: 'unclosed", &FormatOptions::default()).is_err());
+}
@@ -0,0 +1,318 @@
+//! End-to-end tests of the `eonfmt` binary: exit codes, output, and what happens to the files.
+
+use std::path::Path;
+
+const UNFORMATTED: &str = "{a:1,b:[1,2,3]}";
+const FORMATTED: &str = "a: 1\nb: [1, 2, 3]\n";
+
+/// The result of running `eonfmt`.
+#[derive(Debug, PartialEq, Eq)]
+struct Output {
+    code: i32,
+    stdout: String,
+    stderr: String,
+}
+
+/// Run `eonfmt` in `dir` with the given arguments.
+fn eonfmt(dir: &Path, args: &[&str]) -> Output {
+    let output = assert_cmd::Command::cargo_bin("eonfmt")
+        .expect("Failed to find the eonfmt binary")
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .expect("Failed to run eonfmt");
+    Output {
+        code: output.status.code().expect("Killed by a signal"),
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+    }
+}
+
+/// Create a temporary directory with the given `(path, contents)` files.
+fn temp_dir_with(files: &[(&str, &str)]) -> tempfile::TempDir {
+    let dir = tempfile::tempdir().expect("Failed to create a temporary directory");
+    for (path, contents) in files {
+        let path = dir.path().join(path);
+        std::fs::create_dir_all(path.parent().expect("No parent directory"))
+            .expect("Failed to create directory");
+        std::fs::write(path, contents).expect("Failed to write file");
+    }
+    dir
+}
+
+fn read(dir: &Path, path: &str) -> String {
+    std::fs::read_to_string(dir.join(path)).expect("Failed to read file")
+}
+
+#[test]
+fn test_write_mode() {
+    let dir = temp_dir_with(&[("a.eon", UNFORMATTED), ("b.eon", FORMATTED)]);
+
+    let output = eonfmt(dir.path(), &["a.eon", "b.eon"]);
+    insta::assert_debug_snapshot!(output, @r#"
+    Output {
+        code: 0,
+        stdout: "",
+        stderr: "Formatted: a.eon\nFormatted 1 file(s), 1 file(s) left unchanged\n",
+    }
+    "#);
+    assert_eq!(read(dir.path(), "a.eon"), FORMATTED);
+    assert_eq!(read(dir.path(), "b.eon"), FORMATTED);
+
+    // Formatting again changes nothing:
+    let output = eonfmt(dir.path(), &["a.eon", "b.eon"]);
+    insta::assert_debug_snapshot!(output, @r#"
+    Output {
+        code: 0,
+        stdout: "",
+        stderr: "Formatted 0 file(s), 2 file(s) left unchanged\n",
+    }
+    "#);
+}
+
+#[test]
+fn test_check_mode() {
+    let dir = temp_dir_with(&[("a.eon", UNFORMATTED)]);
+
+    let output = eonfmt(dir.path(), &["--check", "a.eon"]);
+    insta::assert_debug_snapshot!(output, @r#"
+    Output {
+        code: 1,
+        stdout: "",
+        stderr: "Would format: a.eon\n1/1 file(s) would be reformatted\n",
+    }
+    "#);
+    assert_eq!(
+        read(dir.path(), "a.eon"),
+        UNFORMATTED,
+        "--check must not modify files"
+    );
+
+    std::fs::write(dir.path().join("a.eon"), FORMATTED).unwrap();
+    let output = eonfmt(dir.path(), &["--check", "a.eon"]);
+    insta::assert_debug_snapshot!(output, @r#"
+    Output {
+        code: 0,
+        stdout: "",
+        stderr: "All 1 file(s) are correctly formatted\n",
+    }
+    "#);
+}
+
+#[test]
+fn test_list_different() {
+    let dir = temp_dir_with(&[("a.eon", UNFORMATTED), ("b.eon", FORMATTED)]);
+
+    let output = eonfmt(dir.path(), &["--list-different", "a.eon", "b.eon"]);
+    insta::assert_debug_snapshot!(output, @r#"
+    Output {
+        code: 1,
+        stdout: "a.eon\n",
+        stderr: "",
+    }
+    "#);
+    assert_eq!(read(dir.path(), "a.eon"), UNFORMATTED);
+}
+
+#[test]
+fn test_list_different_directory() {
+    let dir = temp_dir_with(&[
+        ("a.eon", UNFORMATTED),
+        ("b.eon", FORMATTED),
+        ("sub/c.eon", UNFORMATTED),
+        ("sub/d.eon", FORMATTED),
+    ]);
+
+    let output = eonfmt(dir.path(), &["-l", "."]);
+    assert_eq!(output.code, 1, "{}", output.stderr);
+    assert_eq!(output.stderr, "");
+    let mut listed: Vec<&str> = output.stdout.lines().collect();
+    listed.sort_unstable();
+    assert_eq!(listed, ["./a.eon", "./sub/c.eon"]);
+    assert_eq!(
+        read(dir.path(), "sub/c.eon"),
+        UNFORMATTED,
+        "Nothing is written"
+    );
+
+    // Once everything is formatted, nothing is listed:
+    let output = eonfmt(dir.path(), &["."]);
+    assert_eq!(output.code, 0, "{}", output.stderr);
+    let output = eonfmt(dir.path(), &["--list-different", "."]);
+    assert_eq!(output.code, 0, "{}", output.stderr);
+    assert_eq!(output.stdout, "");
+}
+
+#[test]
+fn test_outer_braces() {
+    let dir = temp_dir_with(&[("a.eon", UNFORMATTED), ("b.eon", FORMATTED)]);
+
+    let output = eonfmt(dir.path(), &["--outer-braces", "a.eon", "b.eon"]);
+    assert_eq!(output.code, 0, "{}", output.stderr);
+    let braced = "{\n\ta: 1\n\tb: [1, 2, 3]\n}\n";
+    assert_eq!(read(dir.path(), "a.eon"), braced);
+    assert_eq!(read(dir.path(), "b.eon"), braced);
+
+    // Without the flag, the braces are removed again:
+    let output = eonfmt(dir.path(), &["a.eon"]);
+    assert_eq!(output.code, 0, "{}", output.stderr);
+    assert_eq!(read(dir.path(), "a.eon"), FORMATTED);
+}
+
+#[test]
+fn test_invalid_file() {
+    let dir = temp_dir_with(&[("bad.eon", "a: [1, 2"), ("good.eon", UNFORMATTED)]);
+
+    let output = eonfmt(dir.path(), &["bad.eon", "good.eon"]);
+    assert_eq!(output.code, 1);
+    assert!(
+        output.stderr.starts_with("Error processing file bad.eon: "),
+        "{}",
+        output.stderr
+    );
+    assert!(
+        output
+            .stderr
+            .ends_with("Formatted: good.eon\nFormatted 1 file(s), 1 file(s) left unchanged\n"),
+        "{}",
+        output.stderr
+    );
+
+    // The invalid file is left as is, but the valid one is still formatted:
+    assert_eq!(read(dir.path(), "bad.eon"), "a: [1, 2");
+    assert_eq!(read(dir.path(), "good.eon"), FORMATTED);
+}
+
+#[test]
+fn test_missing_path() {
+    let dir = temp_dir_with(&[]);
+
+    let output = eonfmt(dir.path(), &["missing.eon"]);
+    insta::assert_debug_snapshot!(output, @r#"
+    Output {
+        code: 1,
+        stdout: "",
+        stderr: "Error walking directory: Path does not exist: missing.eon\nFormatted 0 file(s), 0 file(s) left unchanged\n",
+    }
+    "#);
+}
+
+#[test]
+fn test_extension_filtering() {
+    let dir = temp_dir_with(&[
+        ("configs/a.eon", UNFORMATTED),
+        ("configs/b.conf", UNFORMATTED),
+    ]);
+
+    let output = eonfmt(dir.path(), &["--check", "configs"]);
+    assert_eq!(output.code, 1);
+    assert!(output.stderr.contains("1/1 file(s)"), "{}", output.stderr);
+
+    let output = eonfmt(dir.path(), &["--ext", "conf", "configs"]);
+    assert_eq!(output.code, 0, "{}", output.stderr);
+    assert_eq!(read(dir.path(), "configs/a.eon"), UNFORMATTED);
+    assert_eq!(read(dir.path(), "configs/b.conf"), FORMATTED);
+}
+
+#[test]
+fn test_walk_current_directory() {
+    let dir = temp_dir_with(&[
+        ("a.eon", UNFORMATTED),
+        ("sub/b.eon", UNFORMATTED),
+        (".hidden/c.eon", UNFORMATTED),
+        ("target/d.eon", UNFORMATTED),
+    ]);
+
+    let output = eonfmt(
+        dir.path(),
+        &["--list-different", "--exclude", "target/", "."],
+    );
+    assert_eq!(output.code, 1, "{}", output.stderr);
+    let mut listed: Vec<&str> = output.stdout.lines().collect();
+    listed.sort_unstable();
+    assert_eq!(listed, ["./a.eon", "./sub/b.eon"]);
+
+    let output = eonfmt(dir.path(), &["--hidden", "."]);
+    assert_eq!(output.code, 0, "{}", output.stderr);
+    assert!(
+        output
+            .stderr
+            .ends_with("Formatted 4 file(s), 0 file(s) left unchanged\n"),
+        "{}",
+        output.stderr
+    );
+    assert_eq!(read(dir.path(), ".hidden/c.eon"), FORMATTED);
+    assert_eq!(read(dir.path(), "target/d.eon"), FORMATTED);
+}
+
+#[test]
+fn test_no_arguments() {
+    let dir = temp_dir_with(&[]);
+
+    let output = eonfmt(dir.path(), &[]);
+    assert_eq!(output.code, 2, "clap exits with 2 on usage errors");
+    assert!(output.stderr.contains("Usage"), "{}", output.stderr);
+}
+
+#[test]
+fn test_convert_to_json() {
+    let dir = temp_dir_with(&[
+        ("ok.eon", "name: \"Eon\"\nports: [80, 8080]\n"),
+        ("inf.eon", "a: 1\nb: +inf\n"),
+        ("bytes.eon", "a: b\"ff\"\n"),
+        ("keys.eon", "1: \"one\"\n"),
+        ("big.eon", "a: 340282366920938463463374607431768211455\n"),
+    ]);
+
+    let output = eonfmt(dir.path(), &["convert", "--to", "json", "ok.eon"]);
+    assert_eq!(output.code, 0, "{}", output.stderr);
+    assert_eq!(
+        output.stdout,
+        "{\n  \"name\": \"Eon\",\n  \"ports\": [\n    80,\n    8080\n  ]\n}\n"
+    );
+
+    // Values that plain JSON can't represent are errors, instead of being silently changed:
+    for (file, problem) in [
+        ("inf.eon", "JSON has no infinity or NaN"),
+        ("bytes.eon", "JSON has no bytes"),
+        ("keys.eon", "JSON keys must be strings"),
+        (
+            "big.eon",
+            "JSON can't represent integers that don't fit in 64 bits",
+        ),
+    ] {
+        let output = eonfmt(dir.path(), &["convert", "--to", "json", file]);
+        assert_eq!(output.code, 1, "{file} should fail to convert");
+        assert_eq!(output.stdout, "", "Nothing should be written for {file}");
+        assert!(output.stderr.contains(problem), "{}", output.stderr);
+    }
+}
+
+#[test]
+fn test_extract_include_to_sibling_directory() {
+    let dir = temp_dir_with(&[
+        ("configs/main.eon", "name: \"main\"\nserver: { port: 80 }\n"),
+        ("shared/.keep", ""),
+    ]);
+
+    let output = eonfmt(
+        dir.path(),
+        &[
+            "extract",
+            "configs/main.eon",
+            "server",
+            "--out",
+            "shared/server.eon",
+            "--include",
+        ],
+    );
+    assert_eq!(output.code, 0, "{}", output.stderr);
+    assert_eq!(
+        read(dir.path(), "configs/main.eon"),
+        "name: \"main\"\nserver: {\n\t@include \"../shared/server.eon\"\n}\n"
+    );
+    assert_eq!(read(dir.path(), "shared/server.eon"), "port: 80\n");
+
+    let value = eon::Value::from_file(dir.path().join("configs/main.eon")).unwrap();
+    assert_eq!(value.get_path("server.port"), Some(&eon::Value::from(80)));
+}
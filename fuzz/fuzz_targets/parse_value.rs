@@ -0,0 +1,12 @@
+//! Parse arbitrary input as a [`eon::Value`], and if that works,
+//! check that formatting and re-parsing it gives back the same value.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|source: &str| {
+    if let Ok(value) = source.parse::<eon::Value>() {
+        eon_test_utils::assert_value_round_trips(&value);
+    }
+});
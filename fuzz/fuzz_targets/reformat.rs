@@ -0,0 +1,14 @@
+//! Reformat arbitrary input, and if that works, check that reformatting again changes nothing.
+//!
+//! Unlike `parse_value`, this keeps comments, so it covers their placement too.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|source: &str| {
+    let options = eon::FormatOptions::default();
+    if eon::reformat(source, &options).is_ok() {
+        eon_test_utils::assert_reformat_is_stable(source, &options);
+    }
+});